@@ -1,23 +1,46 @@
-use std::path::PathBuf;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
 use std::thread;
 use std::time::{Duration, Instant};
 
-use anyhow::Result;
-use chrono::Utc;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, Utc};
+use ratatui::buffer::Buffer;
 
 use crate::config::Config;
-use crate::config::ExcludedAp;
+use crate::config::{ExcludedAp, ExclusionPolicy, Profile};
+use crate::data::alternate::Alternation;
+use crate::data::anonymize::{Anonymize, Anonymizer};
+use crate::data::calibration::{Calibration, CalibrationMsg};
+use crate::data::export::{ExportFormat, ExportManifest, NdjsonStream, SvgSeries, WebhookPayload};
+use crate::data::formula::Formula;
+use crate::data::spill::{self, Spill};
+use crate::data::trend::{self, SessionTrend};
+use crate::data::walk::WalkTest;
 use crate::data::{
-    export, list_adapter_dirs, list_session_infos_in_dir, load_session_validated, save_session,
-    Adapter, ScanResult, Session,
+    default_columns, export, find_run_sessions, list_adapter_dirs, list_session_infos_in_dir,
+    list_sessions_in_dir, legacy_sessions, load_session, load_session_validated,
+    migrate_legacy_sessions, new_session_path, save_session_to, find_label_dir_sessions,
+    move_into_adapter_dir,
+    AccessPoint, Adapter, ApStats, CompareSort, ConnectTiming, DataPaths, LoadTest, ScanResult, Session,
+    ScanTrigger, SessionMarker, SessionSetup, SessionSummary,
 };
-use crate::scanner::{detect_adapters, scan_wifi};
+use crate::scanner::adapter;
+use crate::scanner::doctor::{self, Check};
+use crate::scanner::gps::gpsd_fix;
+use crate::scanner::host::host_context;
+use crate::scanner::privilege::{privilege, refresh_sudo, sudo_ready, Privilege};
+use crate::scanner::replay::Replay;
+use crate::scanner::reset::ResetMethod;
+use crate::scanner::{ScanBackend, ScanError};
 use crate::ui::popups::FilePickerState;
-use crate::ui::{CompareState, HistoryState, LiveState};
+use crate::ui::{compare, ChannelsState, CompareState, HistoryState, LiveState};
+use crate::web::{ApView, CompareView, Dashboard, Snapshot, WebCommand};
 
 /// Result from background scan thread
-type ScanResultMsg = Result<ScanResult, String>;
+type ScanResultMsg = Result<ScanResult, ScanError>;
 
 /// Current screen
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -25,24 +48,197 @@ pub enum Screen {
     Live,
     History,
     Compare,
+    Channels,
+}
+
+/// How often to look for added or removed wireless interfaces
+const HOTPLUG_POLL: Duration = Duration::from_secs(2);
+/// How often to refresh the sudo timestamp (sudo's default timeout is 5-15 min)
+const SUDO_REFRESH: Duration = Duration::from_secs(120);
+/// Longest a single `iw scan` may run before it counts as timed out
+const SCAN_TIMEOUT: Duration = Duration::from_secs(30);
+/// Consecutive transient failures retried quickly before falling back to the interval
+const MAX_QUICK_RETRIES: u32 = 3;
+
+/// How long a finished background write stays in the status toast
+const TOAST_DURATION: Duration = Duration::from_secs(4);
+/// Quiet time after the last config edit before it is written, leaving a window to undo
+const CONFIG_SAVE_DELAY: Duration = Duration::from_secs(3);
+
+/// How far ahead a synchronized scan's trigger is set, so every scan thread is up and
+/// waiting when it fires
+const SYNC_LEAD_MS: i64 = 100;
+
+/// Proximity beep interval at the weakest and strongest signals
+const GEIGER_SLOWEST: Duration = Duration::from_millis(2000);
+const GEIGER_FASTEST: Duration = Duration::from_millis(100);
+const GEIGER_RANGE_DBM: (i32, i32) = (-90, -30);
+
+/// How often the web dashboard's state is refreshed
+const WEB_PUBLISH: Duration = Duration::from_secs(1);
+/// Scans of signal history sent to the web dashboard per AP
+const WEB_HISTORY_SCANS: usize = 60;
+
+/// A probe location scanned in lockstep with the Live adapter (`--remote` given more than once)
+pub struct Node {
+    pub backend: ScanBackend,
+    pub adapter: Adapter,
+    pub session: Session,
+    /// File the node's session saves to (None: a new timestamped file)
+    path: Option<PathBuf>,
+    receiver: Option<Receiver<ScanResultMsg>>,
+    scan_started: Option<Instant>,
+    /// Why the node's last scan failed, cleared by the next good one
+    pub last_error: Option<ScanError>,
+}
+
+/// File write run off the UI thread
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobKind {
+    Save,
+    Export,
+    Webhook,
+    Config,
+    Migrate,
+}
+
+impl JobKind {
+    pub fn running(&self) -> &'static str {
+        match self {
+            JobKind::Save => "Saving",
+            JobKind::Export => "Exporting",
+            JobKind::Webhook => "Posting webhook",
+            JobKind::Config => "Saving config",
+            JobKind::Migrate => "Moving sessions",
+        }
+    }
+
+    fn done(&self) -> &'static str {
+        match self {
+            JobKind::Save => "Saved to",
+            JobKind::Export => "Exported to",
+            JobKind::Webhook => "Posted webhook for",
+            JobKind::Config => "Saved config to",
+            JobKind::Migrate => "Moved old sessions into",
+        }
+    }
+
+    fn failed(&self) -> &'static str {
+        match self {
+            JobKind::Save => "Save failed",
+            JobKind::Export => "Export failed",
+            JobKind::Webhook => "Webhook failed",
+            JobKind::Config => "Config save failed",
+            JobKind::Migrate => "Moving sessions failed",
+        }
+    }
+}
+
+/// Config edits not yet written, with what they replaced so they can be undone
+struct PendingConfig {
+    /// Time of the latest edit; the write waits for `CONFIG_SAVE_DELAY` after it
+    changed: Instant,
+    /// Oldest first; undo reverts the latest
+    edits: Vec<ConfigEdit>,
+}
+
+/// What one config edit changed, so undoing it leaves everything else alone
+struct ConfigEdit {
+    /// Config fields the edit changed, as they were before it
+    fields: serde_json::Map<String, serde_json::Value>,
+    /// Session exclusions the edit added
+    excluded_bssids: Vec<String>,
+    /// Calibration offsets the edit changed, as they were before it
+    offsets: Vec<(String, Option<i32>)>,
+    /// The adapter's label before the edit, if the edit changed it
+    adapter_label: Option<Option<String>>,
+}
+
+/// Top-level config fields by name
+fn config_fields(config: &Config) -> serde_json::Map<String, serde_json::Value> {
+    match serde_json::to_value(config) {
+        Ok(serde_json::Value::Object(fields)) => fields,
+        _ => serde_json::Map::new(),
+    }
+}
+
+/// A background write and where its result arrives
+struct Job {
+    kind: JobKind,
+    path: PathBuf,
+    receiver: Receiver<Result<()>>,
 }
 
 /// Popup state
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Popup {
     None,
-    AdapterCollision { selected: usize },
+    /// Switching to an adapter that already has saved sessions
+    AdapterCollision {
+        adapter: Adapter,
+        existing: PathBuf,
+        selected: usize,
+    },
+    /// Pick the scanning interface from detected adapters
+    InterfaceSwitch { adapters: Vec<Adapter>, selected: usize },
     RenameAdapter { input: String, cursor: usize },
     TimerSetup { input: String, cursor: usize },
     FilePicker,
-    ExportChoice { selected: usize },
-    Error { message: String },
+    ExportChoice { selected: usize, anonymize: Anonymize },
+    Error { message: String, scroll: u16 },
     /// Confirm quit with unsaved data
     ConfirmQuit { selected: usize },
     /// Exclude AP options (session or permanent)
     ExcludeAp { bssid: String, ssid: String, selected: usize },
+    /// Manage permanent exclusions
+    Exclusions { selected: usize },
+    /// Enter an SSID glob pattern to exclude
+    ExclusionPattern { input: String, cursor: usize },
+    /// A new wireless interface appeared; offer to switch to it
+    AdapterAdded { adapter: Adapter, selected: usize },
+    /// Scans keep failing; offer to bounce the interface
+    ResetInterface { interface: String, failures: u32, selected: usize },
     /// Session has issues warning
-    SessionWarning { message: String, path: std::path::PathBuf },
+    SessionWarning { message: String, path: std::path::PathBuf, scroll: u16 },
+    /// Summary shown after saving (timer expiry or quit)
+    SessionSummary {
+        summary: SessionSummary,
+        saved: Option<PathBuf>,
+        /// Exit once the summary is dismissed
+        quit_after: bool,
+    },
+    /// Snapshot format choice (text dump or SVG chart)
+    Snapshot { selected: usize },
+    /// Calibration wizard (progress, then Save/Discard)
+    Calibration { selected: usize },
+    /// Pick a scenario profile to apply
+    ProfilePicker { profiles: Vec<String>, selected: usize },
+    /// Edit the session's antenna and placement notes
+    SessionSetup { values: [String; 5], field: usize, cursor: usize },
+    /// Sessions found in the old flat layout; offer to sort them into adapter directories
+    LegacySessions { files: Vec<PathBuf>, selected: usize },
+    /// Ask whether sessions saved under another label are this adapter's
+    LabelDirSessions { adapter: Adapter, files: Vec<PathBuf>, selected: usize },
+    /// Everything known about the selected live AP
+    ApDetail { message: String, scroll: u16 },
+    /// Show, hide, reorder and resize the Live list's columns
+    Columns { selected: usize },
+    /// Sort keys of the Live or Compare AP list, whichever screen is showing
+    Sort { selected: usize },
+    /// Find an AP by SSID or BSSID (vim keymap `/`)
+    Search { input: String, cursor: usize },
+    /// Run a named action (vim keymap `:`)
+    Command { input: String, cursor: usize },
+    /// Note for a marker on the live session's timeline
+    Marker { input: String, cursor: usize },
+    /// Checkpoints of a walk test, comma-separated, before it starts
+    WalkSetup { input: String, cursor: usize },
+    /// Walk-test prompt (Arrived/Stop)
+    Walk { selected: usize },
+    /// Minutes per turn of an A/B test, before it starts
+    AlternateSetup { input: String, cursor: usize },
+    /// An A/B turn is over: swap the adapters
+    Swap { selected: usize },
 }
 
 /// Main application state
@@ -51,68 +247,278 @@ pub struct App {
     pub screen: Screen,
     pub popup: Popup,
     pub config: Config,
+    pub paths: DataPaths,
+    /// Scenario profile in effect (`--profile` or the picker), kept out of the saved config
+    pub profile: Option<(String, Profile)>,
+    /// Config before any profile was applied; each profile is applied over it
+    profile_base: Option<Config>,
+
+    // Refresh
+    pub tick_rate: Duration,
+    pub low_power: bool,
+    pub needs_redraw: bool,
+    pub focused: bool,
+    /// A `g` of the vim keymap waiting for the second one of `gg`
+    pub vim_pending_g: bool,
+    /// Last `/` search, repeated by an empty one
+    pub last_search: Option<String>,
+    /// Terminal columns and rows, kept current on resize
+    pub terminal_size: (u16, u16),
+    /// Time for the status bar clock; fixed in snapshot tests
+    pub clock: fn() -> DateTime<Local>,
+    /// Last frame drawn without a popup, for snapshots
+    pub last_frame: Option<Buffer>,
 
     // Screen states
     pub live: LiveState,
     pub history: HistoryState,
     pub compare: CompareState,
+    pub channels: ChannelsState,
 
     // File picker state
     pub file_picker: FilePickerState,
 
     // Current session
-    pub current_session: Option<Session>,
+    /// Shared with History while it's on screen; scans copy it only if it's still shared
+    pub current_session: Option<Arc<Session>>,
     pub session_modified: bool,
+    /// File the current session saves to (None: a new timestamped file)
+    pub session_path: Option<PathBuf>,
 
     // Timing
     pub last_scan: Option<Instant>,
     pub session_start: Option<Instant>,
+    /// Expiry actions already ran for the current timer
+    timer_fired: bool,
+
+    /// Source of scans and adapters (real hardware or `--demo`)
+    pub backend: ScanBackend,
+    /// Recorded session being fed into Live (`--replay`)
+    pub replay: Option<Replay>,
+
+    // Hotplug
+    known_interfaces: Vec<String>,
+    /// Plugged-in interfaces not yet offered, held while another popup is open
+    pending_hotplug: Vec<String>,
+    /// Interface list being read on a worker
+    hotplug_receiver: Option<Receiver<Vec<String>>>,
+    last_hotplug_check: Instant,
+    /// When proximity mode last rang the bell
+    last_geiger_beep: Instant,
+    last_sudo_refresh: Instant,
+
+    /// NDJSON tee of every scan, if enabled
+    pub stream: Option<NdjsonStream>,
+    /// Where the live session's oldest scans went once it passed `live_scan_limit`
+    spill: Option<Spill>,
+    /// Set when spilling failed, so the session stays in memory instead
+    spill_failed: bool,
+    /// Evil twin suspects already logged this session
+    alerted_twins: HashSet<String>,
+    /// Spill files of earlier sessions, removed on exit
+    old_spills: Vec<PathBuf>,
+    /// gpsd address to tag scans with a position, if enabled
+    pub gpsd: Option<String>,
 
     // Background scan
     scan_receiver: Option<Receiver<ScanResultMsg>>,
+    scan_started: Option<Instant>,
+    /// Other probe locations, scanned whenever the Live adapter scans
+    pub nodes: Vec<Node>,
+
+    // Calibration wizard
+    pub calibration: Option<Calibration>,
+    calibration_receiver: Option<Receiver<CalibrationMsg>>,
+
+    /// Guided walk test in progress
+    pub walk: Option<WalkTest>,
+    /// A/B test of adapters taking turns in one slot
+    pub alternation: Option<Alternation>,
+
+    /// Connect test running on the Live adapter
+    connect_receiver: Option<Receiver<Result<ConnectTiming>>>,
+
+    /// Load test running on the Live adapter
+    load_receiver: Option<Receiver<Result<LoadTest>>>,
+    /// Interface reset running in the background
+    reset_receiver: Option<Receiver<(String, Result<ResetMethod>)>>,
+
+    /// Saved sessions being loaded for History's across-sessions mode
+    trend_receiver: Option<Receiver<Vec<SessionTrend>>>,
+    /// Sessions under another label that may be the new adapter's, found in the background
+    label_dir_receiver: Option<Receiver<(Adapter, Vec<PathBuf>)>>,
+    /// Those sessions, until no other popup is in the way of asking about them
+    unsure_sessions: Option<(Adapter, Vec<PathBuf>)>,
+    /// Other sessions of a run added to Compare, found in the background
+    run_receiver: Option<Receiver<Vec<Session>>>,
+
+    // Background saves and exports
+    jobs: Vec<Job>,
+    /// Last finished job's message and when it finished
+    pub toast: Option<(String, Instant)>,
+    /// Exclusion and calibration edits waiting to be written to the config file
+    pending_config: Option<PendingConfig>,
+
+    /// Read-only web dashboard (`--web`), if enabled
+    pub web: Option<Dashboard>,
+    last_web_publish: Instant,
 }
 
 impl App {
-    pub fn new() -> Result<Self> {
-        let config = Config::load().unwrap_or_default();
-
-        let mut live = LiveState::default();
-        live.auto_scan_interval = config.auto_scan_interval_secs;
-        live.timer_target_secs = Some(config.default_timer_secs);
-        live.show_channel = config.show_channel;
-        live.show_band = config.show_band;
-        live.highlight_best = config.highlight_best;
-        live.frequency_filter = config.frequency_filter;
-        live.sort_by = config.sort_by;
-
-        let mut history = HistoryState::default();
-        history.time_window_mins = config.history_time_window_mins;
-        history.show_average = config.history_show_average;
-
-        let mut compare = CompareState::default();
-        compare.match_by = config.compare_match_by;
-        compare.metric = config.compare_metric;
-
-        Ok(Self {
+    pub fn new(paths: DataPaths, backend: ScanBackend) -> Result<Self> {
+        let config = Config::load(&paths).unwrap_or_else(|e| {
+            log::warn!("using default config: {:#}", e);
+            Config::default()
+        });
+
+        let live = LiveState {
+            auto_scan_interval: config.auto_scan_interval_secs,
+            timer_target_secs: Some(config.default_timer_secs),
+            timer_mode: config.timer_mode,
+            show_channel: config.show_channel,
+            show_band: config.show_band,
+            highlight_best: config.highlight_best,
+            columns: if config.ap_columns.is_empty() {
+                default_columns()
+            } else {
+                config.ap_columns.clone()
+            },
+            frequency_filter: config.frequency_filter,
+            capability_filter: config.capability_filter,
+            type_filter: config.type_filter,
+            sort: config.live_sort(),
+            grouped: config.group_bands,
+            split: config.live_split,
+            probe_ssids: config.probe_ssids.clone(),
+            connect_network: config.connect_test_network.clone(),
+            load_target: config.load_test_target.clone(),
+            pinned_ssid: config.pinned_ssid.clone(),
+            ..Default::default()
+        };
+
+        let history = HistoryState {
+            time_window_mins: config.history_time_window_mins,
+            show_average: config.history_show_average,
+            drop_outliers: config.history_drop_outliers,
+            ..Default::default()
+        };
+
+        let mut formula_errors = Vec::new();
+        let custom_metrics: Vec<(String, Formula)> = config
+            .custom_metrics
+            .iter()
+            .filter_map(|metric| match Formula::parse(&metric.formula) {
+                Ok(formula) => Some((metric.name.clone(), formula)),
+                Err(e) => {
+                    formula_errors.push(format!("Custom metric {}: {}", metric.name, e));
+                    None
+                }
+            })
+            .collect();
+        let custom_metric = config
+            .compare_custom_metric
+            .as_ref()
+            .and_then(|name| custom_metrics.iter().position(|(n, _)| n == name));
+        let compare = CompareState {
+            match_by: config.compare_match_by,
+            metric: config.compare_metric,
+            custom_metrics,
+            custom_metric,
+            normalize: config.compare_normalize,
+            calibrated: config.compare_calibrated,
+            drop_outliers: config.compare_drop_outliers,
+            window: config.compare_window,
+            count_ratio: config.compare_count_ratio,
+            buckets: config.compare_buckets,
+            band_filter: config.compare_band_filter,
+            min_samples: config.compare_min_samples,
+            sort: config.compare_order(),
+            offsets: config.calibration_offsets.clone(),
+            ..Default::default()
+        };
+        let gpsd = config.gpsd.clone();
+
+        let mut app = Self {
             running: true,
             screen: Screen::Live,
             popup: Popup::None,
+            tick_rate: Duration::from_millis(config.tick_rate_ms.max(50)),
+            low_power: config.low_power,
+            needs_redraw: true,
+            focused: true,
+            vim_pending_g: false,
+            last_search: None,
+            terminal_size: (80, 24),
+            clock: Local::now,
             config,
+            paths,
+            profile: None,
+            profile_base: None,
+            last_frame: None,
             live,
             history,
             compare,
+            channels: ChannelsState::default(),
             file_picker: FilePickerState::default(),
             current_session: None,
             session_modified: false,
+            session_path: None,
             last_scan: None,
             session_start: None,
+            timer_fired: false,
+            known_interfaces: backend.interfaces(),
+            pending_hotplug: Vec::new(),
+            hotplug_receiver: None,
+            backend,
+            replay: None,
+            last_hotplug_check: Instant::now(),
+            last_geiger_beep: Instant::now(),
+            last_sudo_refresh: Instant::now(),
+            stream: None,
+            spill: None,
+            spill_failed: false,
+            alerted_twins: HashSet::new(),
+            old_spills: Vec::new(),
+            gpsd,
             scan_receiver: None,
-        })
+            scan_started: None,
+            nodes: Vec::new(),
+            calibration: None,
+            calibration_receiver: None,
+            walk: None,
+            alternation: None,
+            connect_receiver: None,
+            load_receiver: None,
+            reset_receiver: None,
+            trend_receiver: None,
+            label_dir_receiver: None,
+            unsure_sessions: None,
+            run_receiver: None,
+            jobs: Vec::new(),
+            toast: None,
+            pending_config: None,
+            web: None,
+            last_web_publish: Instant::now(),
+        };
+        if !formula_errors.is_empty() {
+            app.show_error(formula_errors.join("\n"));
+        }
+        Ok(app)
     }
 
-    pub fn init(&mut self) -> Result<()> {
+    /// Start on `adapter` if given, otherwise the first detected adapter
+    pub fn init(&mut self, adapter: Option<Adapter>) -> Result<()> {
+        if self.uses_sudo() && !sudo_ready() {
+            self.show_error("sudo needs a password.\nRun `sudo -v` elsewhere, then Space.".to_string());
+        }
+
+        if let Some(adapter) = adapter {
+            self.set_adapter(adapter);
+            return Ok(());
+        }
+
         // Detect adapters
-        match detect_adapters() {
+        match self.detect_adapters() {
             Ok(adapters) => {
                 if let Some(adapter) = adapters.into_iter().next() {
                     self.set_adapter(adapter);
@@ -122,17 +528,213 @@ impl App {
                 self.show_error(format!("Failed to detect adapters: {}", e));
             }
         }
+        self.offer_legacy_migration();
         Ok(())
     }
 
-    fn set_adapter(&mut self, adapter: Adapter) {
+    /// Ask to move sessions saved by older versions into adapter directories, where
+    /// the file picker lists them
+    fn offer_legacy_migration(&mut self) {
+        let files = legacy_sessions(&self.paths);
+        if !files.is_empty() && self.popup == Popup::None {
+            self.popup = Popup::LegacySessions { files, selected: 0 };
+        }
+    }
+
+    /// Move legacy sessions into adapter directories in the background
+    pub fn migrate_legacy_sessions(&mut self, files: Vec<PathBuf>) {
+        let paths = self.paths.clone();
+        self.spawn_job(JobKind::Migrate, paths.sessions_dir.clone(), move || {
+            let moved = migrate_legacy_sessions(&paths, &files)?;
+            log::info!("moved {} of {} legacy sessions", moved, files.len());
+            Ok(())
+        });
+    }
+
+    /// Detected adapters, with their registered labels
+    fn detect_adapters(&self) -> Result<Vec<Adapter>> {
+        let mut adapters = self.backend.detect_adapters()?;
+        for adapter in &mut adapters {
+            self.config.label_adapter(adapter);
+        }
+        Ok(adapters)
+    }
+
+    /// Gather the adapter's sessions saved under its old labels into its id directory in
+    /// the background. Sessions that might be an identical adapter's are asked about.
+    fn migrate_adapter_dirs(&mut self, adapter: &Adapter) {
+        if adapter.id.is_none() {
+            return;
+        }
+        let registered = self.config.adapter_labels.get(&adapter.label_key()).cloned();
+        let (tx, rx) = mpsc::channel();
+        let (paths, adapter) = (self.paths.clone(), adapter.clone());
+        thread::spawn(move || {
+            let migrate = || -> Result<Vec<PathBuf>> {
+                let found = find_label_dir_sessions(&paths, &adapter, registered.as_deref())?;
+                let moved = move_into_adapter_dir(&paths, &adapter, &found.ours)?;
+                if moved > 0 {
+                    log::info!("moved {} sessions of {} by id", moved, adapter.interface);
+                }
+                Ok(found.unsure)
+            };
+            match migrate() {
+                Ok(unsure) if unsure.is_empty() => {}
+                Ok(unsure) => {
+                    let _ = tx.send((adapter, unsure));
+                }
+                Err(e) => log::warn!("couldn't migrate sessions of {}: {:#}", adapter.interface, e),
+            }
+        });
+        self.label_dir_receiver = Some(rx);
+    }
+
+    /// Ask about sessions found under another label once no other popup is in the way
+    fn offer_label_dir_sessions(&mut self) {
+        if let Some(found) = self.label_dir_receiver.as_ref().and_then(|r| r.try_recv().ok()) {
+            self.label_dir_receiver = None;
+            self.unsure_sessions = Some(found);
+        }
+        if self.popup == Popup::None {
+            if let Some((adapter, files)) = self.unsure_sessions.take() {
+                self.popup = Popup::LabelDirSessions { adapter, files, selected: 1 };
+                self.needs_redraw = true;
+            }
+        }
+    }
+
+    /// Move sessions the user claimed for `adapter` into its directory
+    pub fn claim_sessions(&mut self, adapter: Adapter, files: Vec<PathBuf>) {
+        let paths = self.paths.clone();
+        self.spawn_job(JobKind::Migrate, paths.sessions_dir.clone(), move || {
+            let moved = move_into_adapter_dir(&paths, &adapter, &files)?;
+            log::info!("moved {} sessions into {}", moved, adapter.dir_name());
+            Ok(())
+        });
+    }
+
+    fn set_adapter(&mut self, mut adapter: Adapter) {
+        self.config.label_adapter(&mut adapter);
+        self.migrate_adapter_dirs(&adapter);
         self.live.adapter = Some(adapter.clone());
 
         // Create new session
         let duration = self.live.timer_target_secs.map(Duration::from_secs);
-        self.current_session = Some(Session::new(adapter, duration));
+        // Offer the previous session's setup, since consecutive runs often share most of it
+        let previous_setup = self.current_session.as_ref().and_then(|s| s.setup.clone());
+        let radio = self.backend.radio_context(&adapter.interface);
+        log::info!("radio context of {}: {:?}", adapter.interface, radio);
+        let mut session = Session::new(adapter, duration);
+        session.node = self.backend.node();
+        session.radio = radio;
+        self.current_session = Some(Arc::new(session));
+        self.alerted_twins.clear();
+        if let Some(spill) = self.spill.take() {
+            self.old_spills.push(spill.path);
+        }
+        self.spill_failed = false;
         self.session_start = Some(Instant::now());
         self.session_modified = false;
+        self.live.elapsed_secs = 0;
+        self.timer_fired = false;
+        self.start_node_sessions();
+        if self.config.prompt_setup && self.popup == Popup::None {
+            self.show_setup_popup(previous_setup.unwrap_or_default());
+        }
+    }
+
+    /// Scan these probe locations alongside the Live adapter from now on
+    pub fn add_nodes(&mut self, nodes: Vec<(ScanBackend, Adapter)>) {
+        for (backend, adapter) in nodes {
+            self.nodes.push(Node {
+                backend,
+                session: Session::new(adapter.clone(), None),
+                adapter,
+                path: None,
+                receiver: None,
+                scan_started: None,
+                last_error: None,
+            });
+        }
+        self.start_node_sessions();
+    }
+
+    /// Give every node a fresh session in the same run as the current one
+    fn start_node_sessions(&mut self) {
+        let primary = self.current_session.as_mut().filter(|_| !self.nodes.is_empty());
+        let Some(primary) = primary.map(Arc::make_mut) else {
+            return;
+        };
+        let run_id = primary.started_at.format("%Y%m%d_%H%M%S").to_string();
+        primary.run_id = Some(run_id.clone());
+
+        for node in &mut self.nodes {
+            let mut session = Session::new(node.adapter.clone(), primary.duration_target());
+            session.started_at = primary.started_at;
+            session.node = node.backend.node();
+            session.run_id = Some(run_id.clone());
+            session.radio = node.backend.radio_context(&node.adapter.interface);
+            node.session = session;
+            node.path = None;
+            node.receiver = None;
+            node.last_error = None;
+        }
+        self.update_node_status();
+    }
+
+    /// Healthy/total node count for the Live header
+    fn update_node_status(&mut self) {
+        let healthy = self.nodes.iter().filter(|n| n.last_error.is_none()).count();
+        self.live.nodes = (!self.nodes.is_empty()).then_some((healthy, self.nodes.len()));
+    }
+
+    /// Run the configured timer-expiry actions
+    fn on_timer_expired(&mut self) {
+        let expiry = self.config.timer_expiry.clone();
+        self.needs_redraw = true;
+
+        if expiry.beep {
+            let _ = crate::utils::beep();
+        }
+        if expiry.stop_scan && !expiry.next_round {
+            self.live.auto_scan = false;
+        }
+
+        let threshold = self.config.alert_threshold_dbm;
+        let summary = self.current_session.as_ref().map(|s| s.summarize(threshold));
+        let mut saved = None;
+        if (expiry.autosave || expiry.next_round) && self.replay.is_none() {
+            match self.save_current_session_in_background() {
+                Ok(path) => saved = Some(path),
+                Err(e) => {
+                    self.show_error(format!("Auto-save failed: {}", e));
+                    return;
+                }
+            }
+        }
+
+        if expiry.next_round && self.replay.is_none() {
+            if let Some(adapter) = self.live.adapter.clone() {
+                self.session_path = None;
+                self.set_adapter(adapter);
+            }
+        }
+
+        // A save already posted the webhook
+        if let (Some(summary), None) = (&summary, &saved) {
+            let node = self.current_session.as_ref().and_then(|s| s.node.clone());
+            self.send_webhook("timer_expired", summary.clone(), node, None);
+        }
+
+        if let Some(summary) = summary.filter(|_| expiry.summary) {
+            if self.popup == Popup::None {
+                self.popup = Popup::SessionSummary {
+                    summary,
+                    saved,
+                    quit_after: false,
+                };
+            }
+        }
     }
 
     pub fn switch_screen(&mut self, screen: Screen) {
@@ -142,9 +744,50 @@ impl App {
         // Load session into history view when switching
         if screen == Screen::History {
             if let Some(session) = &self.current_session {
-                self.history.session = Some(session.clone());
+                self.set_history_session(Arc::clone(session));
             }
+        } else if let (Some(shown), Some(live)) = (&self.history.session, &self.current_session) {
+            // Let go of the live session, so the next scan needn't copy it
+            if Arc::ptr_eq(shown, live) {
+                self.history.session = None;
+            }
+        }
+    }
+
+    /// Show `session` in History, leaving across-sessions mode if its adapter differs
+    fn set_history_session(&mut self, session: Arc<Session>) {
+        if self.history.trend_adapter != Some(session.adapter.dir_name()) {
+            self.history.trends = None;
+            self.history.trend_adapter = None;
+            self.history.trends_loading = false;
+            self.trend_receiver = None;
+        }
+        self.history.session = Some(session);
+    }
+
+    /// Toggle History between one session and the selected AP across all saved
+    /// sessions of the adapter, which load in the background
+    pub fn toggle_across_sessions(&mut self) {
+        if self.history.across_sessions() {
+            self.history.trends = None;
+            self.history.trend_adapter = None;
+            self.history.trends_loading = false;
+            self.trend_receiver = None;
+            return;
         }
+        let Some(session) = &self.history.session else {
+            self.show_error("Load a session first".to_string());
+            return;
+        };
+        let adapter = session.adapter.dir_name();
+        let dir = self.paths.sessions_dir.join(&adapter);
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let _ = sender.send(trend::load_trends(&dir));
+        });
+        self.trend_receiver = Some(receiver);
+        self.history.trend_adapter = Some(adapter);
+        self.history.trends_loading = true;
     }
 
     pub fn tick(&mut self) {
@@ -153,157 +796,1222 @@ impl App {
             self.live.elapsed_secs = start.elapsed().as_secs();
         }
 
+        // A replay runs on recorded time, so the timer and its expiry follow it
+        if let Some(replay) = &mut self.replay {
+            let due = replay.due();
+            self.live.elapsed_secs = replay.position().as_secs();
+            let (done, total) = replay.progress();
+            self.live.replay = Some((done, total, replay.speed));
+            for scan in due {
+                self.record_scan(scan);
+            }
+        }
+
         // Check for scan results from background thread
         if let Some(receiver) = &self.scan_receiver {
             match receiver.try_recv() {
                 Ok(Ok(result)) => {
-                    self.live.access_points = result.access_points.clone();
-                    self.live.last_scan_error = None;
-
-                    // Add to session
-                    if let Some(session) = &mut self.current_session {
-                        session.add_scan(result);
-                        self.session_modified = true;
-                    }
-
-                    self.last_scan = Some(Instant::now());
-                    self.live.scanning = false;
-                    self.scan_receiver = None;
-                }
-                Ok(Err(e)) => {
-                    self.live.last_scan_error = Some(e);
                     self.live.scanning = false;
                     self.scan_receiver = None;
+                    self.scan_started = None;
+                    self.record_scan(result);
                 }
+                Ok(Err(e)) => self.scan_failed(e),
                 Err(mpsc::TryRecvError::Empty) => {
-                    // Still scanning, do nothing
+                    // Give up on an iw that hangs; its late result is dropped with the receiver
+                    if self.scan_started.is_some_and(|t| t.elapsed() >= SCAN_TIMEOUT) {
+                        self.scan_failed(ScanError::Timeout);
+                    }
                 }
                 Err(mpsc::TryRecvError::Disconnected) => {
-                    self.live.last_scan_error = Some("Scan thread crashed".to_string());
-                    self.live.scanning = false;
-                    self.scan_receiver = None;
+                    self.scan_failed(ScanError::Other("Scan thread crashed".to_string()));
                 }
             }
         }
 
-        // Check for auto-scan
-        if self.live.auto_scan && self.screen == Screen::Live && self.popup == Popup::None {
-            let should_scan = match self.last_scan {
-                Some(last) => last.elapsed().as_secs() >= self.live.auto_scan_interval,
-                None => true,
-            };
+        self.poll_nodes();
 
-            if should_scan && !self.live.scanning {
-                self.perform_scan();
+        // Drain calibration progress
+        if let (Some(receiver), Some(calibration)) =
+            (&self.calibration_receiver, &mut self.calibration)
+        {
+            while let Ok(msg) = receiver.try_recv() {
+                calibration.apply(msg);
+                self.needs_redraw = true;
+            }
+            if calibration.done {
+                self.calibration_receiver = None;
             }
         }
-    }
 
-    pub fn perform_scan(&mut self) {
-        // Don't start a new scan if one is already in progress
-        if self.live.scanning {
-            return;
+        self.poll_connect_test();
+        self.poll_load_test();
+        self.poll_reset();
+        self.poll_run_sessions();
+        self.offer_label_dir_sessions();
+
+        if let Some(trends) = self.trend_receiver.as_ref().and_then(|r| r.try_recv().ok()) {
+            self.trend_receiver = None;
+            self.history.trends_loading = false;
+            self.history.trends = Some(trends);
+            self.needs_redraw = true;
         }
 
-        let Some(adapter) = &self.live.adapter else {
-            return;
-        };
+        self.poll_jobs();
+        self.write_pending_config();
 
-        self.live.scanning = true;
-        self.live.last_scan_error = None;
+        if let Some(signal) = self.live.geiger_signal() {
+            if self.last_geiger_beep.elapsed() >= geiger_interval(signal) {
+                let _ = crate::utils::beep();
+                self.last_geiger_beep = Instant::now();
+            }
+        }
 
-        // Spawn background thread for scanning
-        let (tx, rx): (Sender<ScanResultMsg>, Receiver<ScanResultMsg>) = mpsc::channel();
-        let interface = adapter.interface.clone();
+        if !self.timer_fired && self.live.timer_expired() {
+            self.timer_fired = true;
+            self.on_timer_expired();
+        }
 
-        thread::spawn(move || {
-            let result = scan_wifi(&interface).map_err(|e| e.to_string());
-            let _ = tx.send(result);
-        });
+        if self.alternation.as_ref().is_some_and(Alternation::swap_due)
+            && self.popup == Popup::None
+        {
+            self.end_turn();
+        }
+
+        // Keep cached sudo credentials from expiring mid-session
+        if self.uses_sudo() && self.last_sudo_refresh.elapsed() >= SUDO_REFRESH {
+            self.last_sudo_refresh = Instant::now();
+            thread::spawn(refresh_sudo);
+        }
+
+        let commands = self.web.as_ref().map(|web| web.commands()).unwrap_or_default();
+        for command in commands {
+            match command {
+                WebCommand::Scan => self.perform_scan(),
+            }
+        }
+        if self.web.is_some() && self.last_web_publish.elapsed() >= WEB_PUBLISH {
+            self.last_web_publish = Instant::now();
+            self.publish_web();
+        }
+
+        if self.replay.is_none() {
+            self.check_hotplug();
+        }
+
+        // Check for auto-scan (suspended while the terminal is unfocused or unplugged, and
+        // behind popups other than the walk-test prompt, which waits on its dwell scans)
+        if self.live.auto_scan
+            && self.focused
+            && !self.live.adapter_missing
+            && self.screen == Screen::Live
+            && matches!(self.popup, Popup::None | Popup::Walk { .. })
+        {
+            // Quick retries for transient failures; some failures wait for a manual scan
+            let regular = self.live.auto_scan_interval;
+            let interval = match &self.live.last_scan_error {
+                Some(e) => e.retry_after(regular).map(|secs| {
+                    if self.live.scan_failures <= MAX_QUICK_RETRIES {
+                        secs
+                    } else {
+                        regular
+                    }
+                }),
+                None => Some(regular),
+            };
+            let should_scan = match (self.last_scan, interval) {
+                (_, None) => false,
+                (Some(last), Some(interval)) => last.elapsed().as_secs() >= interval,
+                (None, Some(_)) => true,
+            };
 
-        self.scan_receiver = Some(rx);
+            if should_scan && !self.live.scanning {
+                self.perform_scan();
+            }
+        }
     }
 
-    pub fn save_current_session(&mut self) -> Result<PathBuf> {
-        let session = self
+    /// Send the current Live and Compare state to the web dashboard
+    fn publish_web(&self) {
+        let Some(web) = &self.web else {
+            return;
+        };
+        let recent = self
             .current_session
             .as_ref()
-            .ok_or_else(|| anyhow::anyhow!("No session to save"))?;
+            .map(|s| &s.scans[s.scans.len().saturating_sub(WEB_HISTORY_SCANS)..])
+            .unwrap_or_default();
+        let access_points = self
+            .live
+            .access_points
+            .iter()
+            .map(|ap| {
+                let stats =
+                    self.current_session.as_ref().and_then(|s| s.ap_stats(&ap.bssid));
+                let history = recent
+                    .iter()
+                    .map(|scan| {
+                        let seen = scan.access_points.iter().find(|a| a.bssid == ap.bssid);
+                        seen.map(|a| a.signal_dbm)
+                    })
+                    .collect();
+                ApView {
+                    bssid: ap.bssid.clone(),
+                    ssid: ap.ssid.clone(),
+                    signal_dbm: ap.signal_dbm,
+                    channel: ap.channel,
+                    band: ap.band().short_name(),
+                    avg: stats.as_ref().map(|s| s.avg),
+                    min: stats.as_ref().map(|s| s.min),
+                    max: stats.as_ref().map(|s| s.max),
+                    history,
+                }
+            })
+            .collect();
+        let compare = self.compare.get_selected_ap().map(|(bssid, ssid)| CompareView {
+            ssid,
+            bssid,
+            metric: self.compare.metric_name().to_string(),
+            match_by: self.compare.match_by.name(),
+            rows: self.compare.get_comparison_data(),
+            best: self.compare.best_adapter(),
+        });
+        web.publish(&Snapshot {
+            adapter: self.live.adapter.as_ref().map(|a| a.display_name()),
+            scanning: self.live.scanning,
+            scan_count: self.current_session.as_ref().map(|s| s.scans.len()).unwrap_or(0)
+                + self.spill.as_ref().map_or(0, |spill| spill.count),
+            elapsed_secs: self.live.elapsed_secs,
+            error: self.live.last_scan_error.as_ref().map(|e| e.to_string()),
+            access_points,
+            compare,
+        });
+    }
 
-        let path = save_session(session)?;
-        self.session_modified = false;
-        Ok(path)
+    /// Pause when the active interface vanishes, offer to switch when one appears
+    /// Poll the interface list on a worker, since on Windows, macOS and FreeBSD listing
+    /// adapters runs netsh, networksetup or ifconfig
+    fn check_hotplug(&mut self) {
+        if let Some(receiver) = &self.hotplug_receiver {
+            match receiver.try_recv() {
+                Ok(current) => {
+                    self.hotplug_receiver = None;
+                    self.interfaces_changed(current);
+                }
+                Err(mpsc::TryRecvError::Empty) => return,
+                Err(mpsc::TryRecvError::Disconnected) => self.hotplug_receiver = None,
+            }
+        }
+        if self.last_hotplug_check.elapsed() >= HOTPLUG_POLL {
+            self.last_hotplug_check = Instant::now();
+            let (tx, rx) = mpsc::channel();
+            let backend = self.backend;
+            thread::spawn(move || {
+                let _ = tx.send(backend.interfaces());
+            });
+            self.hotplug_receiver = Some(rx);
+        }
     }
 
-    pub fn load_session_file(&mut self, path: &PathBuf) -> Result<()> {
-        let (session, validation) = load_session_validated(path)?;
+    /// Track unplugged and newly plugged interfaces, then offer one that was plugged in
+    fn interfaces_changed(&mut self, current: Vec<String>) {
+        if current != self.known_interfaces {
+            let added = current.iter().filter(|i| !self.known_interfaces.contains(i));
+            let added: Vec<String> = added.cloned().collect();
+            self.known_interfaces = current;
+            self.pending_hotplug.extend(added);
+            self.pending_hotplug.retain(|i| self.known_interfaces.contains(i));
+            self.needs_redraw = true;
+            log::info!("wireless interfaces changed: [{}]", self.known_interfaces.join(", "));
 
-        // Show warning if session has issues but still load it
-        if !validation.warnings.is_empty() {
-            let warning_msg = format!(
-                "Session loaded with warnings:\n{}",
-                validation.warnings.join("\n")
-            );
-            // We'll show this after loading
-            self.popup = Popup::SessionWarning {
-                message: warning_msg,
-                path: path.clone(),
-            };
+            if let Some(adapter) = &self.live.adapter {
+                let missing = !self.known_interfaces.contains(&adapter.interface);
+                if missing && !self.live.adapter_missing {
+                    self.scan_receiver = None;
+                    self.live.scanning = false;
+                    self.live.last_scan_error =
+                        Some(ScanError::Other(format!("{} was unplugged", adapter.interface)));
+                } else if !missing && self.live.adapter_missing {
+                    self.live.last_scan_error = None;
+                    self.live.scan_failures = 0;
+                }
+                self.live.adapter_missing = missing;
+            }
         }
+        self.offer_hotplugged();
+    }
 
-        match self.screen {
-            Screen::History => {
-                self.history.session = Some(session);
+    /// Offer the oldest pending plugged-in interface, once no other popup is in the way
+    fn offer_hotplugged(&mut self) {
+        let swapping = self.alternation.as_ref().is_some_and(Alternation::waiting);
+        if self.pending_hotplug.is_empty() || (self.popup != Popup::None && !swapping) {
+            return;
+        }
+        let interface = self.pending_hotplug.remove(0);
+        let adapter = self
+            .detect_adapters()
+            .ok()
+            .and_then(|adapters| adapters.into_iter().find(|a| a.interface == interface));
+        if let Some(adapter) = adapter {
+            if swapping {
+                self.swap_in(adapter);
+                return;
             }
-            Screen::Compare => {
-                self.compare.add_session(session);
-                // Ensure visibility after adding
-                let len = self.compare.sessions.len();
-                self.compare.selected_session_idx = len.saturating_sub(1);
+            // A replugged active adapter just resumes
+            if self.live.adapter.as_ref().map(|a| &a.interface) == Some(&adapter.interface) {
+                return;
             }
-            _ => {}
+            self.popup = Popup::AdapterAdded {
+                adapter,
+                selected: 0,
+            };
         }
-        Ok(())
-    }
-
-    pub fn refresh_adapter_list(&mut self) -> Result<()> {
-        let adapters = list_adapter_dirs()?;
-        self.file_picker.set_adapters(adapters);
-        Ok(())
     }
 
-    pub fn get_selected_session_path(&self) -> Option<PathBuf> {
-        self.file_picker
-            .get_selected_session()
-            .map(|info| info.path.clone())
+    /// Show the interface switcher with freshly detected adapters
+    pub fn show_interface_switcher(&mut self) {
+        match self.detect_adapters() {
+            Ok(adapters) if adapters.is_empty() => {
+                self.show_error("No wireless adapters detected".to_string())
+            }
+            Ok(adapters) => {
+                let selected = self
+                    .live
+                    .adapter
+                    .as_ref()
+                    .and_then(|cur| adapters.iter().position(|a| a.interface == cur.interface))
+                    .unwrap_or(0);
+                self.popup = Popup::InterfaceSwitch { adapters, selected };
+            }
+            Err(e) => self.show_error(format!("Failed to detect adapters: {}", e)),
+        }
     }
 
-    pub fn show_file_picker(&mut self) {
-        if let Err(e) = self.refresh_adapter_list() {
-            self.show_error(format!("Failed to list adapters: {}", e));
+    /// Switch to `adapter`, asking what to do if it already has saved sessions
+    pub fn request_switch(&mut self, adapter: Adapter) {
+        self.popup = Popup::None;
+        if self.live.adapter.as_ref().map(|a| &a.interface) == Some(&adapter.interface)
+            && !self.live.adapter_missing
+        {
             return;
         }
-        self.popup = Popup::FilePicker;
+
+        let existing = list_sessions_in_dir(&self.paths.sessions_dir.join(adapter.dir_name()))
+            .ok()
+            .and_then(|sessions| sessions.into_iter().next());
+        if let Some(existing) = existing {
+            self.popup = Popup::AdapterCollision {
+                adapter,
+                existing,
+                selected: 0,
+            };
+        } else if let Err(e) = self.switch_adapter(adapter) {
+            self.show_error(format!("Failed to switch adapter: {}", e));
+        }
     }
 
-    /// Enter selected adapter directory in file picker
-    pub fn file_picker_enter_adapter(&mut self) -> Result<()> {
-        if let Some(adapter) = self.file_picker.get_selected_adapter().cloned() {
-            let sessions = list_session_infos_in_dir(&adapter.path)?;
-            self.file_picker.enter_adapter(&adapter, sessions);
+    /// Resolve an adapter collision: 0 = overwrite the latest saved session,
+    /// 1 = append to it, 2 = start a new session file
+    pub fn resolve_collision(
+        &mut self,
+        adapter: Adapter,
+        existing: PathBuf,
+        choice: usize,
+    ) -> Result<()> {
+        self.popup = Popup::None;
+        match choice {
+            0 => {
+                self.switch_adapter(adapter)?;
+                self.session_path = Some(existing);
+            }
+            1 => {
+                let mut session = load_session(&existing)?;
+                self.switch_adapter(adapter)?;
+                if let Some(live) = &mut self.live.adapter {
+                    live.label = session.adapter.label.clone();
+                    session.adapter = live.clone();
+                }
+                self.current_session = Some(Arc::new(session));
+                self.session_path = Some(existing);
+            }
+            _ => self.switch_adapter(adapter)?,
+        }
+        Ok(())
+    }
+
+    /// Save the running session (if modified) and start a new one on `adapter`
+    pub fn switch_adapter(&mut self, adapter: Adapter) -> Result<()> {
+        if self.session_modified {
+            self.save_current_session()?;
+        }
+        self.scan_receiver = None;
+        self.live.scanning = false;
+        self.live.adapter_missing = false;
+        self.live.last_scan_error = None;
+        self.live.scan_failures = 0;
+        self.live.access_points.clear();
+        self.live.ap_list_state.selected = 0;
+        self.live.ap_list_state.offset = 0;
+        self.last_scan = None;
+        self.session_path = None;
+        self.set_adapter(adapter);
+        Ok(())
+    }
+
+    /// Track terminal focus; auto-scan is suspended while unfocused
+    pub fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+        self.live.suspended = !focused;
+        self.needs_redraw = true;
+    }
+
+    /// Track the terminal size so key handlers agree with the layout
+    pub fn resize(&mut self, width: u16, height: u16) {
+        self.terminal_size = (width, height);
+        self.compare.ensure_session_visible(self.compare_list_height());
+        self.needs_redraw = true;
+    }
+
+    /// Problems needing attention now: possible evil twins, a failing scan and a
+    /// missing adapter
+    pub fn alert_count(&self) -> usize {
+        self.live.twin_suspects.len()
+            + usize::from(self.live.last_scan_error.is_some())
+            + usize::from(self.live.adapter_missing)
+    }
+
+    /// Rows the Compare session list gets at the current terminal size
+    pub fn compare_list_height(&self) -> usize {
+        // One row each goes to the tab bar and the status bar
+        compare::session_list_height(self.terminal_size.1.saturating_sub(2)) as usize
+    }
+
+    /// Rows PgUp/PgDn move a list selection by: roughly the list rows left once the
+    /// tab and status bars, headers and footer have their share of the terminal
+    pub fn page_rows(&self) -> usize {
+        (self.terminal_size.1 as usize).saturating_sub(11).max(1)
+    }
+
+    pub fn perform_scan(&mut self) {
+        // Replaying, a manual scan skips ahead to the next recorded one
+        if let Some(replay) = &mut self.replay {
+            let scan = replay.skip();
+            let (done, total) = replay.progress();
+            self.live.replay = Some((done, total, replay.speed));
+            if let Some(scan) = scan {
+                self.record_scan(scan);
+            }
+            return;
+        }
+
+        // Don't start a new scan if one is already in progress, or disturb a connect or
+        // load test
+        if self.live.scanning
+            || self.live.adapter_missing
+            || self.live.connect_testing
+            || self.live.load_testing
+            || self.live.resetting
+        {
+            return;
+        }
+
+        let Some(adapter) = &self.live.adapter else {
+            return;
+        };
+
+        // Synchronized scans all wait for one trigger, so none may still be running
+        let sync = self.config.sync_scans && !self.nodes.is_empty();
+        if sync && self.nodes.iter().any(|n| n.receiver.is_some()) {
+            return;
+        }
+        let trigger = sync.then(|| Utc::now() + chrono::Duration::milliseconds(SYNC_LEAD_MS));
+
+        self.live.scanning = true;
+        self.scan_started = Some(Instant::now());
+        let probe_ssids = &self.live.probe_ssids;
+        let interface = &adapter.interface;
+        self.scan_receiver =
+            Some(spawn_scan(self.backend, interface, probe_ssids, self.gpsd.clone(), trigger));
+
+        // Nodes start together so their scans cover the same moment; one still
+        // running from last time is left to finish
+        for node in self.nodes.iter_mut().filter(|n| n.receiver.is_none()) {
+            let interface = &node.adapter.interface;
+            node.receiver = Some(spawn_scan(node.backend, interface, probe_ssids, None, trigger));
+            node.scan_started = Some(Instant::now());
+        }
+    }
+
+    /// Start the Live adapter's and the nodes' scans from one shared trigger, or go
+    /// back to starting each as soon as it's free
+    pub fn toggle_sync_scans(&mut self) {
+        if self.nodes.is_empty() {
+            let message = "Synchronized scans need a second adapter (--with or --remote)";
+            self.toast = Some((message.to_string(), Instant::now()));
+            return;
+        }
+        let sync = !self.config.sync_scans;
+        let message = format!("Synchronized scans {}", if sync { "on" } else { "off" });
+        self.edit_config(message, |app| {
+            app.config.sync_scans = sync;
+            app.live.sync = None;
+        });
+    }
+
+    /// Keep the slowest start of the latest synchronized trigger for the Live header
+    fn note_trigger(&mut self, trigger: Option<ScanTrigger>) {
+        let Some(trigger) = trigger else {
+            return;
+        };
+        match &mut self.live.sync {
+            Some(last) if last.at == trigger.at => {
+                last.latency_ms = last.latency_ms.max(trigger.latency_ms);
+            }
+            Some(last) if last.at > trigger.at => {}
+            _ => self.live.sync = Some(trigger),
+        }
+    }
+
+    /// Collect finished node scans into their sessions
+    fn poll_nodes(&mut self) {
+        let mut finished = Vec::new();
+        for (idx, node) in self.nodes.iter_mut().enumerate() {
+            let Some(receiver) = &node.receiver else {
+                continue;
+            };
+            let result = match receiver.try_recv() {
+                Ok(result) => result,
+                Err(mpsc::TryRecvError::Empty) => {
+                    if node.scan_started.is_none_or(|t| t.elapsed() < SCAN_TIMEOUT) {
+                        continue;
+                    }
+                    Err(ScanError::Timeout)
+                }
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    Err(ScanError::Other("Scan thread crashed".to_string()))
+                }
+            };
+            node.receiver = None;
+            node.scan_started = None;
+            finished.push((idx, result));
+        }
+        if finished.is_empty() {
+            return;
+        }
+
+        for (idx, result) in finished {
+            match result {
+                Ok(mut scan) => {
+                    scan.access_points
+                        .retain(|ap| self.exclusion_policy(ap) != Some(ExclusionPolicy::Drop));
+                    self.note_trigger(scan.trigger);
+                    let node = &mut self.nodes[idx];
+                    // Local adapters (`--with`) share this machine's conditions
+                    if self.config.host_context && node.backend.node().is_none() {
+                        scan.host = host_context();
+                    }
+                    node.session.add_scan(scan);
+                    node.last_error = None;
+                    self.session_modified = true;
+                }
+                Err(e) => {
+                    let node = &mut self.nodes[idx];
+                    let name = node.session.node.as_deref().unwrap_or_default();
+                    log::warn!("scan on node {} failed: {}", name, e);
+                    node.last_error = Some(e);
+                }
+            }
+        }
+        self.update_node_status();
+        self.needs_redraw = true;
+    }
+
+    /// Show a finished scan and add it to the session
+    fn record_scan(&mut self, mut result: ScanResult) {
+        result
+            .access_points
+            .retain(|ap| self.exclusion_policy(ap) != Some(ExclusionPolicy::Drop));
+        self.live.access_points = result
+            .access_points
+            .iter()
+            .filter(|ap| self.exclusion_policy(ap).is_none())
+            .cloned()
+            .collect();
+        self.live.noise_floor = result
+            .channel_dwell
+            .iter()
+            .filter_map(|d| Some((d.frequency_mhz, d.noise_dbm?)))
+            .collect();
+        self.live.last_scan_error = None;
+        self.live.scan_failures = 0;
+        self.note_trigger(result.trigger);
+        // A replayed scan keeps the conditions it was recorded in; a remote one was taken
+        // on another machine
+        if self.config.host_context && self.replay.is_none() && self.backend.node().is_none() {
+            result.host = host_context();
+        }
+        self.check_twins();
+
+        // Add to session
+        let mut stream_error = None;
+        if let Some(session) = self.current_session.as_mut().map(Arc::make_mut) {
+            if let Some(stream) = &mut self.stream {
+                if let Err(e) = stream.append(&session.adapter, &result) {
+                    stream_error = Some(format!(
+                        "Streaming to {} stopped: {:#}",
+                        stream.path.display(),
+                        e
+                    ));
+                }
+            }
+            let timestamp = result.timestamp;
+            session.add_scan(result);
+            if let Some(checkpoint) = self.walk.as_mut().and_then(|w| w.scan(timestamp)) {
+                // The nodes walk along with the Live adapter, so the checkpoint is theirs too
+                for node in &mut self.nodes {
+                    node.session.checkpoints.push(checkpoint.clone());
+                }
+                session.checkpoints.push(checkpoint);
+            }
+            // A replay is already saved; don't ask to save it again
+            self.session_modified = self.replay.is_none();
+        }
+        if let Some(message) = stream_error {
+            self.stream = None;
+            self.show_error(message);
+        }
+        match &self.walk {
+            Some(walk) if walk.is_done() => self.finish_walk(),
+            // Bring the prompt back if an error replaced it
+            Some(_) if matches!(self.popup, Popup::None) => self.popup = Popup::Walk { selected: 0 },
+            _ => {}
+        }
+        if let Err(e) = self.spill_old_scans() {
+            self.spill_failed = true;
+            self.show_error(format!("Keeping all scans in memory: {:#}", e));
+        }
+
+        self.last_scan = Some(Instant::now());
+        self.needs_redraw = true;
+    }
+
+    /// Alert once per session about each new possible evil twin of the pinned SSID
+    fn check_twins(&mut self) {
+        self.live.update_twin_suspects();
+        let ssid = self.live.pinned_ssid.clone().unwrap_or_default();
+        for suspect in &self.live.twin_suspects {
+            if !self.alerted_twins.insert(suspect.bssid.clone()) {
+                continue;
+            }
+            let message =
+                format!("Possible evil twin of {}: {} ({})", ssid, suspect.bssid, suspect.reason);
+            log::warn!("{}", message);
+            let _ = crate::utils::beep();
+            if let Some(session) = self.current_session.as_mut().map(Arc::make_mut) {
+                session.log_event(message);
+            }
+        }
+    }
+
+    /// Move the oldest scans to the spill file once the session holds more than
+    /// `live_scan_limit`
+    fn spill_old_scans(&mut self) -> Result<()> {
+        let limit = self.config.live_scan_limit;
+        let Some(session) = self.current_session.as_mut().map(Arc::make_mut) else {
+            return Ok(());
+        };
+        if limit == 0 || self.spill_failed || session.scans.len() <= limit {
+            return Ok(());
+        }
+        let spill = match &mut self.spill {
+            Some(spill) => spill,
+            None => {
+                // The spill file sits beside the session file, so pick that now
+                let path = match &self.session_path {
+                    Some(path) => path.clone(),
+                    None => new_session_path(&self.paths, session)?,
+                };
+                self.session_path = Some(path.clone());
+                let spill = Spill::create(&path)?;
+                log::info!("spilling scans beyond {} to {}", limit, spill.path.display());
+                self.spill.insert(spill)
+            }
+        };
+        let excess = session.scans.len() - limit;
+        spill.append(&session.scans[..excess])?;
+        session.scans.drain(..excess);
+        Ok(())
+    }
+
+    /// Scans of the current session that were moved to disk: the file and how many
+    fn spilled(&self) -> Option<(PathBuf, usize)> {
+        self.spill.as_ref().map(|spill| (spill.path.clone(), spill.count))
+    }
+
+    /// Delete every spill file; the saved sessions already hold their scans
+    pub fn remove_spills(&mut self) {
+        let current = self.spill.take().map(|spill| spill.path);
+        for path in self.old_spills.drain(..).chain(current) {
+            spill::remove_spill(&path);
+        }
+    }
+
+    /// Whether scans come from real adapters (not `--demo` or a replay)
+    fn uses_hardware(&self) -> bool {
+        self.backend.is_hardware() && self.replay.is_none()
+    }
+
+    /// Whether scans go through sudo, so its credentials need to stay cached
+    fn uses_sudo(&self) -> bool {
+        self.uses_hardware() && self.backend.needs_privilege() && privilege() == Privilege::Sudo
+    }
+
+    /// Start feeding a recorded session into the Live screen
+    pub fn start_replay(&mut self, session: Session, speed: f64) {
+        self.live.auto_scan = false;
+        self.set_adapter(session.adapter.clone());
+        if let Some(current) = self.current_session.as_mut().map(Arc::make_mut) {
+            current.started_at = session.started_at;
+            current.duration_target_secs = session.duration_target_secs;
+        }
+        self.live.timer_target_secs = session.duration_target_secs.or(self.live.timer_target_secs);
+        self.replay = Some(Replay::new(&session, speed));
+        self.live.replay = self.replay.as_ref().map(|r| (0, r.progress().1, r.speed));
+    }
+
+    /// Record a failed scan; auto-scan decides when to retry from the error kind
+    fn scan_failed(&mut self, error: ScanError) {
+        log::warn!("scan failed ({} in a row): {}", self.live.scan_failures + 1, error);
+        // A reset needs the same privilege, so it can't help with permission errors
+        let resettable = !matches!(error, ScanError::PermissionDenied(_));
+        self.live.last_scan_error = Some(error);
+        self.live.scan_failures += 1;
+        self.live.scanning = false;
+        self.scan_receiver = None;
+        self.scan_started = None;
+        self.last_scan = Some(Instant::now());
+        self.needs_redraw = true;
+
+        let limit = self.config.reset_after_failures;
+        if resettable && limit > 0 && self.live.scan_failures == limit {
+            if self.config.auto_reset {
+                self.reset_interface();
+            } else if self.popup == Popup::None {
+                if let Some(adapter) = &self.live.adapter {
+                    self.popup = Popup::ResetInterface {
+                        interface: adapter.interface.clone(),
+                        failures: limit,
+                        selected: 0,
+                    };
+                }
+            }
+        }
+
+        // A failing first scan usually means a setup problem, so show what the doctor finds
+        let first = self.current_session.as_ref().is_some_and(|s| s.scans.is_empty())
+            && self.live.scan_failures == 1;
+        if first && self.uses_hardware() && self.popup == Popup::None {
+            let problems: Vec<Check> = doctor::run_checks(self.backend, &self.paths)
+                .into_iter()
+                .filter(|check| check.status != doctor::Status::Ok)
+                .collect();
+            if !problems.is_empty() {
+                self.show_error(format!(
+                    "First scan failed. `wificomp doctor` found:\n\n{}",
+                    doctor::report(&problems)
+                ));
+            }
+        }
+    }
+
+    /// Time joining the configured network on the Live adapter, in the background
+    pub fn start_connect_test(&mut self) {
+        let (Some(adapter), Some(network)) = (&self.live.adapter, &self.live.connect_network)
+        else {
+            return;
+        };
+        if self.live.connect_testing || self.live.load_testing || self.replay.is_some() {
+            return;
+        }
+        let (tx, rx) = mpsc::channel();
+        let backend = self.backend;
+        let interface = adapter.interface.clone();
+        let network = network.clone();
+        self.toast = Some((format!("Connecting {} to {}...", interface, network), Instant::now()));
+        thread::spawn(move || {
+            let _ = tx.send(backend.connect_test(&interface, &network));
+        });
+        self.connect_receiver = Some(rx);
+        self.live.connect_testing = true;
+        self.needs_redraw = true;
+    }
+
+    /// Store a finished connect test in the session
+    fn poll_connect_test(&mut self) {
+        let Some(result) = self.connect_receiver.as_ref().and_then(|r| r.try_recv().ok()) else {
+            return;
+        };
+        self.connect_receiver = None;
+        self.live.connect_testing = false;
+        match result {
+            Ok(timing) => {
+                let mut message = format!(
+                    "Joined {} in {} ms (assoc {}, 4-way {}, DHCP {})",
+                    timing.network,
+                    timing.total_ms(),
+                    timing.association_ms,
+                    timing.handshake_ms,
+                    timing.dhcp_ms
+                );
+                if let Some(reachability) = timing.reachability {
+                    message.push_str(&format!(", {}", reachability.name()));
+                }
+                log::info!("{}", message);
+                if let Some(session) = self.current_session.as_mut().map(Arc::make_mut) {
+                    session.connect_tests.push(timing);
+                    self.session_modified = true;
+                }
+                self.toast = Some((message, Instant::now()));
+            }
+            Err(e) => {
+                let message = format!("Connect test failed: {:#}", e);
+                if let Some(session) = self.current_session.as_mut().map(Arc::make_mut) {
+                    session.log_event(message.clone());
+                    self.session_modified = true;
+                }
+                self.show_error(message);
+            }
+        }
+        self.needs_redraw = true;
+    }
+
+    /// Ping the configured target from the Live adapter under a UDP load, in the background
+    pub fn start_load_test(&mut self) {
+        let (Some(adapter), Some(target)) = (&self.live.adapter, &self.live.load_target) else {
+            return;
+        };
+        if self.live.load_testing || self.live.connect_testing || self.replay.is_some() {
+            return;
+        }
+        let (tx, rx) = mpsc::channel();
+        let backend = self.backend;
+        let interface = adapter.interface.clone();
+        let target = target.clone();
+        let (secs, mbps) = (self.config.load_test_secs.max(1), self.config.load_test_mbps);
+        self.toast = Some((
+            format!("Loading {} with {} Mbit/s for {}s...", interface, mbps, secs),
+            Instant::now(),
+        ));
+        thread::spawn(move || {
+            let _ = tx.send(backend.load_test(&interface, &target, secs, mbps));
+        });
+        self.load_receiver = Some(rx);
+        self.live.load_testing = true;
+        self.needs_redraw = true;
+    }
+
+    /// Store a finished load test in the session
+    fn poll_load_test(&mut self) {
+        let Some(result) = self.load_receiver.as_ref().and_then(|r| r.try_recv().ok()) else {
+            return;
+        };
+        self.load_receiver = None;
+        self.live.load_testing = false;
+        match result {
+            Ok(test) => {
+                let rtt = match (test.rtt_p50_ms, test.rtt_p95_ms, test.rtt_p99_ms) {
+                    (Some(p50), Some(p95), Some(p99)) => {
+                        format!("RTT p50 {:.1} / p95 {:.1} / p99 {:.1} ms", p50, p95, p99)
+                    }
+                    _ => "no replies".to_string(),
+                };
+                let message = format!(
+                    "{} Mbit/s to {}: {:.1}% loss, {}",
+                    test.load_mbps,
+                    test.target,
+                    test.loss_pct(),
+                    rtt
+                );
+                log::info!("{}", message);
+                if let Some(session) = self.current_session.as_mut().map(Arc::make_mut) {
+                    session.load_tests.push(test);
+                    self.session_modified = true;
+                }
+                self.toast = Some((message, Instant::now()));
+            }
+            Err(e) => {
+                let message = format!("Load test failed: {:#}", e);
+                if let Some(session) = self.current_session.as_mut().map(Arc::make_mut) {
+                    session.log_event(message.clone());
+                    self.session_modified = true;
+                }
+                self.show_error(message);
+            }
+        }
+        self.needs_redraw = true;
+    }
+
+    /// Bounce the scanning interface in the background; usbreset can take seconds
+    pub fn reset_interface(&mut self) {
+        let Some(adapter) = &self.live.adapter else {
+            return;
+        };
+        if self.live.resetting {
+            return;
+        }
+        let (tx, rx) = mpsc::channel();
+        let backend = self.backend;
+        let interface = adapter.interface.clone();
+        self.toast = Some((format!("Resetting {}...", interface), Instant::now()));
+        thread::spawn(move || {
+            let result = backend.reset_interface(&interface);
+            let _ = tx.send((interface, result));
+        });
+        self.reset_receiver = Some(rx);
+        self.live.resetting = true;
+        self.needs_redraw = true;
+    }
+
+    /// Log a finished reset into the session
+    fn poll_reset(&mut self) {
+        let Some((interface, result)) =
+            self.reset_receiver.as_ref().and_then(|r| r.try_recv().ok())
+        else {
+            return;
+        };
+        self.reset_receiver = None;
+        self.live.resetting = false;
+        let failures = self.live.scan_failures;
+
+        let message = match &result {
+            Ok(method) => format!(
+                "Reset {} ({}) after {} failed scans",
+                interface,
+                method.name(),
+                failures
+            ),
+            Err(e) => format!(
+                "Resetting {} after {} failed scans failed: {:#}",
+                interface, failures, e
+            ),
+        };
+        log::info!("{}", message);
+        if let Some(session) = self.current_session.as_mut().map(Arc::make_mut) {
+            session.log_event(message.clone());
+            self.session_modified = true;
+        }
+
+        if result.is_ok() {
+            self.live.last_scan_error = None;
+            self.live.scan_failures = 0;
+            self.last_scan = None;
+            self.toast = Some((message, Instant::now()));
+        } else {
+            self.show_error(message);
+        }
+        self.needs_redraw = true;
+    }
+
+    /// Summarize the current session and pick the file it saves to
+    fn prepare_save(&mut self) -> Result<(PathBuf, &Arc<Session>)> {
+        let session = self
+            .current_session
+            .as_mut()
+            .map(Arc::make_mut)
+            .ok_or_else(|| anyhow::anyhow!("No session to save"))?;
+        let summary = session.summarize(self.config.alert_threshold_dbm);
+        session.summary = Some(summary.clone());
+        let node = session.node.clone();
+
+        let path = match &self.session_path {
+            Some(path) => path.clone(),
+            None => new_session_path(&self.paths, session)?,
+        };
+        self.session_path = Some(path.clone());
+        self.session_modified = false;
+        self.send_webhook("saved", summary, node, Some(path.clone()));
+        let session = self.current_session.as_ref().context("No session to save")?;
+        Ok((path, session))
+    }
+
+    /// Post a finished session's summary to the configured webhook, if any
+    fn send_webhook(
+        &mut self,
+        event: &'static str,
+        summary: SessionSummary,
+        node: Option<String>,
+        path: Option<PathBuf>,
+    ) {
+        let Some(url) = self.config.webhook_url.clone() else {
+            return;
+        };
+        let label = path.clone().unwrap_or_else(|| PathBuf::from(&summary.adapter));
+        self.spawn_job(JobKind::Webhook, label, move || {
+            WebhookPayload::new(event, &summary, path.as_deref(), node.as_deref()).post(&url)
+        });
+    }
+
+    pub fn save_current_session(&mut self) -> Result<PathBuf> {
+        let spilled = self.spilled();
+        let (path, session) = self.prepare_save()?;
+        let mut scans = session.scans.len();
+        match &spilled {
+            Some((spill, count)) => {
+                spill::save_spilled_session(&path, session, spill, *count)?;
+                scans += count;
+            }
+            None => {
+                save_session_to(&path, session)?;
+            }
+        }
+        log::info!("saved {} scans to {}", scans, path.display());
+        for (node_path, node_session) in self.prepare_node_saves()? {
+            save_session_to(&node_path, &node_session)?;
+        }
+        Ok(path)
+    }
+
+    /// Like `save_current_session`, but writes on a worker thread; the path is known up front
+    pub fn save_current_session_in_background(&mut self) -> Result<PathBuf> {
+        let spilled = self.spilled();
+        let (path, session) = self.prepare_save()?;
+        // Shared with the worker; the next scan copies it only if the write is still running
+        let session = Arc::clone(session);
+        let target = path.clone();
+        self.spawn_job(JobKind::Save, path.clone(), move || {
+            match spilled {
+                Some((spill, count)) => {
+                    spill::save_spilled_session(&target, &session, &spill, count)
+                }
+                None => save_session_to(&target, &session),
+            }
+            .map(|_| ())
+        });
+        for (node_path, node_session) in self.prepare_node_saves()? {
+            let target = node_path.clone();
+            self.spawn_job(JobKind::Save, node_path, move || {
+                save_session_to(&target, &node_session).map(|_| ())
+            });
+        }
+        Ok(path)
+    }
+
+    /// Summarize each node's session and pick the file it saves to
+    fn prepare_node_saves(&mut self) -> Result<Vec<(PathBuf, Session)>> {
+        let threshold = self.config.alert_threshold_dbm;
+        let mut saves = Vec::new();
+        for node in &mut self.nodes {
+            node.session.summary = Some(node.session.summarize(threshold));
+            let path = match &node.path {
+                Some(path) => path.clone(),
+                None => new_session_path(&self.paths, &node.session)?,
+            };
+            node.path = Some(path.clone());
+            saves.push((path, node.session.clone()));
+        }
+        Ok(saves)
+    }
+
+    /// Run a file write on a worker thread, reporting through the status toast
+    fn spawn_job(
+        &mut self,
+        kind: JobKind,
+        path: PathBuf,
+        write: impl FnOnce() -> Result<()> + Send + 'static,
+    ) {
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let _ = tx.send(write());
+        });
+        self.jobs.push(Job {
+            kind,
+            path,
+            receiver: rx,
+        });
+        self.needs_redraw = true;
+    }
+
+    /// Collect finished background writes
+    fn poll_jobs(&mut self) {
+        let mut finished = Vec::new();
+        self.jobs.retain(|job| match job.receiver.try_recv() {
+            Ok(result) => {
+                finished.push((job.kind, job.path.clone(), result));
+                false
+            }
+            Err(mpsc::TryRecvError::Empty) => true,
+            Err(mpsc::TryRecvError::Disconnected) => {
+                let error = anyhow::anyhow!("worker thread crashed");
+                finished.push((job.kind, job.path.clone(), Err(error)));
+                false
+            }
+        });
+
+        for (kind, path, result) in finished {
+            match result {
+                Ok(()) => {
+                    log::info!("{} {}", kind.done(), path.display());
+                    let message = format!("{} {}", kind.done(), path.display());
+                    self.toast = Some((message, Instant::now()));
+                }
+                Err(e) => {
+                    log::error!("{} for {}: {:#}", kind.failed(), path.display(), e);
+                    if kind == JobKind::Save {
+                        self.session_modified = true;
+                    }
+                    self.show_error(format!("{}: {:#}", kind.failed(), e));
+                }
+            }
+        }
+
+        // Keep the spinner turning and clear the toast once it has been read
+        if !self.jobs.is_empty() {
+            self.needs_redraw = true;
+        }
+        if self.toast.as_ref().is_some_and(|(_, at)| at.elapsed() >= TOAST_DURATION) {
+            self.toast = None;
+            self.needs_redraw = true;
+        }
+    }
+
+    /// Running background writes, oldest first
+    pub fn running_jobs(&self) -> Vec<JobKind> {
+        self.jobs.iter().map(|job| job.kind).collect()
+    }
+
+    /// Block until every background write has finished, so quitting never truncates a file
+    pub fn wait_for_jobs(&mut self) {
+        for job in self.jobs.drain(..) {
+            match job.receiver.recv() {
+                Ok(Ok(())) => log::info!("{} {}", job.kind.done(), job.path.display()),
+                Ok(Err(e)) => {
+                    eprintln!("Warning: {} for {}: {:#}", job.kind.failed(), job.path.display(), e)
+                }
+                Err(_) => {}
+            }
+        }
+    }
+
+    pub fn load_session_file(&mut self, path: &Path) -> Result<()> {
+        let (session, validation) = load_session_validated(path)?;
+
+        // Show warning if session has issues but still load it
+        if !validation.warnings.is_empty() {
+            let warning_msg = format!(
+                "Session loaded with warnings:\n{}",
+                validation.warnings.join("\n")
+            );
+            // We'll show this after loading
+            self.popup = Popup::SessionWarning {
+                message: warning_msg,
+                path: path.to_path_buf(),
+                scroll: 0,
+            };
+        }
+
+        match self.screen {
+            Screen::History => {
+                self.set_history_session(Arc::new(session));
+            }
+            Screen::Compare => {
+                // A coordinator run is compared as a unit: bring in the other nodes' sessions
+                if let Some(run_id) = session.run_id.clone() {
+                    let (tx, rx) = mpsc::channel();
+                    let (paths, path) = (self.paths.clone(), path.to_path_buf());
+                    thread::spawn(move || {
+                        let _ = tx.send(find_run_sessions(&paths, &run_id, &path));
+                    });
+                    self.run_receiver = Some(rx);
+                }
+                self.compare.add_session(Arc::new(session));
+                // Ensure visibility after adding
+                let len = self.compare.sessions.len();
+                self.compare.selected_session_idx = len.saturating_sub(1);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Add the other sessions of a run once they've been found
+    fn poll_run_sessions(&mut self) {
+        let Some(siblings) = self.run_receiver.as_ref().and_then(|r| r.try_recv().ok()) else {
+            return;
+        };
+        self.run_receiver = None;
+        for sibling in siblings {
+            // Local adapters scanned alongside (`--with`) share the node, and the turns
+            // of an A/B test the node and often the interface too
+            let loaded = self.compare.sessions.iter().any(|s| {
+                s.run_id == sibling.run_id
+                    && s.node == sibling.node
+                    && s.adapter.interface == sibling.adapter.interface
+                    && s.started_at == sibling.started_at
+            });
+            if !loaded {
+                self.compare.add_session(Arc::new(sibling));
+            }
+        }
+        let len = self.compare.sessions.len();
+        self.compare.selected_session_idx = len.saturating_sub(1);
+        self.needs_redraw = true;
+    }
+
+    pub fn refresh_adapter_list(&mut self) -> Result<()> {
+        let adapters = list_adapter_dirs(&self.paths)?;
+        self.file_picker.set_adapters(adapters);
+        Ok(())
+    }
+
+    pub fn get_selected_session_path(&self) -> Option<PathBuf> {
+        self.file_picker
+            .get_selected_session()
+            .map(|info| info.path.clone())
+    }
+
+    pub fn show_file_picker(&mut self) {
+        if let Err(e) = self.refresh_adapter_list() {
+            self.show_error(format!("Failed to list adapters: {}", e));
+            return;
+        }
+        self.popup = Popup::FilePicker;
+    }
+
+    /// Enter selected adapter directory in file picker
+    pub fn file_picker_enter_adapter(&mut self) -> Result<()> {
+        if let Some(adapter) = self.file_picker.get_selected_adapter().cloned() {
+            let sessions = list_session_infos_in_dir(&adapter.path)?;
+            self.file_picker.enter_adapter(&adapter, sessions);
         }
         Ok(())
     }
 
     /// Go back to adapter list in file picker
     pub fn file_picker_go_back(&mut self) -> Result<()> {
-        let adapters = list_adapter_dirs()?;
+        let adapters = list_adapter_dirs(&self.paths)?;
         self.file_picker.go_back(adapters);
         Ok(())
     }
 
     pub fn show_error(&mut self, message: String) {
-        self.popup = Popup::Error { message };
+        self.popup = Popup::Error { message, scroll: 0 };
+    }
+
+    /// Detail of the selected live AP, flagging a country that differs from the adapter's
+    pub fn show_ap_detail(&mut self) {
+        let Some(ap) = self.live.get_selected_ap() else {
+            return;
+        };
+        let client_country = self
+            .current_session
+            .as_ref()
+            .and_then(|s| s.radio.as_ref())
+            .and_then(|r| r.country.as_deref());
+        let mut message = ap_detail_text(ap, client_country);
+        if let Some(suspect) = self.live.twin_suspects.iter().find(|s| s.bssid == ap.bssid) {
+            message.push_str(&format!("\n⚠ Possible evil twin: {}", suspect.reason));
+        }
+        self.popup = Popup::ApDetail { message, scroll: 0 };
     }
 
     pub fn show_rename_popup(&mut self) {
@@ -315,28 +2023,308 @@ impl App {
             .unwrap_or_default();
         self.popup = Popup::RenameAdapter {
             input: current.clone(),
-            cursor: current.len(),
+            cursor: current.chars().count(),
+        };
+    }
+
+    pub fn show_timer_popup(&mut self) {
+        let current = self
+            .live
+            .timer_target_secs
+            .map(|s| (s / 60).to_string())
+            .unwrap_or_default();
+        self.popup = Popup::TimerSetup {
+            input: current.clone(),
+            cursor: current.chars().count(),
+        };
+    }
+
+    pub fn show_search_popup(&mut self) {
+        self.popup = Popup::Search { input: String::new(), cursor: 0 };
+    }
+
+    pub fn show_command_popup(&mut self) {
+        self.popup = Popup::Command { input: String::new(), cursor: 0 };
+    }
+
+    /// Select the next AP after the current one whose SSID or BSSID contains `query`,
+    /// wrapping around; an empty query repeats the last search
+    pub fn search(&mut self, query: String) {
+        self.popup = Popup::None;
+        let query = match (query.is_empty(), &self.last_search) {
+            (false, _) => query,
+            (true, Some(last)) => last.clone(),
+            (true, None) => return,
+        };
+        let (aps, selected) = match self.screen {
+            Screen::Live => {
+                let rows = self.live.ap_list().rows();
+                let aps = rows.iter().map(|row| (row.ap().bssid.clone(), row.ap().ssid.clone()));
+                (aps.collect(), self.live.ap_list_state.selected)
+            }
+            Screen::History => {
+                let aps = self.history.session.as_ref().map(|s| s.unique_aps());
+                (aps.unwrap_or_default(), self.history.selected_ap_idx)
+            }
+            Screen::Compare => (self.compare.all_aps(), self.compare.selected_ap_idx),
+            Screen::Channels => {
+                self.toast = Some(("Search works on AP lists".to_string(), Instant::now()));
+                return;
+            }
+        };
+        match find_ap(&aps, selected + 1, &query) {
+            Some(found) => match self.screen {
+                Screen::Live => self.live.ap_list_state.selected = found,
+                Screen::History => self.history.selected_ap_idx = found,
+                Screen::Compare => self.compare.selected_ap_idx = found,
+                Screen::Channels => {}
+            },
+            None => {
+                self.toast = Some((format!("No AP matches \"{}\"", query), Instant::now()));
+            }
+        }
+        self.last_search = Some(query);
+    }
+
+    pub fn apply_rename(&mut self, name: String) {
+        self.popup = Popup::None;
+        let Some(key) = self.live.adapter.as_ref().map(Adapter::label_key) else {
+            return;
+        };
+        let label = if name.is_empty() { None } else { Some(name) };
+        let message = match &label {
+            Some(label) => format!("Renamed adapter to {}", label),
+            None => "Adapter label cleared".to_string(),
+        };
+        self.edit_config(message, |app| {
+            match &label {
+                Some(label) => app.config.adapter_labels.insert(key, label.clone()),
+                None => app.config.adapter_labels.remove(&key),
+            };
+            app.set_adapter_label(label);
+        });
+    }
+
+    /// Label the live adapter and its running session
+    fn set_adapter_label(&mut self, label: Option<String>) {
+        if let Some(adapter) = &mut self.live.adapter {
+            adapter.label = label.clone();
+        }
+        if let Some(session) = self.current_session.as_mut().map(Arc::make_mut) {
+            session.adapter.label = label;
+        }
+    }
+
+    /// Edit `setup`, starting on its first field
+    pub fn show_setup_popup(&mut self, setup: SessionSetup) {
+        let values = setup.values();
+        let cursor = values[0].chars().count();
+        self.popup = Popup::SessionSetup { values, field: 0, cursor };
+    }
+
+    /// Edit the current session's setup notes
+    pub fn edit_setup(&mut self) {
+        match &self.current_session {
+            Some(session) => self.show_setup_popup(session.setup.clone().unwrap_or_default()),
+            None => self.show_error("No session to describe".to_string()),
+        }
+    }
+
+    pub fn show_marker_popup(&mut self) {
+        match &self.current_session {
+            Some(_) => self.popup = Popup::Marker { input: String::new(), cursor: 0 },
+            None => self.show_error("No session to mark".to_string()),
+        }
+    }
+
+    /// Drop a marker with `note` at the current time; an empty note cancels
+    pub fn add_marker(&mut self, note: String) {
+        self.popup = Popup::None;
+        let note = note.trim().to_string();
+        if note.is_empty() {
+            return;
+        }
+        if let Some(session) = self.current_session.as_mut().map(Arc::make_mut) {
+            let marker = SessionMarker { timestamp: Utc::now(), note: note.clone() };
+            for node in &mut self.nodes {
+                node.session.markers.push(marker.clone());
+            }
+            session.markers.push(marker);
+            self.session_modified |= !session.scans.is_empty();
+            self.toast = Some((format!("Marked: {}", note), Instant::now()));
+        }
+    }
+
+    /// Ask for the A/B turn length, or stop the A/B test if one is running
+    pub fn show_alternate_setup(&mut self) {
+        if self.alternation.is_some() {
+            self.stop_alternation();
+            return;
+        }
+        if self.live.adapter.is_none() || self.current_session.is_none() || self.replay.is_some() {
+            self.show_error("No adapter scanning to start an A/B test with".to_string());
+            return;
+        }
+        let input = self.config.alternate_minutes.to_string();
+        self.popup = Popup::AlternateSetup { cursor: input.chars().count(), input };
+    }
+
+    /// Start an A/B test with the Live adapter taking the first turn of `minutes`
+    pub fn start_alternation(&mut self, minutes: String) {
+        self.popup = Popup::None;
+        let minutes = match minutes.trim().parse::<u64>() {
+            Ok(minutes) if minutes > 0 => minutes,
+            _ => return,
         };
+        let (Some(adapter), Some(session)) =
+            (self.live.adapter.clone(), self.current_session.as_mut().map(Arc::make_mut))
+        else {
+            return;
+        };
+        let run_id = session
+            .run_id
+            .get_or_insert_with(|| session.started_at.format("%Y%m%d_%H%M%S").to_string())
+            .clone();
+        self.session_modified |= !session.scans.is_empty();
+        if minutes != self.config.alternate_minutes {
+            let message = format!("A/B turns set to {} min", minutes);
+            self.edit_config(message, |app| app.config.alternate_minutes = minutes);
+        }
+        let turn = Duration::from_secs(minutes * 60);
+        self.alternation = Some(Alternation::new(adapter, turn, run_id));
+        self.live.auto_scan = true;
+    }
+
+    /// The current turn is over: save its session and prompt for the swap. Scans pause
+    /// behind the prompt until the other adapter shows up.
+    fn end_turn(&mut self) {
+        if self.session_modified {
+            if let Err(e) = self.save_current_session_in_background() {
+                self.show_error(format!("Save failed: {:#}", e));
+                return;
+            }
+        }
+        if let Some(alternation) = &mut self.alternation {
+            alternation.end_turn();
+        }
+        let _ = crate::utils::beep();
+        self.popup = Popup::Swap { selected: 0 };
+        self.needs_redraw = true;
+    }
+
+    /// A new adapter appeared during a swap: give it the next turn in a session of its own
+    fn swap_in(&mut self, adapter: Adapter) {
+        let Some(alternation) = &mut self.alternation else {
+            return;
+        };
+        if !alternation.begin_turn(adapter.clone()) {
+            return;
+        }
+        let (turn, run_id) = (alternation.turns.len(), alternation.run_id.clone());
+        if let Err(e) = self.switch_adapter(adapter) {
+            self.show_error(format!("Couldn't switch adapters: {:#}", e));
+            return;
+        }
+        if let Some(session) = self.current_session.as_mut().map(Arc::make_mut) {
+            session.run_id = Some(run_id);
+        }
+        if matches!(self.popup, Popup::Swap { .. }) {
+            self.popup = Popup::None;
+        }
+        self.live.auto_scan = true;
+        let name = self.live.adapter.as_ref().map(Adapter::display_name).unwrap_or_default();
+        self.toast = Some((format!("A/B turn {}: {}", turn, name), Instant::now()));
+    }
+
+    pub fn stop_alternation(&mut self) {
+        if let Some(alternation) = self.alternation.take() {
+            let message = format!("A/B test stopped after {} turns", alternation.turns.len());
+            self.toast = Some((message, Instant::now()));
+        }
+        if matches!(self.popup, Popup::Swap { .. }) {
+            self.popup = Popup::None;
+        }
+    }
+
+    /// Ask for the walk test's checkpoints, offering the last ones used
+    pub fn show_walk_setup(&mut self) {
+        if self.current_session.is_none() {
+            self.show_error("No session to walk".to_string());
+            return;
+        }
+        let input = self.config.walk_checkpoints.join(", ");
+        self.popup = Popup::WalkSetup { cursor: input.chars().count(), input };
     }
 
-    pub fn show_timer_popup(&mut self) {
-        let current = self
-            .live
-            .timer_target_secs
-            .map(|s| (s / 60).to_string())
-            .unwrap_or_default();
-        self.popup = Popup::TimerSetup {
-            input: current.clone(),
-            cursor: current.len(),
+    /// Start a walk test through the comma-separated `checkpoints`, scanning
+    /// automatically so the dwell scans come by themselves
+    pub fn start_walk(&mut self, checkpoints: String) {
+        let checkpoints: Vec<String> = checkpoints
+            .split(',')
+            .map(|c| c.trim().to_string())
+            .filter(|c| !c.is_empty())
+            .collect();
+        if checkpoints.is_empty() {
+            self.popup = Popup::None;
+            return;
+        }
+        if checkpoints != self.config.walk_checkpoints {
+            let message = format!("Saved {} walk checkpoints", checkpoints.len());
+            let saved = checkpoints.clone();
+            self.edit_config(message, |app| app.config.walk_checkpoints = saved);
+        }
+        self.walk = Some(WalkTest::new(checkpoints, self.config.walk_dwell_scans));
+        self.live.auto_scan = true;
+        self.popup = Popup::Walk { selected: 0 };
+    }
+
+    /// At the current checkpoint: mark it on the timeline and start its dwell scans
+    pub fn walk_arrived(&mut self) {
+        let Some(walk) = self.walk.as_mut().filter(|w| w.arrived.is_none()) else {
+            return;
         };
+        let now = Utc::now();
+        walk.arrive(now);
+        let note = walk.current().unwrap_or_default().to_string();
+        if let Some(session) = self.current_session.as_mut().map(Arc::make_mut) {
+            let marker = SessionMarker { timestamp: now, note };
+            for node in &mut self.nodes {
+                node.session.markers.push(marker.clone());
+            }
+            session.markers.push(marker);
+        }
+        self.needs_redraw = true;
     }
 
-    pub fn apply_rename(&mut self, name: String) {
-        if let Some(adapter) = &mut self.live.adapter {
-            adapter.label = if name.is_empty() { None } else { Some(name.clone()) };
+    /// Abandon the walk test, keeping the checkpoints already done
+    pub fn stop_walk(&mut self) {
+        if let Some(walk) = self.walk.take() {
+            let message = format!(
+                "Walk test stopped after {} of {} checkpoints",
+                walk.index,
+                walk.checkpoints.len()
+            );
+            self.toast = Some((message, Instant::now()));
+        }
+        if matches!(self.popup, Popup::Walk { .. }) {
+            self.popup = Popup::None;
+        }
+    }
+
+    fn finish_walk(&mut self) {
+        let count = self.walk.take().map_or(0, |w| w.checkpoints.len());
+        if matches!(self.popup, Popup::Walk { .. }) {
+            self.popup = Popup::None;
         }
-        if let Some(session) = &mut self.current_session {
-            session.adapter.label = if name.is_empty() { None } else { Some(name) };
+        let message = format!("Walk test done: {} checkpoints · Compare [W] to compare them", count);
+        self.toast = Some((message, Instant::now()));
+    }
+
+    pub fn apply_setup(&mut self, values: [String; 5]) {
+        let setup = SessionSetup::from_values(values);
+        if let Some(session) = self.current_session.as_mut().map(Arc::make_mut) {
+            session.setup = (!setup.is_empty()).then_some(setup);
+            self.session_modified |= !session.scans.is_empty();
         }
         self.popup = Popup::None;
     }
@@ -344,53 +2332,305 @@ impl App {
     pub fn apply_timer(&mut self, mins_str: String) {
         if let Ok(mins) = mins_str.parse::<u64>() {
             self.live.timer_target_secs = if mins == 0 { None } else { Some(mins * 60) };
-            if let Some(session) = &mut self.current_session {
+            if let Some(session) = self.current_session.as_mut().map(Arc::make_mut) {
                 session.duration_target_secs = self.live.timer_target_secs;
             }
+            self.timer_fired = self.live.timer_expired();
         }
         self.popup = Popup::None;
     }
 
-    pub fn export_current(&mut self, csv: bool) -> Result<PathBuf> {
+    /// Open the export dialog with the configured anonymization
+    pub fn show_export_choice(&mut self) {
+        let anonymize = self.config.export_anonymize;
+        self.popup = Popup::ExportChoice { selected: 0, anonymize };
+    }
+
+    /// Export the session on screen in the background, returning the file being written
+    pub fn export_current(
+        &mut self,
+        format: ExportFormat,
+        anonymize: Anonymize,
+    ) -> Result<PathBuf> {
         let session = match self.screen {
             Screen::History => self.history.session.as_ref(),
             _ => self.current_session.as_ref(),
         };
 
         let session = session.ok_or_else(|| anyhow::anyhow!("No session to export"))?;
+        // WiGLE maps real networks, so disguised ones would pollute it
+        if format == ExportFormat::Wigle && anonymize != Anonymize::Off {
+            anyhow::bail!("WiGLE exports can't be anonymized");
+        }
+        let offsets = self.config.calibration_offsets.clone().into_iter().collect();
+        let session = Anonymizer::new(anonymize, self.config.anonymize_ssids).session(session);
 
-        let ext = if csv { "csv" } else { "json" };
-        let filename = format!(
-            "wificomp_export_{}.{}",
-            Utc::now().format("%Y%m%d_%H%M%S"),
-            ext
-        );
-        let path = PathBuf::from(&filename);
+        let stamp = Utc::now().format("%Y%m%d_%H%M%S").to_string();
+        let path = PathBuf::from(format.filename(&stamp));
+        let target = path.clone();
+        self.spawn_job(JobKind::Export, path.clone(), move || {
+            format.export(&session, &target)?;
+            let anonymized = anonymize != Anonymize::Off;
+            let mut manifest = ExportManifest::new(&session, format, &target, anonymized, offsets);
+            manifest.kernel = adapter::kernel_version();
+            manifest.write(&ExportManifest::path_for(&target))
+        });
 
-        if csv {
-            export::export_csv(session, &path)?;
-        } else {
-            export::export_json(session, &path)?;
+        Ok(path)
+    }
+
+    /// Save the current view as a text dump, or the selected AP as an SVG chart
+    pub fn snapshot(&mut self, svg: bool) -> Result<PathBuf> {
+        let stamp = Utc::now().format("%Y%m%d_%H%M%S");
+
+        if !svg {
+            let frame = self
+                .last_frame
+                .as_ref()
+                .ok_or_else(|| anyhow::anyhow!("Nothing rendered yet"))?;
+            let path = PathBuf::from(format!("wificomp_screen_{}.txt", stamp));
+            std::fs::write(&path, crate::ui::buffer_text(frame))
+                .context("Failed to write snapshot")?;
+            return Ok(path);
         }
 
+        let (title, series) = self
+            .svg_series()
+            .ok_or_else(|| anyhow::anyhow!("No AP selected"))?;
+        let path = PathBuf::from(format!("wificomp_chart_{}.svg", stamp));
+        export::export_svg(&title, &series, &path)?;
         Ok(path)
     }
 
-    pub fn save_config(&self) -> Result<()> {
+    /// Signal-over-time series for the selected AP on the current screen
+    fn svg_series(&self) -> Option<(String, Vec<SvgSeries>)> {
+        let single = |session: &Session, bssid: &str, ssid: &str| {
+            let series = session_series(session, 0, |ap| ap.bssid == bssid);
+            (format!("{} ({})", ssid, bssid), vec![series])
+        };
+
+        match self.screen {
+            Screen::Live => {
+                let ap = self.live.get_selected_ap()?;
+                Some(single(self.current_session.as_ref()?, &ap.bssid, &ap.ssid))
+            }
+            Screen::History => {
+                let (bssid, ssid) = self.history.get_selected_ap()?;
+                Some(single(self.history.session.as_ref()?, &bssid, &ssid))
+            }
+            Screen::Compare => {
+                let (bssid, ssid) = self.compare.get_selected_ap()?;
+                let match_by = self.compare.match_by;
+                let series = self
+                    .compare
+                    .sessions
+                    .iter()
+                    .map(|session| {
+                        session_series(session, self.compare.offset_for(session), |ap| {
+                            match_by.matches(ap, &bssid, &ssid)
+                        })
+                    })
+                    .collect();
+                Some((format!("{} ({})", ssid, bssid), series))
+            }
+            Screen::Channels => None,
+        }
+    }
+
+    /// List saved profiles to choose from
+    pub fn show_profile_picker(&mut self) {
+        let profiles = Profile::list(&self.paths);
+        if profiles.is_empty() {
+            self.show_error(format!(
+                "No profiles yet.\nSave them as JSON in {}",
+                self.paths.profiles_dir().display()
+            ));
+            return;
+        }
+        let selected = self
+            .profile
+            .as_ref()
+            .and_then(|(name, _)| profiles.iter().position(|p| p == name))
+            .unwrap_or(0);
+        self.popup = Popup::ProfilePicker { profiles, selected };
+    }
+
+    /// Load a saved profile and switch to its settings
+    pub fn apply_profile(&mut self, name: &str) -> Result<()> {
+        let profile = Profile::load(&self.paths, name)?;
+        self.use_profile(name.to_string(), profile);
+        self.toast = Some((format!("Profile {} applied", name), Instant::now()));
+        Ok(())
+    }
+
+    /// Switch the running settings to a profile's
+    pub fn use_profile(&mut self, name: String, profile: Profile) {
+        log::info!("using profile {}", name);
+        let base = self.profile_base.get_or_insert_with(|| self.config.clone());
+        // Fields only the previous profile set go back to the base
+        let mut timer_changed = profile.default_timer_secs.is_some();
+        if let Some((_, previous)) = &self.profile {
+            previous.restore(&mut self.config, base);
+            timer_changed |= previous.default_timer_secs.is_some();
+        }
+        profile.apply(&mut self.config);
+        self.live.auto_scan_interval = self.config.auto_scan_interval_secs;
+        if timer_changed {
+            self.live.timer_target_secs = Some(self.config.default_timer_secs);
+        }
+        self.live.frequency_filter = self.config.frequency_filter;
+        self.live.sort = self.config.live_sort();
+        self.live.grouped = self.config.group_bands;
+        self.live.probe_ssids = self.config.probe_ssids.clone();
+        self.profile = Some((name, profile));
+        self.needs_redraw = true;
+    }
+
+    /// Apply a config edit and schedule writing it, so it survives a crash.
+    /// Edits in quick succession are written together, and undone one at a time.
+    fn edit_config(&mut self, message: String, edit: impl FnOnce(&mut Self)) {
+        let fields = config_fields(&self.config);
+        let excluded = self.live.session_excluded_bssids.clone();
+        let offsets = self.compare.offsets.clone();
+        let label = self.adapter_label();
+        edit(self);
+
+        let after = config_fields(&self.config);
+        let mut names: Vec<&String> = fields.keys().chain(after.keys()).collect();
+        names.sort();
+        names.dedup();
+        let fields = names
+            .into_iter()
+            .filter(|name| fields.get(*name) != after.get(*name))
+            .map(|name| (name.clone(), fields.get(name).cloned().unwrap_or_default()))
+            .collect();
+        let mut offset_names: Vec<&String> =
+            offsets.keys().chain(self.compare.offsets.keys()).collect();
+        offset_names.sort();
+        offset_names.dedup();
+        let offsets = offset_names
+            .into_iter()
+            .filter(|name| offsets.get(*name) != self.compare.offsets.get(*name))
+            .map(|name| (name.clone(), offsets.get(name).copied()))
+            .collect();
+        let edit = ConfigEdit {
+            fields,
+            excluded_bssids: self
+                .live
+                .session_excluded_bssids
+                .difference(&excluded)
+                .cloned()
+                .collect(),
+            offsets,
+            adapter_label: (self.adapter_label() != label).then_some(label),
+        };
+
+        let pending = self.pending_config.get_or_insert_with(|| PendingConfig {
+            changed: Instant::now(),
+            edits: Vec::new(),
+        });
+        pending.changed = Instant::now();
+        pending.edits.push(edit);
+        self.toast = Some((format!("{} · [u]ndo", message), Instant::now()));
+        self.needs_redraw = true;
+    }
+
+    fn adapter_label(&self) -> Option<String> {
+        self.live.adapter.as_ref().and_then(|a| a.label.clone())
+    }
+
+    /// Write config edits in the background once they've settled
+    fn write_pending_config(&mut self) {
+        if self.pending_config.as_ref().is_some_and(|p| p.changed.elapsed() >= CONFIG_SAVE_DELAY) {
+            self.pending_config = None;
+            let config = self.config_to_save();
+            let paths = self.paths.clone();
+            self.spawn_job(JobKind::Config, paths.config_file.clone(), move || {
+                config.save(&paths)
+            });
+        }
+    }
+
+    /// Revert the latest config edit that hasn't been written yet
+    pub fn undo_config(&mut self) {
+        let Some(pending) = &mut self.pending_config else {
+            return;
+        };
+        let Some(edit) = pending.edits.pop() else {
+            return;
+        };
+        if pending.edits.is_empty() {
+            self.pending_config = None;
+        }
+
+        if !edit.fields.is_empty() {
+            let mut fields = config_fields(&self.config);
+            fields.extend(edit.fields);
+            match serde_json::from_value(serde_json::Value::Object(fields)) {
+                Ok(config) => self.config = config,
+                Err(e) => log::warn!("couldn't undo the config edit: {}", e),
+            }
+        }
+        for bssid in &edit.excluded_bssids {
+            self.live.session_excluded_bssids.remove(bssid);
+        }
+        for (name, offset) in edit.offsets {
+            match offset {
+                Some(offset) => self.compare.offsets.insert(name, offset),
+                None => self.compare.offsets.remove(&name),
+            };
+        }
+        if let Some(label) = edit.adapter_label {
+            self.set_adapter_label(label);
+        }
+        self.toast = Some(("Undone".to_string(), Instant::now()));
+        self.needs_redraw = true;
+    }
+
+    pub fn save_config(&mut self) -> Result<()> {
+        self.pending_config = None;
+        self.config_to_save().save(&self.paths)
+    }
+
+    /// The config as it would be saved now: running settings, minus the profile's
+    fn config_to_save(&self) -> Config {
         let mut config = self.config.clone();
         config.auto_scan_interval_secs = self.live.auto_scan_interval;
         config.default_timer_secs = self.live.timer_target_secs.unwrap_or(300);
+        config.timer_mode = self.live.timer_mode;
         config.show_channel = self.live.show_channel;
         config.show_band = self.live.show_band;
         config.highlight_best = self.live.highlight_best;
+        config.ap_columns = self.live.columns.clone();
         config.frequency_filter = self.live.frequency_filter;
-        config.sort_by = self.live.sort_by;
+        config.capability_filter = self.live.capability_filter;
+        config.type_filter = self.live.type_filter;
+        config.pinned_ssid = self.live.pinned_ssid.clone();
+        config.sort_by = self.live.sort.primary().unwrap_or_default();
+        config.sort_order = self.live.sort.clone();
+        config.group_bands = self.live.grouped;
+        config.live_split = self.live.split;
         config.history_time_window_mins = self.history.time_window_mins;
         config.history_show_average = self.history.show_average;
+        config.history_drop_outliers = self.history.drop_outliers;
         config.compare_match_by = self.compare.match_by;
         config.compare_metric = self.compare.metric;
-        config.save()?;
-        Ok(())
+        config.compare_custom_metric = self.compare.custom_metric.map(|idx| {
+            self.compare.custom_metrics[idx].0.clone()
+        });
+        config.compare_normalize = self.compare.normalize;
+        config.compare_calibrated = self.compare.calibrated;
+        config.compare_drop_outliers = self.compare.drop_outliers;
+        config.compare_window = self.compare.window;
+        config.compare_buckets = self.compare.buckets;
+        config.compare_band_filter = self.compare.band_filter;
+        config.compare_min_samples = self.compare.min_samples;
+        config.compare_sort_delta = self.compare.sort.primary() == Some(CompareSort::Delta);
+        config.compare_sort = self.compare.sort.clone();
+        if let (Some((_, profile)), Some(base)) = (&self.profile, &self.profile_base) {
+            profile.restore(&mut config, base);
+        }
+        config
     }
 
     /// Request quit - shows confirmation if there's unsaved data or active scan
@@ -404,10 +2644,12 @@ impl App {
 
     /// Force quit without confirmation
     pub fn force_quit(&mut self) {
-        // Save session if modified
+        // Save session if modified, then show its summary before exiting
+        let mut saved = None;
         if self.session_modified {
-            if let Err(e) = self.save_current_session() {
-                eprintln!("Warning: Failed to save session: {}", e);
+            match self.save_current_session() {
+                Ok(path) => saved = Some(path),
+                Err(e) => eprintln!("Warning: Failed to save session: {}", e),
             }
         }
 
@@ -416,7 +2658,17 @@ impl App {
             eprintln!("Warning: Failed to save config: {}", e);
         }
 
-        self.running = false;
+        let summary = self.current_session.as_ref().and_then(|s| s.summary.clone());
+        match (saved, summary) {
+            (Some(path), Some(summary)) => {
+                self.popup = Popup::SessionSummary {
+                    summary,
+                    saved: Some(path),
+                    quit_after: true,
+                };
+            }
+            _ => self.running = false,
+        }
     }
 
     /// Quit without saving session
@@ -428,6 +2680,185 @@ impl App {
         self.running = false;
     }
 
+    /// Start the calibration wizard against the selected AP.
+    ///
+    /// The current adapter is the reference; the first other detected adapter is
+    /// calibrated against it.
+    pub fn start_calibration(&mut self) {
+        let Some(reference) = self.live.adapter.clone() else {
+            self.show_error("No adapter detected".to_string());
+            return;
+        };
+        let Some(ap) = self.live.get_selected_ap() else {
+            self.show_error("Select a reference AP first".to_string());
+            return;
+        };
+        let (bssid, ssid) = (ap.bssid.clone(), ap.ssid.clone());
+
+        let target = match self.detect_adapters() {
+            Ok(adapters) => adapters
+                .into_iter()
+                .find(|a| a.interface != reference.interface),
+            Err(e) => {
+                self.show_error(format!("Failed to detect adapters: {}", e));
+                return;
+            }
+        };
+        let Some(target) = target else {
+            self.show_error("Calibration needs a second adapter connected".to_string());
+            return;
+        };
+
+        let rounds = self.config.calibration_rounds.max(1);
+        let (tx, rx) = mpsc::channel();
+        let ref_iface = reference.interface.clone();
+        let target_iface = target.interface.clone();
+        let thread_bssid = bssid.clone();
+        let backend = self.backend;
+
+        thread::spawn(move || {
+            for _ in 0..rounds {
+                for (is_target, iface) in [(false, &ref_iface), (true, &target_iface)] {
+                    let signal = backend.scan(iface, &[]).ok().and_then(|r| {
+                        r.access_points
+                            .iter()
+                            .find(|ap| ap.bssid == thread_bssid)
+                            .map(|ap| ap.signal_dbm)
+                    });
+                    // Receiver dropped means the wizard was cancelled
+                    if tx
+                        .send(CalibrationMsg::Reading {
+                            target: is_target,
+                            signal,
+                        })
+                        .is_err()
+                    {
+                        return;
+                    }
+                }
+            }
+            let _ = tx.send(CalibrationMsg::Done);
+        });
+
+        self.calibration = Some(Calibration::new(reference, target, bssid, ssid, rounds));
+        self.calibration_receiver = Some(rx);
+        self.popup = Popup::Calibration { selected: 0 };
+    }
+
+    /// Close the calibration wizard, optionally storing the computed offset
+    pub fn finish_calibration(&mut self, save: bool) {
+        if let Some(calibration) = self.calibration.take() {
+            if let (true, Some(offset)) = (save, calibration.offset()) {
+                // Chain onto the reference adapter's own offset
+                let reference_offset = self
+                    .config
+                    .calibration_offsets
+                    .get(&calibration.reference.display_name())
+                    .copied()
+                    .unwrap_or(0);
+                let name = calibration.target.display_name();
+                let message = format!("Calibrated {} by {:+} dB", name, reference_offset + offset);
+                self.edit_config(message, |app| {
+                    app.config
+                        .calibration_offsets
+                        .insert(name.clone(), reference_offset + offset);
+                    app.compare.offsets.insert(name, reference_offset + offset);
+                });
+            }
+        }
+        self.calibration_receiver = None;
+        self.popup = Popup::None;
+    }
+
+    /// Copy the current selection (AP details and stats, or the Compare summary)
+    pub fn yank(&mut self) {
+        let Some(text) = self.clipboard_text() else {
+            self.show_error("Nothing selected to copy".to_string());
+            return;
+        };
+        match crate::utils::copy_to_clipboard(&text) {
+            Ok(()) => self.show_error("Copied to clipboard".to_string()),
+            Err(e) => self.show_error(format!("Copy failed: {}", e)),
+        }
+    }
+
+    /// Tab-separated text for the current selection
+    pub fn clipboard_text(&self) -> Option<String> {
+        match self.screen {
+            Screen::Live => {
+                let ap = self.live.get_selected_ap()?;
+                let stats = self
+                    .current_session
+                    .as_ref()
+                    .and_then(|s| s.ap_stats(&ap.bssid));
+                Some(ap_clipboard_text(ap, stats))
+            }
+            Screen::History => {
+                let session = self.history.session.as_ref()?;
+                let (bssid, _) = self.history.get_selected_ap()?;
+                let latest = session
+                    .scans
+                    .iter()
+                    .rev()
+                    .flat_map(|s| &s.access_points)
+                    .find(|ap| ap.bssid == bssid)?;
+                Some(ap_clipboard_text(latest, session.ap_stats(&bssid)))
+            }
+            Screen::Compare if self.compare.aligned => {
+                let (bssid, ssid) = self.compare.get_selected_ap()?;
+                let (bucket_secs, series) = self.compare.aligned_series();
+                let mut text = format!("ap\t{}\t{}\nelapsed_secs", ssid, bssid);
+                let buckets = series.first().map(|(_, v)| v.len()).unwrap_or(0);
+                for b in 0..buckets {
+                    text.push_str(&format!("\t{}", b as u64 * bucket_secs));
+                }
+                for (name, values) in series {
+                    text.push_str(&format!("\n{}", name));
+                    for value in values {
+                        let value = value.map(|v| v.to_string()).unwrap_or_default();
+                        text.push_str(&format!("\t{}", value));
+                    }
+                }
+                text.push('\n');
+                Some(text)
+            }
+            Screen::Compare => {
+                let (bssid, ssid) = self.compare.get_selected_ap()?;
+                let mut text = format!("ap\t{}\t{}\n", ssid, bssid);
+                text.push_str(&format!(
+                    "adapter\t{} ({} match)\n",
+                    self.compare.metric_name(),
+                    self.compare.match_by.name()
+                ));
+                for (name, value) in self.compare.get_comparison_data() {
+                    let value = value.map(|v| v.to_string()).unwrap_or_else(|| "N/A".to_string());
+                    text.push_str(&format!("{}\t{}\n", name, value));
+                }
+                if let Some(best) = self.compare.best_adapter() {
+                    text.push_str(&format!("best\t{}\n", best));
+                }
+                for (band, best) in self.compare.best_adapter_by_band() {
+                    text.push_str(&format!("best_{}\t{}\n", band.short_name(), best));
+                }
+                Some(text)
+            }
+            Screen::Channels => {
+                let stats = self.channels.stats(self.current_session.as_ref()?);
+                let channel = stats.get(self.channels.selected)?;
+                let opt = |v: Option<String>| v.unwrap_or_default();
+                Some(format!(
+                    "frequency_mhz\taps\tstddev_db\tnoise_dbm\tbusy_pct\thints\n{}\t{}\t{}\t{}\t{}\t{}\n",
+                    channel.frequency_mhz,
+                    channel.ap_count,
+                    opt(channel.signal_stddev.map(|s| format!("{:.1}", s))),
+                    opt(channel.noise_dbm.map(|n| n.to_string())),
+                    opt(channel.busy_ratio().map(|r| format!("{:.0}", r * 100.0))),
+                    channel.interference_hints().join(",")
+                ))
+            }
+        }
+    }
+
     /// Show exclude AP popup for the selected AP
     pub fn show_exclude_popup(&mut self) {
         if let Some(ap) = self.live.get_selected_ap() {
@@ -447,16 +2878,589 @@ impl App {
 
     /// Exclude AP permanently (add to config)
     pub fn exclude_permanent(&mut self, bssid: &str, ssid: &str) {
-        self.config.excluded_aps.push(ExcludedAp {
-            bssid: bssid.to_string(),
-            ssid: ssid.to_string(),
+        let name = if ssid.is_empty() { bssid } else { ssid };
+        self.edit_config(format!("Excluded {} permanently", name), |app| {
+            app.config.excluded_aps.push(ExcludedAp::bssid(bssid, ssid));
+            app.live.exclude_session(bssid);
         });
-        self.live.exclude_session(bssid);
         self.popup = Popup::None;
     }
 
-    /// Check if AP is permanently excluded
-    pub fn is_permanently_excluded(&self, bssid: &str) -> bool {
-        self.config.excluded_aps.iter().any(|ap| ap.bssid == bssid)
+    /// Policy of the first permanent exclusion matching this AP, if any
+    pub fn exclusion_policy(&self, ap: &AccessPoint) -> Option<ExclusionPolicy> {
+        let default = self.config.exclusion_policy;
+        self.config
+            .excluded_aps
+            .iter()
+            .find(|e| e.matches(ap))
+            .map(|e| e.policy_or(default))
+    }
+
+    /// Flip one exclusion between hide-only and drop
+    pub fn toggle_exclusion_policy(&mut self, index: usize) {
+        let default = self.config.exclusion_policy;
+        let Some(policy) = self.config.excluded_aps.get(index).map(|e| e.policy_or(default))
+        else {
+            return;
+        };
+        let policy = policy.toggle();
+        self.edit_config(format!("Exclusion policy set to {}", policy.name()), |app| {
+            app.config.excluded_aps[index].policy = Some(policy);
+        });
+    }
+
+    /// Flip the default policy for exclusions without their own
+    pub fn toggle_default_exclusion_policy(&mut self) {
+        let policy = self.config.exclusion_policy.toggle();
+        self.edit_config(format!("Default exclusion policy set to {}", policy.name()), |app| {
+            app.config.exclusion_policy = policy;
+        });
+    }
+
+    pub fn show_exclusions(&mut self) {
+        self.popup = Popup::Exclusions { selected: 0 };
+    }
+
+    /// Add an SSID pattern exclusion and drop matching APs from the live list
+    pub fn add_exclusion_pattern(&mut self, pattern: String) {
+        let pattern = pattern.trim();
+        if !pattern.is_empty() {
+            let exclusion = ExcludedAp::pattern(pattern);
+            self.live.access_points.retain(|ap| !exclusion.matches(ap));
+            self.live.ap_list_state.selected = 0;
+            self.live.ap_list_state.offset = 0;
+            if !self.config.excluded_aps.contains(&exclusion) {
+                self.edit_config(format!("Excluded {}", pattern), |app| {
+                    app.config.excluded_aps.push(exclusion);
+                });
+            }
+        }
+        self.popup = Popup::Exclusions {
+            selected: self.config.excluded_aps.len().saturating_sub(1),
+        };
+    }
+
+    /// Remove a permanent exclusion; the AP reappears from the next scan
+    pub fn remove_exclusion(&mut self, index: usize) {
+        if index < self.config.excluded_aps.len() {
+            self.edit_config("Exclusion removed".to_string(), |app| {
+                let removed = app.config.excluded_aps.remove(index);
+                if removed.pattern.is_none() {
+                    app.live.session_excluded_bssids.remove(&removed.bssid);
+                }
+            });
+        }
+        self.popup = Popup::Exclusions {
+            selected: index.min(self.config.excluded_aps.len().saturating_sub(1)),
+        };
+    }
+}
+
+/// Time between proximity beeps: faster as the signal strengthens, like a Geiger counter
+fn geiger_interval(signal_dbm: i32) -> Duration {
+    let (weakest, strongest) = GEIGER_RANGE_DBM;
+    let level = (signal_dbm.clamp(weakest, strongest) - weakest) as f64
+        / (strongest - weakest) as f64;
+    GEIGER_SLOWEST.mul_f64(1.0 - level) + GEIGER_FASTEST.mul_f64(level)
+}
+
+/// Scan on a worker thread, tagging the result with a gpsd position if given. With a
+/// trigger, the scan waits for it and records how late it started.
+fn spawn_scan(
+    backend: ScanBackend,
+    interface: &str,
+    probe_ssids: &[String],
+    gpsd: Option<String>,
+    trigger: Option<DateTime<Utc>>,
+) -> Receiver<ScanResultMsg> {
+    let (tx, rx): (Sender<ScanResultMsg>, Receiver<ScanResultMsg>) = mpsc::channel();
+    let interface = interface.to_string();
+    let probe_ssids = probe_ssids.to_vec();
+
+    thread::spawn(move || {
+        if let Some(wait) = trigger.and_then(|at| (at - Utc::now()).to_std().ok()) {
+            thread::sleep(wait);
+        }
+        let started = Utc::now();
+        let result = backend.scan(&interface, &probe_ssids).map(|mut scan| {
+            scan.trigger = trigger.map(|at| ScanTrigger {
+                at,
+                latency_ms: (started - at).num_microseconds().unwrap_or(0).max(0) as f32 / 1000.0,
+            });
+            if let Some(addr) = gpsd.as_deref() {
+                scan.position = gpsd_fix(addr);
+                if scan.position.is_none() {
+                    log::debug!("no GPS fix from gpsd at {}", addr);
+                }
+            }
+            scan
+        });
+        let _ = tx.send(result);
+    });
+    rx
+}
+
+/// Header and row describing one AP and its session stats
+fn ap_clipboard_text(ap: &AccessPoint, stats: Option<ApStats>) -> String {
+    let stats = stats
+        .map(|s| {
+            format!(
+                "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                s.avg, s.median, s.p10, s.p90, s.min, s.max, s.count
+            )
+        })
+        .unwrap_or_else(|| "\t\t\t\t\t\t0".to_string());
+    format!(
+        "ssid\tbssid\tsignal_dbm\tchannel\tband\tavg\tmedian\tp10\tp90\tmin\tmax\tsamples\tmld\n\
+         {}\t{}\t{}\t{}\t{}\t{}\t{}\n",
+        ap.ssid,
+        ap.bssid,
+        ap.signal_dbm,
+        ap.channel,
+        ap.band().short_name(),
+        stats,
+        ap.mld_address.as_deref().unwrap_or("")
+    )
+}
+
+/// Lines of the AP detail popup; fields the AP didn't advertise are left out
+fn ap_detail_text(ap: &AccessPoint, client_country: Option<&str>) -> String {
+    let ssid = if ap.ssid.is_empty() { "<hidden>" } else { &ap.ssid };
+    let width = ap.channel_width_mhz.map(|w| format!(", {} MHz wide", w)).unwrap_or_default();
+    let mut lines = vec![
+        format!("SSID       {}", ssid),
+        format!("BSSID      {}", ap.bssid),
+        format!("Signal     {} dBm", ap.signal_dbm),
+        format!("Channel    {} ({} MHz{})", ap.channel, ap.frequency_mhz, width),
+        format!("Security   {}", ap.security.map(|s| s.short_name()).unwrap_or("unknown")),
+    ];
+    if !ap.bss_type.is_infrastructure() {
+        lines.push(format!("Type       {}", ap.bss_type.short_name()));
+    }
+    if let Some(mld) = &ap.mld_address {
+        lines.push(format!("MLD        {}", mld));
+    }
+    if let Some(interval) = ap.beacon_interval_tu {
+        lines.push(format!("Beacon     every {} TU", interval));
+    }
+    if let Some(util) = ap.channel_utilization {
+        let stations = ap.station_count.map(|n| format!(", {} stations", n)).unwrap_or_default();
+        lines.push(format!("Load       {}% busy{}", util, stations));
+    }
+    if !ap.roaming.is_none() {
+        let r = ap.roaming;
+        let amendments: Vec<&str> = [(r.k, "802.11k"), (r.v, "802.11v"), (r.r, "802.11r")]
+            .iter()
+            .filter_map(|&(on, name)| on.then_some(name))
+            .collect();
+        lines.push(format!("Roaming    {}", amendments.join(", ")));
+    }
+    if ap.wps {
+        lines.push("WPS        enabled".to_string());
+    }
+    if let Some(reg) = &ap.regulatory {
+        if let Some(country) = &reg.country {
+            lines.push(format!("Country    {}", country));
+        }
+        if let Some(max) = reg.max_power_dbm {
+            let local = match (reg.power_constraint_db, reg.local_max_dbm()) {
+                (Some(db), Some(local)) if db > 0 => {
+                    format!(" ({} dB constraint: {} dBm)", db, local)
+                }
+                _ => String::new(),
+            };
+            lines.push(format!("Max power  {} dBm{}", max, local));
+        }
+        if let Some(tx) = reg.tx_power_dbm {
+            lines.push(format!("AP power   {} dBm", tx));
+        }
+        if let Some(client) = client_country.filter(|c| reg.mismatches(c)) {
+            lines.push(String::new());
+            lines.push(format!(
+                "⚠ AP advertises {} but the adapter's regulatory domain is {}; \
+                 channels and power limits may differ",
+                reg.country.as_deref().unwrap_or_default(),
+                client
+            ));
+        }
+    }
+    let weaknesses = ap.weaknesses();
+    if !weaknesses.is_empty() {
+        lines.push(String::new());
+        lines.push(format!("⚠ Weak configuration: {}", weaknesses.join(", ")));
+    }
+    lines.join("\n")
+}
+
+/// Strongest matching reading per scan, offset applied
+fn session_series<F>(session: &Session, offset: i32, pred: F) -> SvgSeries
+where
+    F: Fn(&AccessPoint) -> bool,
+{
+    let points = session
+        .scans
+        .iter()
+        .filter_map(|scan| {
+            let signal = scan
+                .access_points
+                .iter()
+                .filter(|ap| pred(ap))
+                .map(|ap| ap.signal_dbm)
+                .max()?;
+            let secs = (scan.timestamp - session.started_at).num_milliseconds() as f64 / 1000.0;
+            Some((secs.max(0.0), signal + offset))
+        })
+        .collect();
+    SvgSeries {
+        name: session.adapter.display_name(),
+        points,
+    }
+}
+
+/// Index of the first of `aps` (BSSID, SSID) from `from` on whose SSID or BSSID
+/// contains `query`, ignoring case and wrapping around to the start
+fn find_ap(aps: &[(String, String)], from: usize, query: &str) -> Option<usize> {
+    let query = query.to_lowercase();
+    (0..aps.len()).map(|i| (from + i) % aps.len()).find(|&i| {
+        let (bssid, ssid) = &aps[i];
+        ssid.to_lowercase().contains(&query) || bssid.to_lowercase().contains(&query)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::FrequencyFilter;
+
+    #[test]
+    fn test_background_save() {
+        let dir = std::env::temp_dir().join(format!("wificomp-jobs-{}", std::process::id()));
+        let mut app = App::new(DataPaths::in_dir(&dir), ScanBackend::Demo).unwrap();
+        app.init(None).unwrap();
+        app.session_modified = true;
+
+        let path = app.save_current_session_in_background().unwrap();
+        assert!(!app.session_modified);
+        assert_eq!(app.running_jobs(), vec![JobKind::Save]);
+
+        app.wait_for_jobs();
+        assert!(app.running_jobs().is_empty());
+        assert!(load_session(&path).is_ok());
+        // Later saves go to the same file
+        assert_eq!(app.save_current_session().unwrap(), path);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_ap_detail_flags_country_mismatch() {
+        let scan = crate::scanner::demo::demo_scan("demo0", Utc::now());
+        let detail = |bssid: &str| {
+            let ap = scan.access_points.iter().find(|ap| ap.bssid == bssid).unwrap();
+            ap_detail_text(ap, Some("US"))
+        };
+        let office = detail("F0:9F:C2:20:00:02");
+        assert!(office.contains("Country    CA"));
+        assert!(office.contains("⚠ AP advertises CA"));
+        let home = detail("3C:84:6A:10:00:02");
+        assert!(home.contains("Max power  23 dBm (3 dB constraint: 20 dBm)"));
+        assert!(!home.contains('⚠'));
+        let printer = detail("DC:A6:32:60:00:01");
+        assert!(printer.contains("⚠ Weak configuration: TKIP only, WPS"));
+    }
+
+    #[test]
+    fn test_evil_twin_logged_once() {
+        let dir = std::env::temp_dir().join(format!("wificomp-twins-{}", std::process::id()));
+        let mut app = App::new(DataPaths::in_dir(&dir), ScanBackend::Demo).unwrap();
+        app.init(None).unwrap();
+        app.live.pinned_ssid = Some("HomeNet".to_string());
+
+        let mut scan = crate::scanner::demo::demo_scan("demo0", Utc::now());
+        let twin = scan.access_points.iter_mut().find(|ap| ap.ssid == "HomeNet").unwrap();
+        twin.bssid = "00:11:22:33:44:55".to_string();
+        twin.security = Some(crate::data::Security::Open);
+        app.record_scan(scan.clone());
+        app.record_scan(scan);
+
+        assert_eq!(app.live.twin_suspects.len(), 1);
+        let events = &app.current_session.as_ref().unwrap().events;
+        assert_eq!(events.len(), 1);
+        assert!(events[0].message.starts_with("Possible evil twin of HomeNet: 00:11:22:33:44:55"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_search() {
+        let dir = std::env::temp_dir().join(format!("wificomp-search-{}", std::process::id()));
+        let mut app = App::new(DataPaths::in_dir(&dir), ScanBackend::Demo).unwrap();
+        app.init(None).unwrap();
+        app.record_scan(crate::scanner::demo::demo_scan("demo0", Utc::now()));
+        let selected = |app: &App| app.live.get_selected_ap().unwrap().bssid.clone();
+
+        app.search("guest".to_string());
+        assert_eq!(selected(&app), "F0:9F:C2:20:00:01");
+        // An empty search finds the next match of the last one
+        app.search("homenet".to_string());
+        let first = selected(&app);
+        app.search(String::new());
+        assert_ne!(selected(&app), first);
+        assert_eq!(app.live.get_selected_ap().unwrap().ssid, "HomeNet");
+
+        app.search("no such network".to_string());
+        assert!(app.toast.as_ref().unwrap().0.starts_with("No AP matches"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_add_marker() {
+        let dir = std::env::temp_dir().join(format!("wificomp-marker-{}", std::process::id()));
+        let mut app = App::new(DataPaths::in_dir(&dir), ScanBackend::Demo).unwrap();
+        app.init(None).unwrap();
+        app.record_scan(crate::scanner::demo::demo_scan("demo0", Utc::now()));
+        app.session_modified = false;
+
+        app.show_marker_popup();
+        assert!(matches!(app.popup, Popup::Marker { .. }));
+        app.add_marker("  moved to hallway ".to_string());
+        // A blank note cancels
+        app.add_marker(" ".to_string());
+        let markers = &app.current_session.as_ref().unwrap().markers;
+        assert_eq!(markers.len(), 1);
+        assert_eq!(markers[0].note, "moved to hallway");
+        assert!(app.session_modified);
+        assert!(matches!(app.popup, Popup::None));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_walk_test() {
+        let dir = std::env::temp_dir().join(format!("wificomp-walk-{}", std::process::id()));
+        let mut app = App::new(DataPaths::in_dir(&dir), ScanBackend::Demo).unwrap();
+        app.init(None).unwrap();
+        app.config.walk_dwell_scans = 2;
+        let second = ScanBackend::Demo.find_adapter("demo1").unwrap();
+        app.add_nodes(vec![(ScanBackend::Demo, second)]);
+
+        app.show_walk_setup();
+        assert!(matches!(app.popup, Popup::WalkSetup { .. }));
+        app.start_walk(" desk, ,hallway ".to_string());
+        assert_eq!(app.config.walk_checkpoints, vec!["desk", "hallway"]);
+        assert!(app.live.auto_scan);
+        assert!(matches!(app.popup, Popup::Walk { .. }));
+
+        let scan = || crate::scanner::demo::demo_scan("demo0", Utc::now());
+        for _ in 0..2 {
+            app.walk_arrived();
+            for _ in 0..2 {
+                app.nodes[0].session.add_scan(scan());
+                app.record_scan(scan());
+            }
+        }
+        let session = app.current_session.as_ref().unwrap();
+        let names: Vec<&str> = session.checkpoints.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["desk", "hallway"]);
+        assert_eq!(session.markers.len(), 2);
+        assert!(session.checkpoints.iter().all(|c| c.scans(&session.scans).len() == 2));
+        // The node walked along: its scans fall within the same checkpoints
+        let node = &app.nodes[0].session;
+        assert_eq!((&node.checkpoints, &node.markers), (&session.checkpoints, &session.markers));
+        assert!(node.checkpoints.iter().all(|c| c.scans(&node.scans).len() == 2));
+        assert!(app.walk.is_none());
+        assert!(matches!(app.popup, Popup::None));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_sync_scans() {
+        let at = Utc::now() + chrono::Duration::milliseconds(20);
+        let scan = spawn_scan(ScanBackend::Demo, "demo0", &[], None, Some(at)).recv().unwrap();
+        let trigger = scan.unwrap().trigger.unwrap();
+        assert_eq!(trigger.at, at);
+        assert!(trigger.latency_ms >= 0.0);
+
+        let dir = std::env::temp_dir().join(format!("wificomp-sync-{}", std::process::id()));
+        let mut app = App::new(DataPaths::in_dir(&dir), ScanBackend::Demo).unwrap();
+        app.init(None).unwrap();
+        // Nothing to synchronize with yet
+        app.toggle_sync_scans();
+        assert!(!app.config.sync_scans);
+        let second = ScanBackend::Demo.find_adapter("demo1").unwrap();
+        app.add_nodes(vec![(ScanBackend::Demo, second)]);
+        app.toggle_sync_scans();
+        assert!(app.config.sync_scans);
+
+        // The slowest start of the newest trigger is kept
+        let trigger = |at, latency_ms| Some(ScanTrigger { at, latency_ms });
+        app.note_trigger(trigger(at, 2.0));
+        app.note_trigger(trigger(at, 5.0));
+        app.note_trigger(trigger(at - chrono::Duration::seconds(1), 9.0));
+        assert_eq!(app.live.sync, trigger(at, 5.0));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_alternation() {
+        let dir = std::env::temp_dir().join(format!("wificomp-ab-{}", std::process::id()));
+        let mut app = App::new(DataPaths::in_dir(&dir), ScanBackend::Demo).unwrap();
+        app.init(None).unwrap();
+        app.record_scan(crate::scanner::demo::demo_scan("demo0", Utc::now()));
+
+        app.show_alternate_setup();
+        assert!(matches!(app.popup, Popup::AlternateSetup { .. }));
+        app.start_alternation("2".to_string());
+        assert_eq!(app.config.alternate_minutes, 2);
+        let run_id = app.current_session.as_ref().unwrap().run_id.clone();
+        assert!(run_id.is_some());
+
+        app.end_turn();
+        assert!(matches!(app.popup, Popup::Swap { .. }));
+        assert!(!app.session_modified);
+        // The same adapter plugged back in keeps waiting
+        let first = app.live.adapter.clone().unwrap();
+        app.swap_in(first);
+        assert!(matches!(app.popup, Popup::Swap { .. }));
+        app.swap_in(ScanBackend::Demo.find_adapter("demo1").unwrap());
+        assert!(matches!(app.popup, Popup::None));
+        let session = app.current_session.as_ref().unwrap();
+        assert_eq!(session.adapter.interface, "demo1");
+        assert_eq!(session.run_id, run_id);
+
+        app.show_alternate_setup();
+        assert!(app.alternation.is_none());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_hotplug_behind_popup() {
+        let dir = std::env::temp_dir().join(format!("wificomp-hotplug-{}", std::process::id()));
+        let mut app = App::new(DataPaths::in_dir(&dir), ScanBackend::Demo).unwrap();
+        app.init(None).unwrap();
+        app.known_interfaces = vec!["demo0".to_string()];
+
+        // Plugged in while the timer popup is open: held until it closes
+        app.popup = Popup::TimerSetup { input: String::new(), cursor: 0 };
+        app.interfaces_changed(vec!["demo0".to_string(), "demo1".to_string()]);
+        assert!(matches!(app.popup, Popup::TimerSetup { .. }));
+        app.popup = Popup::None;
+        app.interfaces_changed(vec!["demo0".to_string(), "demo1".to_string()]);
+        let offered = |popup: &Popup| {
+            matches!(popup, Popup::AdapterAdded { adapter, .. } if adapter.interface == "demo1")
+        };
+        assert!(offered(&app.popup));
+
+        // Unplugged again before the popup closed: nothing to offer
+        app.popup = Popup::FilePicker;
+        app.interfaces_changed(vec!["demo0".to_string()]);
+        app.interfaces_changed(vec!["demo0".to_string(), "demo1".to_string()]);
+        app.interfaces_changed(vec!["demo0".to_string()]);
+        app.popup = Popup::None;
+        app.interfaces_changed(vec!["demo0".to_string()]);
+        assert_eq!(app.popup, Popup::None);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_geiger_interval() {
+        assert_eq!(geiger_interval(-95), GEIGER_SLOWEST);
+        assert_eq!(geiger_interval(-60), Duration::from_millis(1050));
+        assert_eq!(geiger_interval(-20), GEIGER_FASTEST);
+    }
+
+    #[test]
+    fn test_history_shares_live_session() {
+        let dir = std::env::temp_dir().join(format!("wificomp-share-{}", std::process::id()));
+        let mut app = App::new(DataPaths::in_dir(&dir), ScanBackend::Demo).unwrap();
+        app.init(None).unwrap();
+
+        app.switch_screen(Screen::History);
+        let live = app.current_session.as_ref().unwrap();
+        assert!(Arc::ptr_eq(app.history.session.as_ref().unwrap(), live));
+
+        app.switch_screen(Screen::Live);
+        assert!(app.history.session.is_none());
+        assert_eq!(Arc::strong_count(app.current_session.as_ref().unwrap()), 1);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_adapter_label_remembered() {
+        let dir = std::env::temp_dir().join(format!("wificomp-labels-{}", std::process::id()));
+        let mut app = App::new(DataPaths::in_dir(&dir), ScanBackend::Demo).unwrap();
+        app.init(None).unwrap();
+        app.apply_rename("Bench".to_string());
+        app.save_config().unwrap();
+
+        let mut app = App::new(DataPaths::in_dir(&dir), ScanBackend::Demo).unwrap();
+        app.init(None).unwrap();
+        let adapter = app.live.adapter.as_ref().unwrap();
+        assert_eq!(adapter.label.as_deref(), Some("Bench"));
+        assert_eq!(app.current_session.as_ref().unwrap().adapter.safe_name(), "Bench");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_exclusion_saved_after_delay_unless_undone() {
+        let dir = std::env::temp_dir().join(format!("wificomp-pending-{}", std::process::id()));
+        let paths = DataPaths::in_dir(&dir);
+        let mut app = App::new(paths.clone(), ScanBackend::Demo).unwrap();
+
+        app.exclude_permanent("AA:BB:CC:DD:EE:FF", "Neighbor");
+        app.undo_config();
+        assert!(app.config.excluded_aps.is_empty());
+        assert!(app.live.session_excluded_bssids.is_empty());
+
+        app.exclude_permanent("AA:BB:CC:DD:EE:FF", "Neighbor");
+        app.write_pending_config();
+        assert!(app.running_jobs().is_empty(), "written before the undo window closed");
+
+        app.pending_config.as_mut().unwrap().changed -= CONFIG_SAVE_DELAY;
+        app.write_pending_config();
+        app.wait_for_jobs();
+        assert_eq!(Config::load(&paths).unwrap().excluded_aps.len(), 1);
+        // Once written, there is nothing left to undo
+        app.undo_config();
+        assert_eq!(app.config.excluded_aps.len(), 1);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_switch_profiles() {
+        let dir = std::env::temp_dir().join(format!("wificomp-profiles-{}", std::process::id()));
+        let mut app = App::new(DataPaths::in_dir(&dir), ScanBackend::Demo).unwrap();
+        let walk: Profile =
+            serde_json::from_str(r#"{"group_bands": true, "frequency_filter": "FiveGHz"}"#)
+                .unwrap();
+        let soak: Profile = serde_json::from_str(r#"{"frequency_filter": "SixGHz"}"#).unwrap();
+
+        app.use_profile("walk".to_string(), walk);
+        assert!(app.live.grouped);
+        // Switching profiles drops the fields only the first one set
+        app.use_profile("soak".to_string(), soak);
+        assert!(!app.live.grouped);
+        assert_eq!(app.live.frequency_filter, FrequencyFilter::SixGHz);
+        assert_eq!(app.config_to_save().frequency_filter, FrequencyFilter::All);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_undo_one_edit_at_a_time() {
+        let dir = std::env::temp_dir().join(format!("wificomp-undo-{}", std::process::id()));
+        let mut app = App::new(DataPaths::in_dir(&dir), ScanBackend::Demo).unwrap();
+        let policy = app.config.exclusion_policy;
+
+        app.exclude_permanent("AA:BB:CC:DD:EE:FF", "Neighbor");
+        app.config.show_band = !app.config.show_band;
+        app.toggle_default_exclusion_policy();
+        app.undo_config();
+        assert_eq!(app.config.exclusion_policy, policy);
+        assert_eq!(app.config.excluded_aps.len(), 1);
+
+        // Changes made outside an edit survive undoing the ones around them
+        app.undo_config();
+        assert!(app.config.excluded_aps.is_empty());
+        assert!(app.live.session_excluded_bssids.is_empty());
+        assert_ne!(app.config.show_band, Config::default().show_band);
+        assert!(app.pending_config.is_none());
+        let _ = std::fs::remove_dir_all(&dir);
     }
 }
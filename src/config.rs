@@ -1,15 +1,132 @@
 use anyhow::{Context, Result};
+use log::LevelFilter;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
 
-use crate::data::{CompareMetric, FrequencyFilter, MatchBy, SortBy, TimerMode};
+use crate::data::anonymize::Anonymize;
+use crate::data::formula::CustomMetric;
+use crate::data::{
+    default_columns, AccessPoint, Adapter, CapabilityFilter, ColumnConfig, CompareMetric,
+    CompareSort, DataPaths, FrequencyFilter, MatchBy, SampleWindow, SortBy, SortOrder, TimerMode,
+    TypeFilter,
+};
+use crate::utils::glob_match;
 
-/// Excluded AP entry
+/// What happens when the session timer runs out
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TimerExpiry {
+    /// Turn auto-scan off
+    pub stop_scan: bool,
+    /// Save the session
+    pub autosave: bool,
+    /// Show a summary popup with per-AP stats
+    pub summary: bool,
+    /// Ring the terminal bell
+    pub beep: bool,
+    /// Start a new session on the same adapter and keep scanning
+    pub next_round: bool,
+}
+
+impl Default for TimerExpiry {
+    fn default() -> Self {
+        Self {
+            stop_scan: false,
+            autosave: false,
+            summary: true,
+            beep: false,
+            next_round: false,
+        }
+    }
+}
+
+/// What an exclusion does to matching APs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ExclusionPolicy {
+    /// Hidden from the live list but still recorded in the session
+    #[default]
+    Hide,
+    /// Never recorded or saved
+    Drop,
+}
+
+impl ExclusionPolicy {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExclusionPolicy::Hide => "hide",
+            ExclusionPolicy::Drop => "drop",
+        }
+    }
+
+    pub fn toggle(&self) -> Self {
+        match self {
+            ExclusionPolicy::Hide => ExclusionPolicy::Drop,
+            ExclusionPolicy::Drop => ExclusionPolicy::Hide,
+        }
+    }
+}
+
+/// Excluded AP entry: a single BSSID, or every SSID matching a glob pattern
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub struct ExcludedAp {
+    #[serde(default)]
     pub bssid: String,
+    #[serde(default)]
     pub ssid: String,
+    /// SSID glob (`*`, `?`, case-insensitive); overrides `bssid` when set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    /// Overrides the global `exclusion_policy` for this entry
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub policy: Option<ExclusionPolicy>,
+}
+
+impl ExcludedAp {
+    pub fn bssid(bssid: &str, ssid: &str) -> Self {
+        Self {
+            bssid: bssid.to_string(),
+            ssid: ssid.to_string(),
+            pattern: None,
+            policy: None,
+        }
+    }
+
+    pub fn pattern(pattern: &str) -> Self {
+        Self {
+            bssid: String::new(),
+            ssid: String::new(),
+            pattern: Some(pattern.to_string()),
+            policy: None,
+        }
+    }
+
+    pub fn matches(&self, ap: &AccessPoint) -> bool {
+        match &self.pattern {
+            Some(pattern) => glob_match(pattern, &ap.ssid),
+            None => self.bssid == ap.bssid,
+        }
+    }
+
+    /// Policy for this entry, falling back to the global default
+    pub fn policy_or(&self, default: ExclusionPolicy) -> ExclusionPolicy {
+        self.policy.unwrap_or(default)
+    }
+
+    /// One-line description for the exclusions list
+    pub fn describe(&self, default: ExclusionPolicy) -> String {
+        let target = match &self.pattern {
+            Some(pattern) => format!("SSID {}", pattern),
+            None if self.ssid.is_empty() => format!("{} <hidden>", self.bssid),
+            None => format!("{} {}", self.bssid, self.ssid),
+        };
+        let policy = self.policy_or(default).name();
+        if self.policy.is_some() {
+            format!("[{}] {}", policy, target)
+        } else {
+            format!("({}) {}", policy, target)
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,9 +137,24 @@ pub struct Config {
     #[serde(default = "default_timer")]
     pub default_timer_secs: u64,
 
+    /// UI tick/refresh rate in milliseconds
+    #[serde(default = "default_tick_rate")]
+    pub tick_rate_ms: u64,
+
+    /// Only redraw on input or scan completion
+    #[serde(default)]
+    pub low_power: bool,
+
+    /// Vim-style keys: hjkl, gg/G, `/` search and `:` commands
+    #[serde(default)]
+    pub vim_keys: bool,
+
     #[serde(default)]
     pub timer_mode: TimerMode,
 
+    #[serde(default)]
+    pub timer_expiry: TimerExpiry,
+
     #[serde(default = "default_true")]
     pub show_channel: bool,
 
@@ -32,12 +164,41 @@ pub struct Config {
     #[serde(default = "default_true")]
     pub highlight_best: bool,
 
+    /// Live list columns in display order, with optional fixed widths
+    #[serde(default = "default_columns")]
+    pub ap_columns: Vec<ColumnConfig>,
+
+    /// Collapse co-located multi-band radios in the Live list
+    #[serde(default)]
+    pub group_bands: bool,
+
+    /// Split the Live screen to graph the selected AP below the list
+    #[serde(default)]
+    pub live_split: bool,
+
+    /// First key of the Live sort; `sort_order` adds tie-breakers behind it
     #[serde(default)]
     pub sort_by: SortBy,
 
+    /// Every Live sort key, set in the sort popup
+    #[serde(default)]
+    pub sort_order: SortOrder<SortBy>,
+
     #[serde(default)]
     pub frequency_filter: FrequencyFilter,
 
+    /// Live list restricted to APs with a capability (e.g. Wi-Fi 7 MLO)
+    #[serde(default)]
+    pub capability_filter: CapabilityFilter,
+
+    /// Live list restricted to APs, mesh points or ad-hoc networks
+    #[serde(default)]
+    pub type_filter: TypeFilter,
+
+    /// SSID watched for evil twins
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pinned_ssid: Option<String>,
+
     #[serde(default)]
     pub alert_threshold_dbm: Option<i32>,
 
@@ -47,15 +208,170 @@ pub struct Config {
     #[serde(default)]
     pub history_show_average: bool,
 
+    /// Leave Hampel-filter outliers out of History's graph and stats
+    #[serde(default)]
+    pub history_drop_outliers: bool,
+
     #[serde(default)]
     pub compare_match_by: MatchBy,
 
     #[serde(default)]
     pub compare_metric: CompareMetric,
 
-    /// Permanently excluded APs (by BSSID)
+    /// Custom metric selected in Compare, by name (overrides `compare_metric`)
+    #[serde(default)]
+    pub compare_custom_metric: Option<String>,
+
+    /// Formula metrics added to the Compare metric cycle
+    #[serde(default)]
+    pub custom_metrics: Vec<CustomMetric>,
+
+    /// Normalize Compare results by channel dwell time
+    #[serde(default)]
+    pub compare_normalize: bool,
+
+    /// Apply calibration offsets in Compare
+    #[serde(default)]
+    pub compare_calibrated: bool,
+
+    /// Leave Hampel-filter outliers out of Compare's stats
+    #[serde(default)]
+    pub compare_drop_outliers: bool,
+
+    /// Stops of the guided walk test, in walking order
+    #[serde(default)]
+    pub walk_checkpoints: Vec<String>,
+
+    /// Scans to take at each walk-test checkpoint before moving on
+    #[serde(default = "default_walk_dwell_scans")]
+    pub walk_dwell_scans: usize,
+
+    /// Start the scans of the Live adapter and its nodes from one shared trigger
+    #[serde(default)]
+    pub sync_scans: bool,
+
+    /// Minutes each adapter scans for in an A/B test before the swap prompt
+    #[serde(default = "default_alternate_minutes")]
+    pub alternate_minutes: u64,
+
+    /// Record CPU temperature and battery state with each local scan
+    #[serde(default)]
+    pub host_context: bool,
+
+    /// Which scans of each session Compare uses
+    #[serde(default)]
+    pub compare_window: SampleWindow,
+
+    /// Elapsed-time buckets in Compare's time-aligned mode
+    #[serde(default = "default_compare_buckets")]
+    pub compare_buckets: usize,
+
+    /// Compare AP list band filter
+    #[serde(default)]
+    pub compare_band_filter: FrequencyFilter,
+
+    /// Hide Compare APs no session saw at least this many times
+    #[serde(default)]
+    pub compare_min_samples: usize,
+
+    /// Order Compare APs by largest metric difference between sessions
+    #[serde(default)]
+    pub compare_sort_delta: bool,
+
+    /// Compare sort keys from the sort popup; empty keeps first-seen order
+    #[serde(default)]
+    pub compare_sort: SortOrder<CompareSort>,
+
+    /// Warn in Compare when scan counts differ by more than this factor
+    #[serde(default = "default_count_ratio")]
+    pub compare_count_ratio: f64,
+
+    /// Per-adapter dBm calibration offsets (keyed by adapter display name)
+    #[serde(default)]
+    pub calibration_offsets: HashMap<String, i32>,
+
+    /// Adapter labels set with rename, keyed by `Adapter::label_key`
+    #[serde(default)]
+    pub adapter_labels: HashMap<String, String>,
+
+    /// NetworkManager connection profile the connect test joins
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connect_test_network: Option<String>,
+
+    /// Host the load test pings and sends its UDP stream to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub load_test_target: Option<String>,
+
+    /// How long a load test runs, in seconds
+    #[serde(default = "default_load_test_secs")]
+    pub load_test_secs: u64,
+
+    /// Bandwidth of the load test's UDP stream in Mbit/s
+    #[serde(default = "default_load_test_mbps")]
+    pub load_test_mbps: u32,
+
+    /// Scans per adapter in the calibration wizard
+    #[serde(default = "default_calibration_rounds")]
+    pub calibration_rounds: usize,
+
+    /// Already asked whether to grant CAP_NET_ADMIN to the binary
+    #[serde(default)]
+    pub capability_prompted: bool,
+
+    /// Permanently excluded APs (by BSSID or SSID pattern)
     #[serde(default)]
     pub excluded_aps: Vec<ExcludedAp>,
+
+    /// Hidden SSIDs to actively probe for on every scan
+    #[serde(default)]
+    pub probe_ssids: Vec<String>,
+
+    /// gpsd address (host:port) for tagging scans with a position
+    #[serde(default)]
+    pub gpsd: Option<String>,
+
+    /// WiGLE API name and token for `--wigle-upload`
+    #[serde(default)]
+    pub wigle_api_name: Option<String>,
+    #[serde(default)]
+    pub wigle_api_token: Option<String>,
+
+    /// URL that receives a JSON summary when a session is saved or its timer expires
+    #[serde(default)]
+    pub webhook_url: Option<String>,
+
+    /// Ask for antenna and placement notes whenever a session starts
+    #[serde(default)]
+    pub prompt_setup: bool,
+
+    /// Anonymization preselected in the export dialog
+    #[serde(default)]
+    pub export_anonymize: Anonymize,
+
+    /// Also anonymize SSIDs when exports are anonymized
+    #[serde(default)]
+    pub anonymize_ssids: bool,
+
+    /// Default for exclusions without their own policy
+    #[serde(default)]
+    pub exclusion_policy: ExclusionPolicy,
+
+    /// Consecutive failed scans before offering an interface reset (0 disables)
+    #[serde(default = "default_reset_after_failures")]
+    pub reset_after_failures: u32,
+
+    /// Reset the interface without asking once the failure limit is reached
+    #[serde(default)]
+    pub auto_reset: bool,
+
+    /// Scans of the live session kept in memory; older ones move to a file beside the
+    /// session (0 keeps everything in memory)
+    #[serde(default = "default_live_scan_limit")]
+    pub live_scan_limit: usize,
+
+    /// Level for the debug log file (`--log-level` overrides)
+    #[serde(default = "default_log_level")]
+    pub log_level: LevelFilter,
 }
 
 fn default_auto_scan_interval() -> u64 {
@@ -66,6 +382,50 @@ fn default_timer() -> u64 {
     300
 }
 
+fn default_tick_rate() -> u64 {
+    250
+}
+
+fn default_calibration_rounds() -> usize {
+    5
+}
+
+fn default_load_test_secs() -> u64 {
+    10
+}
+
+fn default_load_test_mbps() -> u32 {
+    20
+}
+
+fn default_compare_buckets() -> usize {
+    12
+}
+
+fn default_alternate_minutes() -> u64 {
+    5
+}
+
+fn default_walk_dwell_scans() -> usize {
+    3
+}
+
+fn default_count_ratio() -> f64 {
+    2.0
+}
+
+fn default_reset_after_failures() -> u32 {
+    5
+}
+
+fn default_live_scan_limit() -> usize {
+    2880
+}
+
+fn default_log_level() -> LevelFilter {
+    LevelFilter::Warn
+}
+
 fn default_true() -> bool {
     true
 }
@@ -79,36 +439,78 @@ impl Default for Config {
         Self {
             auto_scan_interval_secs: 5,
             default_timer_secs: 300,
+            tick_rate_ms: 250,
+            low_power: false,
+            vim_keys: false,
             timer_mode: TimerMode::Countdown,
+            timer_expiry: TimerExpiry::default(),
             show_channel: true,
             show_band: true,
             highlight_best: true,
+            ap_columns: default_columns(),
+            group_bands: false,
+            live_split: false,
             sort_by: SortBy::Signal,
+            sort_order: SortOrder::default(),
             frequency_filter: FrequencyFilter::All,
+            capability_filter: CapabilityFilter::All,
+            type_filter: TypeFilter::All,
+            pinned_ssid: None,
             alert_threshold_dbm: None,
             history_time_window_mins: 5,
             history_show_average: false,
+            history_drop_outliers: false,
             compare_match_by: MatchBy::Bssid,
             compare_metric: CompareMetric::Avg,
+            compare_custom_metric: None,
+            custom_metrics: Vec::new(),
+            compare_normalize: false,
+            compare_calibrated: false,
+            compare_drop_outliers: false,
+            walk_checkpoints: Vec::new(),
+            walk_dwell_scans: default_walk_dwell_scans(),
+            sync_scans: false,
+            alternate_minutes: default_alternate_minutes(),
+            host_context: false,
+            compare_window: SampleWindow::All,
+            compare_buckets: 12,
+            compare_band_filter: FrequencyFilter::All,
+            compare_min_samples: 1,
+            compare_sort_delta: false,
+            compare_sort: SortOrder::default(),
+            compare_count_ratio: 2.0,
+            calibration_offsets: HashMap::new(),
+            adapter_labels: HashMap::new(),
+            connect_test_network: None,
+            load_test_target: None,
+            load_test_secs: 10,
+            load_test_mbps: 20,
+            calibration_rounds: 5,
+            capability_prompted: false,
             excluded_aps: Vec::new(),
+            exclusion_policy: ExclusionPolicy::Hide,
+            probe_ssids: Vec::new(),
+            gpsd: None,
+            wigle_api_name: None,
+            wigle_api_token: None,
+            webhook_url: None,
+            prompt_setup: false,
+            export_anonymize: Anonymize::Off,
+            anonymize_ssids: false,
+            reset_after_failures: 5,
+            auto_reset: false,
+            live_scan_limit: 2880,
+            log_level: LevelFilter::Warn,
         }
     }
 }
 
 impl Config {
-    /// Get config file path
-    pub fn path() -> Result<PathBuf> {
-        let config_dir = dirs::config_dir()
-            .context("Could not find config directory")?
-            .join("wificomp");
-        Ok(config_dir.join("config.json"))
-    }
-
     /// Load config from disk, or create default
-    pub fn load() -> Result<Self> {
-        let path = Self::path()?;
+    pub fn load(paths: &DataPaths) -> Result<Self> {
+        let path = &paths.config_file;
         if path.exists() {
-            let contents = fs::read_to_string(&path).context("Failed to read config file")?;
+            let contents = fs::read_to_string(path).context("Failed to read config file")?;
             let config: Config =
                 serde_json::from_str(&contents).context("Failed to parse config file")?;
             Ok(config)
@@ -117,14 +519,178 @@ impl Config {
         }
     }
 
+    /// Give a detected adapter the label it was renamed to in an earlier run. Labels
+    /// saved before adapters had ids are keyed by interface and still apply.
+    pub fn label_adapter(&self, adapter: &mut Adapter) {
+        if adapter.label.is_none() {
+            adapter.label = [adapter.label_key(), adapter.interface.clone()]
+                .iter()
+                .find_map(|key| self.adapter_labels.get(key))
+                .cloned();
+        }
+    }
+
+    /// Live sort keys. `sort_by` wins when a profile or older config set it to
+    /// something else than the first saved key.
+    pub fn live_sort(&self) -> SortOrder<SortBy> {
+        match self.sort_order.primary() {
+            Some(first) if first == self.sort_by => self.sort_order.clone(),
+            _ => SortOrder::new(self.sort_by),
+        }
+    }
+
+    /// Compare sort keys, read from `compare_sort_delta` in configs older than the
+    /// sort popup
+    pub fn compare_order(&self) -> SortOrder<CompareSort> {
+        if self.compare_sort.is_empty() && self.compare_sort_delta {
+            SortOrder::by_delta()
+        } else {
+            self.compare_sort.clone()
+        }
+    }
+
     /// Save config to disk
-    pub fn save(&self) -> Result<()> {
-        let path = Self::path()?;
+    pub fn save(&self, paths: &DataPaths) -> Result<()> {
+        let path = &paths.config_file;
         if let Some(parent) = path.parent() {
             fs::create_dir_all(parent).context("Failed to create config directory")?;
         }
         let json = serde_json::to_string_pretty(self).context("Failed to serialize config")?;
-        fs::write(&path, json).context("Failed to write config file")?;
+        fs::write(path, json).context("Failed to write config file")?;
         Ok(())
     }
 }
+
+/// Named bundle of settings for a test scenario ("walk-test", "bench-soak"), stored as
+/// `profiles/<name>.json` beside the config file. Unset fields keep the config's value.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    pub auto_scan_interval_secs: Option<u64>,
+    pub default_timer_secs: Option<u64>,
+    pub timer_expiry: Option<TimerExpiry>,
+    pub frequency_filter: Option<FrequencyFilter>,
+    pub sort_by: Option<SortBy>,
+    pub group_bands: Option<bool>,
+    pub alert_threshold_dbm: Option<i32>,
+    pub probe_ssids: Option<Vec<String>>,
+}
+
+impl Profile {
+    pub fn load(paths: &DataPaths, name: &str) -> Result<Self> {
+        let path = paths.profiles_dir().join(format!("{}.json", name));
+        let contents = fs::read_to_string(&path).with_context(|| {
+            let available = Profile::list(paths);
+            if available.is_empty() {
+                format!("No profile {} (none in {})", name, paths.profiles_dir().display())
+            } else {
+                format!("No profile {} (have: {})", name, available.join(", "))
+            }
+        })?;
+        serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse profile {}", path.display()))
+    }
+
+    /// Names of the saved profiles, sorted
+    pub fn list(paths: &DataPaths) -> Vec<String> {
+        let Ok(entries) = fs::read_dir(paths.profiles_dir()) else {
+            return Vec::new();
+        };
+        let mut names: Vec<String> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let path = entry.path();
+                let is_json = path.extension().is_some_and(|e| e == "json");
+                is_json.then(|| path.file_stem()?.to_str().map(str::to_string))?
+            })
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Override the config with the fields this profile sets
+    pub fn apply(&self, config: &mut Config) {
+        if let Some(v) = self.auto_scan_interval_secs {
+            config.auto_scan_interval_secs = v;
+        }
+        if let Some(v) = self.default_timer_secs {
+            config.default_timer_secs = v;
+        }
+        if let Some(v) = &self.timer_expiry {
+            config.timer_expiry = v.clone();
+        }
+        if let Some(v) = self.frequency_filter {
+            config.frequency_filter = v;
+        }
+        if let Some(v) = self.sort_by {
+            config.sort_by = v;
+        }
+        if let Some(v) = self.group_bands {
+            config.group_bands = v;
+        }
+        if let Some(v) = self.alert_threshold_dbm {
+            config.alert_threshold_dbm = Some(v);
+        }
+        if let Some(v) = &self.probe_ssids {
+            config.probe_ssids = v.clone();
+        }
+    }
+
+    /// Put back `saved`'s values for the fields this profile sets, so saving the config
+    /// never writes the profile into it
+    pub fn restore(&self, config: &mut Config, saved: &Config) {
+        if self.auto_scan_interval_secs.is_some() {
+            config.auto_scan_interval_secs = saved.auto_scan_interval_secs;
+        }
+        if self.default_timer_secs.is_some() {
+            config.default_timer_secs = saved.default_timer_secs;
+        }
+        if self.timer_expiry.is_some() {
+            config.timer_expiry = saved.timer_expiry.clone();
+        }
+        if self.frequency_filter.is_some() {
+            config.frequency_filter = saved.frequency_filter;
+        }
+        if self.sort_by.is_some() {
+            config.sort_by = saved.sort_by;
+        }
+        if self.group_bands.is_some() {
+            config.group_bands = saved.group_bands;
+        }
+        if self.alert_threshold_dbm.is_some() {
+            config.alert_threshold_dbm = saved.alert_threshold_dbm;
+        }
+        if self.probe_ssids.is_some() {
+            config.probe_ssids = saved.probe_ssids.clone();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_apply_and_restore() {
+        let profile: Profile = serde_json::from_str(
+            r#"{"auto_scan_interval_secs": 2, "frequency_filter": "FiveGHz",
+                "alert_threshold_dbm": -70}"#,
+        )
+        .unwrap();
+        let saved = Config::default();
+        let mut config = saved.clone();
+        profile.apply(&mut config);
+        assert_eq!(config.auto_scan_interval_secs, 2);
+        assert_eq!(config.frequency_filter, FrequencyFilter::FiveGHz);
+        assert_eq!(config.alert_threshold_dbm, Some(-70));
+        assert_eq!(config.default_timer_secs, saved.default_timer_secs);
+
+        // A setting changed while the profile was active is kept; profile fields aren't
+        config.show_band = false;
+        profile.restore(&mut config, &saved);
+        assert_eq!(config.auto_scan_interval_secs, 5);
+        assert_eq!(config.frequency_filter, FrequencyFilter::All);
+        assert_eq!(config.alert_threshold_dbm, None);
+        assert!(!config.show_band);
+    }
+}
@@ -0,0 +1,98 @@
+use std::time::{Duration, Instant};
+
+use super::models::Adapter;
+
+/// A/B test on one adapter slot: adapters take turns, swapped by hand every
+/// `turn_length`, each turn saved as its own session of one run
+#[derive(Debug, Clone)]
+pub struct Alternation {
+    pub turn_length: Duration,
+    /// Shared by the sessions of every turn
+    pub run_id: String,
+    /// Adapter of each turn so far; the last one is scanning or being unplugged
+    pub turns: Vec<Adapter>,
+    /// When the current turn started; None while waiting for the swap
+    pub turn_started: Option<Instant>,
+}
+
+impl Alternation {
+    pub fn new(adapter: Adapter, turn_length: Duration, run_id: String) -> Self {
+        Self {
+            turn_length,
+            run_id,
+            turns: vec![adapter],
+            turn_started: Some(Instant::now()),
+        }
+    }
+
+    /// Whether the current turn is over and the adapters should be swapped
+    pub fn swap_due(&self) -> bool {
+        self.turn_started.is_some_and(|started| started.elapsed() >= self.turn_length)
+    }
+
+    /// Waiting for the other adapter to be plugged in
+    pub fn waiting(&self) -> bool {
+        self.turn_started.is_none()
+    }
+
+    pub fn end_turn(&mut self) {
+        self.turn_started = None;
+    }
+
+    /// Start the next turn on `adapter`, unless it's the one just unplugged being
+    /// plugged back in. Returns whether the turn started.
+    pub fn begin_turn(&mut self, adapter: Adapter) -> bool {
+        if self.turns.last().is_some_and(|last| last.same_device(&adapter)) {
+            return false;
+        }
+        self.turns.push(adapter);
+        self.turn_started = Some(Instant::now());
+        true
+    }
+
+    /// What to swap, for the swap prompt
+    pub fn prompt(&self) -> String {
+        let out = self.turns.last().map(Adapter::display_name).unwrap_or_default();
+        // The adapter before the current one takes the next turn; on the first swap
+        // there's no telling which one it is
+        let next = match self.turns.len() {
+            0 | 1 => "the other adapter".to_string(),
+            n => self.turns[n - 2].display_name(),
+        };
+        format!("Turn {} done. Unplug {}\nand plug in {}", self.turns.len(), out, next)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::fixtures;
+
+    fn adapter(id: &str, chipset: &str) -> Adapter {
+        Adapter {
+            driver: "mt7921u".to_string(),
+            chipset: chipset.to_string(),
+            id: Some(id.to_string()),
+            ..fixtures::adapter("wlan0")
+        }
+    }
+
+    #[test]
+    fn test_alternation() {
+        let (a, b) = (adapter("usb:0e8d:7961:1", "MT7921"), adapter("usb:0bda:8812:2", "RTL8812"));
+        let mut ab = Alternation::new(a.clone(), Duration::ZERO, "run".to_string());
+        assert!(ab.swap_due());
+        ab.end_turn();
+        assert!(ab.waiting() && !ab.swap_due());
+        assert_eq!(ab.prompt(), "Turn 1 done. Unplug MT7921\nand plug in the other adapter");
+
+        // Plugging the same adapter back in doesn't count as a swap
+        assert!(!ab.begin_turn(a.clone()));
+        assert!(ab.begin_turn(b));
+        assert!(!ab.waiting());
+        ab.end_turn();
+        assert_eq!(ab.prompt(), "Turn 2 done. Unplug RTL8812\nand plug in MT7921");
+        assert!(ab.begin_turn(a));
+        assert_eq!(ab.turns.len(), 3);
+    }
+}
@@ -0,0 +1,243 @@
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+
+use super::models::Session;
+
+/// How exports disguise BSSIDs (and SSIDs, with `anonymize_ssids`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Anonymize {
+    /// Export identifiers as scanned
+    #[default]
+    Off,
+    /// Keep the vendor prefix (OUI) and number the rest in order of appearance
+    Mask,
+    /// Replace with a keyed hash; the key is random per export, so it can't be reversed
+    Hash,
+}
+
+impl Anonymize {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Anonymize::Off => "off",
+            Anonymize::Mask => "mask",
+            Anonymize::Hash => "hash",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            Anonymize::Off => Anonymize::Mask,
+            Anonymize::Mask => Anonymize::Hash,
+            Anonymize::Hash => Anonymize::Off,
+        }
+    }
+}
+
+/// Maps identifiers consistently, so the same AP gets the same stand-in throughout a session
+pub struct Anonymizer {
+    mode: Anonymize,
+    ssids: bool,
+    key: RandomState,
+    bssid_map: HashMap<String, String>,
+    ssid_map: HashMap<String, String>,
+}
+
+impl Anonymizer {
+    pub fn new(mode: Anonymize, ssids: bool) -> Self {
+        Self {
+            mode,
+            ssids,
+            key: RandomState::new(),
+            bssid_map: HashMap::new(),
+            ssid_map: HashMap::new(),
+        }
+    }
+
+    fn bssid(&mut self, bssid: &str) -> String {
+        let next = self.bssid_map.len() + 1;
+        let (mode, key) = (self.mode, &self.key);
+        let entry = self.bssid_map.entry(bssid.to_lowercase());
+        entry
+            .or_insert_with_key(|bssid| match mode {
+                Anonymize::Off => bssid.clone(),
+                Anonymize::Mask => {
+                    let oui = bssid.get(..8).unwrap_or("00:00:00");
+                    let n = next.to_be_bytes();
+                    format!("{}:{:02x}:{:02x}:{:02x}", oui, n[5], n[6], n[7])
+                }
+                // Locally administered, so it can't be mistaken for a vendor address
+                Anonymize::Hash => {
+                    let hash = key.hash_one(bssid).to_be_bytes();
+                    let octets: Vec<String> =
+                        hash[..5].iter().map(|b| format!("{:02x}", b)).collect();
+                    format!("02:{}", octets.join(":"))
+                }
+            })
+            .clone()
+    }
+
+    fn ssid(&mut self, ssid: &str) -> String {
+        // Hidden networks stay recognizably hidden
+        if !self.ssids || ssid.is_empty() {
+            return ssid.to_string();
+        }
+        let next = self.ssid_map.len() + 1;
+        let (mode, key) = (self.mode, &self.key);
+        self.ssid_map
+            .entry(ssid.to_string())
+            .or_insert_with_key(|ssid| match mode {
+                Anonymize::Off => ssid.clone(),
+                Anonymize::Mask => format!("ssid-{}", next),
+                Anonymize::Hash => format!("ssid-{:08x}", key.hash_one(ssid) as u32),
+            })
+            .clone()
+    }
+
+    /// Free text with the MAC addresses and known SSIDs in it replaced, in one pass so a
+    /// stand-in is never replaced again
+    fn text(&mut self, text: &str) -> String {
+        let mut ssids: Vec<String> = self.ssid_map.keys().cloned().collect();
+        // Longest first, so "Home Guest" wins over "Home"
+        ssids.sort_by_key(|ssid| std::cmp::Reverse(ssid.len()));
+        let mut out = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(c) = rest.chars().next() {
+            if let Some(mac) = rest.get(..17).filter(|s| is_mac(s)) {
+                out.push_str(&self.bssid(mac));
+                rest = &rest[17..];
+            } else if let Some(ssid) = ssids.iter().find(|ssid| rest.starts_with(ssid.as_str())) {
+                out.push_str(&self.ssid_map[ssid]);
+                rest = &rest[ssid.len()..];
+            } else {
+                out.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+        out
+    }
+
+    /// Copy of `session` with its identifiers replaced
+    pub fn session(&mut self, session: &Session) -> Session {
+        let mut session = session.clone();
+        if self.mode == Anonymize::Off {
+            return session;
+        }
+        for scan in &mut session.scans {
+            for ap in &mut scan.access_points {
+                ap.bssid = self.bssid(&ap.bssid);
+                ap.ssid = self.ssid(&ap.ssid);
+                ap.mld_address = ap.mld_address.as_deref().map(|mld| self.bssid(mld));
+            }
+            if let Some(link) = &mut scan.link {
+                link.bssid = self.bssid(&link.bssid);
+                link.ssid = self.ssid(&link.ssid);
+            }
+        }
+        if let Some(summary) = &mut session.summary {
+            for ap in &mut summary.top_aps {
+                ap.bssid = self.bssid(&ap.bssid);
+                ap.ssid = self.ssid(&ap.ssid);
+            }
+            // Alerts are free text naming the APs
+            summary.alerts.clear();
+        }
+        for test in &mut session.connect_tests {
+            // Profiles are usually named after the network
+            test.network = self.ssid(&test.network);
+            // The portal's address gives the venue away
+            test.portal_url = None;
+        }
+        // Events name APs too, e.g. evil-twin alerts
+        for event in &mut session.events {
+            event.message = self.text(&event.message);
+        }
+        session.reindex();
+        session
+    }
+}
+
+/// Whether `s` is a colon-separated MAC address like `aa:bb:cc:dd:ee:ff`
+fn is_mac(s: &str) -> bool {
+    s.len() == 17
+        && s.bytes().enumerate().all(|(i, b)| match i % 3 {
+            2 => b == b':',
+            _ => b.is_ascii_hexdigit(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::fixtures::{adapter, ap, scan};
+    use crate::data::ConnectTiming;
+    use chrono::Utc;
+
+    fn session() -> Session {
+        let mut session = Session::new(adapter("wlan0"), None);
+        for _ in 0..2 {
+            session.scans.push(scan(vec![
+                ap("AA:BB:CC:11:22:33", "Home", -60),
+                ap("aa:bb:cc:44:55:66", "", -60),
+                ap("DD:EE:FF:11:22:33", "Home", -60),
+            ]));
+        }
+        session
+    }
+
+    fn identifiers(session: &Session, scan: usize) -> Vec<(String, String)> {
+        let aps = &session.scans[scan].access_points;
+        aps.iter().map(|ap| (ap.bssid.clone(), ap.ssid.clone())).collect()
+    }
+
+    #[test]
+    fn test_anonymizer() {
+        let masked = Anonymizer::new(Anonymize::Mask, true).session(&session());
+        let expected = vec![
+            ("aa:bb:cc:00:00:01".to_string(), "ssid-1".to_string()),
+            ("aa:bb:cc:00:00:02".to_string(), String::new()),
+            ("dd:ee:ff:00:00:03".to_string(), "ssid-1".to_string()),
+        ];
+        assert_eq!(identifiers(&masked, 0), expected);
+        assert_eq!(identifiers(&masked, 1), expected);
+
+        let bssids_only = Anonymizer::new(Anonymize::Mask, false).session(&session());
+        assert_eq!(identifiers(&bssids_only, 0)[0].1, "Home");
+
+        let hashed = Anonymizer::new(Anonymize::Hash, true).session(&session());
+        let first = identifiers(&hashed, 0);
+        assert_eq!(first, identifiers(&hashed, 1));
+        assert!(first.iter().all(|(bssid, _)| bssid.starts_with("02:") && bssid.len() == 17));
+        assert_ne!(first[0].0, first[2].0);
+        assert_eq!(first[0].1, first[2].1);
+        assert_ne!(first[0].1, "Home");
+    }
+
+    #[test]
+    fn test_anonymize_events() {
+        let mut session = session();
+        session.log_event("Possible evil twin of Home: DD:EE:FF:11:22:33 (open)".to_string());
+        session.connect_tests.push(ConnectTiming {
+            timestamp: Utc::now(),
+            network: "Home".to_string(),
+            association_ms: 120,
+            handshake_ms: 40,
+            dhcp_ms: 900,
+            reachability: None,
+            portal_url: Some("http://portal.cafe.example/login".to_string()),
+        });
+
+        let masked = Anonymizer::new(Anonymize::Mask, true).session(&session);
+        let message = &masked.events[0].message;
+        assert_eq!(message, "Possible evil twin of ssid-1: dd:ee:ff:00:00:03 (open)");
+        assert_eq!(masked.connect_tests[0].network, "ssid-1");
+        assert_eq!(masked.connect_tests[0].portal_url, None);
+
+        // With SSIDs kept only the address is replaced
+        let bssids_only = Anonymizer::new(Anonymize::Mask, false).session(&session);
+        let message = &bssids_only.events[0].message;
+        assert_eq!(message, "Possible evil twin of Home: dd:ee:ff:00:00:03 (open)");
+        assert_eq!(bssids_only.connect_tests[0].network, "Home");
+    }
+}
@@ -0,0 +1,94 @@
+use super::models::Adapter;
+
+/// Progress message from the calibration thread
+#[derive(Debug, Clone)]
+pub enum CalibrationMsg {
+    /// One scan finished; `signal` is None if the reference AP wasn't seen
+    Reading { target: bool, signal: Option<i32> },
+    /// All rounds finished
+    Done,
+}
+
+/// State of a running (or finished) calibration
+#[derive(Debug, Clone)]
+pub struct Calibration {
+    pub reference: Adapter,
+    pub target: Adapter,
+    pub bssid: String,
+    pub ssid: String,
+    pub rounds: usize,
+    pub reference_readings: Vec<i32>,
+    pub target_readings: Vec<i32>,
+    /// Scans completed (including ones that missed the AP)
+    pub reference_scans: usize,
+    pub target_scans: usize,
+    pub done: bool,
+}
+
+impl Calibration {
+    pub fn new(
+        reference: Adapter,
+        target: Adapter,
+        bssid: String,
+        ssid: String,
+        rounds: usize,
+    ) -> Self {
+        Self {
+            reference,
+            target,
+            bssid,
+            ssid,
+            rounds,
+            reference_readings: Vec::new(),
+            target_readings: Vec::new(),
+            reference_scans: 0,
+            target_scans: 0,
+            done: false,
+        }
+    }
+
+    pub fn apply(&mut self, msg: CalibrationMsg) {
+        match msg {
+            CalibrationMsg::Reading { target, signal } => {
+                let (scans, readings) = if target {
+                    (&mut self.target_scans, &mut self.target_readings)
+                } else {
+                    (&mut self.reference_scans, &mut self.reference_readings)
+                };
+                *scans += 1;
+                if let Some(s) = signal {
+                    readings.push(s);
+                }
+            }
+            CalibrationMsg::Done => self.done = true,
+        }
+    }
+
+    /// Offset to add to the target adapter's readings
+    pub fn offset(&self) -> Option<i32> {
+        calibration_offset(&self.reference_readings, &self.target_readings)
+    }
+}
+
+/// Average difference between reference and target readings of the same AP,
+/// rounded to the nearest dB. Adding the result to target readings aligns them
+/// with the reference adapter.
+pub fn calibration_offset(reference: &[i32], target: &[i32]) -> Option<i32> {
+    if reference.is_empty() || target.is_empty() {
+        return None;
+    }
+    let mean = |v: &[i32]| v.iter().sum::<i32>() as f32 / v.len() as f32;
+    Some((mean(reference) - mean(target)).round() as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calibration_offset() {
+        assert_eq!(calibration_offset(&[-50, -52], &[-55, -57]), Some(5));
+        assert_eq!(calibration_offset(&[-60], &[-57, -59]), Some(-2));
+        assert_eq!(calibration_offset(&[], &[-57]), None);
+    }
+}
@@ -1,8 +1,117 @@
 use anyhow::{Context, Result};
-use std::fs;
-use std::path::Path;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
-use super::models::Session;
+use super::models::{Adapter, ScanResult, Security, Session, SessionMarker};
+use super::summary::SessionSummary;
+
+/// File formats offered by the export dialog
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Json,
+    Csv,
+    Wigle,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 3] = [ExportFormat::Json, ExportFormat::Csv, ExportFormat::Wigle];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExportFormat::Json => "JSON",
+            ExportFormat::Csv => "CSV",
+            ExportFormat::Wigle => "WiGLE CSV",
+        }
+    }
+
+    fn file_suffix(&self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Csv => "csv",
+            ExportFormat::Wigle => "wigle.csv",
+        }
+    }
+
+    /// Timestamped file name in the current directory
+    pub fn filename(&self, stamp: &str) -> String {
+        format!("wificomp_export_{}.{}", stamp, self.file_suffix())
+    }
+
+    pub fn export(&self, session: &Session, path: &Path) -> Result<()> {
+        match self {
+            ExportFormat::Json => export_json(session, path),
+            ExportFormat::Csv => export_csv(session, path),
+            ExportFormat::Wigle => export_wigle_csv(session, path),
+        }
+    }
+}
+
+/// Provenance written beside every export, so published numbers can be traced to the
+/// adapter, software and calibration that produced them
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportManifest {
+    pub wificomp_version: &'static str,
+    pub exported_at: DateTime<Utc>,
+    pub export_file: String,
+    pub format: &'static str,
+    pub anonymized: bool,
+    /// Includes the driver and firmware versions recorded with the session
+    pub adapter: Adapter,
+    pub kernel: Option<String>,
+    pub os: &'static str,
+    pub session_started_at: DateTime<Utc>,
+    pub scan_count: usize,
+    /// Offset for this adapter's readings in Compare, if it has been calibrated
+    pub calibration_offset_db: Option<i32>,
+    /// Every stored offset, keyed by adapter name
+    pub calibration_offsets_db: BTreeMap<String, i32>,
+}
+
+impl ExportManifest {
+    /// Manifest for an export of `session`, with the kernel left for the caller to probe
+    pub fn new(
+        session: &Session,
+        format: ExportFormat,
+        export: &Path,
+        anonymized: bool,
+        offsets: BTreeMap<String, i32>,
+    ) -> Self {
+        Self {
+            wificomp_version: env!("CARGO_PKG_VERSION"),
+            exported_at: Utc::now(),
+            export_file: export
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            format: format.name(),
+            anonymized,
+            adapter: session.adapter.clone(),
+            kernel: None,
+            os: std::env::consts::OS,
+            session_started_at: session.started_at,
+            scan_count: session.scans.len(),
+            calibration_offset_db: offsets.get(&session.adapter.display_name()).copied(),
+            calibration_offsets_db: offsets,
+        }
+    }
+
+    /// `<export>.manifest.json`, next to the export
+    pub fn path_for(export: &Path) -> PathBuf {
+        let mut name = export.as_os_str().to_owned();
+        name.push(".manifest.json");
+        PathBuf::from(name)
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self).context("Failed to serialize manifest")?;
+        fs::write(path, json).context("Failed to write manifest")?;
+        Ok(())
+    }
+}
 
 /// Export a session to JSON
 pub fn export_json(session: &Session, path: &Path) -> Result<()> {
@@ -13,60 +122,332 @@ pub fn export_json(session: &Session, path: &Path) -> Result<()> {
 
 /// Export a session to CSV
 pub fn export_csv(session: &Session, path: &Path) -> Result<()> {
+    fs::write(path, render_csv(session)).context("Failed to write CSV file")?;
+    Ok(())
+}
+
+fn render_csv(session: &Session) -> String {
     let mut csv = String::new();
 
     // Header
-    csv.push_str("timestamp,bssid,ssid,signal_dbm,channel,frequency_mhz,band\n");
+    csv.push_str(
+        "timestamp,bssid,ssid,signal_dbm,channel,frequency_mhz,band,security,width_mhz,marker\n",
+    );
+
+    // Markers get rows of their own, in time order among the scans
+    let mut markers = session.markers.iter().peekable();
+    let marker_row = |csv: &mut String, marker: &SessionMarker| {
+        let timestamp = marker.timestamp.format("%Y-%m-%d %H:%M:%S");
+        csv.push_str(&format!("{},,,,,,,,,{}\n", timestamp, escape_csv(&marker.note)));
+    };
 
     // Data rows
     for scan in &session.scans {
+        while let Some(marker) = markers.next_if(|m| m.timestamp <= scan.timestamp) {
+            marker_row(&mut csv, marker);
+        }
         let timestamp = scan.timestamp.format("%Y-%m-%d %H:%M:%S").to_string();
         for ap in &scan.access_points {
             csv.push_str(&format!(
-                "{},{},{},{},{},{},{}\n",
+                "{},{},{},{},{},{},{},{},{},\n",
                 timestamp,
                 ap.bssid,
                 escape_csv(&ap.ssid),
                 ap.signal_dbm,
                 ap.channel,
                 ap.frequency_mhz,
-                ap.band().short_name()
+                ap.band().short_name(),
+                ap.security.map(|s| s.short_name()).unwrap_or(""),
+                ap.channel_width_mhz.map(|w| w.to_string()).unwrap_or_default()
             ));
         }
     }
+    for marker in markers {
+        marker_row(&mut csv, marker);
+    }
+    csv
+}
 
-    fs::write(path, csv).context("Failed to write CSV file")?;
+/// WiGLE upload endpoint
+const WIGLE_UPLOAD_URL: &str = "https://api.wigle.net/api/v2/file/upload";
+
+/// Export GPS-tagged scans in WiGLE's CSV format
+pub fn export_wigle_csv(session: &Session, path: &Path) -> Result<()> {
+    let csv = render_wigle_csv(session)?;
+    fs::write(path, csv).context("Failed to write WiGLE CSV file")?;
     Ok(())
 }
 
+fn render_wigle_csv(session: &Session) -> Result<String> {
+    let mut csv = format!(
+        "WigleWifi-1.4,appRelease={},model={},release=,device=wificomp,\
+         display=,board=,brand=\n",
+        env!("CARGO_PKG_VERSION"),
+        escape_csv(&session.adapter.chipset)
+    );
+    csv.push_str(
+        "MAC,SSID,AuthMode,FirstSeen,Channel,RSSI,CurrentLatitude,CurrentLongitude,\
+         AltitudeMeters,AccuracyMeters,Type\n",
+    );
+
+    // WiGLE places observations by position, so scans without a fix are skipped
+    let mut rows = 0;
+    for scan in &session.scans {
+        let Some(fix) = scan.position else {
+            continue;
+        };
+        let timestamp = scan.timestamp.format("%Y-%m-%d %H:%M:%S").to_string();
+        for ap in &scan.access_points {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{},{},{},{},WIFI\n",
+                ap.bssid.to_lowercase(),
+                escape_csv(&ap.ssid),
+                wigle_auth_mode(ap.security),
+                timestamp,
+                ap.channel,
+                ap.signal_dbm,
+                fix.lat,
+                fix.lon,
+                fix.alt_m.unwrap_or(0.0),
+                fix.accuracy_m.unwrap_or(0.0)
+            ));
+            rows += 1;
+        }
+    }
+
+    if rows == 0 {
+        anyhow::bail!("Session has no GPS-tagged scans (start with --gpsd)");
+    }
+    Ok(csv)
+}
+
+/// Capabilities string in the Android format WiGLE expects
+fn wigle_auth_mode(security: Option<Security>) -> &'static str {
+    match security {
+        Some(Security::Open) => "[ESS]",
+        Some(Security::Wep) => "[WEP][ESS]",
+        Some(Security::Wpa) => "[WPA-PSK-TKIP][ESS]",
+        Some(Security::Wpa2) => "[WPA2-PSK-CCMP][ESS]",
+        Some(Security::Wpa3) => "[WPA3-SAE-CCMP][ESS]",
+        None => "",
+    }
+}
+
+/// Upload a WiGLE CSV with an API name/token pair (from wigle.net/account), via curl
+pub fn upload_wigle(path: &Path, api_name: &str, api_token: &str) -> Result<String> {
+    let config = [
+        ("user", format!("{}:{}", api_name, api_token)),
+        ("form", upload_form(path)),
+        ("url", WIGLE_UPLOAD_URL.to_string()),
+    ];
+    let output = curl(&["--silent", "--show-error", "--fail-with-body"], &config)?;
+
+    let body = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if !output.status.success() {
+        anyhow::bail!(
+            "WiGLE upload failed: {} {}",
+            String::from_utf8_lossy(&output.stderr).trim(),
+            body
+        );
+    }
+    Ok(body)
+}
+
+/// `-F` value attaching `path`; -F splits on ';' and ',' unless the file name is quoted
+fn upload_form(path: &Path) -> String {
+    let name = path.to_string_lossy().replace('\\', "\\\\").replace('"', "\\\"");
+    format!("file=@\"{}\"", name)
+}
+
+/// Run curl with `options` read from a config on stdin, so tokens and URLs stay out of
+/// the process list
+fn curl(args: &[&str], options: &[(&str, String)]) -> Result<std::process::Output> {
+    use std::process::{Command, Stdio};
+    let mut child = Command::new("curl")
+        .args(args)
+        .args(["--config", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run curl. Is curl installed?")?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(curl_config(options).as_bytes())?;
+    }
+    Ok(child.wait_with_output()?)
+}
+
+/// `name = "value"` lines of a curl config
+fn curl_config(options: &[(&str, String)]) -> String {
+    options
+        .iter()
+        .map(|(name, value)| format!("{} = {}\n", name, quote(value)))
+        .collect()
+}
+
+/// Double-quote a curl config value
+fn quote(value: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' | '\\' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Longest a webhook POST may take, so a dead endpoint can't hold up quitting
+const WEBHOOK_TIMEOUT_SECS: &str = "10";
+
+/// JSON posted to `webhook_url` when a session finishes. `text` is what Slack and
+/// Matrix webhook bridges display.
+#[derive(Debug, Serialize)]
+pub struct WebhookPayload<'a> {
+    pub text: String,
+    /// `saved` or `timer_expired`
+    pub event: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<&'a Path>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node: Option<&'a str>,
+    pub summary: &'a SessionSummary,
+}
+
+impl<'a> WebhookPayload<'a> {
+    pub fn new(
+        event: &'a str,
+        summary: &'a SessionSummary,
+        path: Option<&'a Path>,
+        node: Option<&'a str>,
+    ) -> Self {
+        let mut text = format!(
+            "wificomp: {} session {} ({} scans over {}m{:02}s, {} APs",
+            summary.adapter,
+            event.replace('_', " "),
+            summary.scan_count,
+            summary.duration_secs / 60,
+            summary.duration_secs % 60,
+            summary.ap_count
+        );
+        if let Some(best) = summary.top_aps.first() {
+            text.push_str(&format!(", best {} at {} dBm", best.ssid, best.avg));
+        }
+        text.push(')');
+        match summary.alerts.len() {
+            0 => {}
+            1 => text.push_str("; 1 alert"),
+            n => text.push_str(&format!("; {} alerts", n)),
+        }
+        Self {
+            text,
+            event,
+            path,
+            node,
+            summary,
+        }
+    }
+
+    /// POST this payload to `url`, via curl
+    pub fn post(&self, url: &str) -> Result<()> {
+        let config = [
+            ("header", "Content-Type: application/json".to_string()),
+            ("data-binary", serde_json::to_string(self)?),
+            ("url", url.to_string()),
+        ];
+        let args =
+            ["--silent", "--show-error", "--fail-with-body", "--max-time", WEBHOOK_TIMEOUT_SECS];
+        let output = curl(&args, &config)?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "{} {}",
+                String::from_utf8_lossy(&output.stderr).trim(),
+                String::from_utf8_lossy(&output.stdout).trim()
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Appends each scan as one JSON line while a session runs
+#[derive(Debug)]
+pub struct NdjsonStream {
+    pub path: PathBuf,
+    file: File,
+}
+
+#[derive(Serialize)]
+struct StreamRecord<'a> {
+    adapter: &'a Adapter,
+    #[serde(flatten)]
+    scan: &'a ScanResult,
+}
+
+impl NdjsonStream {
+    /// Open `path` for appending, creating it if needed
+    pub fn open(path: &Path) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open stream file {}", path.display()))?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            file,
+        })
+    }
+
+    /// Write one scan as a single line
+    pub fn append(&mut self, adapter: &Adapter, scan: &ScanResult) -> Result<()> {
+        let mut line = serde_json::to_string(&StreamRecord { adapter, scan })
+            .context("Failed to serialize scan")?;
+        line.push('\n');
+        self.file
+            .write_all(line.as_bytes())
+            .context("Failed to write stream file")?;
+        Ok(())
+    }
+}
+
 /// Export comparison results to CSV
+#[allow(dead_code)]
 pub fn export_comparison_csv(
     sessions: &[Session],
     ap_bssid: &str,
-    ap_ssid: &str,
+    _ap_ssid: &str,
     path: &Path,
 ) -> Result<()> {
     let mut csv = String::new();
 
     // Header
-    csv.push_str("adapter,interface,label,avg_signal,min_signal,max_signal,scan_count\n");
+    csv.push_str(
+        "adapter,interface,label,avg_signal,median_signal,min_signal,max_signal,scan_count\n",
+    );
 
     // Data rows
     for session in sessions {
         if let Some(stats) = session.ap_stats(ap_bssid) {
             csv.push_str(&format!(
-                "{},{},{},{},{},{},{}\n",
+                "{},{},{},{},{},{},{},{}\n",
                 escape_csv(&session.adapter.chipset),
                 session.adapter.interface,
                 session.adapter.label.as_deref().unwrap_or(""),
                 stats.avg,
+                stats.median,
                 stats.min,
                 stats.max,
                 stats.count
             ));
         } else {
             csv.push_str(&format!(
-                "{},{},{},N/A,N/A,N/A,0\n",
+                "{},{},{},N/A,N/A,N/A,N/A,0\n",
                 escape_csv(&session.adapter.chipset),
                 session.adapter.interface,
                 session.adapter.label.as_deref().unwrap_or("")
@@ -78,6 +459,99 @@ pub fn export_comparison_csv(
     Ok(())
 }
 
+/// One line on an SVG signal chart; points are (seconds since start, dBm)
+#[derive(Debug, Clone)]
+pub struct SvgSeries {
+    pub name: String,
+    pub points: Vec<(f64, i32)>,
+}
+
+const SVG_COLORS: [&str; 6] = [
+    "#1f77b4", "#d62728", "#2ca02c", "#ff7f0e", "#9467bd", "#17becf",
+];
+
+/// Export a signal-over-time line chart as SVG
+pub fn export_svg(title: &str, series: &[SvgSeries], path: &Path) -> Result<()> {
+    fs::write(path, render_svg(title, series)).context("Failed to write SVG file")?;
+    Ok(())
+}
+
+fn render_svg(title: &str, series: &[SvgSeries]) -> String {
+    let (width, height) = (800.0, 400.0);
+    let (left, right, top, bottom) = (60.0, 160.0, 40.0, 40.0);
+    let plot_w = width - left - right;
+    let plot_h = height - top - bottom;
+
+    let max_t = series
+        .iter()
+        .flat_map(|s| s.points.iter().map(|p| p.0))
+        .fold(1.0_f64, f64::max);
+    let (min_dbm, max_dbm) = (-100.0, -20.0);
+    let x = |t: f64| left + t / max_t * plot_w;
+    let y = |dbm: i32| {
+        top + (max_dbm - (dbm as f64).clamp(min_dbm, max_dbm)) / (max_dbm - min_dbm) * plot_h
+    };
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" font-family=\"monospace\" font-size=\"12\">\n\
+         <rect width=\"{w}\" height=\"{h}\" fill=\"white\"/>\n\
+         <text x=\"{left}\" y=\"24\" font-size=\"14\">{title}</text>\n",
+        w = width,
+        h = height,
+        left = left,
+        title = escape_xml(title)
+    );
+
+    // Horizontal grid every 20 dBm
+    for dbm in (-100..=-20).step_by(20) {
+        svg.push_str(&format!(
+            "<line x1=\"{}\" y1=\"{y:.1}\" x2=\"{}\" y2=\"{y:.1}\" stroke=\"#ddd\"/>\n\
+             <text x=\"{}\" y=\"{:.1}\" text-anchor=\"end\">{}</text>\n",
+            left,
+            left + plot_w,
+            left - 6.0,
+            y(dbm) + 4.0,
+            dbm,
+            y = y(dbm)
+        ));
+    }
+    svg.push_str(&format!(
+        "<text x=\"{}\" y=\"{}\" text-anchor=\"end\">{:.0}s</text>\n",
+        left + plot_w,
+        height - 12.0,
+        max_t
+    ));
+
+    for (i, s) in series.iter().enumerate() {
+        let color = SVG_COLORS[i % SVG_COLORS.len()];
+        let points: Vec<String> = s
+            .points
+            .iter()
+            .map(|&(t, dbm)| format!("{:.1},{:.1}", x(t), y(dbm)))
+            .collect();
+        svg.push_str(&format!(
+            "<polyline fill=\"none\" stroke=\"{}\" stroke-width=\"2\" points=\"{}\"/>\n\
+             <text x=\"{}\" y=\"{}\" fill=\"{}\">{}</text>\n",
+            color,
+            points.join(" "),
+            left + plot_w + 10.0,
+            top + 16.0 * (i as f64 + 1.0),
+            color,
+            escape_xml(&s.name)
+        ));
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
 fn escape_csv(s: &str) -> String {
     if s.contains(',') || s.contains('"') || s.contains('\n') {
         format!("\"{}\"", s.replace('"', "\"\""))
@@ -85,3 +559,123 @@ fn escape_csv(s: &str) -> String {
         s.to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::fixtures::{adapter, ap, scan};
+    use crate::data::{AccessPoint, GpsFix};
+
+    #[test]
+    fn test_webhook_payload() {
+        let summary = SessionSummary {
+            adapter: "wlan0 (Test)".to_string(),
+            duration_secs: 305,
+            scan_count: 61,
+            ap_count: 12,
+            top_aps: vec![super::super::summary::ApSummary {
+                bssid: "AA:BB:CC:DD:EE:FF".to_string(),
+                ssid: "HomeNet".to_string(),
+                avg: -48,
+                min: -55,
+                max: -44,
+                count: 61,
+            }],
+            bands: Vec::new(),
+            alerts: vec!["Cafe dropped to -85 dBm".to_string()],
+        };
+        let payload = WebhookPayload::new("timer_expired", &summary, None, Some("garage"));
+        assert_eq!(
+            payload.text,
+            "wificomp: wlan0 (Test) session timer expired (61 scans over 5m05s, 12 APs, \
+             best HomeNet at -48 dBm); 1 alert"
+        );
+        let json: serde_json::Value = serde_json::to_value(&payload).unwrap();
+        assert_eq!(json["node"], "garage");
+        assert_eq!(json["summary"]["scan_count"], 61);
+        assert!(json.get("path").is_none());
+    }
+
+    #[test]
+    fn test_curl_config() {
+        let form = upload_form(Path::new("/tmp/a;b,\"c\".csv"));
+        assert_eq!(form, r#"file=@"/tmp/a;b,\"c\".csv""#);
+        let config = [("user", "me:t\\ok".to_string()), ("form", form)];
+        assert_eq!(
+            curl_config(&config),
+            concat!(
+                r#"user = "me:t\\ok""#,
+                "\n",
+                r#"form = "file=@\"/tmp/a;b,\\\"c\\\".csv\"""#,
+                "\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_export_manifest() {
+        let adapter = Adapter {
+            driver: "rtw88_8822bu".to_string(),
+            chipset: "RTL8822BU".to_string(),
+            label: Some("Dongle".to_string()),
+            ..adapter("wlan1")
+        };
+        let session = Session::new(adapter, None);
+        let offsets = BTreeMap::from([("Dongle".to_string(), -3), ("Intel WiFi".to_string(), 0)]);
+        let export = Path::new("out/wificomp_export_20240101_120000.csv");
+        let manifest = ExportManifest::new(&session, ExportFormat::Csv, export, false, offsets);
+        assert_eq!(manifest.export_file, "wificomp_export_20240101_120000.csv");
+        assert_eq!(manifest.calibration_offset_db, Some(-3));
+        assert_eq!(
+            ExportManifest::path_for(export),
+            Path::new("out/wificomp_export_20240101_120000.csv.manifest.json")
+        );
+        let json = serde_json::to_value(&manifest).unwrap();
+        assert_eq!(json["adapter"]["driver"], "rtw88_8822bu");
+        assert_eq!(json["calibration_offsets_db"]["Intel WiFi"], 0);
+        assert!(json["adapter"]["firmware_version"].is_null());
+    }
+
+    #[test]
+    fn test_render_csv() {
+        let mut session = Session::new(adapter("wlan0"), None);
+        let ap = AccessPoint {
+            security: Some(Security::Wpa2),
+            ..ap("AA:BB:CC:DD:EE:FF", "Cafe, Upstairs", -61)
+        };
+        let mut scan = scan(vec![ap]);
+        session.add_scan(scan.clone());
+        assert!(render_wigle_csv(&session).is_err());
+
+        scan.position = Some(GpsFix {
+            lat: 51.5,
+            lon: -0.12,
+            alt_m: None,
+            accuracy_m: Some(4.0),
+        });
+        session.add_scan(scan);
+        let csv = render_wigle_csv(&session).unwrap();
+        let rows: Vec<&str> = csv.lines().collect();
+        assert_eq!(rows.len(), 3);
+        assert!(rows[0].starts_with("WigleWifi-1.4,"));
+        assert!(rows[2].starts_with("aa:bb:cc:dd:ee:ff,\"Cafe, Upstairs\",[WPA2-PSK-CCMP][ESS],"));
+        assert!(rows[2].ends_with(",6,-61,51.5,-0.12,0,4,WIFI"));
+
+        // Markers get rows of their own among the scans
+        let first = session.scans[0].timestamp;
+        for (secs, note) in [(-5, "start, by the door"), (3600, "microwave on")] {
+            session.markers.push(SessionMarker {
+                timestamp: first + chrono::Duration::seconds(secs),
+                note: note.to_string(),
+            });
+        }
+        let csv = render_csv(&session);
+        let rows: Vec<&str> = csv.lines().collect();
+        assert_eq!(rows.len(), 5);
+        assert!(rows[0].ends_with(",width_mhz,marker"));
+        assert!(rows[1].ends_with(",,,,,,,,,\"start, by the door\""));
+        assert!(rows[2].starts_with(&format!("{},AA:BB:CC:DD:EE:FF,", first.format("%F %T"))));
+        assert!(rows[2].ends_with(",WPA2,,"));
+        assert!(rows[4].ends_with(",,,,,,,,,microwave on"));
+    }
+}
@@ -0,0 +1,56 @@
+//! Adapters, APs and scans for tests, so each test only spells out the fields it is
+//! about (`AccessPoint { frequency_mhz: 5180, ..ap("AA", "Home", -60) }`).
+
+use chrono::Utc;
+
+use super::{AccessPoint, Adapter, BssType, Roaming, ScanResult};
+
+/// Intel adapter on `interface`, without an id or versions
+pub fn adapter(interface: &str) -> Adapter {
+    Adapter {
+        interface: interface.to_string(),
+        driver: "iwlwifi".to_string(),
+        chipset: "Intel".to_string(),
+        label: None,
+        id: None,
+        driver_version: None,
+        firmware_version: None,
+        usb: None,
+    }
+}
+
+/// Infrastructure AP on channel 6 with no security or capabilities set
+pub fn ap(bssid: &str, ssid: &str, signal_dbm: i32) -> AccessPoint {
+    AccessPoint {
+        bssid: bssid.to_string(),
+        ssid: ssid.to_string(),
+        signal_dbm,
+        channel: 6,
+        frequency_mhz: 2437,
+        security: None,
+        channel_width_mhz: None,
+        mld_address: None,
+        bss_type: BssType::Infrastructure,
+        beacon_interval_tu: None,
+        station_count: None,
+        channel_utilization: None,
+        regulatory: None,
+        wps: false,
+        tkip_only: false,
+        roaming: Roaming::default(),
+        last_seen_ms: None,
+    }
+}
+
+/// Scan taken now that saw `access_points`
+pub fn scan(access_points: Vec<AccessPoint>) -> ScanResult {
+    ScanResult {
+        timestamp: Utc::now(),
+        access_points,
+        channel_dwell: Vec::new(),
+        link: None,
+        position: None,
+        trigger: None,
+        host: None,
+    }
+}
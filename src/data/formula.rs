@@ -0,0 +1,145 @@
+use rhai::{Dynamic, Engine, Map, Scope, AST};
+use serde::{Deserialize, Serialize};
+
+use super::models::ApStats;
+
+/// A user-defined Compare metric from the config,
+/// e.g. `{"name": "Floor", "formula": "p10 - (max - min) / 2"}`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomMetric {
+    pub name: String,
+    pub formula: String,
+}
+
+/// Steps a formula may take per value, so a runaway loop can't freeze the UI
+const MAX_OPERATIONS: u64 = 10_000;
+/// Nesting allowed in expressions, and in the bodies of functions a formula defines
+const MAX_EXPR_DEPTH: usize = 32;
+const MAX_FUNCTION_DEPTH: usize = 16;
+const MAX_CALL_LEVELS: usize = 16;
+/// Size of strings, arrays and maps a formula may build
+const MAX_DATA_SIZE: usize = 1_000;
+
+/// Session data a formula can read as `session.<field>`, over the sessions behind
+/// one value (one Compare column)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionVars {
+    /// Adapter label
+    pub adapter: String,
+    /// Remote node, empty for local adapters
+    pub node: String,
+    /// Scans in the sample window
+    pub scans: usize,
+    /// Time from the first to the last scan in the sample window
+    pub minutes: f64,
+}
+
+impl SessionVars {
+    fn to_map(&self) -> Map {
+        let mut map = Map::new();
+        map.insert("adapter".into(), self.adapter.clone().into());
+        map.insert("node".into(), self.node.clone().into());
+        map.insert("scans".into(), (self.scans as rhai::INT).into());
+        map.insert("minutes".into(), self.minutes.into());
+        map
+    }
+}
+
+/// A Rhai script ranking an AP's signal in one session; higher results rank better.
+///
+/// The script reads the stats variables (`avg`, `p10`, `count` and so on, in dBm as
+/// floats) and `session`, and returns a number. Besides arithmetic it may use `let`,
+/// `if`, loops and functions, within operation and nesting limits.
+#[derive(Debug)]
+pub struct Formula {
+    engine: Engine,
+    ast: AST,
+}
+
+/// Engine with limits that keep formulas quick, and without output to the terminal
+fn engine() -> Engine {
+    let mut engine = Engine::new();
+    engine
+        .set_max_operations(MAX_OPERATIONS)
+        .set_max_expr_depths(MAX_EXPR_DEPTH, MAX_FUNCTION_DEPTH)
+        .set_max_call_levels(MAX_CALL_LEVELS)
+        .set_max_string_size(MAX_DATA_SIZE)
+        .set_max_array_size(MAX_DATA_SIZE)
+        .set_max_map_size(MAX_DATA_SIZE)
+        .set_strict_variables(true)
+        .on_print(|_| {})
+        .on_debug(|_, _, _| {});
+    engine.disable_symbol("eval");
+    engine
+}
+
+/// Scope holding the stats and session variables
+fn scope(stats: &ApStats, session: &SessionVars) -> Scope<'static> {
+    let mut scope = Scope::new();
+    let values = [
+        ("avg", stats.avg as f64),
+        ("median", stats.median as f64),
+        ("p10", stats.p10 as f64),
+        ("p90", stats.p90 as f64),
+        ("min", stats.min as f64),
+        ("max", stats.max as f64),
+        ("count", stats.count as f64),
+    ];
+    for (name, value) in values {
+        scope.push(name, value);
+    }
+    scope.push("session", session.to_map());
+    scope
+}
+
+impl Formula {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let engine = engine();
+        let example = ApStats::from_signals(vec![0]).expect("one reading has stats");
+        let scope = scope(&example, &SessionVars::default());
+        let ast = engine.compile_with_scope(&scope, text).map_err(|e| e.to_string())?;
+        Ok(Self { engine, ast })
+    }
+
+    /// Value for one AP's stats; None when the script fails, runs out of operations
+    /// or doesn't give a finite number
+    pub fn eval(&self, stats: &ApStats, session: &SessionVars) -> Option<f64> {
+        let mut scope = scope(stats, session);
+        let value: Dynamic = self.engine.eval_ast_with_scope(&mut scope, &self.ast).ok()?;
+        let value = match value.as_float() {
+            Ok(value) => value,
+            Err(_) => value.as_int().ok()? as f64,
+        };
+        Some(value).filter(|v| v.is_finite())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_formula() {
+        let stats = ApStats::from_signals(vec![-50, -60, -70, -80]).unwrap();
+        let session = SessionVars { scans: 20, minutes: 4.0, ..Default::default() };
+        let eval = |text: &str| Formula::parse(text).unwrap().eval(&stats, &session);
+
+        assert_eq!(eval("avg"), Some(-65.0));
+        assert_eq!(eval("p10 - (max - min) / 2"), Some(-77.0 - 15.0));
+        assert_eq!(eval("-2 * abs(min) + count"), Some(-156.0));
+        assert_eq!(eval("max(max(p90, -55), median)"), Some(-53.0));
+        assert_eq!(eval("avg / (max - max)"), None);
+        assert_eq!(eval("let spread = max - min; median - spread"), Some(-95.0));
+        assert_eq!(eval("avg + count / session.scans * 10"), Some(-63.0));
+        assert_eq!(eval("if session.minutes < 5.0 { avg - 3 } else { avg }"), Some(-68.0));
+        // Runaway scripts stop at the operation limit
+        assert_eq!(eval("loop {}"), None);
+        assert_eq!(eval("\"loud\""), None);
+
+        assert!(Formula::parse("avg +").is_err());
+        assert!(Formula::parse("(avg").is_err());
+        assert!(Formula::parse("rssi * 2").unwrap_err().contains("rssi"));
+        assert!(Formula::parse(&format!("{}avg{}", "(".repeat(100), ")".repeat(100))).is_err());
+        assert!(Formula::parse("avg avg").is_err());
+    }
+}
@@ -0,0 +1,159 @@
+use std::collections::HashMap;
+
+use super::models::{AccessPoint, Band};
+
+/// Key identifying co-located radios of the same physical AP.
+///
+/// Multi-band APs usually broadcast the same SSID from BSSIDs that only differ
+/// in the low nibble of the last octet and/or the locally-administered bit of
+/// the first octet. Hidden networks are never grouped.
+pub fn colocation_key(bssid: &str, ssid: &str) -> String {
+    if ssid.is_empty() {
+        return bssid.to_uppercase();
+    }
+
+    let mut octets: Vec<u8> = bssid
+        .split(':')
+        .filter_map(|o| u8::from_str_radix(o, 16).ok())
+        .collect();
+    if octets.len() != 6 {
+        return format!("{}|{}", ssid, bssid.to_uppercase());
+    }
+
+    octets[0] &= !0x02;
+    octets[5] &= 0xF0;
+
+    let masked: Vec<String> = octets.iter().map(|o| format!("{:02X}", o)).collect();
+    format!("{}|{}", ssid, masked.join(":"))
+}
+
+/// A set of co-located radios collapsed into one row
+#[derive(Debug, Clone)]
+pub struct ApGroup<'a> {
+    pub key: String,
+    pub members: Vec<&'a AccessPoint>,
+}
+
+impl<'a> ApGroup<'a> {
+    /// Strongest member, used for sorting and selection
+    pub fn best(&self) -> &'a AccessPoint {
+        self.members
+            .iter()
+            .copied()
+            .max_by_key(|ap| ap.signal_dbm)
+            .expect("group has at least one member")
+    }
+
+    pub fn is_multi(&self) -> bool {
+        self.members.len() > 1
+    }
+
+    /// Best signal per band, in band order
+    pub fn band_signals(&self) -> Vec<(Band, i32)> {
+        let mut bands: Vec<(Band, i32)> = Vec::new();
+        for band in [Band::TwoPointFourGHz, Band::FiveGHz, Band::SixGHz] {
+            if let Some(signal) = self
+                .members
+                .iter()
+                .filter(|ap| ap.band() == band)
+                .map(|ap| ap.signal_dbm)
+                .max()
+            {
+                bands.push((band, signal));
+            }
+        }
+        bands
+    }
+}
+
+/// Group APs by co-location, preserving first-seen order
+pub fn group_aps<'a>(aps: &[&'a AccessPoint]) -> Vec<ApGroup<'a>> {
+    let mut index: HashMap<String, usize> = HashMap::new();
+    let mut groups: Vec<ApGroup<'a>> = Vec::new();
+
+    for ap in aps {
+        // Links of a multi-link AP name their MLD, which beats guessing from the BSSIDs
+        let key = match &ap.mld_address {
+            Some(mld) => format!("MLD|{}", mld),
+            None => colocation_key(&ap.bssid, &ap.ssid),
+        };
+        match index.get(&key) {
+            Some(&i) => groups[i].members.push(ap),
+            None => {
+                index.insert(key.clone(), groups.len());
+                groups.push(ApGroup {
+                    key,
+                    members: vec![ap],
+                });
+            }
+        }
+    }
+
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::fixtures;
+
+    fn ap(bssid: &str, ssid: &str, signal_dbm: i32, frequency_mhz: u32) -> AccessPoint {
+        AccessPoint {
+            channel: 0,
+            frequency_mhz,
+            ..fixtures::ap(bssid, ssid, signal_dbm)
+        }
+    }
+
+    #[test]
+    fn test_colocation_key() {
+        assert_eq!(
+            colocation_key("AA:BB:CC:DD:EE:F0", "Home"),
+            colocation_key("AA:BB:CC:DD:EE:F4", "Home")
+        );
+        assert_eq!(
+            colocation_key("A8:BB:CC:DD:EE:F0", "Home"),
+            colocation_key("AA:BB:CC:DD:EE:F1", "Home")
+        );
+        assert_ne!(
+            colocation_key("AA:BB:CC:DD:EE:F0", "Home"),
+            colocation_key("AA:BB:CC:DD:EE:F0", "Guest")
+        );
+        assert_ne!(
+            colocation_key("AA:BB:CC:DD:EE:F0", ""),
+            colocation_key("AA:BB:CC:DD:EE:F1", "")
+        );
+    }
+
+    #[test]
+    fn test_group_aps() {
+        let a = ap("AA:BB:CC:DD:EE:F0", "Home", -61, 2437);
+        let b = ap("AA:BB:CC:DD:EE:F1", "Home", -48, 5180);
+        let c = ap("11:22:33:44:55:66", "Other", -70, 2412);
+        let groups = group_aps(&[&a, &c, &b]);
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].members.len(), 2);
+        assert_eq!(groups[0].best().signal_dbm, -48);
+        assert_eq!(
+            groups[0].band_signals(),
+            vec![(Band::TwoPointFourGHz, -61), (Band::FiveGHz, -48)]
+        );
+        assert!(!groups[1].is_multi());
+    }
+
+    #[test]
+    fn test_group_mlo_links() {
+        // Links with unrelated BSSIDs still group when they share an MLD
+        let mut a = ap("AA:BB:CC:DD:EE:F0", "Home", -61, 5180);
+        let mut b = ap("12:34:56:78:9A:BC", "Home", -55, 5955);
+        let c = ap("AA:BB:CC:DD:EE:F1", "Home", -48, 2437);
+        a.mld_address = Some("AA:BB:CC:DD:EE:00".to_string());
+        b.mld_address = a.mld_address.clone();
+        let groups = group_aps(&[&a, &b, &c]);
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].members.len(), 2);
+        assert!(!groups[1].is_multi());
+    }
+}
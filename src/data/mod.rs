@@ -1,6 +1,23 @@
+pub mod alternate;
+pub mod anonymize;
+pub mod calibration;
 pub mod export;
+#[cfg(test)]
+pub mod fixtures;
+pub mod formula;
+pub mod grouping;
 pub mod models;
+pub mod outlier;
+pub mod rogue;
 pub mod session;
+pub mod spill;
+pub mod summary;
+pub mod trend;
+pub mod vendor;
+pub mod walk;
 
+pub use grouping::*;
 pub use models::*;
+pub use rogue::*;
 pub use session::*;
+pub use summary::*;
@@ -1,9 +1,15 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
 use std::time::Duration;
 
+use super::grouping::colocation_key;
+use crate::utils::percentile;
+
 /// WiFi frequency band
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum Band {
     TwoPointFourGHz,
     FiveGHz,
@@ -31,12 +37,61 @@ impl Band {
 }
 
 /// WiFi adapter information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Adapter {
     pub interface: String,
     pub driver: String,
     pub chipset: String,
     pub label: Option<String>,
+    /// Stable identity that survives renames and replugging: `mac:<permanent MAC>`, or
+    /// `usb:<vendor>:<product>` when the MAC is randomized
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Driver version from `ethtool -i`, else `modinfo`, at detection
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub driver_version: Option<String>,
+    /// Firmware version from `ethtool -i` at detection
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub firmware_version: Option<String>,
+    /// USB link the adapter negotiated, from sysfs at detection
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usb: Option<UsbLink>,
+}
+
+/// How a USB adapter is attached: the speed and power budget it negotiated, and whether
+/// it sits behind a hub or on a root port
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UsbLink {
+    /// Negotiated speed in Mbit/s: 480 for USB 2.0, 5000 and up for USB 3.x
+    pub speed_mbps: u32,
+    /// Current the adapter's configuration draws from the bus
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_power_ma: Option<u32>,
+    /// USB device path, e.g. `2-1`, or `1-4.2` for port 2 of a hub on port 4
+    pub port: String,
+}
+
+impl UsbLink {
+    /// Plugged into a hub rather than straight into the host's root port
+    pub fn behind_hub(&self) -> bool {
+        self.port.contains('.')
+    }
+
+    /// USB generation the speed belongs to
+    pub fn generation(&self) -> &'static str {
+        match self.speed_mbps {
+            0..=12 => "USB 1.1",
+            13..=480 => "USB 2.0",
+            481..=5000 => "USB 3.0",
+            _ => "USB 3.1+",
+        }
+    }
+
+    /// Short description, e.g. `USB 2.0 hub` or `USB 3.0 root`
+    pub fn label(&self) -> String {
+        let attach = if self.behind_hub() { "hub" } else { "root" };
+        format!("{} {}", self.generation(), attach)
+    }
 }
 
 impl Adapter {
@@ -51,6 +106,17 @@ impl Adapter {
         }
     }
 
+    /// Short version tag, `fw <firmware>` or else `drv <driver version>`, trimmed to the
+    /// version proper (firmware strings often trail the image file name)
+    pub fn version_label(&self) -> Option<String> {
+        let first = |v: &String| v.split_whitespace().next().unwrap_or_default().to_string();
+        match (&self.firmware_version, &self.driver_version) {
+            (Some(firmware), _) => Some(format!("fw {}", first(firmware))),
+            (None, Some(driver)) => Some(format!("drv {}", first(driver))),
+            (None, None) => None,
+        }
+    }
+
     /// Full display with interface info
     pub fn display_name_full(&self) -> String {
         let name = self.display_name();
@@ -61,12 +127,89 @@ impl Adapter {
         }
     }
 
+    /// Key of the adapter's entry in the label registry
+    pub fn label_key(&self) -> String {
+        self.id.clone().unwrap_or_else(|| self.interface.clone())
+    }
+
     /// Safe name for filenames
     pub fn safe_name(&self) -> String {
-        let name = self.display_name();
-        name.chars()
-            .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
-            .collect()
+        safe_file_name(&self.display_name())
+    }
+
+    /// Directory holding the adapter's sessions: its stable id when known, so renaming
+    /// doesn't move them, else its display name as before ids existed
+    pub fn dir_name(&self) -> String {
+        match &self.id {
+            Some(id) => safe_file_name(id),
+            None => self.safe_name(),
+        }
+    }
+
+    /// Whether a saved session's adapter is this one
+    pub fn same_device(&self, other: &Adapter) -> bool {
+        match (&self.id, &other.id) {
+            (Some(a), Some(b)) => a == b,
+            _ => {
+                self.interface == other.interface
+                    && self.driver == other.driver
+                    && self.chipset == other.chipset
+            }
+        }
+    }
+}
+
+fn safe_file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect()
+}
+
+/// Security advertised by an AP
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Security {
+    Open,
+    Wep,
+    Wpa,
+    Wpa2,
+    Wpa3,
+}
+
+impl Security {
+    pub fn short_name(&self) -> &'static str {
+        match self {
+            Security::Open => "Open",
+            Security::Wep => "WEP",
+            Security::Wpa => "WPA",
+            Security::Wpa2 => "WPA2",
+            Security::Wpa3 => "WPA3",
+        }
+    }
+}
+
+/// Kind of network a BSS belongs to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BssType {
+    /// An AP with clients
+    #[default]
+    Infrastructure,
+    /// An 802.11s mesh point
+    Mesh,
+    /// An ad-hoc (IBSS) network
+    Ibss,
+}
+
+impl BssType {
+    pub fn short_name(&self) -> &'static str {
+        match self {
+            BssType::Infrastructure => "AP",
+            BssType::Mesh => "Mesh",
+            BssType::Ibss => "IBSS",
+        }
+    }
+
+    pub fn is_infrastructure(&self) -> bool {
+        *self == BssType::Infrastructure
     }
 }
 
@@ -78,6 +221,41 @@ pub struct AccessPoint {
     pub signal_dbm: i32,
     pub channel: u32,
     pub frequency_mhz: u32,
+    #[serde(default)]
+    pub security: Option<Security>,
+    #[serde(default)]
+    pub channel_width_mhz: Option<u32>,
+    /// MLD address shared by the links of a Wi-Fi 7 multi-link (MLO) AP
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mld_address: Option<String>,
+    /// Mesh points and ad-hoc networks are kept out of summaries and Compare
+    #[serde(default, skip_serializing_if = "BssType::is_infrastructure")]
+    pub bss_type: BssType,
+    /// Time between beacons in TUs (1.024 ms)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub beacon_interval_tu: Option<u32>,
+    /// Associated stations, from the BSS Load element
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub station_count: Option<u32>,
+    /// Share of time the AP sensed the channel busy (0-100), from the BSS Load element
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel_utilization: Option<u8>,
+    /// Country and transmit power limits the AP advertises
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub regulatory: Option<ApRegulatory>,
+    /// Advertises Wi-Fi Protected Setup, whose PIN method can be brute-forced
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub wps: bool,
+    /// WPA/RSN with TKIP as the only pairwise cipher
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub tkip_only: bool,
+    /// Assisted roaming the AP advertises (802.11k/v/r)
+    #[serde(default, skip_serializing_if = "Roaming::is_none")]
+    pub roaming: Roaming,
+    /// Milliseconds since the adapter last heard the AP when the scan was read (`iw`
+    /// only); drivers return cached results, so this can be seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_seen_ms: Option<u64>,
 }
 
 impl AccessPoint {
@@ -85,6 +263,29 @@ impl AccessPoint {
         Band::from_frequency(self.frequency_mhz)
     }
 
+    pub fn is_mlo(&self) -> bool {
+        self.mld_address.is_some()
+    }
+
+    /// Deprecated or risky settings: open or WEP security, TKIP-only encryption, WPS
+    pub fn weaknesses(&self) -> Vec<&'static str> {
+        let mut weaknesses = Vec::new();
+        match self.security {
+            Some(Security::Open) => weaknesses.push("open"),
+            Some(Security::Wep) => weaknesses.push("WEP"),
+            _ if self.tkip_only => weaknesses.push("TKIP only"),
+            _ => {}
+        }
+        if self.wps {
+            weaknesses.push("WPS");
+        }
+        weaknesses
+    }
+
+    pub fn is_weak(&self) -> bool {
+        !self.weaknesses().is_empty()
+    }
+
     /// Calculate signal strength as percentage (0-100)
     /// Maps -100 dBm to 0% and -30 dBm to 100%
     pub fn signal_percent(&self) -> u8 {
@@ -93,11 +294,216 @@ impl AccessPoint {
     }
 }
 
+/// Assisted roaming support, from the RM Enabled Capabilities (802.11k), Extended
+/// Capabilities (BSS Transition, 802.11v) and Mobility Domain (802.11r) elements
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Roaming {
+    /// 802.11k radio measurement: neighbor reports point clients at candidate APs
+    #[serde(default)]
+    pub k: bool,
+    /// 802.11v BSS transition management: the AP can steer clients away
+    #[serde(default)]
+    pub v: bool,
+    /// 802.11r fast BSS transition: roams skip the full handshake
+    #[serde(default)]
+    pub r: bool,
+}
+
+impl Roaming {
+    pub fn is_none(&self) -> bool {
+        !(self.k || self.v || self.r)
+    }
+
+    /// Supported amendments as "kvr", with "-" for missing ones, e.g. "k-r"
+    pub fn label(&self) -> String {
+        [(self.k, 'k'), (self.v, 'v'), (self.r, 'r')]
+            .iter()
+            .map(|&(on, c)| if on { c } else { '-' })
+            .collect()
+    }
+}
+
+/// Regulatory information from an AP's Country, Power Constraint and TPC Report elements
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApRegulatory {
+    /// ISO 3166 country code from the Country element
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+    /// Regulatory max transmit power on the AP's channel, from the Country element
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_power_dbm: Option<i32>,
+    /// How far below the regulatory max clients must stay, from the Power Constraint element
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub power_constraint_db: Option<i32>,
+    /// The AP's own transmit power, from the TPC Report element
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tx_power_dbm: Option<i32>,
+}
+
+impl ApRegulatory {
+    /// Max power a client may use in this BSS: the regulatory max less the constraint
+    pub fn local_max_dbm(&self) -> Option<i32> {
+        Some(self.max_power_dbm? - self.power_constraint_db.unwrap_or(0))
+    }
+
+    /// Whether the advertised country disagrees with the adapter's regulatory domain.
+    /// The world domain (`00`) mismatches every country.
+    pub fn mismatches(&self, client_country: &str) -> bool {
+        self.country.as_deref().is_some_and(|c| !c.eq_ignore_ascii_case(client_country))
+    }
+}
+
+/// Time the radio spent on one channel, as reported by `iw survey dump`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChannelDwell {
+    pub frequency_mhz: u32,
+    pub active_ms: u64,
+    /// Noise floor, if the driver reports it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub noise_dbm: Option<i32>,
+    /// Time the channel was sensed busy (any energy, WiFi or not)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub busy_ms: Option<u64>,
+}
+
+/// Association state of the scanning adapter, from `iw dev <iface> link`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkStats {
+    pub bssid: String,
+    pub ssid: String,
+    pub signal_dbm: Option<i32>,
+    pub tx_bitrate_mbps: Option<f32>,
+    pub rx_bitrate_mbps: Option<f32>,
+}
+
+/// Transmit power and regulatory domain at session start. Either changes what an
+/// adapter can hear, so sessions that differ aren't directly comparable.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RadioContext {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tx_power_dbm: Option<f32>,
+    /// ISO 3166 country code from `iw reg get` (`00` is the world domain)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+}
+
+/// Physical test setup, entered by the user at session start
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionSetup {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub antenna: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub orientation: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usb_port: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extension_cable: Option<String>,
+    /// Where the adapter sat, e.g. "desk, 1m up"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<String>,
+}
+
+impl SessionSetup {
+    /// Field labels, in the order of `values`
+    pub const FIELDS: [&'static str; 5] =
+        ["Antenna", "Orientation", "USB port", "Extension cable", "Position"];
+
+    /// Field values for editing, empty where unset
+    pub fn values(&self) -> [String; 5] {
+        self.fields().map(|field| field.clone().unwrap_or_default())
+    }
+
+    /// Setup from edited values; blank fields are left unset
+    pub fn from_values(values: [String; 5]) -> Self {
+        let [antenna, orientation, usb_port, extension_cable, position] = values.map(|value| {
+            let value = value.trim();
+            (!value.is_empty()).then(|| value.to_string())
+        });
+        Self {
+            antenna,
+            orientation,
+            usb_port,
+            extension_cable,
+            position,
+        }
+    }
+
+    fn fields(&self) -> [&Option<String>; 5] {
+        [
+            &self.antenna,
+            &self.orientation,
+            &self.usb_port,
+            &self.extension_cable,
+            &self.position,
+        ]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields().iter().all(|field| field.is_none())
+    }
+
+    /// The set fields joined into one line, e.g. "dipole, vertical, USB3 rear"
+    pub fn describe(&self) -> String {
+        let values: Vec<&str> = self.fields().iter().filter_map(|f| f.as_deref()).collect();
+        values.join(", ")
+    }
+}
+
+/// Position reported by gpsd when a scan finished
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GpsFix {
+    pub lat: f64,
+    pub lon: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alt_m: Option<f64>,
+    /// Horizontal error estimate
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accuracy_m: Option<f64>,
+}
+
+/// Conditions of the scanning machine. A hot or throttled host, or a laptop on battery
+/// cutting USB power, can drag a USB adapter's results down.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct HostContext {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_temp_c: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub battery_pct: Option<u8>,
+    /// Running on battery rather than mains power
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_battery: Option<bool>,
+}
+
 /// Single scan result
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScanResult {
     pub timestamp: DateTime<Utc>,
     pub access_points: Vec<AccessPoint>,
+    /// Time spent on each channel during this scan, from the driver's survey counters
+    /// before and after it (empty if the driver doesn't report them)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub channel_dwell: Vec<ChannelDwell>,
+    /// Link the adapter was associated with during the scan (None if not connected)
+    #[serde(default)]
+    pub link: Option<LinkStats>,
+    /// GPS fix at scan time, if gpsd was enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<GpsFix>,
+    /// Shared trigger, if the scan was started in sync with other adapters
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trigger: Option<ScanTrigger>,
+    /// CPU temperature and battery when the scan finished, if `host_context` is on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host: Option<HostContext>,
+}
+
+/// Wall-clock moment the scans of several adapters were all released at, so their
+/// results cover the same environment
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ScanTrigger {
+    pub at: DateTime<Utc>,
+    /// How long after `at` this adapter's scan actually started
+    pub latency_ms: f32,
 }
 
 /// Sort options for AP list
@@ -107,6 +513,10 @@ pub enum SortBy {
     Signal,
     Ssid,
     Channel,
+    /// 2.4 GHz first, then 5 and 6 GHz
+    Band,
+    /// Least loaded first, by BSS Load channel utilization
+    Utilization,
 }
 
 impl SortBy {
@@ -114,19 +524,293 @@ impl SortBy {
         match self {
             SortBy::Signal => SortBy::Ssid,
             SortBy::Ssid => SortBy::Channel,
-            SortBy::Channel => SortBy::Signal,
+            SortBy::Channel => SortBy::Band,
+            SortBy::Band => SortBy::Utilization,
+            SortBy::Utilization => SortBy::Signal,
         }
     }
+}
 
-    pub fn name(&self) -> &'static str {
+impl SortField for SortBy {
+    const ALL: &'static [Self] =
+        &[SortBy::Signal, SortBy::Ssid, SortBy::Channel, SortBy::Band, SortBy::Utilization];
+
+    fn name(&self) -> &'static str {
         match self {
             SortBy::Signal => "signal",
             SortBy::Ssid => "ssid",
             SortBy::Channel => "channel",
+            SortBy::Band => "band",
+            SortBy::Utilization => "util",
         }
     }
 }
 
+/// Something a list can be sorted by
+pub trait SortField: Copy + PartialEq + 'static {
+    const ALL: &'static [Self];
+
+    fn name(&self) -> &'static str;
+}
+
+/// One key of a multi-key sort
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SortKey<K> {
+    pub by: K,
+    /// Flip the key's usual order
+    #[serde(default)]
+    pub reverse: bool,
+}
+
+impl<K> SortKey<K> {
+    /// `ordering` in this key's direction
+    pub fn apply(&self, ordering: Ordering) -> Ordering {
+        if self.reverse {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+}
+
+/// Sort keys in priority order, each later one breaking ties of those before it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SortOrder<K>(pub Vec<SortKey<K>>);
+
+impl<K> Default for SortOrder<K> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+
+impl<K: SortField> SortOrder<K> {
+    pub fn new(by: K) -> Self {
+        Self(vec![SortKey { by, reverse: false }])
+    }
+
+    pub fn keys(&self) -> &[SortKey<K>] {
+        &self.0
+    }
+
+    pub fn primary(&self) -> Option<K> {
+        self.0.first().map(|key| key.by)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Order of `a` and `b` by the first key telling them apart; `compare` orders
+    /// them by one key, in that key's direction
+    pub fn compare<T>(
+        &self,
+        a: &T,
+        b: &T,
+        compare: impl Fn(&SortKey<K>, &T, &T) -> Ordering,
+    ) -> Ordering {
+        self.0
+            .iter()
+            .map(|key| compare(key, a, b))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    }
+
+    /// Every field with its key when sorting by it: the keys in priority order, then
+    /// the unused fields
+    pub fn choices(&self) -> Vec<(K, Option<SortKey<K>>)> {
+        let mut choices: Vec<(K, Option<SortKey<K>>)> =
+            self.0.iter().map(|key| (key.by, Some(*key))).collect();
+        for &by in K::ALL {
+            if !self.0.iter().any(|key| key.by == by) {
+                choices.push((by, None));
+            }
+        }
+        choices
+    }
+
+    /// Make the first key sort by `by`, dropping any later key that already did
+    pub fn set_primary(&mut self, by: K) {
+        self.0.retain(|key| key.by != by);
+        self.0.insert(0, SortKey { by, reverse: false });
+    }
+
+    /// Sort by `by` last, or stop sorting by it
+    pub fn toggle(&mut self, by: K) {
+        match self.0.iter().position(|key| key.by == by) {
+            Some(i) => {
+                self.0.remove(i);
+            }
+            None => self.0.push(SortKey { by, reverse: false }),
+        }
+    }
+
+    pub fn toggle_reverse(&mut self, by: K) {
+        if let Some(key) = self.0.iter_mut().find(|key| key.by == by) {
+            key.reverse = !key.reverse;
+        }
+    }
+
+    /// Move the key sorting by `by` up or down the priorities
+    pub fn move_key(&mut self, by: K, delta: isize) {
+        let Some(i) = self.0.iter().position(|key| key.by == by) else {
+            return;
+        };
+        let to = i.saturating_add_signed(delta).min(self.0.len() - 1);
+        let key = self.0.remove(i);
+        self.0.insert(to, key);
+    }
+
+    /// Short form for hints, e.g. "band,-signal" (a "-" marks a reversed key)
+    pub fn label(&self) -> String {
+        let names: Vec<String> = self
+            .0
+            .iter()
+            .map(|key| format!("{}{}", if key.reverse { "-" } else { "" }, key.by.name()))
+            .collect();
+        names.join(",")
+    }
+}
+
+/// A column of the Live AP list
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ApColumn {
+    Ssid,
+    Bssid,
+    /// Signal in dBm and its bar
+    Signal,
+    /// Signal as a percentage
+    Percent,
+    /// Signal above the channel's noise floor
+    Snr,
+    Channel,
+    /// Channel width in MHz
+    Width,
+    Band,
+    Security,
+    Vendor,
+    /// Time since the adapter last heard the AP
+    Age,
+    /// BSS Load channel utilization
+    Util,
+    /// 802.11k/v/r support
+    Roaming,
+    /// Mesh point or ad-hoc network
+    Type,
+    /// Weak security or WPS
+    Warning,
+}
+
+impl ApColumn {
+    pub const ALL: [ApColumn; 15] = [
+        ApColumn::Ssid,
+        ApColumn::Bssid,
+        ApColumn::Signal,
+        ApColumn::Percent,
+        ApColumn::Snr,
+        ApColumn::Channel,
+        ApColumn::Width,
+        ApColumn::Band,
+        ApColumn::Security,
+        ApColumn::Vendor,
+        ApColumn::Age,
+        ApColumn::Util,
+        ApColumn::Roaming,
+        ApColumn::Type,
+        ApColumn::Warning,
+    ];
+
+    /// Name in the column chooser
+    pub fn name(&self) -> &'static str {
+        match self {
+            ApColumn::Percent => "Signal %",
+            ApColumn::Channel => "Channel",
+            ApColumn::Width => "Width",
+            ApColumn::Security => "Security",
+            ApColumn::Roaming => "k/v/r",
+            ApColumn::Warning => "Warning",
+            column => column.title(),
+        }
+    }
+
+    /// Header text
+    pub fn title(&self) -> &'static str {
+        match self {
+            ApColumn::Ssid => "SSID",
+            ApColumn::Bssid => "BSSID",
+            ApColumn::Signal => "Signal",
+            ApColumn::Percent => "%",
+            ApColumn::Snr => "SNR",
+            ApColumn::Channel => "CH",
+            ApColumn::Width => "MHz",
+            ApColumn::Band => "Band",
+            ApColumn::Security => "Sec",
+            ApColumn::Vendor => "Vendor",
+            ApColumn::Age => "Age",
+            ApColumn::Util => "Util",
+            ApColumn::Roaming => "kvr",
+            ApColumn::Type => "Type",
+            ApColumn::Warning => "⚠",
+        }
+    }
+
+    /// Width including the gap before the next column; `None` for the SSID and signal
+    /// columns, which share whatever the others leave
+    pub fn default_width(&self) -> Option<u16> {
+        match self {
+            ApColumn::Ssid | ApColumn::Signal => None,
+            ApColumn::Bssid => Some(18),
+            ApColumn::Percent | ApColumn::Band | ApColumn::Util | ApColumn::Type => Some(5),
+            ApColumn::Snr | ApColumn::Channel | ApColumn::Width | ApColumn::Roaming => Some(4),
+            ApColumn::Security | ApColumn::Age => Some(6),
+            ApColumn::Vendor => Some(11),
+            ApColumn::Warning => Some(2),
+        }
+    }
+
+    /// Columns that only appear while some listed AP has something to show in them
+    pub fn is_sparse(&self) -> bool {
+        matches!(self, ApColumn::Util | ApColumn::Roaming | ApColumn::Type | ApColumn::Warning)
+    }
+}
+
+/// A column shown in the Live AP list, in list order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnConfig {
+    pub column: ApColumn,
+    /// Fixed width, overriding the column's default (or its share of the free space)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<u16>,
+}
+
+impl ColumnConfig {
+    pub fn new(column: ApColumn) -> Self {
+        Self { column, width: None }
+    }
+
+    /// Width in use, `None` while the column shares the free space
+    pub fn width(&self) -> Option<u16> {
+        self.width.or(self.column.default_width())
+    }
+}
+
+/// Columns of the Live list until the user picks others
+pub fn default_columns() -> Vec<ColumnConfig> {
+    [
+        ApColumn::Ssid,
+        ApColumn::Signal,
+        ApColumn::Channel,
+        ApColumn::Band,
+        ApColumn::Util,
+        ApColumn::Roaming,
+        ApColumn::Type,
+        ApColumn::Warning,
+    ]
+    .into_iter()
+    .map(ColumnConfig::new)
+    .collect()
+}
+
 /// Frequency filter for AP list
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub enum FrequencyFilter {
@@ -166,6 +850,87 @@ impl FrequencyFilter {
     }
 }
 
+/// AP capability filter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum CapabilityFilter {
+    #[default]
+    All,
+    /// Wi-Fi 7 multi-link APs only
+    Mlo,
+    /// APs with deprecated security or WPS
+    Weak,
+    /// APs advertising any of 802.11k/v/r
+    Roaming,
+}
+
+impl CapabilityFilter {
+    pub fn next(&self) -> Self {
+        match self {
+            CapabilityFilter::All => CapabilityFilter::Mlo,
+            CapabilityFilter::Mlo => CapabilityFilter::Weak,
+            CapabilityFilter::Weak => CapabilityFilter::Roaming,
+            CapabilityFilter::Roaming => CapabilityFilter::All,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CapabilityFilter::All => "All",
+            CapabilityFilter::Mlo => "MLO",
+            CapabilityFilter::Weak => "Weak",
+            CapabilityFilter::Roaming => "k/v/r",
+        }
+    }
+
+    pub fn matches(&self, ap: &AccessPoint) -> bool {
+        match self {
+            CapabilityFilter::All => true,
+            CapabilityFilter::Mlo => ap.is_mlo(),
+            CapabilityFilter::Weak => ap.is_weak(),
+            CapabilityFilter::Roaming => !ap.roaming.is_none(),
+        }
+    }
+}
+
+/// BSS type filter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum TypeFilter {
+    #[default]
+    All,
+    Infrastructure,
+    Mesh,
+    Ibss,
+}
+
+impl TypeFilter {
+    pub fn next(&self) -> Self {
+        match self {
+            TypeFilter::All => TypeFilter::Infrastructure,
+            TypeFilter::Infrastructure => TypeFilter::Mesh,
+            TypeFilter::Mesh => TypeFilter::Ibss,
+            TypeFilter::Ibss => TypeFilter::All,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TypeFilter::All => "All",
+            TypeFilter::Infrastructure => BssType::Infrastructure.short_name(),
+            TypeFilter::Mesh => BssType::Mesh.short_name(),
+            TypeFilter::Ibss => BssType::Ibss.short_name(),
+        }
+    }
+
+    pub fn matches(&self, bss_type: BssType) -> bool {
+        match self {
+            TypeFilter::All => true,
+            TypeFilter::Infrastructure => bss_type == BssType::Infrastructure,
+            TypeFilter::Mesh => bss_type == BssType::Mesh,
+            TypeFilter::Ibss => bss_type == BssType::Ibss,
+        }
+    }
+}
+
 /// Timer mode for sessions
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub enum TimerMode {
@@ -174,6 +939,15 @@ pub enum TimerMode {
     Elapsed,
 }
 
+impl TimerMode {
+    pub fn next(&self) -> Self {
+        match self {
+            TimerMode::Countdown => TimerMode::Elapsed,
+            TimerMode::Elapsed => TimerMode::Countdown,
+        }
+    }
+}
+
 /// Compare match mode
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub enum MatchBy {
@@ -181,6 +955,8 @@ pub enum MatchBy {
     Bssid,
     Ssid,
     Both,
+    /// Co-located radios of a multi-band AP
+    Group,
 }
 
 impl MatchBy {
@@ -188,7 +964,8 @@ impl MatchBy {
         match self {
             MatchBy::Bssid => MatchBy::Ssid,
             MatchBy::Ssid => MatchBy::Both,
-            MatchBy::Both => MatchBy::Bssid,
+            MatchBy::Both => MatchBy::Group,
+            MatchBy::Group => MatchBy::Bssid,
         }
     }
 
@@ -197,6 +974,29 @@ impl MatchBy {
             MatchBy::Bssid => "BSSID",
             MatchBy::Ssid => "SSID",
             MatchBy::Both => "Both",
+            MatchBy::Group => "Group",
+        }
+    }
+
+    /// Key used to deduplicate APs under this match mode
+    pub fn key(&self, bssid: &str, ssid: &str) -> String {
+        match self {
+            MatchBy::Bssid => bssid.to_string(),
+            MatchBy::Ssid => ssid.to_string(),
+            MatchBy::Both => format!("{}|{}", bssid, ssid),
+            MatchBy::Group => colocation_key(bssid, ssid),
+        }
+    }
+
+    /// Whether an AP reading matches the selected (bssid, ssid)
+    pub fn matches(&self, ap: &AccessPoint, bssid: &str, ssid: &str) -> bool {
+        match self {
+            MatchBy::Bssid => ap.bssid == bssid,
+            MatchBy::Ssid => ap.ssid == ssid,
+            MatchBy::Both => ap.bssid == bssid && ap.ssid == ssid,
+            MatchBy::Group => {
+                ap.ssid == ssid && colocation_key(&ap.bssid, &ap.ssid) == colocation_key(bssid, ssid)
+            }
         }
     }
 }
@@ -206,6 +1006,11 @@ impl MatchBy {
 pub enum CompareMetric {
     #[default]
     Avg,
+    /// Robust to single outliers, usually the best for ranking adapters
+    Median,
+    /// Signal the AP stays above 90% of the time
+    P10,
+    P90,
     Min,
     Max,
 }
@@ -213,7 +1018,10 @@ pub enum CompareMetric {
 impl CompareMetric {
     pub fn next(&self) -> Self {
         match self {
-            CompareMetric::Avg => CompareMetric::Min,
+            CompareMetric::Avg => CompareMetric::Median,
+            CompareMetric::Median => CompareMetric::P10,
+            CompareMetric::P10 => CompareMetric::P90,
+            CompareMetric::P90 => CompareMetric::Min,
             CompareMetric::Min => CompareMetric::Max,
             CompareMetric::Max => CompareMetric::Avg,
         }
@@ -222,12 +1030,262 @@ impl CompareMetric {
     pub fn name(&self) -> &'static str {
         match self {
             CompareMetric::Avg => "Avg",
+            CompareMetric::Median => "Median",
+            CompareMetric::P10 => "P10",
+            CompareMetric::P90 => "P90",
             CompareMetric::Min => "Min",
             CompareMetric::Max => "Max",
         }
     }
 }
 
+/// Which scans of each session Compare uses
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum SampleWindow {
+    /// Every scan
+    #[default]
+    All,
+    /// Only scans inside the time range all sessions cover
+    Overlap,
+    /// The first N scans of each session, N being the smallest scan count
+    Equal,
+}
+
+impl SampleWindow {
+    pub fn next(&self) -> Self {
+        match self {
+            SampleWindow::All => SampleWindow::Overlap,
+            SampleWindow::Overlap => SampleWindow::Equal,
+            SampleWindow::Equal => SampleWindow::All,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SampleWindow::All => "All",
+            SampleWindow::Overlap => "Overlap",
+            SampleWindow::Equal => "Equal",
+        }
+    }
+}
+
+/// What each Compare row stands for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CompareRows {
+    #[default]
+    Session,
+    /// A probe location, pooling its sessions
+    Node,
+    /// An adapter on one firmware and driver version, pooling its sessions
+    Firmware,
+}
+
+impl CompareRows {
+    pub fn next(&self) -> Self {
+        match self {
+            CompareRows::Session => CompareRows::Node,
+            CompareRows::Node => CompareRows::Firmware,
+            CompareRows::Firmware => CompareRows::Session,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CompareRows::Session => "Session",
+            CompareRows::Node => "Node",
+            CompareRows::Firmware => "Firmware",
+        }
+    }
+}
+
+/// Host reading drawn under History's signal graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HostSeries {
+    #[default]
+    Off,
+    CpuTemp,
+    Battery,
+}
+
+impl HostSeries {
+    pub fn next(&self) -> Self {
+        match self {
+            HostSeries::Off => HostSeries::CpuTemp,
+            HostSeries::CpuTemp => HostSeries::Battery,
+            HostSeries::Battery => HostSeries::Off,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            HostSeries::Off => "off",
+            HostSeries::CpuTemp => "cpu",
+            HostSeries::Battery => "batt",
+        }
+    }
+
+    /// Suffix of the series' values
+    pub fn unit(&self) -> &'static str {
+        match self {
+            HostSeries::Off => "",
+            HostSeries::CpuTemp => "°C",
+            HostSeries::Battery => "%",
+        }
+    }
+
+    /// The series' value in a host reading, rounded to a whole unit
+    pub fn value(&self, host: &HostContext) -> Option<i32> {
+        match self {
+            HostSeries::Off => None,
+            HostSeries::CpuTemp => host.cpu_temp_c.map(|c| c.round() as i32),
+            HostSeries::Battery => host.battery_pct.map(i32::from),
+        }
+    }
+}
+
+/// What the Compare AP list can be sorted by; with no keys it keeps first-seen order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CompareSort {
+    /// Largest spread of the current metric across sessions first
+    Delta,
+    /// Strongest mean of the current metric across sessions first
+    Metric,
+    Ssid,
+    /// Least loaded first, by mean BSS Load channel utilization
+    Utilization,
+}
+
+impl SortField for CompareSort {
+    const ALL: &'static [Self] =
+        &[CompareSort::Delta, CompareSort::Metric, CompareSort::Ssid, CompareSort::Utilization];
+
+    fn name(&self) -> &'static str {
+        match self {
+            CompareSort::Delta => "Δ",
+            CompareSort::Metric => "metric",
+            CompareSort::Ssid => "ssid",
+            CompareSort::Utilization => "util",
+        }
+    }
+}
+
+impl SortOrder<CompareSort> {
+    /// Where adapters disagree most first, the less loaded AP first among equals
+    pub fn by_delta() -> Self {
+        let mut order = Self::new(CompareSort::Delta);
+        order.toggle(CompareSort::Utilization);
+        order
+    }
+}
+
+/// Something that happened during a session besides a scan (e.g. an interface reset)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionEvent {
+    pub timestamp: DateTime<Utc>,
+    pub message: String,
+}
+
+/// A note dropped on the timeline during a live session, e.g. "moved to hallway"
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionMarker {
+    pub timestamp: DateTime<Utc>,
+    pub note: String,
+}
+
+/// A stop of a guided walk test, from pressing Enter there until its dwell scans were in
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub name: String,
+    pub arrived_at: DateTime<Utc>,
+    pub left_at: DateTime<Utc>,
+}
+
+impl Checkpoint {
+    /// Scans taken while dwelling here
+    pub fn scans<'a>(&self, scans: &'a [ScanResult]) -> &'a [ScanResult] {
+        let from = scans.partition_point(|s| s.timestamp < self.arrived_at);
+        let to = scans.partition_point(|s| s.timestamp <= self.left_at).max(from);
+        &scans[from..to]
+    }
+}
+
+/// What an HTTP 204 check found after a connect test joined a network
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Reachability {
+    /// The check got its 204
+    Online,
+    /// Something answered in place of the check, usually a login page
+    CaptivePortal,
+    /// No answer at all
+    Offline,
+}
+
+impl Reachability {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Reachability::Online => "online",
+            Reachability::CaptivePortal => "captive portal",
+            Reachability::Offline => "offline",
+        }
+    }
+}
+
+/// How long joining a network took in a connect test, phase by phase
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConnectTiming {
+    pub timestamp: DateTime<Utc>,
+    /// NetworkManager connection profile that was brought up
+    pub network: String,
+    /// Until the adapter reported a link to the AP
+    pub association_ms: u64,
+    /// Until the 4-way handshake finished and IP configuration started
+    pub handshake_ms: u64,
+    /// Until a DHCP lease was acquired
+    pub dhcp_ms: u64,
+    /// Whether the internet was reachable once connected
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reachability: Option<Reachability>,
+    /// Where a captive portal redirected the check to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub portal_url: Option<String>,
+}
+
+impl ConnectTiming {
+    pub fn total_ms(&self) -> u64 {
+        self.association_ms + self.handshake_ms + self.dhcp_ms
+    }
+}
+
+/// Ping loss and latency while the adapter was sending a sustained UDP stream
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoadTest {
+    pub timestamp: DateTime<Utc>,
+    /// Host that was pinged and sent the UDP stream
+    pub target: String,
+    pub duration_secs: u64,
+    /// Bandwidth the UDP stream aimed for
+    pub load_mbps: u32,
+    pub pings_sent: u32,
+    pub pings_received: u32,
+    /// Round-trip time percentiles, None when no ping came back
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rtt_p50_ms: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rtt_p95_ms: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rtt_p99_ms: Option<f32>,
+}
+
+impl LoadTest {
+    pub fn loss_pct(&self) -> f32 {
+        if self.pings_sent == 0 {
+            return 0.0;
+        }
+        100.0 * self.pings_sent.saturating_sub(self.pings_received) as f32
+            / self.pings_sent as f32
+    }
+}
+
 /// Complete session
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Session {
@@ -238,6 +1296,39 @@ pub struct Session {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub duration_target_secs: Option<u64>,
     pub scans: Vec<ScanResult>,
+    /// Summary written when the session was last saved
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<super::summary::SessionSummary>,
+    /// Recovery actions and other notable events, in order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<SessionEvent>,
+    /// Notes the user dropped while scanning, in order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub markers: Vec<SessionMarker>,
+    /// Stops of a guided walk test, in the order they were walked
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub checkpoints: Vec<Checkpoint>,
+    /// Probe location (remote host) the scans were taken at
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub node: Option<String>,
+    /// Shared by the sessions of all nodes scanned in lockstep by one coordinator run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<String>,
+    /// Adapter tx power and country code when the session started
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub radio: Option<RadioContext>,
+    /// Antenna, orientation and placement notes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub setup: Option<SessionSetup>,
+    /// Connect tests run during the session, in order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub connect_tests: Vec<ConnectTiming>,
+    /// Load tests run during the session, in order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub load_tests: Vec<LoadTest>,
+    /// Running per-AP totals over every scan added, including any moved out of `scans`
+    #[serde(skip)]
+    pub totals: SessionTotals,
 }
 
 fn default_version() -> String {
@@ -247,22 +1338,50 @@ fn default_version() -> String {
 impl Session {
     pub fn new(adapter: Adapter, duration_target: Option<Duration>) -> Self {
         Self {
-            version: "1.0".to_string(),
+            version: super::session::CURRENT_VERSION.to_string(),
             adapter,
             started_at: Utc::now(),
             duration_target_secs: duration_target.map(|d| d.as_secs()),
             scans: Vec::new(),
+            summary: None,
+            events: Vec::new(),
+            markers: Vec::new(),
+            checkpoints: Vec::new(),
+            node: None,
+            run_id: None,
+            radio: None,
+            setup: None,
+            connect_tests: Vec::new(),
+            load_tests: Vec::new(),
+            totals: SessionTotals::default(),
         }
     }
 
     pub fn add_scan(&mut self, scan: ScanResult) {
+        self.totals.add(&scan);
         self.scans.push(scan);
     }
 
+    /// Recompute `totals` from `scans`, after loading or rewriting them
+    pub fn reindex(&mut self) {
+        self.totals = SessionTotals::default();
+        for scan in &self.scans {
+            self.totals.add(scan);
+        }
+    }
+
+    pub fn log_event(&mut self, message: String) {
+        self.events.push(SessionEvent {
+            timestamp: Utc::now(),
+            message,
+        });
+    }
+
     pub fn duration_target(&self) -> Option<Duration> {
         self.duration_target_secs.map(Duration::from_secs)
     }
 
+    #[allow(dead_code)]
     pub fn elapsed(&self) -> Duration {
         let now = Utc::now();
         let elapsed = now.signed_duration_since(self.started_at);
@@ -284,49 +1403,392 @@ impl Session {
         aps
     }
 
+    /// Per-channel dwell time, sample counts, noise and signal stability
+    pub fn channel_stats(&self) -> Vec<ChannelStats> {
+        let mut stats: std::collections::BTreeMap<u32, ChannelStats> =
+            std::collections::BTreeMap::new();
+        let mut readings: std::collections::HashMap<(u32, &str), Vec<i32>> =
+            std::collections::HashMap::new();
+        let mut noise: std::collections::HashMap<u32, Vec<i32>> = std::collections::HashMap::new();
+        let blank = |freq: u32| ChannelStats {
+            frequency_mhz: freq,
+            ..Default::default()
+        };
+
+        for scan in &self.scans {
+            for d in &scan.channel_dwell {
+                let entry = stats
+                    .entry(d.frequency_mhz)
+                    .or_insert_with(|| blank(d.frequency_mhz));
+                entry.dwell_ms += d.active_ms;
+                if let Some(busy) = d.busy_ms {
+                    *entry.busy_ms.get_or_insert(0) += busy;
+                }
+                if let Some(n) = d.noise_dbm {
+                    noise.entry(d.frequency_mhz).or_default().push(n);
+                }
+            }
+            for ap in &scan.access_points {
+                stats
+                    .entry(ap.frequency_mhz)
+                    .or_insert_with(|| blank(ap.frequency_mhz))
+                    .samples += 1;
+                readings
+                    .entry((ap.frequency_mhz, ap.bssid.as_str()))
+                    .or_default()
+                    .push(ap.signal_dbm);
+            }
+        }
+
+        // Mean per-AP standard deviation, over APs seen at least twice
+        let mut deviations: std::collections::HashMap<u32, Vec<f32>> =
+            std::collections::HashMap::new();
+        for ((freq, _), signals) in &readings {
+            if let Some(entry) = stats.get_mut(freq) {
+                entry.ap_count += 1;
+            }
+            if signals.len() >= 2 {
+                deviations.entry(*freq).or_default().push(std_dev(signals));
+            }
+        }
+
+        for entry in stats.values_mut() {
+            if let Some(devs) = deviations.get(&entry.frequency_mhz) {
+                entry.signal_stddev = Some(devs.iter().sum::<f32>() / devs.len() as f32);
+            }
+            if let Some(n) = noise.get(&entry.frequency_mhz) {
+                entry.noise_dbm =
+                    Some((n.iter().sum::<i32>() as f32 / n.len() as f32).round() as i32);
+            }
+        }
+
+        stats.into_values().collect()
+    }
+
+    /// Whether any scan in this session carries dwell data
+    pub fn has_dwell_data(&self) -> bool {
+        self.scans.iter().any(|s| !s.channel_dwell.is_empty())
+    }
+
     /// Get signal statistics for a specific AP
     pub fn ap_stats(&self, bssid: &str) -> Option<ApStats> {
-        let signals: Vec<i32> = self
-            .scans
-            .iter()
-            .flat_map(|s| s.access_points.iter())
-            .filter(|ap| ap.bssid == bssid)
-            .map(|ap| ap.signal_dbm)
-            .collect();
+        self.totals.get(bssid)?.stats()
+    }
+}
 
-        if signals.is_empty() {
-            return None;
+/// Per-AP running totals of a session, in order of first appearance
+#[derive(Debug, Clone, Default)]
+pub struct SessionTotals {
+    /// Scans counted
+    pub scans: usize,
+    pub aps: Vec<ApTotals>,
+    /// Position in `aps` by BSSID
+    index: HashMap<String, usize>,
+}
+
+impl SessionTotals {
+    fn add(&mut self, scan: &ScanResult) {
+        self.scans += 1;
+        for ap in &scan.access_points {
+            let idx = *self.index.entry(ap.bssid.clone()).or_insert_with(|| {
+                self.aps.push(ApTotals::new(ap));
+                self.aps.len() - 1
+            });
+            self.aps[idx].add(ap.signal_dbm);
         }
+    }
 
-        let sum: i32 = signals.iter().sum();
-        let avg = sum as f32 / signals.len() as f32;
-        let min = *signals.iter().min().unwrap();
-        let max = *signals.iter().max().unwrap();
+    pub fn get(&self, bssid: &str) -> Option<&ApTotals> {
+        self.aps.get(*self.index.get(bssid)?)
+    }
+}
+
+/// Count, sum, sum of squares and extremes of one AP's readings.
+///
+/// Readings are whole dBm, so a histogram of them stays small and gives exact percentiles.
+#[derive(Debug, Clone)]
+pub struct ApTotals {
+    pub bssid: String,
+    /// SSID, band and BSS type of the first reading
+    pub ssid: String,
+    pub band: Band,
+    pub bss_type: BssType,
+    pub count: usize,
+    pub sum: i64,
+    pub sum_sq: i64,
+    pub min: i32,
+    pub max: i32,
+    histogram: BTreeMap<i32, usize>,
+}
+
+impl ApTotals {
+    fn new(ap: &AccessPoint) -> Self {
+        Self {
+            bssid: ap.bssid.clone(),
+            ssid: ap.ssid.clone(),
+            band: ap.band(),
+            bss_type: ap.bss_type,
+            count: 0,
+            sum: 0,
+            sum_sq: 0,
+            min: i32::MAX,
+            max: i32::MIN,
+            histogram: BTreeMap::new(),
+        }
+    }
+
+    fn add(&mut self, signal_dbm: i32) {
+        self.count += 1;
+        self.sum += signal_dbm as i64;
+        self.sum_sq += (signal_dbm as i64).pow(2);
+        self.min = self.min.min(signal_dbm);
+        self.max = self.max.max(signal_dbm);
+        *self.histogram.entry(signal_dbm).or_default() += 1;
+    }
+
+    /// Population standard deviation of the readings, in dB
+    pub fn std_dev(&self) -> f64 {
+        if self.count == 0 {
+            return 0.0;
+        }
+        let n = self.count as f64;
+        let mean = self.sum as f64 / n;
+        (self.sum_sq as f64 / n - mean * mean).max(0.0).sqrt()
+    }
 
+    /// The `rank`th reading in ascending order
+    fn nth(&self, rank: usize) -> i32 {
+        let mut seen = 0;
+        for (&signal, &n) in &self.histogram {
+            seen += n;
+            if rank < seen {
+                return signal;
+            }
+        }
+        self.max
+    }
+
+    /// Same as `ApStats::from_signals` over the readings counted
+    pub fn stats(&self) -> Option<ApStats> {
+        if self.count == 0 {
+            return None;
+        }
+        let percentile = |p: f64| {
+            let rank = p / 100.0 * (self.count - 1) as f64;
+            let (lo, hi) = (self.nth(rank.floor() as usize), self.nth(rank.ceil() as usize));
+            let value = lo as f64 + (hi - lo) as f64 * (rank - rank.floor());
+            value.round() as i32
+        };
         Some(ApStats {
-            avg: avg.round() as i32,
-            min,
-            max,
-            count: signals.len(),
+            avg: (self.sum as f32 / self.count as f32).round() as i32,
+            min: self.min,
+            max: self.max,
+            median: percentile(50.0),
+            p10: percentile(10.0),
+            p90: percentile(90.0),
+            count: self.count,
         })
     }
 }
 
+/// Dwell time, sample count and interference indicators for one channel
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChannelStats {
+    pub frequency_mhz: u32,
+    pub dwell_ms: u64,
+    pub samples: usize,
+    /// Distinct BSSIDs seen on the channel
+    pub ap_count: usize,
+    /// Mean per-AP signal standard deviation in dB
+    pub signal_stddev: Option<f32>,
+    /// Average noise floor from survey data
+    pub noise_dbm: Option<i32>,
+    /// Total busy time from survey data
+    pub busy_ms: Option<u64>,
+}
+
+/// Noise floor above which a channel is suspected of non-WiFi interference
+const NOISE_SUSPECT_DBM: i32 = -85;
+/// Signal deviation that counts as unstable on a quiet channel
+const UNSTABLE_STDDEV_DB: f32 = 6.0;
+/// Busy fraction that is unexplained on a quiet channel
+const BUSY_SUSPECT_RATIO: f32 = 0.5;
+/// "Quiet" channel: this many APs or fewer
+const QUIET_CHANNEL_APS: usize = 2;
+
+impl ChannelStats {
+    /// Fraction of dwell time the channel was busy
+    pub fn busy_ratio(&self) -> Option<f32> {
+        let busy = self.busy_ms?;
+        (self.dwell_ms > 0).then(|| busy as f32 / self.dwell_ms as f32)
+    }
+
+    /// Reasons this channel may be affected by non-WiFi interference.
+    ///
+    /// A raised noise floor is suspicious on its own; unstable signals or high
+    /// busy time only count when too few APs are around to explain them.
+    pub fn interference_hints(&self) -> Vec<&'static str> {
+        let mut hints = Vec::new();
+        if self.noise_dbm.is_some_and(|n| n > NOISE_SUSPECT_DBM) {
+            hints.push("noise");
+        }
+        let quiet = self.ap_count <= QUIET_CHANNEL_APS;
+        if quiet && self.signal_stddev.is_some_and(|s| s >= UNSTABLE_STDDEV_DB) {
+            hints.push("unstable");
+        }
+        if quiet && self.busy_ratio().is_some_and(|r| r >= BUSY_SUSPECT_RATIO) {
+            hints.push("busy");
+        }
+        hints
+    }
+}
+
+/// Population standard deviation of dBm readings
+pub fn std_dev(values: &[i32]) -> f32 {
+    let mean = values.iter().sum::<i32>() as f32 / values.len() as f32;
+    let var = values
+        .iter()
+        .map(|&v| (v as f32 - mean).powi(2))
+        .sum::<f32>()
+        / values.len() as f32;
+    var.sqrt()
+}
+
 /// Statistics for an access point
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ApStats {
     pub avg: i32,
     pub min: i32,
     pub max: i32,
+    pub median: i32,
+    pub p10: i32,
+    pub p90: i32,
     pub count: usize,
 }
 
 impl ApStats {
+    /// Stats over a set of dBm readings (None if empty)
+    pub fn from_signals(mut signals: Vec<i32>) -> Option<Self> {
+        if signals.is_empty() {
+            return None;
+        }
+        signals.sort_unstable();
+
+        let sum: i32 = signals.iter().sum();
+        let avg = sum as f32 / signals.len() as f32;
+        let percentile = |p| percentile(&signals, p).map_or(0, |v| v.round() as i32);
+
+        Some(ApStats {
+            avg: avg.round() as i32,
+            min: signals[0],
+            max: signals[signals.len() - 1],
+            median: percentile(50.0),
+            p10: percentile(10.0),
+            p90: percentile(90.0),
+            count: signals.len(),
+        })
+    }
+
+    /// The same stats with every reading shifted by `db`, as a calibration offset does
+    pub fn offset(&self, db: i32) -> Self {
+        ApStats {
+            avg: self.avg + db,
+            min: self.min + db,
+            max: self.max + db,
+            median: self.median + db,
+            p10: self.p10 + db,
+            p90: self.p90 + db,
+            count: self.count,
+        }
+    }
+
     pub fn get(&self, metric: CompareMetric) -> i32 {
         match metric {
             CompareMetric::Avg => self.avg,
+            CompareMetric::Median => self.median,
+            CompareMetric::P10 => self.p10,
+            CompareMetric::P90 => self.p90,
             CompareMetric::Min => self.min,
             CompareMetric::Max => self.max,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::fixtures::{ap, scan};
+
+    #[test]
+    fn test_ap_stats_percentiles() {
+        let stats = ApStats::from_signals(vec![-60, -50, -90, -55, -52]).unwrap();
+        assert_eq!(stats.median, -55);
+        assert_eq!(stats.p10, -78);
+        assert_eq!(stats.p90, -51);
+        assert_eq!(stats.avg, -61);
+
+        let single = ApStats::from_signals(vec![-70]).unwrap();
+        assert_eq!((single.median, single.p10, single.p90), (-70, -70, -70));
+        assert!(ApStats::from_signals(Vec::new()).is_none());
+    }
+
+    #[test]
+    fn test_running_totals_match_stats() {
+        let signals = [-60, -50, -90, -55, -52, -55, -71];
+        let mut totals = SessionTotals::default();
+        for &signal_dbm in &signals {
+            totals.add(&scan(vec![ap("AA:BB:CC:DD:EE:FF", "Home", signal_dbm)]));
+        }
+        let ap = totals.get("AA:BB:CC:DD:EE:FF").unwrap();
+        assert_eq!(ap.stats(), ApStats::from_signals(signals.to_vec()));
+        assert!((ap.std_dev() - 13.15).abs() < 0.01);
+        assert_eq!(totals.scans, 7);
+        assert!(totals.get("00:00:00:00:00:00").is_none());
+    }
+
+    #[test]
+    fn test_interference_hints() {
+        let quiet = ChannelStats {
+            frequency_mhz: 2437,
+            dwell_ms: 1000,
+            ap_count: 1,
+            signal_stddev: Some(8.0),
+            busy_ms: Some(700),
+            ..Default::default()
+        };
+        assert_eq!(quiet.interference_hints(), vec!["unstable", "busy"]);
+
+        // Busy, jittery channels are expected when crowded
+        let crowded = ChannelStats { ap_count: 12, ..quiet.clone() };
+        assert!(crowded.interference_hints().is_empty());
+
+        let noisy = ChannelStats { noise_dbm: Some(-80), ..crowded };
+        assert_eq!(noisy.interference_hints(), vec!["noise"]);
+    }
+
+    #[test]
+    fn test_sort_order() {
+        let mut order = SortOrder::new(SortBy::Signal);
+        order.toggle(SortBy::Band);
+        order.move_key(SortBy::Band, -1);
+        order.toggle_reverse(SortBy::Signal);
+        assert_eq!(order.label(), "band,-signal");
+        assert_eq!(order.choices()[2], (SortBy::Ssid, None));
+
+        // Cycling onto a tie-breaker makes it the first key rather than a repeat
+        order.set_primary(SortBy::Signal);
+        assert_eq!(order.label(), "signal,band");
+        order.toggle(SortBy::Band);
+        assert_eq!(order, SortOrder::new(SortBy::Signal));
+    }
+
+    #[test]
+    fn test_session_setup_values() {
+        let values = ["dipole", " ", "USB3 rear ", "", "desk"].map(String::from);
+        let setup = SessionSetup::from_values(values);
+        assert_eq!(setup.orientation, None);
+        assert_eq!(setup.describe(), "dipole, USB3 rear, desk");
+        assert_eq!(setup.values()[2], "USB3 rear");
+        assert!(SessionSetup::from_values(Default::default()).is_empty());
+    }
+}
@@ -0,0 +1,85 @@
+use std::collections::{HashMap, HashSet};
+
+use super::models::{AccessPoint, ScanResult};
+
+/// Readings on each side of a point that its window spans
+const HALF_WINDOW: usize = 3;
+
+/// Scaled deviations a reading may stray from its window's median
+const THRESHOLD: f64 = 3.0;
+
+/// Median absolute deviation to standard deviation, for normally distributed noise
+const MAD_SCALE: f64 = 1.4826;
+
+/// Smallest spread a window is taken to have, in dB. Readings are whole dBm, so a
+/// steady AP's MAD is often 0 and would otherwise flag every 1 dB wobble.
+const MIN_SPREAD_DB: f64 = 1.0;
+
+/// Hampel filter over a time-ordered series: flags each reading that lies more than
+/// three scaled median absolute deviations from the median of the readings around it
+pub fn hampel(signals: &[i32]) -> Vec<bool> {
+    (0..signals.len())
+        .map(|idx| {
+            let from = idx.saturating_sub(HALF_WINDOW);
+            let to = (idx + HALF_WINDOW + 1).min(signals.len());
+            let mut window: Vec<f64> = signals[from..to].iter().map(|&s| s as f64).collect();
+            let center = median(&mut window);
+            let mut deviations: Vec<f64> = window.iter().map(|s| (s - center).abs()).collect();
+            let spread = (MAD_SCALE * median(&mut deviations)).max(MIN_SPREAD_DB);
+            (signals[idx] as f64 - center).abs() > THRESHOLD * spread
+        })
+        .collect()
+}
+
+/// Readings the Hampel filter flags in the series of each AP passing `filter`, as
+/// (scan index, BSSID)
+pub fn outlier_readings(
+    scans: &[ScanResult],
+    filter: impl Fn(&AccessPoint) -> bool,
+) -> HashSet<(usize, String)> {
+    let mut series: HashMap<&str, Vec<(usize, i32)>> = HashMap::new();
+    for (idx, scan) in scans.iter().enumerate() {
+        for ap in scan.access_points.iter().filter(|ap| filter(ap)) {
+            series.entry(&ap.bssid).or_default().push((idx, ap.signal_dbm));
+        }
+    }
+    let mut outliers = HashSet::new();
+    for (bssid, readings) in series {
+        let signals: Vec<i32> = readings.iter().map(|&(_, signal)| signal).collect();
+        for ((idx, _), outlier) in readings.iter().zip(hampel(&signals)) {
+            if outlier {
+                outliers.insert((*idx, bssid.to_string()));
+            }
+        }
+    }
+    outliers
+}
+
+fn median(values: &mut [f64]) -> f64 {
+    values.sort_by(f64::total_cmp);
+    let mid = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hampel() {
+        let signals = [-60, -61, -60, -92, -59, -61, -60, -62, -60];
+        let flagged: Vec<usize> =
+            hampel(&signals).iter().enumerate().filter(|(_, &o)| o).map(|(i, _)| i).collect();
+        assert_eq!(flagged, vec![3]);
+
+        // A steady AP's 1-2 dB wobble isn't a glitch
+        assert!(hampel(&[-50, -50, -50, -52, -50, -50]).iter().all(|&o| !o));
+        // A real step change keeps both levels
+        assert!(hampel(&[-50, -50, -50, -50, -70, -70, -70, -70]).iter().all(|&o| !o));
+        assert!(hampel(&[]).is_empty());
+    }
+}
@@ -0,0 +1,129 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use super::models::AccessPoint;
+use super::vendor::oui;
+
+/// A BSSID broadcasting the pinned SSID that doesn't look like the rest of the network
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TwinSuspect {
+    pub bssid: String,
+    /// Why it stands out, e.g. "Open where the others use WPA3"
+    pub reason: String,
+}
+
+/// BSSIDs broadcasting `ssid` that could be an evil twin: mesh points and ad-hoc
+/// networks, and APs whose security or vendor prefix (OUI) differs from the rest.
+///
+/// The most common security and OUI are taken as genuine. When two are equally
+/// common there's no telling which is, so every BSSID of the network is a suspect.
+/// A lone AP has nothing to disagree with.
+pub fn twin_suspects(aps: &[AccessPoint], ssid: &str) -> Vec<TwinSuspect> {
+    let network: Vec<&AccessPoint> = aps.iter().filter(|ap| ap.ssid == ssid).collect();
+    let mut suspects: Vec<TwinSuspect> = Vec::new();
+    let mut flag = |bssid: &str, reason: String| {
+        match suspects.iter_mut().find(|s| s.bssid == bssid) {
+            Some(suspect) => {
+                suspect.reason.push_str("; ");
+                suspect.reason.push_str(&reason);
+            }
+            None => suspects.push(TwinSuspect { bssid: bssid.to_string(), reason }),
+        }
+    };
+
+    for ap in network.iter().filter(|ap| !ap.bss_type.is_infrastructure()) {
+        flag(&ap.bssid, format!("{} network, not an AP", ap.bss_type.short_name()));
+    }
+
+    // Backends that can't tell security report none; leave those out
+    let secured: Vec<&AccessPoint> =
+        network.iter().copied().filter(|ap| ap.security.is_some()).collect();
+    let security = |ap: &AccessPoint| ap.security.map(|s| s.short_name()).unwrap_or_default();
+    for (ap, usual) in outliers(&secured, security) {
+        let reason = match usual {
+            Some(usual) => format!("{} where the others use {}", security(ap), usual),
+            None => format!("{} among mixed security", security(ap)),
+        };
+        flag(&ap.bssid, reason);
+    }
+
+    for (ap, usual) in outliers(&network, |ap| oui(&ap.bssid)) {
+        let reason = match usual {
+            Some(usual) => format!("vendor {} where the others are {}", oui(&ap.bssid), usual),
+            None => format!("vendor {} among mixed vendors", oui(&ap.bssid)),
+        };
+        flag(&ap.bssid, reason);
+    }
+
+    suspects
+}
+
+/// APs whose `key` isn't the most common one, with that key; `None` when several
+/// keys tie for most common, making every AP an outlier
+fn outliers<'a, K: Eq + Hash + Clone>(
+    network: &[&'a AccessPoint],
+    key: impl Fn(&AccessPoint) -> K,
+) -> Vec<(&'a AccessPoint, Option<K>)> {
+    let mut counts: HashMap<K, usize> = HashMap::new();
+    for ap in network {
+        *counts.entry(key(ap)).or_default() += 1;
+    }
+    if counts.len() < 2 {
+        return Vec::new();
+    }
+    let most = counts.values().copied().max().unwrap_or(0);
+    let mut leaders = counts.into_iter().filter(|&(_, count)| count == most);
+    let usual = match (leaders.next(), leaders.next()) {
+        (Some((key, _)), None) => Some(key),
+        _ => None,
+    };
+    network
+        .iter()
+        .filter(|ap| usual.as_ref() != Some(&key(ap)))
+        .map(|ap| (*ap, usual.clone()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{fixtures, BssType, Security};
+
+    fn ap(bssid: &str, ssid: &str, security: Security) -> AccessPoint {
+        AccessPoint {
+            security: Some(security),
+            ..fixtures::ap(bssid, ssid, -60)
+        }
+    }
+
+    #[test]
+    fn test_twin_suspects() {
+        let mut aps = vec![
+            ap("3C:84:6A:10:00:01", "Campus", Security::Wpa3),
+            ap("3C:84:6A:10:00:02", "Campus", Security::Wpa3),
+            // Extra BSSID of the same radio
+            ap("3E:84:6A:10:00:03", "Campus", Security::Wpa3),
+            ap("00:14:6C:30:00:01", "Campus", Security::Open),
+            ap("F0:9F:C2:20:00:01", "Guest", Security::Open),
+        ];
+        let suspects = twin_suspects(&aps, "Campus");
+        assert_eq!(
+            suspects,
+            vec![TwinSuspect {
+                bssid: "00:14:6C:30:00:01".to_string(),
+                reason: "Open where the others use WPA3; \
+                         vendor 00:14:6C where the others are 3C:84:6A"
+                    .to_string(),
+            }]
+        );
+
+        aps[3].bss_type = BssType::Ibss;
+        assert!(twin_suspects(&aps, "Campus")[0].reason.starts_with("IBSS network"));
+
+        // Nothing to compare against
+        assert!(twin_suspects(&aps, "Guest").is_empty());
+        // Two different APs: either could be the impostor
+        let pair = [aps[0].clone(), ap("00:14:6C:30:00:01", "Campus", Security::Wpa3)];
+        assert_eq!(twin_suspects(&pair, "Campus").len(), 2);
+    }
+}
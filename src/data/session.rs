@@ -3,59 +3,243 @@ use chrono::Utc;
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use super::models::Session;
-
-/// Get the sessions directory path
-pub fn sessions_dir() -> Result<PathBuf> {
-    let data_dir = dirs::data_dir()
-        .context("Could not find data directory")?
-        .join("wificomp")
-        .join("sessions");
-    Ok(data_dir)
+use super::models::{Adapter, Session};
+
+mod migrations;
+
+/// Session file format version written by this build
+pub const CURRENT_VERSION: &str = "2.0";
+
+/// Environment variable overriding the data directory
+pub const DATA_DIR_ENV: &str = "WIFICOMP_DATA_DIR";
+
+/// File in an adapter directory recording whose sessions it holds, with the label to show
+const ADAPTER_META: &str = "adapter.meta";
+/// Adapter directory for legacy sessions that don't say which adapter recorded them
+const UNKNOWN_ADAPTER_DIR: &str = "unknown";
+
+/// Resolved locations for sessions and config
+#[derive(Debug, Clone)]
+pub struct DataPaths {
+    pub sessions_dir: PathBuf,
+    pub config_file: PathBuf,
+}
+
+impl DataPaths {
+    /// Resolve paths: `--data-dir` flag, then `WIFICOMP_DATA_DIR`, then XDG defaults.
+    ///
+    /// An override directory holds both `sessions/` and `config.json`.
+    pub fn resolve(data_dir: Option<PathBuf>) -> Result<Self> {
+        let override_dir = data_dir.or_else(|| {
+            std::env::var_os(DATA_DIR_ENV)
+                .filter(|v| !v.is_empty())
+                .map(PathBuf::from)
+        });
+
+        if let Some(dir) = override_dir {
+            return Ok(Self::in_dir(&dir));
+        }
+
+        let sessions_dir = dirs::data_dir()
+            .context("Could not find data directory")?
+            .join("wificomp")
+            .join("sessions");
+        let config_file = dirs::config_dir()
+            .context("Could not find config directory")?
+            .join("wificomp")
+            .join("config.json");
+        Ok(Self {
+            sessions_dir,
+            config_file,
+        })
+    }
+
+    /// Debug log, next to the sessions directory
+    pub fn log_file(&self) -> PathBuf {
+        self.sessions_dir.with_file_name("wificomp.log")
+    }
+
+    /// Scenario profiles, next to the config file
+    pub fn profiles_dir(&self) -> PathBuf {
+        self.config_file.with_file_name("profiles")
+    }
+
+    /// Paths rooted at a single directory
+    pub fn in_dir(dir: &Path) -> Self {
+        Self {
+            sessions_dir: dir.join("sessions"),
+            config_file: dir.join("config.json"),
+        }
+    }
 }
 
 /// Ensure the sessions directory exists
-pub fn ensure_sessions_dir() -> Result<PathBuf> {
-    let dir = sessions_dir()?;
+pub fn ensure_sessions_dir(paths: &DataPaths) -> Result<PathBuf> {
+    let dir = paths.sessions_dir.clone();
     fs::create_dir_all(&dir).context("Failed to create sessions directory")?;
     Ok(dir)
 }
 
-/// Ensure an adapter subdirectory exists
-pub fn ensure_adapter_dir(adapter: &super::models::Adapter) -> Result<PathBuf> {
-    let base_dir = ensure_sessions_dir()?;
-    let adapter_dir = base_dir.join(adapter.safe_name());
+/// Ensure an adapter subdirectory exists, with its metadata up to date
+pub fn ensure_adapter_dir(paths: &DataPaths, adapter: &Adapter) -> Result<PathBuf> {
+    let base_dir = ensure_sessions_dir(paths)?;
+    let adapter_dir = base_dir.join(adapter.dir_name());
     fs::create_dir_all(&adapter_dir).context("Failed to create adapter directory")?;
+    if adapter.id.is_some() && read_adapter_meta(&adapter_dir).as_ref() != Some(adapter) {
+        let json = serde_json::to_string_pretty(adapter).context("Failed to serialize adapter")?;
+        fs::write(adapter_dir.join(ADAPTER_META), json)
+            .context("Failed to write adapter metadata")?;
+    }
     Ok(adapter_dir)
 }
 
+/// The adapter an id-keyed directory belongs to; None for directories named by label
+fn read_adapter_meta(adapter_dir: &Path) -> Option<Adapter> {
+    let contents = fs::read_to_string(adapter_dir.join(ADAPTER_META)).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+/// The adapter's sessions still in directories named by labels (as used before adapters
+/// had stable ids)
+#[derive(Debug, Default, PartialEq)]
+pub struct LabelDirSessions {
+    /// Saved with the adapter's id, or under the label registered for its id
+    pub ours: Vec<PathBuf>,
+    /// Same interface, driver and chipset under another label: maybe an identical
+    /// adapter's, so only moved if the user says so
+    pub unsure: Vec<PathBuf>,
+}
+
+/// Find the adapter's sessions in label directories. Only looks before its id directory
+/// exists, so it runs once per adapter.
+pub fn find_label_dir_sessions(
+    paths: &DataPaths,
+    adapter: &Adapter,
+    registered_label: Option<&str>,
+) -> Result<LabelDirSessions> {
+    let mut found = LabelDirSessions::default();
+    let target = paths.sessions_dir.join(adapter.dir_name());
+    if adapter.id.is_none() || target.exists() || !paths.sessions_dir.exists() {
+        return Ok(found);
+    }
+
+    for entry in fs::read_dir(&paths.sessions_dir).context("Failed to read sessions directory")? {
+        let dir = entry?.path();
+        if !dir.is_dir() || dir.join(ADAPTER_META).exists() {
+            continue;
+        }
+        for path in list_sessions_in_dir(&dir)? {
+            let Ok(saved) = load_session(&path).map(|s| s.adapter) else { continue };
+            if !adapter.same_device(&saved) {
+                continue;
+            }
+            let registered = registered_label.is_some_and(|l| saved.label.as_deref() == Some(l));
+            if saved.id.is_some() || registered {
+                found.ours.push(path);
+            } else {
+                found.unsure.push(path);
+            }
+        }
+    }
+    Ok(found)
+}
+
+/// Move sessions into the adapter's id directory, creating it, and remove the directories
+/// they leave empty. A session whose name is taken there stays; returns how many moved.
+pub fn move_into_adapter_dir(
+    paths: &DataPaths,
+    adapter: &Adapter,
+    files: &[PathBuf],
+) -> Result<usize> {
+    let target = ensure_adapter_dir(paths, adapter)?;
+    let mut moved = 0;
+    for path in files {
+        let Some(name) = path.file_name() else { continue };
+        if !target.join(name).exists() {
+            fs::rename(path, target.join(name))
+                .with_context(|| format!("Failed to move {}", path.display()))?;
+            moved += 1;
+        }
+        // Only removed once nothing else is left in it
+        if let Some(dir) = path.parent() {
+            let _ = fs::remove_dir(dir);
+        }
+    }
+    Ok(moved)
+}
+
 /// Generate a session filename (without adapter prefix since it's in a subdirectory now)
 pub fn session_filename() -> String {
     let timestamp = Utc::now().format("%Y%m%d_%H%M%S");
     format!("{}.json", timestamp)
 }
 
-/// Save a session to disk (in adapter subdirectory)
-pub fn save_session(session: &Session) -> Result<PathBuf> {
-    let adapter_dir = ensure_adapter_dir(&session.adapter)?;
-    let filename = session_filename();
-    let path = adapter_dir.join(&filename);
+/// Fresh timestamped file for a session in its adapter subdirectory.
+///
+/// Sessions of one run can share an adapter model and a timestamp, so the node and, for
+/// run sessions, the interface go in the name.
+pub fn new_session_path(paths: &DataPaths, session: &Session) -> Result<PathBuf> {
+    let adapter_dir = ensure_adapter_dir(paths, &session.adapter)?;
+    let safe = |name: &str| -> String {
+        name.chars()
+            .map(|c| if c.is_alphanumeric() || c == '-' { c } else { '_' })
+            .collect()
+    };
+    let mut suffix = String::new();
+    if let Some(node) = &session.node {
+        suffix.push_str(&format!("_{}", safe(node)));
+    }
+    if session.run_id.is_some() {
+        suffix.push_str(&format!("_{}", safe(&session.adapter.interface)));
+    }
+    let filename = session_filename().replace(".json", &format!("{}.json", suffix));
+    Ok(adapter_dir.join(filename))
+}
+
+/// Sessions of other nodes scanned in the same coordinator run. Session files are named
+/// by when they were saved, so ones saved before the run started aren't read.
+pub fn find_run_sessions(paths: &DataPaths, run_id: &str, exclude: &Path) -> Vec<Session> {
+    list_sessions(paths)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|path| path != exclude && !saved_before(path, run_id))
+        .filter_map(|path| load_session(&path).ok())
+        .filter(|session| session.run_id.as_deref() == Some(run_id))
+        .collect()
+}
+
+/// Whether a session file's timestamped name (`20240115_143000...`) is older than
+/// `run_id`, a run's start in the same format. Other names can't be dated.
+fn saved_before(path: &Path, run_id: &str) -> bool {
+    let saved = path.file_stem().and_then(|s| s.to_str()?.get(..run_id.len()));
+    saved.is_some_and(|saved| {
+        saved.bytes().all(|b| b.is_ascii_digit() || b == b'_') && saved < run_id
+    })
+}
 
+/// Save a session to a specific file, replacing it
+pub fn save_session_to(path: &Path, session: &Session) -> Result<PathBuf> {
     let json = serde_json::to_string_pretty(session).context("Failed to serialize session")?;
-    fs::write(&path, json).context("Failed to write session file")?;
+    fs::write(path, json).context("Failed to write session file")?;
 
-    Ok(path)
+    Ok(path.to_path_buf())
 }
 
-/// Load a session from disk
+/// Load a session from disk, upgrading older schema versions
 pub fn load_session(path: &Path) -> Result<Session> {
     let contents = fs::read_to_string(path).context("Failed to read session file")?;
-    let session: Session = serde_json::from_str(&contents).context("Failed to parse session file")?;
+    let raw: serde_json::Value =
+        serde_json::from_str(&contents).context("Failed to parse session file")?;
+    let doc = migrations::migrate(raw)?;
+    let mut session: Session =
+        serde_json::from_value(doc).context("Failed to parse session file")?;
+    session.reindex();
     Ok(session)
 }
 
 /// Session validation result
 #[derive(Debug)]
+#[allow(dead_code)]
 pub struct SessionValidation {
     pub is_valid: bool,
     pub has_scans: bool,
@@ -106,6 +290,44 @@ pub fn load_session_validated(path: &Path) -> Result<(Session, SessionValidation
     Ok((session, validation))
 }
 
+/// Session files saved directly in the sessions directory, as before adapter directories
+pub fn legacy_sessions(paths: &DataPaths) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(&paths.sessions_dir) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_file() && p.extension().is_some_and(|e| e == "json"))
+        .collect()
+}
+
+/// Move legacy sessions into their adapter's directory, or `unknown` when the file
+/// can't tell. Returns how many moved; files whose name is taken stay where they are.
+pub fn migrate_legacy_sessions(paths: &DataPaths, files: &[PathBuf]) -> Result<usize> {
+    let mut moved = 0;
+    for path in files {
+        let dir = match load_session(path) {
+            Ok(session) => ensure_adapter_dir(paths, &session.adapter)?,
+            Err(e) => {
+                log::warn!("no adapter for {}: {:#}", path.display(), e);
+                let dir = paths.sessions_dir.join(UNKNOWN_ADAPTER_DIR);
+                fs::create_dir_all(&dir).context("Failed to create adapter directory")?;
+                dir
+            }
+        };
+        let Some(name) = path.file_name() else { continue };
+        if dir.join(name).exists() {
+            log::warn!("not moving {}: {} has a file by that name", path.display(), dir.display());
+            continue;
+        }
+        fs::rename(path, dir.join(name))
+            .with_context(|| format!("Failed to move {}", path.display()))?;
+        moved += 1;
+    }
+    Ok(moved)
+}
+
 /// Adapter directory info
 #[derive(Debug, Clone)]
 pub struct AdapterDirInfo {
@@ -121,11 +343,8 @@ impl AdapterDirInfo {
 }
 
 /// List all adapter directories
-pub fn list_adapter_dirs() -> Result<Vec<AdapterDirInfo>> {
-    let dir = match sessions_dir() {
-        Ok(d) => d,
-        Err(_) => return Ok(Vec::new()),
-    };
+pub fn list_adapter_dirs(paths: &DataPaths) -> Result<Vec<AdapterDirInfo>> {
+    let dir = &paths.sessions_dir;
 
     if !dir.exists() {
         return Ok(Vec::new());
@@ -133,15 +352,19 @@ pub fn list_adapter_dirs() -> Result<Vec<AdapterDirInfo>> {
 
     let mut adapters: Vec<AdapterDirInfo> = Vec::new();
 
-    for entry in fs::read_dir(&dir).context("Failed to read sessions directory")? {
+    for entry in fs::read_dir(dir).context("Failed to read sessions directory")? {
         let entry = entry?;
         let path = entry.path();
 
         if path.is_dir() {
-            let name = path.file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("unknown")
-                .to_string();
+            let name = match read_adapter_meta(&path) {
+                Some(adapter) => adapter.display_name(),
+                None => path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or("unknown")
+                    .to_string(),
+            };
 
             // Count sessions in this directory
             let session_count = fs::read_dir(&path)
@@ -164,7 +387,7 @@ pub fn list_adapter_dirs() -> Result<Vec<AdapterDirInfo>> {
     }
 
     // Sort by name
-    adapters.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+    adapters.sort_by_key(|a| a.name.to_lowercase());
 
     Ok(adapters)
 }
@@ -193,11 +416,8 @@ pub fn list_sessions_in_dir(adapter_dir: &Path) -> Result<Vec<PathBuf>> {
 }
 
 /// List all saved sessions (legacy - scans all directories)
-pub fn list_sessions() -> Result<Vec<PathBuf>> {
-    let dir = match sessions_dir() {
-        Ok(d) => d,
-        Err(_) => return Ok(Vec::new()),
-    };
+pub fn list_sessions(paths: &DataPaths) -> Result<Vec<PathBuf>> {
+    let dir = &paths.sessions_dir;
 
     if !dir.exists() {
         return Ok(Vec::new());
@@ -206,7 +426,7 @@ pub fn list_sessions() -> Result<Vec<PathBuf>> {
     let mut sessions: Vec<PathBuf> = Vec::new();
 
     // Check for legacy sessions in root directory
-    for entry in fs::read_dir(&dir).context("Failed to read sessions directory")? {
+    for entry in fs::read_dir(dir).context("Failed to read sessions directory")? {
         let entry = entry?;
         let path = entry.path();
 
@@ -232,6 +452,7 @@ pub fn list_sessions() -> Result<Vec<PathBuf>> {
 
 /// Session info for listing purposes
 #[derive(Debug, Clone)]
+#[allow(dead_code)]
 pub struct SessionInfo {
     pub path: PathBuf,
     pub adapter_name: String,
@@ -240,6 +461,7 @@ pub struct SessionInfo {
     pub label: Option<String>,
     pub started_at: String,
     pub scan_count: usize,
+    pub summary: Option<super::summary::SessionSummary>,
 }
 
 impl SessionInfo {
@@ -253,19 +475,24 @@ impl SessionInfo {
             label: session.adapter.label,
             started_at: session.started_at.format("%m-%d %H:%M").to_string(),
             scan_count: session.scans.len(),
+            summary: session.summary,
         })
     }
 
     /// Display string for file picker (shorter, no adapter name since we're in adapter dir)
     pub fn display_string(&self) -> String {
-        format!(
-            "{} - {} scans",
-            self.started_at,
-            self.scan_count
-        )
+        let mut text = format!("{} - {} scans", self.started_at, self.scan_count);
+        if let Some(summary) = &self.summary {
+            text.push_str(&format!(", {} APs", summary.ap_count));
+            if let Some(best) = summary.top_aps.first() {
+                text.push_str(&format!(", best {}", best.avg));
+            }
+        }
+        text
     }
 
     /// Full display string with adapter name
+    #[allow(dead_code)]
     pub fn display_string_full(&self) -> String {
         format!(
             "{} ({}) - {} scans",
@@ -289,13 +516,114 @@ pub fn list_session_infos_in_dir(adapter_dir: &Path) -> Result<Vec<SessionInfo>>
 }
 
 /// List all sessions with info (legacy)
-pub fn list_session_infos() -> Result<Vec<SessionInfo>> {
-    let paths = list_sessions()?;
+#[allow(dead_code)]
+pub fn list_session_infos(paths: &DataPaths) -> Result<Vec<SessionInfo>> {
+    let files = list_sessions(paths)?;
     let mut infos = Vec::new();
-    for path in paths {
+    for path in files {
         if let Ok(info) = SessionInfo::from_path(&path) {
             infos.push(info);
         }
     }
     Ok(infos)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::fixtures;
+
+    fn adapter(label: Option<&str>, id: Option<&str>) -> Adapter {
+        Adapter {
+            driver: "rtw88_8822bu".to_string(),
+            chipset: "Realtek".to_string(),
+            label: label.map(str::to_string),
+            id: id.map(str::to_string),
+            ..fixtures::adapter("wlan1")
+        }
+    }
+
+    #[test]
+    fn test_migrate_adapter_dirs() {
+        let dir = std::env::temp_dir().join(format!("wificomp-migrate-{}", std::process::id()));
+        let paths = DataPaths::in_dir(&dir);
+        // Sessions saved under two earlier labels, and one from another adapter
+        for (label, file) in [("Bench", "a.json"), ("Attic", "b.json")] {
+            let legacy = ensure_adapter_dir(&paths, &adapter(Some(label), None)).unwrap();
+            save_session_to(&legacy.join(file), &Session::new(adapter(Some(label), None), None))
+                .unwrap();
+        }
+        let mut other = adapter(Some("Attic"), None);
+        other.interface = "wlan2".to_string();
+        let attic = paths.sessions_dir.join("Attic");
+        save_session_to(&attic.join("c.json"), &Session::new(other, None)).unwrap();
+
+        // Bench is the label registered for the id; Attic could be an identical adapter
+        let current = adapter(Some("Bench"), Some("mac:00:11:22:33:44:55"));
+        let found = find_label_dir_sessions(&paths, &current, Some("Bench")).unwrap();
+        assert_eq!(found.ours, [paths.sessions_dir.join("Bench").join("a.json")]);
+        assert_eq!(found.unsure, [attic.join("b.json")]);
+
+        assert_eq!(move_into_adapter_dir(&paths, &current, &found.ours).unwrap(), 1);
+        let target = paths.sessions_dir.join(current.dir_name());
+        assert_eq!(list_sessions_in_dir(&target).unwrap().len(), 1);
+        assert!(!paths.sessions_dir.join("Bench").exists());
+        assert_eq!(list_sessions_in_dir(&attic).unwrap().len(), 2);
+        // Listed under the adapter's label, not its id
+        let names: Vec<String> =
+            list_adapter_dirs(&paths).unwrap().into_iter().map(|d| d.name).collect();
+        assert_eq!(names, ["Attic", "Bench"]);
+        // Already migrated
+        let found = find_label_dir_sessions(&paths, &current, Some("Bench")).unwrap();
+        assert_eq!(found, LabelDirSessions::default());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_run_sessions() {
+        let dir = std::env::temp_dir().join(format!("wificomp-run-{}", std::process::id()));
+        let paths = DataPaths::in_dir(&dir);
+        // Two adapters of one model on one node, saved in the same second
+        let run: Vec<PathBuf> = ["wlan1", "wlan2"]
+            .iter()
+            .map(|interface| {
+                let mut session = Session::new(adapter(None, None), None);
+                session.adapter.interface = interface.to_string();
+                session.node = Some("pi".to_string());
+                session.run_id = Some("20240101_120000".to_string());
+                save_session_to(&new_session_path(&paths, &session).unwrap(), &session).unwrap()
+            })
+            .collect();
+        assert_ne!(run[0], run[1]);
+        assert!(run[1].to_string_lossy().ends_with("_pi_wlan2.json"));
+
+        // Named as saved before the run started, so it isn't read
+        let mut stale = Session::new(adapter(None, None), None);
+        stale.run_id = Some("20240101_120000".to_string());
+        let stale_dir = run[0].parent().unwrap();
+        save_session_to(&stale_dir.join("20231231_090000.json"), &stale).unwrap();
+
+        let siblings = find_run_sessions(&paths, "20240101_120000", &run[0]);
+        assert_eq!(siblings.len(), 1);
+        assert_eq!(siblings[0].adapter.interface, "wlan2");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_migrate_legacy_sessions() {
+        let dir = std::env::temp_dir().join(format!("wificomp-legacy-{}", std::process::id()));
+        let paths = DataPaths::in_dir(&dir);
+        ensure_sessions_dir(&paths).unwrap();
+        let session = Session::new(adapter(Some("Bench"), None), None);
+        save_session_to(&paths.sessions_dir.join("old.json"), &session).unwrap();
+        fs::write(paths.sessions_dir.join("broken.json"), "{}").unwrap();
+
+        let files = legacy_sessions(&paths);
+        assert_eq!(files.len(), 2);
+        assert_eq!(migrate_legacy_sessions(&paths, &files).unwrap(), 2);
+        assert!(legacy_sessions(&paths).is_empty());
+        assert!(paths.sessions_dir.join("Bench").join("old.json").exists());
+        assert!(paths.sessions_dir.join("unknown").join("broken.json").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
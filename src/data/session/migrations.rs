@@ -0,0 +1,134 @@
+use anyhow::{bail, Result};
+use serde_json::{Map, Value};
+
+use super::CURRENT_VERSION;
+
+/// Upgrade a raw session document to the current schema.
+///
+/// Files without a version field are treated as v1.0. Versions newer than
+/// [`CURRENT_VERSION`] are rejected rather than silently losing data.
+pub fn migrate(mut doc: Value) -> Result<Value> {
+    let Some(obj) = doc.as_object_mut() else {
+        bail!("Session file is not a JSON object");
+    };
+
+    let version = obj
+        .get("version")
+        .and_then(Value::as_str)
+        .unwrap_or("1.0")
+        .to_string();
+
+    let (major, _) = parse_version(&version)?;
+    let (current_major, _) = parse_version(CURRENT_VERSION)?;
+    if major > current_major {
+        bail!(
+            "Session format v{} is newer than supported (v{}). Update wificomp to load it.",
+            version,
+            CURRENT_VERSION
+        );
+    }
+
+    if major < 2 {
+        v1_to_v2(obj);
+    }
+
+    Ok(doc)
+}
+
+fn parse_version(version: &str) -> Result<(u32, u32)> {
+    let mut parts = version.split('.');
+    let major = parts.next().and_then(|p| p.parse().ok());
+    let minor = parts.next().unwrap_or("0").parse().ok();
+    match (major, minor) {
+        (Some(major), Some(minor)) => Ok((major, minor)),
+        _ => bail!("Invalid session format version '{}'", version),
+    }
+}
+
+/// v1.0 -> v2.0: per-AP security and channel width, per-scan link stats
+fn v1_to_v2(obj: &mut Map<String, Value>) {
+    if let Some(scans) = obj.get_mut("scans").and_then(Value::as_array_mut) {
+        for scan in scans.iter_mut().filter_map(Value::as_object_mut) {
+            scan.entry("link").or_insert(Value::Null);
+            if let Some(aps) = scan.get_mut("access_points").and_then(Value::as_array_mut) {
+                for ap in aps.iter_mut().filter_map(Value::as_object_mut) {
+                    ap.entry("security").or_insert(Value::Null);
+                    ap.entry("channel_width_mhz").or_insert(Value::Null);
+                }
+            }
+        }
+    }
+    obj.insert("version".to_string(), Value::from("2.0"));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::Session;
+    use serde_json::json;
+
+    fn v1_doc() -> Value {
+        json!({
+            "version": "1.0",
+            "adapter": {"interface": "wlan0", "driver": "iwlwifi", "chipset": "Intel AX200", "label": null},
+            "started_at": "2026-01-31T14:30:00Z",
+            "scans": [{
+                "timestamp": "2026-01-31T14:30:05Z",
+                "access_points": [{
+                    "bssid": "AA:BB:CC:DD:EE:FF",
+                    "ssid": "MyNetwork",
+                    "signal_dbm": -45,
+                    "channel": 36,
+                    "frequency_mhz": 5180
+                }]
+            }]
+        })
+    }
+
+    #[test]
+    fn test_v1_to_v2() {
+        let doc = migrate(v1_doc()).unwrap();
+        assert_eq!(doc["version"], "2.0");
+        assert!(doc["scans"][0]["link"].is_null());
+        assert!(doc["scans"][0]["access_points"][0]["security"].is_null());
+        assert!(doc["scans"][0]["access_points"][0]
+            .as_object()
+            .unwrap()
+            .contains_key("channel_width_mhz"));
+
+        let session: Session = serde_json::from_value(doc).unwrap();
+        assert_eq!(session.version, "2.0");
+        assert_eq!(session.scans[0].access_points[0].signal_dbm, -45);
+    }
+
+    #[test]
+    fn test_missing_version_is_v1() {
+        let mut doc = v1_doc();
+        doc.as_object_mut().unwrap().remove("version");
+        let doc = migrate(doc).unwrap();
+        assert_eq!(doc["version"], "2.0");
+    }
+
+    #[test]
+    fn test_current_version_untouched() {
+        let mut doc = migrate(v1_doc()).unwrap();
+        doc["scans"][0]["access_points"][0]["security"] = json!("Wpa2");
+        let doc = migrate(doc).unwrap();
+        assert_eq!(doc["scans"][0]["access_points"][0]["security"], "Wpa2");
+    }
+
+    #[test]
+    fn test_future_version_rejected() {
+        let mut doc = v1_doc();
+        doc["version"] = json!("3.0");
+        let err = migrate(doc).unwrap_err().to_string();
+        assert!(err.contains("newer than supported"));
+    }
+
+    #[test]
+    fn test_invalid_version_rejected() {
+        let mut doc = v1_doc();
+        doc["version"] = json!("banana");
+        assert!(migrate(doc).is_err());
+    }
+}
@@ -0,0 +1,168 @@
+use anyhow::{Context, Result};
+use serde::ser::{Error as _, SerializeMap, SerializeSeq};
+use serde::{Serialize, Serializer};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use super::models::{ScanResult, Session};
+
+/// Sidecar file holding the scans a session moved out of memory
+pub fn spill_path(session_path: &Path) -> PathBuf {
+    session_path.with_extension("spill.ndjson")
+}
+
+/// Older scans of a long live session, one JSON line per scan, oldest first
+pub struct Spill {
+    pub path: PathBuf,
+    file: File,
+    /// Scans written so far
+    pub count: usize,
+}
+
+impl Spill {
+    /// Start the sidecar of `session_path`, replacing any left by an earlier run
+    pub fn create(session_path: &Path) -> Result<Self> {
+        let path = spill_path(session_path);
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&path)
+            .with_context(|| format!("Failed to create spill file {}", path.display()))?;
+        Ok(Self {
+            path,
+            file,
+            count: 0,
+        })
+    }
+
+    /// Append `scans` after the ones already spilled
+    pub fn append(&mut self, scans: &[ScanResult]) -> Result<()> {
+        let mut lines = String::new();
+        for scan in scans {
+            lines.push_str(&serde_json::to_string(scan).context("Failed to serialize scan")?);
+            lines.push('\n');
+        }
+        self.file
+            .write_all(lines.as_bytes())
+            .context("Failed to write spill file")?;
+        self.count += scans.len();
+        Ok(())
+    }
+}
+
+/// The session document with its `scans` array streamed from the spill file first
+struct SpilledSession<'a> {
+    doc: serde_json::Map<String, serde_json::Value>,
+    spill: &'a Path,
+    spilled: usize,
+}
+
+struct SpilledScans<'a> {
+    spill: &'a Path,
+    spilled: usize,
+    scans: &'a serde_json::Value,
+}
+
+impl Serialize for SpilledSession<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.doc.len()))?;
+        for (key, value) in &self.doc {
+            if key == "scans" {
+                let scans = SpilledScans {
+                    spill: self.spill,
+                    spilled: self.spilled,
+                    scans: value,
+                };
+                map.serialize_entry(key, &scans)?;
+            } else {
+                map.serialize_entry(key, value)?;
+            }
+        }
+        map.end()
+    }
+}
+
+impl Serialize for SpilledScans<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let file = File::open(self.spill).map_err(S::Error::custom)?;
+        let mut seq = serializer.serialize_seq(None)?;
+        // Lines past `spilled` were written after the save started and are still in memory
+        for line in BufReader::new(file).lines().take(self.spilled) {
+            let line = line.map_err(S::Error::custom)?;
+            let scan: serde_json::Value = serde_json::from_str(&line).map_err(S::Error::custom)?;
+            seq.serialize_element(&scan)?;
+        }
+        for scan in self.scans.as_array().into_iter().flatten() {
+            seq.serialize_element(scan)?;
+        }
+        seq.end()
+    }
+}
+
+/// Save `session` to `path` with the first `spilled` scans of `spill` ahead of its own,
+/// reading them one at a time
+pub fn save_spilled_session(
+    path: &Path,
+    session: &Session,
+    spill: &Path,
+    spilled: usize,
+) -> Result<PathBuf> {
+    let serde_json::Value::Object(doc) =
+        serde_json::to_value(session).context("Failed to serialize session")?
+    else {
+        anyhow::bail!("Failed to serialize session");
+    };
+    let file = File::create(path).context("Failed to write session file")?;
+    let mut writer = BufWriter::new(file);
+    let document = SpilledSession {
+        doc,
+        spill,
+        spilled,
+    };
+    serde_json::to_writer_pretty(&mut writer, &document).context("Failed to write session file")?;
+    writer.flush().context("Failed to write session file")?;
+    Ok(path.to_path_buf())
+}
+
+/// Delete a spill file that is no longer needed
+pub fn remove_spill(path: &Path) {
+    if let Err(e) = fs::remove_file(path) {
+        log::warn!("couldn't remove spill file {}: {}", path.display(), e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::fixtures::{adapter, ap, scan};
+    use crate::data::load_session;
+
+    fn reading(signal_dbm: i32) -> ScanResult {
+        scan(vec![ap("AA:BB:CC:DD:EE:FF", "Home", signal_dbm)])
+    }
+
+    #[test]
+    fn test_save_spilled_session() {
+        let dir = std::env::temp_dir().join(format!("wificomp-spill-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("session.json");
+        let mut session = Session::new(adapter("wlan0"), None);
+
+        let mut spill = Spill::create(&path).unwrap();
+        spill.append(&[reading(-50), reading(-51)]).unwrap();
+        // Spilled after the save was prepared, so it must not be read
+        let spilled = spill.count;
+        spill.append(&[reading(-52)]).unwrap();
+        session.scans = vec![reading(-52), reading(-53)];
+
+        save_spilled_session(&path, &session, &spill.path, spilled).unwrap();
+        let loaded = load_session(&path).unwrap();
+        let signals: Vec<i32> =
+            loaded.scans.iter().map(|s| s.access_points[0].signal_dbm).collect();
+        assert_eq!(signals, vec![-50, -51, -52, -53]);
+        assert_eq!(loaded.adapter.interface, "wlan0");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,156 @@
+use serde::{Deserialize, Serialize};
+
+use super::models::{Band, Session};
+
+/// APs listed in a summary
+const TOP_APS: usize = 5;
+
+/// Per-AP line in a session summary
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApSummary {
+    pub bssid: String,
+    pub ssid: String,
+    pub avg: i32,
+    pub min: i32,
+    pub max: i32,
+    pub count: usize,
+}
+
+/// AP count and strongest average signal on one band
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BandSummary {
+    pub band: Band,
+    pub ap_count: usize,
+    pub best_avg: i32,
+}
+
+/// Overview of a finished session, also stored in the session file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionSummary {
+    pub adapter: String,
+    pub duration_secs: u64,
+    pub scan_count: usize,
+    pub ap_count: usize,
+    /// Strongest APs by average signal
+    pub top_aps: Vec<ApSummary>,
+    pub bands: Vec<BandSummary>,
+    /// APs that usually sat above the alert threshold but dropped below it
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub alerts: Vec<String>,
+}
+
+impl Session {
+    /// Summarize this session's scans, flagging drops below `alert_threshold_dbm`
+    pub fn summarize(&self, alert_threshold_dbm: Option<i32>) -> SessionSummary {
+        let duration_secs = self
+            .scans
+            .last()
+            .map(|s| (s.timestamp - self.started_at).num_seconds().max(0) as u64)
+            .unwrap_or(0);
+
+        let mut aps: Vec<(ApSummary, Band)> = self
+            .totals
+            .aps
+            .iter()
+            // Mesh points and ad-hoc peers would crowd out the APs
+            .filter(|ap| ap.bss_type.is_infrastructure())
+            .filter_map(|ap| {
+                let stats = ap.stats()?;
+                Some((
+                    ApSummary {
+                        bssid: ap.bssid.clone(),
+                        ssid: ap.ssid.clone(),
+                        avg: stats.avg,
+                        min: stats.min,
+                        max: stats.max,
+                        count: stats.count,
+                    },
+                    ap.band,
+                ))
+            })
+            .collect();
+        aps.sort_by_key(|(ap, _)| std::cmp::Reverse(ap.avg));
+
+        let bands = [Band::TwoPointFourGHz, Band::FiveGHz, Band::SixGHz]
+            .into_iter()
+            .filter_map(|band| {
+                let on_band: Vec<&ApSummary> = aps
+                    .iter()
+                    .filter(|(_, b)| *b == band)
+                    .map(|(ap, _)| ap)
+                    .collect();
+                Some(BandSummary {
+                    band,
+                    ap_count: on_band.len(),
+                    best_avg: on_band.iter().map(|ap| ap.avg).max()?,
+                })
+            })
+            .collect();
+
+        let alerts = match alert_threshold_dbm {
+            Some(threshold) => aps
+                .iter()
+                .filter(|(ap, _)| ap.avg >= threshold && ap.min < threshold)
+                .map(|(ap, _)| {
+                    let name = if ap.ssid.is_empty() { &ap.bssid } else { &ap.ssid };
+                    format!("{} dropped to {} dBm", name, ap.min)
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+
+        SessionSummary {
+            adapter: self.adapter.display_name(),
+            duration_secs,
+            scan_count: self.totals.scans,
+            ap_count: aps.len(),
+            top_aps: aps.into_iter().take(TOP_APS).map(|(ap, _)| ap).collect(),
+            bands,
+            alerts,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::fixtures::{self, adapter, ap};
+    use crate::data::{AccessPoint, BssType, ScanResult};
+
+    fn scan(readings: &[(&str, i32, u32)]) -> ScanResult {
+        fixtures::scan(
+            readings
+                .iter()
+                .map(|&(bssid, signal_dbm, frequency_mhz)| AccessPoint {
+                    channel: 0,
+                    frequency_mhz,
+                    ..ap(bssid, &format!("net-{}", &bssid[..2]), signal_dbm)
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_summarize() {
+        let mut session = Session::new(adapter("wlan0"), None);
+        session.add_scan(scan(&[("AA", -50, 2412), ("BB", -70, 5180)]));
+        session.add_scan(scan(&[("AA", -60, 2412), ("BB", -72, 5180)]));
+        session.add_scan(scan(&[("AA", -82, 2412)]));
+
+        let summary = session.summarize(Some(-80));
+        assert_eq!(summary.scan_count, 3);
+        assert_eq!(summary.ap_count, 2);
+        assert_eq!(summary.top_aps[0].bssid, "AA");
+        assert_eq!(summary.bands.len(), 2);
+        assert_eq!(summary.bands[1].best_avg, -71);
+        assert_eq!(summary.alerts, vec!["net-AA dropped to -82 dBm".to_string()]);
+
+        // A strong mesh point doesn't count as an AP
+        let mut mesh = scan(&[("CC", -40, 5180)]);
+        mesh.access_points[0].bss_type = BssType::Mesh;
+        session.add_scan(mesh);
+        let summary = session.summarize(None);
+        assert_eq!(summary.ap_count, 2);
+        assert_eq!(summary.top_aps[0].bssid, "AA");
+    }
+}
@@ -0,0 +1,145 @@
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::models::{ApStats, Session};
+use super::session::{list_sessions_in_dir, load_session};
+
+/// One session's stats for each AP it saw, kept instead of the session itself
+#[derive(Debug, Clone)]
+pub struct SessionTrend {
+    pub started_at: DateTime<Utc>,
+    /// Keyed by BSSID
+    pub stats: HashMap<String, ApStats>,
+}
+
+impl SessionTrend {
+    pub fn from_session(session: &Session) -> Self {
+        let mut signals: HashMap<&str, Vec<i32>> = HashMap::new();
+        for scan in &session.scans {
+            for ap in &scan.access_points {
+                signals.entry(&ap.bssid).or_default().push(ap.signal_dbm);
+            }
+        }
+        let stats = signals
+            .into_iter()
+            .filter_map(|(bssid, signals)| {
+                Some((bssid.to_string(), ApStats::from_signals(signals)?))
+            })
+            .collect();
+        Self {
+            started_at: session.started_at,
+            stats,
+        }
+    }
+}
+
+/// Stats of every session saved in `adapter_dir`, oldest first. Files that fail to
+/// load are skipped.
+pub fn load_trends(adapter_dir: &Path) -> Vec<SessionTrend> {
+    let paths = list_sessions_in_dir(adapter_dir).unwrap_or_default();
+    let mut trends: Vec<SessionTrend> = paths
+        .iter()
+        .filter_map(|path| match load_session(path) {
+            Ok(session) => Some(SessionTrend::from_session(&session)),
+            Err(e) => {
+                log::warn!("skipping {} in AP trend: {:#}", path.display(), e);
+                None
+            }
+        })
+        .collect();
+    trends.sort_by_key(|trend| trend.started_at);
+    trends
+}
+
+/// Stats of `bssid` in each session that saw it, oldest first
+pub fn ap_trend(trends: &[SessionTrend], bssid: &str) -> Vec<(DateTime<Utc>, ApStats)> {
+    trends
+        .iter()
+        .filter_map(|trend| Some((trend.started_at, trend.stats.get(bssid)?.clone())))
+        .collect()
+}
+
+/// Least-squares slope of the per-session averages in dB per week, over calendar time.
+/// None with fewer than three sessions or all of them started at once.
+pub fn weekly_slope(trend: &[(DateTime<Utc>, ApStats)]) -> Option<f64> {
+    if trend.len() < 3 {
+        return None;
+    }
+    let first = trend[0].0;
+    let points: Vec<(f64, f64)> = trend
+        .iter()
+        .map(|(started, stats)| {
+            let weeks = (*started - first).num_seconds() as f64 / (7.0 * 86400.0);
+            (weeks, stats.avg as f64)
+        })
+        .collect();
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+    let spread: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    if spread == 0.0 {
+        return None;
+    }
+    let covariance: f64 = points.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    Some(covariance / spread)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::fixtures::{adapter, ap, scan};
+    use crate::data::save_session_to;
+
+    fn session(started_at: &str, signals: &[i32]) -> Session {
+        let mut session = Session::new(adapter("wlan0"), None);
+        session.started_at = started_at.parse().unwrap();
+        for &signal_dbm in signals {
+            let mut reading = scan(vec![ap("AA:BB:CC:DD:EE:FF", "Home", signal_dbm)]);
+            reading.timestamp = session.started_at;
+            session.scans.push(reading);
+        }
+        session
+    }
+
+    #[test]
+    fn test_load_trends() {
+        let dir = std::env::temp_dir().join(format!("wificomp-trend-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        save_session_to(&dir.join("b.json"), &session("2024-02-01T10:00:00Z", &[-60, -70]))
+            .unwrap();
+        save_session_to(&dir.join("a.json"), &session("2024-01-01T10:00:00Z", &[-50])).unwrap();
+        save_session_to(&dir.join("c.json"), &session("2024-03-01T10:00:00Z", &[])).unwrap();
+        std::fs::write(dir.join("broken.json"), "{").unwrap();
+
+        let trends = load_trends(&dir);
+        assert_eq!(trends.len(), 3);
+        let averages: Vec<(String, i32)> = ap_trend(&trends, "AA:BB:CC:DD:EE:FF")
+            .iter()
+            .map(|(started, stats)| (started.format("%m-%d").to_string(), stats.avg))
+            .collect();
+        assert_eq!(averages, vec![("01-01".to_string(), -50), ("02-01".to_string(), -65)]);
+        // Two sessions aren't a trend
+        assert_eq!(weekly_slope(&ap_trend(&trends, "AA:BB:CC:DD:EE:FF")), None);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_weekly_slope() {
+        // Losing 1 dB a week, with a noisy middle session
+        let trend: Vec<(DateTime<Utc>, ApStats)> = [
+            ("2024-01-01T10:00:00Z", -50),
+            ("2024-01-08T10:00:00Z", -53),
+            ("2024-01-15T10:00:00Z", -52),
+        ]
+        .iter()
+        .map(|&(started, avg)| {
+            (started.parse().unwrap(), ApStats::from_signals(vec![avg]).unwrap())
+        })
+        .collect();
+        assert!((weekly_slope(&trend).unwrap() + 1.0).abs() < 1e-9);
+
+        let same_time: Vec<_> = trend.iter().map(|(_, s)| (trend[0].0, s.clone())).collect();
+        assert_eq!(weekly_slope(&same_time), None);
+    }
+}
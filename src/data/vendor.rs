@@ -0,0 +1,77 @@
+/// Vendor prefixes (OUI) of common AP and Wi-Fi chipset makers. Far from complete;
+/// unknown prefixes show as hex.
+const VENDORS: [(&str, &str); 28] = [
+    ("00:03:7F", "Atheros"),
+    ("00:0B:86", "Aruba"),
+    ("00:0C:42", "MikroTik"),
+    ("00:11:32", "Synology"),
+    ("00:14:6C", "Netgear"),
+    ("00:17:88", "Philips"),
+    ("00:18:0A", "Meraki"),
+    ("00:1A:1E", "Aruba"),
+    ("00:1D:7E", "Linksys"),
+    ("00:1F:33", "Netgear"),
+    ("00:24:01", "D-Link"),
+    ("00:26:BB", "Apple"),
+    ("00:90:4C", "Broadcom"),
+    ("18:B4:30", "Nest"),
+    ("24:A4:3C", "Ubiquiti"),
+    ("3C:84:6A", "TP-Link"),
+    ("4C:5E:0C", "MikroTik"),
+    ("50:C7:BF", "TP-Link"),
+    ("78:8A:20", "Ubiquiti"),
+    ("7C:DD:90", "Ogemray"),
+    ("88:15:44", "Meraki"),
+    ("A0:63:91", "Netgear"),
+    ("AC:84:C6", "TP-Link"),
+    ("B8:27:EB", "Raspberry"),
+    ("DC:A6:32", "Raspberry"),
+    ("E4:5F:01", "Raspberry"),
+    ("F0:9F:C2", "Ubiquiti"),
+    ("F4:F5:D8", "Google"),
+];
+
+/// Vendor prefix of a BSSID, ignoring the locally-administered bit vendors set on the
+/// extra BSSIDs of one radio
+pub fn oui(bssid: &str) -> String {
+    let mut octets = bssid.split(':').take(3).map(|o| u8::from_str_radix(o, 16).ok());
+    match (octets.next().flatten(), octets.next().flatten(), octets.next().flatten()) {
+        (Some(first), Some(second), Some(third)) => {
+            format!("{:02X}:{:02X}:{:02X}", first & !0x02, second, third)
+        }
+        _ => bssid.to_uppercase(),
+    }
+}
+
+/// Maker of the AP, if its prefix is a known one
+pub fn vendor(bssid: &str) -> Option<&'static str> {
+    let oui = oui(bssid);
+    VENDORS.iter().find(|(prefix, _)| *prefix == oui).map(|&(_, name)| name)
+}
+
+/// Vendor name for display: the maker if known, "local" for unknown locally
+/// administered addresses (randomized or virtual), else the prefix itself
+pub fn vendor_label(bssid: &str) -> String {
+    if let Some(name) = vendor(bssid) {
+        return name.to_string();
+    }
+    let first = bssid.get(..2).and_then(|o| u8::from_str_radix(o, 16).ok());
+    match first {
+        Some(first) if first & 0x02 != 0 => "local".to_string(),
+        _ => oui(bssid),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vendor_label() {
+        assert_eq!(vendor_label("3c:84:6a:10:00:01"), "TP-Link");
+        // Extra BSSID of a TP-Link radio
+        assert_eq!(vendor_label("3E:84:6A:10:00:00"), "TP-Link");
+        assert_eq!(vendor_label("12:34:56:40:00:01"), "local");
+        assert_eq!(vendor_label("00:11:22:33:44:55"), "00:11:22");
+    }
+}
@@ -0,0 +1,105 @@
+use chrono::{DateTime, Utc};
+
+use super::models::Checkpoint;
+
+/// A guided walk test in progress: the checkpoints are walked in order, Enter is pressed
+/// on arrival at each, and the test stays there for a few scans before moving on
+#[derive(Debug, Clone, PartialEq)]
+pub struct WalkTest {
+    pub checkpoints: Vec<String>,
+    /// Scans to take at each checkpoint
+    pub dwell_scans: usize,
+    /// Checkpoint being walked to or dwelt at
+    pub index: usize,
+    /// Arrival time at the current checkpoint and scans taken since, while dwelling
+    pub arrived: Option<(DateTime<Utc>, usize)>,
+}
+
+impl WalkTest {
+    pub fn new(checkpoints: Vec<String>, dwell_scans: usize) -> Self {
+        Self {
+            checkpoints,
+            dwell_scans: dwell_scans.max(1),
+            index: 0,
+            arrived: None,
+        }
+    }
+
+    /// Name of the checkpoint being walked to or dwelt at
+    pub fn current(&self) -> Option<&str> {
+        self.checkpoints.get(self.index).map(String::as_str)
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.index >= self.checkpoints.len()
+    }
+
+    /// Start dwelling at the current checkpoint
+    pub fn arrive(&mut self, at: DateTime<Utc>) {
+        if self.arrived.is_none() && !self.is_done() {
+            self.arrived = Some((at, 0));
+        }
+    }
+
+    /// Count a scan taken at `at`. Once the current checkpoint has its dwell scans,
+    /// moves on to the next one and returns the finished checkpoint. A scan begun on
+    /// the way there doesn't count.
+    pub fn scan(&mut self, at: DateTime<Utc>) -> Option<Checkpoint> {
+        let (arrived_at, scans) = self.arrived.filter(|&(arrived_at, _)| at >= arrived_at)?;
+        if scans + 1 < self.dwell_scans {
+            self.arrived = Some((arrived_at, scans + 1));
+            return None;
+        }
+        let checkpoint = Checkpoint {
+            name: self.current()?.to_string(),
+            arrived_at,
+            left_at: at,
+        };
+        self.index += 1;
+        self.arrived = None;
+        Some(checkpoint)
+    }
+
+    /// What to do next, for the walk-test prompt
+    pub fn prompt(&self) -> String {
+        let Some(name) = self.current() else {
+            return "Walk test done".to_string();
+        };
+        let step = format!("Checkpoint {}/{}: {}", self.index + 1, self.checkpoints.len(), name);
+        match self.arrived {
+            Some((_, scans)) => {
+                format!("{}\nHold still: scan {} of {}", step, scans + 1, self.dwell_scans)
+            }
+            None => format!("{}\nWalk there, then press Enter", step),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_walk_test() {
+        let at = |secs: i64| DateTime::from_timestamp(1_700_000_000 + secs, 0).unwrap();
+        let mut walk = WalkTest::new(vec!["Desk".to_string(), "Hallway".to_string()], 2);
+        // Scans on the way there don't count
+        assert_eq!(walk.scan(at(0)), None);
+        assert!(walk.prompt().ends_with("Walk there, then press Enter"));
+
+        walk.arrive(at(10));
+        assert_eq!(walk.scan(at(8)), None);
+        assert_eq!(walk.scan(at(15)), None);
+        assert_eq!(walk.prompt(), "Checkpoint 1/2: Desk\nHold still: scan 2 of 2");
+        let desk = walk.scan(at(20)).unwrap();
+        assert_eq!((desk.name.as_str(), desk.arrived_at, desk.left_at), ("Desk", at(10), at(20)));
+        assert_eq!(walk.current(), Some("Hallway"));
+
+        walk.arrive(at(40));
+        walk.scan(at(45));
+        assert_eq!(walk.scan(at(50)).unwrap().name, "Hallway");
+        assert!(walk.is_done());
+        walk.arrive(at(60));
+        assert_eq!(walk.arrived, None);
+    }
+}
@@ -0,0 +1,120 @@
+use anyhow::{Context, Result};
+use log::{LevelFilter, Log, Metadata, Record};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Size at which the log file is rotated
+const MAX_LOG_BYTES: u64 = 1024 * 1024;
+/// Rotated files kept (`wificomp.log.1` is the newest)
+const KEEP_ROTATED: usize = 3;
+
+/// Appends log records to a file, so diagnostics never draw over the TUI
+struct FileLogger {
+    path: PathBuf,
+    level: LevelFilter,
+    file: Mutex<Option<(File, u64)>>,
+}
+
+impl Log for FileLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let line = format!(
+            "{} {:<5} {}: {}\n",
+            chrono::Utc::now().format("%Y-%m-%dT%H:%M:%S%.3fZ"),
+            record.level(),
+            record.target(),
+            record.args()
+        );
+
+        let Ok(mut guard) = self.file.lock() else {
+            return;
+        };
+        if guard.as_ref().is_some_and(|(_, size)| *size >= MAX_LOG_BYTES) {
+            *guard = None;
+            rotate(&self.path);
+        }
+        if guard.is_none() {
+            *guard = open(&self.path).ok();
+        }
+        if let Some((file, size)) = guard.as_mut() {
+            if file.write_all(line.as_bytes()).is_ok() {
+                *size += line.len() as u64;
+            }
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(mut guard) = self.file.lock() {
+            if let Some((file, _)) = guard.as_mut() {
+                let _ = file.flush();
+            }
+        }
+    }
+}
+
+/// Open the log for appending, with its current size
+fn open(path: &Path) -> std::io::Result<(File, u64)> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    let size = file.metadata()?.len();
+    Ok((file, size))
+}
+
+/// `wificomp.log` -> `wificomp.log.1` -> ... dropping the oldest
+fn rotate(path: &Path) {
+    let rotated = |n: usize| PathBuf::from(format!("{}.{}", path.display(), n));
+    let _ = fs::remove_file(rotated(KEEP_ROTATED));
+    for n in (1..KEEP_ROTATED).rev() {
+        let _ = fs::rename(rotated(n), rotated(n + 1));
+    }
+    let _ = fs::rename(path, rotated(1));
+}
+
+/// Start logging to `path` at `level` (no-op when off)
+pub fn init(path: &Path, level: LevelFilter) -> Result<()> {
+    if level == LevelFilter::Off {
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).context("Failed to create log directory")?;
+    }
+    let file = open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let logger = FileLogger {
+        path: path.to_path_buf(),
+        level,
+        file: Mutex::new(Some(file)),
+    };
+    log::set_boxed_logger(Box::new(logger)).context("Logger already initialized")?;
+    log::set_max_level(level);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotate() {
+        let dir = std::env::temp_dir().join(format!("wificomp-log-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("wificomp.log");
+        for round in 0..=KEEP_ROTATED {
+            fs::write(&path, format!("round {}", round)).unwrap();
+            rotate(&path);
+        }
+
+        assert!(!path.exists());
+        let read = |n: usize| fs::read_to_string(format!("{}.{}", path.display(), n)).ok();
+        assert_eq!(read(1).as_deref(), Some("round 3"));
+        assert_eq!(read(KEEP_ROTATED).as_deref(), Some("round 1"));
+        assert_eq!(read(KEEP_ROTATED + 1), None);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
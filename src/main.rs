@@ -1,17 +1,25 @@
 mod app;
 mod config;
 mod data;
+mod logging;
 mod scanner;
+#[cfg(test)]
+mod snapshots;
 mod ui;
 mod utils;
+mod web;
 
 use std::io;
-use std::time::Duration;
+use std::path::PathBuf;
+use std::time::Instant;
 
 use anyhow::Result;
-use clap::Parser;
+use clap::{CommandFactory, Parser, Subcommand, ValueHint};
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers},
+    event::{
+        self, DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture,
+        Event, KeyCode, KeyModifiers,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
@@ -24,51 +32,267 @@ use ratatui::{
 };
 
 use app::{App, Popup, Screen};
-use ui::popups::{Dialog, FilePicker, InputPopup};
-use ui::{CompareScreen, HistoryScreen, LiveScreen};
+use data::export::ExportFormat;
+use data::{SortField, SortOrder};
+use ui::popups::{
+    CalibrationPopup, Dialog, FilePicker, InputPopup, MessageDialog, SetupPopup, SummaryPopup,
+};
+use scanner::ScanBackend;
+use ui::live::LiveAction;
+use ui::{ChannelsScreen, CompareScreen, HistoryScreen, LiveScreen};
 
 #[derive(Parser)]
 #[command(name = "wificomp")]
 #[command(about = "WiFi adapter comparison tool")]
 #[command(version)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Interface to use (auto-detects if not specified)
     #[arg(short, long)]
     interface: Option<String>,
 
+    /// Print detected wireless interfaces and exit
+    #[arg(long)]
+    list_interfaces: bool,
+
     /// Disable auto-scan on startup
     #[arg(long)]
     no_auto_scan: bool,
+
+    /// Only redraw on input or scan completion
+    #[arg(long)]
+    low_power: bool,
+
+    /// Directory for sessions and config (overrides WIFICOMP_DATA_DIR)
+    #[arg(long, value_name = "DIR", value_hint = ValueHint::DirPath)]
+    data_dir: Option<PathBuf>,
+
+    /// Append each scan as one JSON line to FILE as it happens
+    #[arg(long, value_name = "FILE")]
+    stream: Option<PathBuf>,
+
+    /// Actively probe for a hidden SSID on every scan (repeatable)
+    #[arg(long, value_name = "SSID")]
+    probe: Vec<String>,
+
+    /// Tag each scan with a position from gpsd (default 127.0.0.1:2947)
+    #[arg(long, value_name = "ADDR", num_args = 0..=1,
+          default_missing_value = scanner::gps::GPSD_DEFAULT)]
+    gpsd: Option<String>,
+
+    /// Convert a saved session to WiGLE CSV, upload it with the configured API token, and exit
+    #[arg(long, value_name = "SESSION")]
+    wigle_upload: Option<PathBuf>,
+
+    /// Use synthetic, drifting APs instead of real hardware
+    #[arg(long)]
+    demo: bool,
+
+    /// Scan an interface on another machine over SSH, running iw there; repeat to scan
+    /// several probe locations in lockstep (the first is shown in Live)
+    #[arg(long, value_name = "[USER@]HOST:IFACE",
+          conflicts_with_all = ["demo", "replay", "interface"])]
+    remote: Vec<scanner::remote::RemoteTarget>,
+
+    /// Also scan this local interface in lockstep with the Live one, in a session of its
+    /// own (repeatable)
+    #[arg(long = "with", value_name = "IFACE", conflicts_with_all = ["remote", "replay"])]
+    with: Vec<String>,
+
+    /// Serve a read-only web dashboard on PORT (e.g. to follow a walk test from a phone)
+    #[arg(long, value_name = "PORT")]
+    web: Option<u16>,
+
+    /// Address the web dashboard listens on; 0.0.0.0 lets other devices connect
+    #[arg(long, value_name = "ADDR", default_value = "127.0.0.1", requires = "web")]
+    web_bind: std::net::IpAddr,
+
+    /// Token HTTP API requests must send as `Authorization: Bearer TOKEN` (random if unset)
+    #[arg(long, value_name = "TOKEN", requires = "web")]
+    web_token: Option<String>,
+
+    /// Play a saved session's scans into the Live screen instead of scanning
+    #[arg(long, value_name = "SESSION")]
+    replay: Option<PathBuf>,
+
+    /// Replay speed multiplier (e.g. 10 plays a 5 minute session in 30s)
+    #[arg(long, value_name = "X", default_value_t = 1.0, requires = "replay",
+          value_parser = scanner::replay::parse_speed)]
+    replay_speed: f64,
+
+    /// Apply a saved scenario profile (profiles/NAME.json beside the config file)
+    #[arg(long, value_name = "NAME")]
+    profile: Option<String>,
+
+    /// Debug log level: off, error, warn, info, debug or trace (overrides config)
+    #[arg(long, value_name = "LEVEL")]
+    log_level: Option<log::LevelFilter>,
+
+    /// Print a man page for wificomp in roff format and exit
+    #[arg(long)]
+    generate_man: bool,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Check tools, permissions, rfkill, interface modes and the data directory, then exit
+    Doctor,
+    /// Print a shell completion script, e.g. `wificomp completions bash > ~/.bash_completion`
+    Completions {
+        #[arg(value_enum)]
+        shell: clap_complete::Shell,
+    },
 }
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
+    if let Some(Command::Completions { shell }) = cli.command {
+        clap_complete::generate(shell, &mut Cli::command(), "wificomp", &mut io::stdout());
+        return Ok(());
+    }
+    if cli.generate_man {
+        clap_mangen::Man::new(Cli::command()).render(&mut io::stdout())?;
+        return Ok(());
+    }
+    let scan_backend = if cli.demo {
+        ScanBackend::Demo
+    } else if !cli.remote.is_empty() {
+        ScanBackend::Remote(0)
+    } else {
+        ScanBackend::default()
+    };
+    let mut remote_adapters = scanner::remote::connect(cli.remote.clone())?.into_iter();
+    let remote_adapter = remote_adapters.next();
+    let mut nodes: Vec<_> = remote_adapters
+        .enumerate()
+        .map(|(i, adapter)| (ScanBackend::Remote(i + 1), adapter))
+        .collect();
+
+    if cli.list_interfaces {
+        for adapter in scan_backend.detect_adapters()? {
+            println!("{}\t{} ({})", adapter.interface, adapter.chipset, adapter.driver);
+        }
+        return Ok(());
+    }
+
+    let paths = data::DataPaths::resolve(cli.data_dir.clone())?;
+    let log_level = match cli.log_level {
+        Some(level) => level,
+        None => config::Config::load(&paths).unwrap_or_default().log_level,
+    };
+    logging::init(&paths.log_file(), log_level)?;
+    log::info!("wificomp {} starting", env!("CARGO_PKG_VERSION"));
+    if let Some(Command::Doctor) = &cli.command {
+        let checks = scanner::doctor::run_checks(scan_backend, &paths);
+        print!("{}", scanner::doctor::report(&checks));
+        if checks.iter().any(|c| c.status == scanner::doctor::Status::Fail) {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+    if let Some(session_path) = &cli.wigle_upload {
+        return wigle_upload(&paths, session_path);
+    }
+    let replay = cli.replay.as_deref().map(data::load_session).transpose()?;
+    if scan_backend.needs_privilege() && replay.is_none() {
+        offer_capability(&paths)?;
+        prime_sudo();
+        log::info!("Scanning with {} privilege", scanner::privilege::privilege().name());
+    }
+
+    // Resolve the requested interface before taking over the terminal
+    let adapter = match (&replay, remote_adapter) {
+        (Some(session), _) => Some(session.adapter.clone()),
+        (None, Some(adapter)) => Some(adapter),
+        (None, None) => cli
+            .interface
+            .as_deref()
+            .map(|interface| scan_backend.find_adapter(interface))
+            .transpose()?,
+    };
+    for interface in &cli.with {
+        nodes.push((scan_backend, scan_backend.find_adapter(interface)?));
+    }
+    let profile = cli
+        .profile
+        .as_deref()
+        .map(|name| config::Profile::load(&paths, name).map(|p| (name.to_string(), p)))
+        .transpose()?;
+    #[cfg(feature = "api")]
+    let random_token = cli.web_token.is_none();
+    let web = cli
+        .web
+        .map(|port| {
+            web::Dashboard::start((cli.web_bind, port).into(), cli.web_token, paths.clone())
+        })
+        .transpose()?;
+    // The startup toast goes away, so leave the token where it can be looked up again
+    #[cfg(feature = "api")]
+    if let Some(web) = web.as_ref().filter(|_| random_token) {
+        eprintln!("HTTP API token: {}", web.token());
+        log::info!("HTTP API token: {}", web.token());
+    }
+    let stream = cli
+        .stream
+        .as_deref()
+        .map(data::export::NdjsonStream::open)
+        .transpose()?;
 
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture, EnableFocusChange)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
     // Create app
-    let mut app = App::new()?;
+    let mut app = App::new(paths, scan_backend)?;
 
+    if let Some((name, profile)) = profile {
+        app.use_profile(name, profile);
+    }
     if cli.no_auto_scan {
         app.live.auto_scan = false;
     }
+    if cli.low_power {
+        app.low_power = true;
+    }
+    app.stream = stream;
+    #[cfg(feature = "api")]
+    if let Some(web) = &web {
+        app.toast = Some((format!("HTTP API token: {}", web.token()), Instant::now()));
+    }
+    app.web = web;
+    if cli.gpsd.is_some() {
+        app.gpsd = cli.gpsd;
+    }
+    for ssid in cli.probe {
+        if !app.live.probe_ssids.contains(&ssid) {
+            app.live.probe_ssids.push(ssid);
+        }
+    }
 
     // Initialize
-    if let Err(e) = app.init() {
+    if let Err(e) = app.init(adapter) {
         // Cleanup before showing error
         disable_raw_mode()?;
         execute!(
             terminal.backend_mut(),
             LeaveAlternateScreen,
-            DisableMouseCapture
+            DisableMouseCapture,
+            DisableFocusChange
         )?;
         return Err(e);
     }
+    if let Some(session) = replay {
+        app.start_replay(session, cli.replay_speed);
+    }
+    app.add_nodes(nodes);
+
+    let size = terminal.size()?;
+    app.resize(size.width, size.height);
 
     // Run app
     let res = run_app(&mut terminal, &mut app);
@@ -78,27 +302,128 @@ fn main() -> Result<()> {
     execute!(
         terminal.backend_mut(),
         LeaveAlternateScreen,
-        DisableMouseCapture
+        DisableMouseCapture,
+        DisableFocusChange
     )?;
 
+    // Saves still running finish on the restored terminal, so their warnings read cleanly
+    app.wait_for_jobs();
+
     if let Err(e) = res {
         eprintln!("Error: {}", e);
         return Err(e);
     }
+    app.remove_spills();
 
     Ok(())
 }
 
-fn run_app<B: ratatui::backend::Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<()> {
-    let tick_rate = Duration::from_millis(250);
+/// Offer (once) to grant CAP_NET_ADMIN so scans never need sudo.
+///
+/// Runs before the TUI starts so `sudo setcap` can prompt normally; on success
+/// the binary re-executes itself to pick up the capability.
+#[cfg(not(target_os = "linux"))]
+fn offer_capability(_paths: &data::DataPaths) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn offer_capability(paths: &data::DataPaths) -> Result<()> {
+    use std::io::{BufRead, IsTerminal, Write};
+    use std::os::unix::process::CommandExt;
+
+    if scanner::privilege::direct_privilege().is_some() || !io::stdin().is_terminal() {
+        return Ok(());
+    }
+    let mut config = config::Config::load(paths)?;
+    if config.capability_prompted {
+        return Ok(());
+    }
+    config.capability_prompted = true;
+    config.save(paths)?;
+
+    let exe = std::env::current_exe()?;
+    print!(
+        "Scanning needs CAP_NET_ADMIN. Grant it to {} with sudo setcap? [y/N] ",
+        exe.display()
+    );
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    if !answer.trim().eq_ignore_ascii_case("y") {
+        println!("Continuing without it.");
+        return Ok(());
+    }
+
+    let status = std::process::Command::new("sudo")
+        .args(["setcap", "cap_net_admin+ep"])
+        .arg(&exe)
+        .status()?;
+    if !status.success() {
+        println!("setcap failed; continuing without it.");
+        return Ok(());
+    }
+
+    let err = std::process::Command::new(&exe)
+        .args(std::env::args_os().skip(1))
+        .exec();
+    Err(err.into())
+}
 
+/// Export a saved session as WiGLE CSV next to it and upload it
+fn wigle_upload(paths: &data::DataPaths, session_path: &std::path::Path) -> Result<()> {
+    use anyhow::Context;
+
+    let config = config::Config::load(paths)?;
+    let (Some(name), Some(token)) = (&config.wigle_api_name, &config.wigle_api_token) else {
+        anyhow::bail!(
+            "Set wigle_api_name and wigle_api_token in {}",
+            paths.config_file.display()
+        );
+    };
+    let session = data::load_session(session_path)
+        .with_context(|| format!("Failed to load {}", session_path.display()))?;
+    let csv_path = session_path.with_extension("wigle.csv");
+    ExportFormat::Wigle.export(&session, &csv_path)?;
+    println!("Wrote {}", csv_path.display());
+
+    let response = data::export::upload_wigle(&csv_path, name, token)?;
+    println!("Uploaded to WiGLE: {}", response);
+    Ok(())
+}
+
+/// Cache sudo credentials before the TUI hides the password prompt
+fn prime_sudo() {
+    use scanner::privilege::{direct_privilege, sudo_ready};
+    use std::io::IsTerminal;
+
+    if direct_privilege().is_some() || !io::stdin().is_terminal() || sudo_ready() {
+        return;
+    }
+    println!("Scanning runs `iw` via sudo; enter your password to continue.");
+    let _ = std::process::Command::new("sudo").arg("-v").status();
+}
+
+fn run_app<B: ratatui::backend::Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<()> {
     while app.running {
-        terminal.draw(|f| draw(f, app))?;
+        // In low-power mode only redraw after input or a completed scan
+        if !app.low_power || app.needs_redraw {
+            let frame = terminal.draw(|f| draw(f, app))?;
+            if app.popup == Popup::None {
+                app.last_frame = Some(frame.buffer.clone());
+            }
+            app.needs_redraw = false;
+        }
 
-        if event::poll(tick_rate)? {
-            if let Event::Key(key) = event::read()? {
-                handle_key(app, key.code, key.modifiers);
+        if event::poll(app.tick_rate)? {
+            match event::read()? {
+                Event::Key(key) => handle_key(app, key.code, key.modifiers),
+                Event::FocusGained => app.set_focused(true),
+                Event::FocusLost => app.set_focused(false),
+                Event::Resize(width, height) => app.resize(width, height),
+                _ => {}
             }
+            app.needs_redraw = true;
         }
 
         app.tick();
@@ -111,10 +436,13 @@ fn draw(f: &mut Frame, app: &App) {
     let size = f.area();
 
     // Check minimum size
-    if size.width < 60 || size.height < 15 {
+    if size.width < ui::MIN_WIDTH || size.height < ui::MIN_HEIGHT {
         let msg = format!(
-            "Terminal too small\nNeed: 60x15\nHave: {}x{}",
-            size.width, size.height
+            "Terminal too small\nNeed: {}x{}\nHave: {}x{}",
+            ui::MIN_WIDTH,
+            ui::MIN_HEIGHT,
+            size.width,
+            size.height
         );
         let block = Block::default()
             .borders(Borders::ALL)
@@ -133,6 +461,7 @@ fn draw(f: &mut Frame, app: &App) {
     let chunks = Layout::vertical([
         Constraint::Length(1), // Tab bar
         Constraint::Min(10),   // Content
+        Constraint::Length(1), // Status bar
     ])
     .split(size);
 
@@ -143,7 +472,8 @@ fn draw(f: &mut Frame, app: &App) {
     let content_area = chunks[1];
     match app.screen {
         Screen::Live => {
-            f.render_widget(LiveScreen::new(&app.live), content_area);
+            let screen = LiveScreen::new(&app.live).session(app.current_session.as_deref());
+            f.render_widget(screen, content_area);
         }
         Screen::History => {
             f.render_widget(HistoryScreen::new(&app.history), content_area);
@@ -151,18 +481,102 @@ fn draw(f: &mut Frame, app: &App) {
         Screen::Compare => {
             f.render_widget(CompareScreen::new(&app.compare), content_area);
         }
+        Screen::Channels => {
+            let screen = ChannelsScreen::new(&app.channels, app.current_session.as_deref());
+            f.render_widget(screen, content_area);
+        }
     }
 
+    draw_status(f, app, chunks[2]);
+    draw_toast(f, app, content_area);
+
     // Popups
     draw_popup(f, app, size);
 }
 
+const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+fn spinner_frame() -> &'static str {
+    SPINNER[(chrono::Utc::now().timestamp_millis() / 100) as usize % 10]
+}
+
+/// Adapter, backend, scan and auto-scan state, unsaved changes and alerts, with the
+/// clock on the right
+fn draw_status(f: &mut Frame, app: &App, area: Rect) {
+    let live = &app.live;
+    let adapter = match &live.adapter {
+        Some(_) if live.adapter_missing => "adapter missing".to_string(),
+        Some(adapter) => adapter.display_name_full(),
+        None => "no adapter".to_string(),
+    };
+    let auto = match live.auto_scan {
+        true if live.suspended => "auto paused".to_string(),
+        true => format!("auto {}s", live.auto_scan_interval),
+        false => "auto off".to_string(),
+    };
+    let mut parts = vec![adapter, app.backend.name().to_string()];
+    if live.scanning {
+        parts.push(format!("{} scanning", spinner_frame()));
+    }
+    parts.push(auto);
+    if app.session_modified {
+        parts.push("● unsaved".to_string());
+    }
+    let left = format!(" {}", parts.join(" │ "));
+
+    let clock = format!("{} ", (app.clock)().format("%H:%M"));
+    let alerts = match app.alert_count() {
+        0 => String::new(),
+        1 => "⚠ 1 alert │ ".to_string(),
+        n => format!("⚠ {} alerts │ ", n),
+    };
+    let right_width = utils::display_width(&alerts) + utils::display_width(&clock);
+    let left = utils::truncate(&left, (area.width as usize).saturating_sub(right_width + 1));
+
+    let buf = f.buffer_mut();
+    let style = Style::default().fg(Color::Gray).bg(Color::Black);
+    buf.set_string(area.x, area.y, " ".repeat(area.width as usize), style);
+    buf.set_string(area.x, area.y, &left, style);
+    let clock_x = area.right().saturating_sub(utils::display_width(&clock) as u16);
+    let alerts_x = clock_x.saturating_sub(utils::display_width(&alerts) as u16);
+    buf.set_string(alerts_x, area.y, &alerts, style.fg(Color::Red).add_modifier(Modifier::BOLD));
+    buf.set_string(clock_x, area.y, &clock, style);
+}
+
+/// Background write progress, or the last result, over the bottom border
+fn draw_toast(f: &mut Frame, app: &App, area: Rect) {
+    let jobs = app.running_jobs();
+    let (text, style) = if let Some(kind) = jobs.first() {
+        let frame = spinner_frame();
+        let more = if jobs.len() > 1 { format!(" (+{})", jobs.len() - 1) } else { String::new() };
+        let text = format!(" {} {}…{} ", frame, kind.running(), more);
+        (text, Style::default().fg(Color::Black).bg(Color::Cyan))
+    } else if let Some((message, _)) = &app.toast {
+        (format!(" {} ", message), Style::default().fg(Color::Black).bg(Color::Green))
+    } else {
+        return;
+    };
+
+    let text = utils::truncate(&text, area.width.saturating_sub(2) as usize);
+    let width = utils::display_width(&text) as u16;
+    let x = area.right().saturating_sub(width + 1);
+    f.buffer_mut().set_string(x, area.bottom().saturating_sub(1), text, style);
+}
+
 fn draw_tabs(f: &mut Frame, app: &App, area: Rect) {
-    let titles = vec!["[1]Live", "[2]Hist", "[3]Cmp"];
     let selected = match app.screen {
         Screen::Live => 0,
         Screen::History => 1,
         Screen::Compare => 2,
+        Screen::Channels => 3,
+    };
+
+    // Narrow terminals drop the title and tab padding and abbreviate the tabs
+    let narrow = area.width < ui::NARROW_WIDTH;
+    let (titles, divider, pad) = if narrow {
+        (vec!["1:Live", "2:Hist", "3:Cmp", "4:Chan"], "│", "")
+    } else {
+        (vec!["[1]Live", "[2]Hist", "[3]Cmp", "[4]Chan"], " │ ", " ")
     };
 
     let tabs = Tabs::new(titles)
@@ -173,10 +587,11 @@ fn draw_tabs(f: &mut Frame, app: &App, area: Rect) {
                 .fg(Color::Yellow)
                 .add_modifier(Modifier::BOLD),
         )
-        .divider(" │ ");
+        .padding(pad, pad)
+        .divider(divider);
 
     // Render with title
-    let title = " wificomp ";
+    let title = if narrow { "" } else { " wificomp " };
     let block = Block::default().title(title);
 
     // Calculate position for tabs (after title)
@@ -195,7 +610,7 @@ fn draw_tabs(f: &mut Frame, app: &App, area: Rect) {
 fn draw_popup(f: &mut Frame, app: &App, area: Rect) {
     match &app.popup {
         Popup::None => {}
-        Popup::AdapterCollision { selected } => {
+        Popup::AdapterCollision { selected, .. } => {
             let dialog = Dialog::new(
                 "Adapter Already Used",
                 "A session exists for this adapter.",
@@ -204,6 +619,31 @@ fn draw_popup(f: &mut Frame, app: &App, area: Rect) {
             .selected(*selected);
             f.render_widget(dialog, area);
         }
+        Popup::InterfaceSwitch { adapters, selected } => {
+            let current = app.live.adapter.as_ref().map(|a| a.interface.as_str());
+            let items: Vec<String> = adapters
+                .iter()
+                .map(|a| {
+                    let marker = if Some(a.interface.as_str()) == current { " *" } else { "" };
+                    format!("{} - {}{}", a.interface, a.chipset, marker)
+                })
+                .collect();
+            let options: Vec<&str> = items.iter().map(String::as_str).collect();
+            let dialog =
+                Dialog::new("Switch Interface", "Scan with:", &options).selected(*selected);
+            f.render_widget(dialog, area);
+        }
+        Popup::ProfilePicker { profiles, selected } => {
+            let current = app.profile.as_ref().map(|(name, _)| name.as_str());
+            let items: Vec<String> = profiles
+                .iter()
+                .map(|p| if Some(p.as_str()) == current { format!("{} *", p) } else { p.clone() })
+                .collect();
+            let options: Vec<&str> = items.iter().map(String::as_str).collect();
+            let dialog =
+                Dialog::new("Profile", "Apply settings from:", &options).selected(*selected);
+            f.render_widget(dialog, area);
+        }
         Popup::RenameAdapter { input, cursor } => {
             let popup = InputPopup::new("Rename Adapter", "Enter label:", input)
                 .cursor_pos(*cursor);
@@ -214,19 +654,106 @@ fn draw_popup(f: &mut Frame, app: &App, area: Rect) {
                 .cursor_pos(*cursor);
             f.render_widget(popup, area);
         }
+        Popup::Search { input, cursor } => {
+            let popup = InputPopup::new("Search", "SSID or BSSID (empty repeats the last):", input)
+                .cursor_pos(*cursor);
+            f.render_widget(popup, area);
+        }
+        Popup::Command { input, cursor } => {
+            let popup = InputPopup::new("Command", "e.g. group, geiger, load, quit:", input)
+                .cursor_pos(*cursor);
+            f.render_widget(popup, area);
+        }
+        Popup::Marker { input, cursor } => {
+            let popup = InputPopup::new("Marker", "Note, e.g. moved to hallway:", input)
+                .cursor_pos(*cursor);
+            f.render_widget(popup, area);
+        }
+        Popup::SessionSetup { values, field, cursor } => {
+            f.render_widget(SetupPopup::new(values, *field, *cursor), area);
+        }
         Popup::FilePicker => {
             let picker = FilePicker::new("Load Session", &app.file_picker);
             f.render_widget(picker, area);
         }
-        Popup::ExportChoice { selected } => {
-            let dialog = Dialog::new("Export Format", "Choose export format:", &["JSON", "CSV"])
-                .selected(*selected);
+        Popup::ExportChoice { selected, anonymize } => {
+            let options = ExportFormat::ALL.map(|f| f.name());
+            let message = format!("Choose export format (a: anonymize {}):", anonymize.name());
+            let dialog = Dialog::new("Export Format", &message, &options).selected(*selected);
             f.render_widget(dialog, area);
         }
-        Popup::Error { message } => {
-            let dialog = Dialog::new("Error", message, &["OK"]);
+        Popup::AdapterAdded { adapter, selected } => {
+            let msg = format!(
+                "{} appeared. Switch to it and start a new session?",
+                adapter.display_name()
+            );
+            let dialog =
+                Dialog::new("New Adapter", &msg, &["Switch", "Ignore"]).selected(*selected);
             f.render_widget(dialog, area);
         }
+        Popup::LegacySessions { files, selected } => {
+            let msg = format!(
+                "{} old sessions aren't in the file picker.\nMove them into adapter folders?",
+                files.len()
+            );
+            let dialog =
+                Dialog::new("Old Sessions", &msg, &["Move", "Not now"]).selected(*selected);
+            f.render_widget(dialog, area);
+        }
+        Popup::LabelDirSessions { adapter, files, selected } => {
+            let msg = format!(
+                "{} sessions under other labels look like {}.\nMove them into its folder?",
+                files.len(),
+                adapter.display_name()
+            );
+            let dialog =
+                Dialog::new("Other Labels", &msg, &["Move", "Leave"]).selected(*selected);
+            f.render_widget(dialog, area);
+        }
+        Popup::ResetInterface { interface, failures, selected } => {
+            let msg = format!(
+                "{} failed scans in a row. Reset {} and try again?",
+                failures, interface
+            );
+            let dialog =
+                Dialog::new("Scans Failing", &msg, &["Reset", "Ignore"]).selected(*selected);
+            f.render_widget(dialog, area);
+        }
+        Popup::SessionSummary { summary, saved, .. } => {
+            let popup = SummaryPopup::new(summary).saved(saved.as_deref());
+            f.render_widget(popup, area);
+        }
+        Popup::WalkSetup { input, cursor } => {
+            let popup =
+                InputPopup::new("Walk Test", "Checkpoints, e.g. desk, hallway, garage:", input)
+                    .cursor_pos(*cursor);
+            f.render_widget(popup, area);
+        }
+        Popup::Walk { selected } => {
+            let prompt = app.walk.as_ref().map(|w| w.prompt()).unwrap_or_default();
+            let dialog =
+                Dialog::new("Walk Test", &prompt, &["Arrived", "Stop"]).selected(*selected);
+            f.render_widget(dialog, area);
+        }
+        Popup::AlternateSetup { input, cursor } => {
+            let popup = InputPopup::new("A/B Test", "Minutes per adapter turn:", input)
+                .cursor_pos(*cursor);
+            f.render_widget(popup, area);
+        }
+        Popup::Swap { selected } => {
+            let prompt = app.alternation.as_ref().map(|a| a.prompt()).unwrap_or_default();
+            let dialog =
+                Dialog::new("Swap Adapters", &prompt, &["Stop A/B test"]).selected(*selected);
+            f.render_widget(dialog, area);
+        }
+        Popup::Snapshot { selected } => {
+            let dialog = Dialog::new("Snapshot", "Save current view as:", &["Text", "SVG chart"])
+                .selected(*selected);
+            f.render_widget(dialog, area);
+        }
+        Popup::Error { message, scroll } => {
+            f.render_widget(MessageDialog::new("Error", message).scroll(*scroll), area);
+        }
         Popup::ConfirmQuit { selected } => {
             let msg = if app.live.scanning {
                 "Scan in progress. Quit anyway?"
@@ -243,24 +770,292 @@ fn draw_popup(f: &mut Frame, app: &App, area: Rect) {
                 .selected(*selected);
             f.render_widget(dialog, area);
         }
-        Popup::SessionWarning { message, .. } => {
-            let dialog = Dialog::new("Warning", message, &["OK"]);
+        Popup::Exclusions { selected } => {
+            let default = app.config.exclusion_policy;
+            let mut items: Vec<String> =
+                app.config.excluded_aps.iter().map(|e| e.describe(default)).collect();
+            if items.is_empty() {
+                items.push("(none)".to_string());
+            }
+            let options: Vec<&str> = items.iter().map(String::as_str).collect();
+            let msg = format!(
+                "[a]dd SSID pattern [d]elete [p]olicy [u]ndo\n\
+                 [g] default: {}  (hide keeps recording)",
+                default.name()
+            );
+            let dialog = Dialog::new("Exclusions", &msg, &options).selected(*selected);
             f.render_widget(dialog, area);
         }
+        Popup::ExclusionPattern { input, cursor } => {
+            let popup = InputPopup::new("Exclude SSIDs", "Pattern (* and ? wildcards):", input)
+                .cursor_pos(*cursor);
+            f.render_widget(popup, area);
+        }
+        Popup::SessionWarning { message, scroll, .. } => {
+            f.render_widget(MessageDialog::new("Warning", message).scroll(*scroll), area);
+        }
+        Popup::ApDetail { message, scroll } => {
+            f.render_widget(MessageDialog::new("AP Detail", message).scroll(*scroll), area);
+        }
+        Popup::Columns { selected } => {
+            let items: Vec<String> = app
+                .live
+                .column_choices()
+                .iter()
+                .map(|(column, config)| {
+                    let (mark, width) = match config.map(|c| c.width()) {
+                        Some(Some(width)) => ("x", width.to_string()),
+                        Some(None) => ("x", "auto".to_string()),
+                        None => (" ", String::new()),
+                    };
+                    format!("[{}] {:<9} {:>4}", mark, column.name(), width)
+                })
+                .collect();
+            let options: Vec<&str> = items.iter().map(String::as_str).collect();
+            let msg = "[spc] show/hide  [ and ] move\n←/→ width  [0] auto  [r]eset";
+            let dialog = Dialog::new("Columns", msg, &options).selected(*selected);
+            f.render_widget(dialog, area);
+        }
+        Popup::Sort { selected } => {
+            let items = match app.screen {
+                Screen::Compare => sort_items(&app.compare.sort),
+                _ => sort_items(&app.live.sort),
+            };
+            let options: Vec<&str> = items.iter().map(String::as_str).collect();
+            let msg = "[spc] use/drop  [r]everse\n[ and ] priority";
+            let dialog = Dialog::new("Sort", msg, &options).selected(*selected);
+            f.render_widget(dialog, area);
+        }
+        Popup::Calibration { selected } => {
+            if let Some(calibration) = &app.calibration {
+                f.render_widget(CalibrationPopup::new(calibration).selected(*selected), area);
+            }
+        }
+    }
+}
+
+/// Sort popup rows: the keys in priority order, then the unused fields
+fn sort_items<K: SortField>(order: &SortOrder<K>) -> Vec<String> {
+    // The keys come first, so their position is their rank
+    order
+        .choices()
+        .iter()
+        .enumerate()
+        .map(|(i, (by, key))| match key {
+            Some(key) => {
+                let reversed = if key.reverse { "reversed" } else { "" };
+                format!("{}. {:<8} {:<8}", i + 1, by.name(), reversed)
+            }
+            None => format!("   {:<8} {:<8}", by.name(), ""),
+        })
+        .collect()
+}
+
+/// Edit `order` in the sort popup. `keep_one` stops the last key being dropped, for
+/// lists without an unsorted order.
+fn sort_popup_key<K: SortField>(
+    order: &mut SortOrder<K>,
+    selected: &mut usize,
+    code: KeyCode,
+    keep_one: bool,
+) {
+    let choices = order.choices();
+    let Some(&(by, _)) = choices.get(*selected) else {
+        return;
+    };
+    match code {
+        KeyCode::Up => *selected = selected.saturating_sub(1),
+        KeyCode::Down => *selected = (*selected + 1).min(choices.len() - 1),
+        KeyCode::Char(' ') | KeyCode::Enter => {
+            if !(keep_one && order.keys().len() == 1 && order.primary() == Some(by)) {
+                order.toggle(by);
+            }
+        }
+        KeyCode::Char('r') => order.toggle_reverse(by),
+        KeyCode::Char('[') => order.move_key(by, -1),
+        KeyCode::Char(']') => order.move_key(by, 1),
+        _ => return,
+    }
+    // Follow the field as it moves between the keys and the unused ones
+    if !matches!(code, KeyCode::Up | KeyCode::Down) {
+        *selected = order.choices().iter().position(|c| c.0 == by).unwrap_or(0);
+    }
+}
+
+/// Scroll a message dialog; true when the key closes it
+fn scroll_message(
+    title: &str,
+    message: &str,
+    scroll: &mut u16,
+    code: KeyCode,
+    screen: Rect,
+) -> bool {
+    let (max_scroll, page) = MessageDialog::new(title, message).scroll_range(screen);
+    *scroll = match code {
+        KeyCode::Enter | KeyCode::Esc => return true,
+        KeyCode::Up => scroll.saturating_sub(1),
+        KeyCode::Down => *scroll + 1,
+        KeyCode::PageUp => scroll.saturating_sub(page),
+        KeyCode::PageDown => *scroll + page,
+        KeyCode::Home => 0,
+        KeyCode::End => max_scroll,
+        _ => *scroll,
     }
+    .min(max_scroll);
+    false
+}
+
+/// Selection a paging key moves a list of `len` rows to, or None for other keys.
+/// `g`/`G` count as `Home`/`End` where `vim_keys` is set.
+fn list_jump(
+    code: KeyCode,
+    selected: usize,
+    len: usize,
+    page: usize,
+    vim_keys: bool,
+) -> Option<usize> {
+    let last = len.saturating_sub(1);
+    let to = match code {
+        KeyCode::PageUp => selected.saturating_sub(page),
+        KeyCode::PageDown => selected + page,
+        KeyCode::Home => 0,
+        KeyCode::Char('g') if vim_keys => 0,
+        KeyCode::End => last,
+        KeyCode::Char('G') if vim_keys => last,
+        _ => return None,
+    };
+    Some(to.min(last))
 }
 
+/// `:` commands of the vim keymap, each running the action of a key of the default one
+const VIM_COMMANDS: [(&str, KeyCode); 15] = [
+    ("q", KeyCode::Char('q')),
+    ("quit", KeyCode::Char('q')),
+    ("live", KeyCode::Char('1')),
+    ("history", KeyCode::Char('2')),
+    ("compare", KeyCode::Char('3')),
+    ("channels", KeyCode::Char('4')),
+    ("scan", KeyCode::Char(' ')),
+    ("sort", KeyCode::Char('s')),
+    ("filter", KeyCode::Char('f')),
+    ("columns", KeyCode::Char('o')),
+    ("export", KeyCode::Char('e')),
+    // Keys the vim keymap takes over for moving
+    ("highlight", KeyCode::Char('h')),
+    ("group", KeyCode::Char('g')),
+    ("geiger", KeyCode::Char('G')),
+    ("load", KeyCode::Char('l')),
+];
+
 fn handle_key(app: &mut App, code: KeyCode, modifiers: KeyModifiers) {
+    let code = if app.config.vim_keys {
+        match vim_key(app, code) {
+            Some(code) => code,
+            None => return,
+        }
+    } else {
+        code
+    };
+    dispatch_key(app, code, modifiers);
+}
+
+/// The default key a key of the vim keymap stands for, or None when it's used up
+/// (the first `g` of `gg`, or `/` and `:` opening their prompt). Text fields and
+/// dialogs other than the session picker get keys as typed.
+fn vim_key(app: &mut App, code: KeyCode) -> Option<KeyCode> {
+    let pending_g = std::mem::take(&mut app.vim_pending_g);
+    let on_screen = app.popup == Popup::None;
+    let picker = app.popup == Popup::FilePicker;
+    if !on_screen && !picker {
+        return Some(code);
+    }
+    Some(match code {
+        KeyCode::Char('j') => KeyCode::Down,
+        KeyCode::Char('k') => KeyCode::Up,
+        KeyCode::Char('h') if picker => KeyCode::Backspace,
+        KeyCode::Char('l') if picker => KeyCode::Enter,
+        KeyCode::Char('h') => KeyCode::Left,
+        KeyCode::Char('l') => KeyCode::Right,
+        KeyCode::Char('g') if pending_g => KeyCode::Home,
+        KeyCode::Char('g') => {
+            app.vim_pending_g = true;
+            return None;
+        }
+        KeyCode::Char('G') => KeyCode::End,
+        KeyCode::Char('/') if on_screen => {
+            app.show_search_popup();
+            return None;
+        }
+        KeyCode::Char(':') if on_screen => {
+            app.show_command_popup();
+            return None;
+        }
+        _ => code,
+    })
+}
+
+/// Run a `:` command through the key it names
+fn run_command(app: &mut App, command: &str) {
+    app.popup = Popup::None;
+    let command = command.trim();
+    match VIM_COMMANDS.iter().find(|(name, _)| *name == command) {
+        Some(&(_, key)) => dispatch_key(app, key, KeyModifiers::NONE),
+        None if command.is_empty() => {}
+        None => app.toast = Some((format!("Unknown command :{}", command), Instant::now())),
+    }
+}
+
+fn dispatch_key(app: &mut App, code: KeyCode, modifiers: KeyModifiers) {
+    let (width, height) = app.terminal_size;
+    let screen = Rect::new(0, 0, width, height);
+
     // Handle popups first
     match &mut app.popup {
         Popup::None => {}
-        Popup::AdapterCollision { selected } => {
+        Popup::AdapterCollision {
+            adapter,
+            existing,
+            selected,
+        } => {
             match code {
                 KeyCode::Up => *selected = selected.saturating_sub(1),
                 KeyCode::Down => *selected = (*selected + 1).min(2),
                 KeyCode::Enter => {
-                    // Handle selection
+                    let choice = *selected;
+                    let (adapter, existing) = (adapter.clone(), existing.clone());
+                    if let Err(e) = app.resolve_collision(adapter, existing, choice) {
+                        app.show_error(format!("Failed to switch adapter: {}", e));
+                    }
+                }
+                KeyCode::Esc => app.popup = Popup::None,
+                _ => {}
+            }
+            return;
+        }
+        Popup::InterfaceSwitch { adapters, selected } => {
+            match code {
+                KeyCode::Up => *selected = selected.saturating_sub(1),
+                KeyCode::Down => *selected = (*selected + 1).min(adapters.len().saturating_sub(1)),
+                KeyCode::Enter => {
+                    if let Some(adapter) = adapters.get(*selected).cloned() {
+                        app.request_switch(adapter);
+                    }
+                }
+                KeyCode::Esc => app.popup = Popup::None,
+                _ => {}
+            }
+            return;
+        }
+        Popup::ProfilePicker { profiles, selected } => {
+            match code {
+                KeyCode::Up => *selected = selected.saturating_sub(1),
+                KeyCode::Down => *selected = (*selected + 1).min(profiles.len().saturating_sub(1)),
+                KeyCode::Enter => {
+                    let name = profiles.get(*selected).cloned().unwrap_or_default();
                     app.popup = Popup::None;
+                    if let Err(e) = app.apply_profile(&name) {
+                        app.show_error(format!("{:#}", e));
+                    }
                 }
                 KeyCode::Esc => app.popup = Popup::None,
                 _ => {}
@@ -270,17 +1065,15 @@ fn handle_key(app: &mut App, code: KeyCode, modifiers: KeyModifiers) {
         Popup::RenameAdapter { input, cursor } => {
             match code {
                 KeyCode::Char(c) => {
-                    input.insert(*cursor, c);
+                    utils::insert_char(input, *cursor, c);
                     *cursor += 1;
                 }
-                KeyCode::Backspace => {
-                    if *cursor > 0 {
-                        *cursor -= 1;
-                        input.remove(*cursor);
-                    }
+                KeyCode::Backspace if *cursor > 0 => {
+                    *cursor -= 1;
+                    utils::remove_char(input, *cursor);
                 }
                 KeyCode::Left => *cursor = cursor.saturating_sub(1),
-                KeyCode::Right => *cursor = (*cursor + 1).min(input.len()),
+                KeyCode::Right => *cursor = (*cursor + 1).min(input.chars().count()),
                 KeyCode::Enter => {
                     let name = input.clone();
                     app.apply_rename(name);
@@ -290,23 +1083,83 @@ fn handle_key(app: &mut App, code: KeyCode, modifiers: KeyModifiers) {
             }
             return;
         }
-        Popup::TimerSetup { input, cursor } => {
+        Popup::SessionSetup { values, field, cursor } => {
+            let input = &mut values[*field];
             match code {
-                KeyCode::Char(c) if c.is_ascii_digit() => {
-                    input.insert(*cursor, c);
+                KeyCode::Char(c) => {
+                    utils::insert_char(input, *cursor, c);
                     *cursor += 1;
                 }
-                KeyCode::Backspace => {
-                    if *cursor > 0 {
-                        *cursor -= 1;
-                        input.remove(*cursor);
+                KeyCode::Backspace if *cursor > 0 => {
+                    *cursor -= 1;
+                    utils::remove_char(input, *cursor);
+                }
+                KeyCode::Left => *cursor = cursor.saturating_sub(1),
+                KeyCode::Right => *cursor = (*cursor + 1).min(input.chars().count()),
+                KeyCode::Up | KeyCode::BackTab => {
+                    *field = field.saturating_sub(1);
+                    *cursor = values[*field].chars().count();
+                }
+                KeyCode::Down | KeyCode::Tab => {
+                    *field = (*field + 1).min(values.len() - 1);
+                    *cursor = values[*field].chars().count();
+                }
+                KeyCode::Enter => {
+                    let values = values.clone();
+                    app.apply_setup(values);
+                }
+                KeyCode::Esc => app.popup = Popup::None,
+                _ => {}
+            }
+            return;
+        }
+        Popup::Search { input, cursor }
+        | Popup::Command { input, cursor }
+        | Popup::Marker { input, cursor }
+        | Popup::WalkSetup { input, cursor } => {
+            match code {
+                KeyCode::Char(c) => {
+                    utils::insert_char(input, *cursor, c);
+                    *cursor += 1;
+                }
+                KeyCode::Backspace if *cursor > 0 => {
+                    *cursor -= 1;
+                    utils::remove_char(input, *cursor);
+                }
+                KeyCode::Left => *cursor = cursor.saturating_sub(1),
+                KeyCode::Right => *cursor = (*cursor + 1).min(input.chars().count()),
+                KeyCode::Enter => {
+                    let line = input.clone();
+                    match app.popup {
+                        Popup::Search { .. } => app.search(line),
+                        Popup::Marker { .. } => app.add_marker(line),
+                        Popup::WalkSetup { .. } => app.start_walk(line),
+                        _ => run_command(app, &line),
                     }
                 }
+                KeyCode::Esc => app.popup = Popup::None,
+                _ => {}
+            }
+            return;
+        }
+        Popup::TimerSetup { input, cursor } | Popup::AlternateSetup { input, cursor } => {
+            match code {
+                KeyCode::Char(c) if c.is_ascii_digit() => {
+                    utils::insert_char(input, *cursor, c);
+                    *cursor += 1;
+                }
+                KeyCode::Backspace if *cursor > 0 => {
+                    *cursor -= 1;
+                    utils::remove_char(input, *cursor);
+                }
                 KeyCode::Left => *cursor = cursor.saturating_sub(1),
-                KeyCode::Right => *cursor = (*cursor + 1).min(input.len()),
+                KeyCode::Right => *cursor = (*cursor + 1).min(input.chars().count()),
                 KeyCode::Enter => {
                     let mins = input.clone();
-                    app.apply_timer(mins);
+                    match app.popup {
+                        Popup::AlternateSetup { .. } => app.start_alternation(mins),
+                        _ => app.apply_timer(mins),
+                    }
                 }
                 KeyCode::Esc => app.popup = Popup::None,
                 _ => {}
@@ -314,6 +1167,11 @@ fn handle_key(app: &mut App, code: KeyCode, modifiers: KeyModifiers) {
             return;
         }
         Popup::FilePicker => {
+            let (selected, len) = (app.file_picker.selected, app.file_picker.items.len());
+            if let Some(to) = list_jump(code, selected, len, app.page_rows(), true) {
+                app.file_picker.selected = to;
+                return;
+            }
             match code {
                 KeyCode::Up => app.file_picker.select_prev(),
                 KeyCode::Down => app.file_picker.select_next(),
@@ -334,12 +1192,80 @@ fn handle_key(app: &mut App, code: KeyCode, modifiers: KeyModifiers) {
                         }
                     }
                 }
-                KeyCode::Backspace => {
-                    // Go back to adapter list
-                    if app.file_picker.is_at_sessions() {
-                        if let Err(e) = app.file_picker_go_back() {
-                            app.show_error(format!("Failed to go back: {}", e));
-                        }
+                // Go back to adapter list
+                KeyCode::Backspace if app.file_picker.is_at_sessions() => {
+                    if let Err(e) = app.file_picker_go_back() {
+                        app.show_error(format!("Failed to go back: {}", e));
+                    }
+                }
+                KeyCode::Esc => app.popup = Popup::None,
+                _ => {}
+            }
+            return;
+        }
+        Popup::ExportChoice { selected, anonymize } => {
+            match code {
+                KeyCode::Up => *selected = selected.saturating_sub(1),
+                KeyCode::Down => *selected = (*selected + 1).min(ExportFormat::ALL.len() - 1),
+                KeyCode::Char('a') => *anonymize = anonymize.next(),
+                KeyCode::Enter => {
+                    let (format, anonymize) = (ExportFormat::ALL[*selected], *anonymize);
+                    // Progress and the result show in the status toast
+                    match app.export_current(format, anonymize) {
+                        Ok(_) => app.popup = Popup::None,
+                        Err(e) => app.show_error(format!("Export failed: {}", e)),
+                    }
+                }
+                KeyCode::Esc => app.popup = Popup::None,
+                _ => {}
+            }
+            return;
+        }
+        Popup::AdapterAdded { adapter, selected } => {
+            match code {
+                KeyCode::Left | KeyCode::Up => *selected = 0,
+                KeyCode::Right | KeyCode::Down => *selected = 1,
+                KeyCode::Enter => {
+                    let adapter = adapter.clone();
+                    let switch = *selected == 0;
+                    app.popup = Popup::None;
+                    if switch {
+                        app.request_switch(adapter);
+                    }
+                }
+                KeyCode::Esc => app.popup = Popup::None,
+                _ => {}
+            }
+            return;
+        }
+        Popup::LegacySessions { files, selected } => {
+            match code {
+                KeyCode::Left | KeyCode::Up => *selected = 0,
+                KeyCode::Right | KeyCode::Down => *selected = 1,
+                KeyCode::Enter => {
+                    let files = std::mem::take(files);
+                    let migrate = *selected == 0;
+                    app.popup = Popup::None;
+                    if migrate {
+                        app.migrate_legacy_sessions(files);
+                    }
+                }
+                KeyCode::Esc => app.popup = Popup::None,
+                _ => {}
+            }
+            return;
+        }
+        Popup::LabelDirSessions { adapter, files, selected } => {
+            match code {
+                KeyCode::Left | KeyCode::Up => *selected = 0,
+                KeyCode::Right | KeyCode::Down => *selected = 1,
+                KeyCode::Enter => {
+                    let files = std::mem::take(files);
+                    let adapter = adapter.clone();
+                    let claim = *selected == 0;
+                    app.popup = Popup::None;
+                    if claim {
+                        app.claim_sessions(adapter, files);
                     }
                 }
                 KeyCode::Esc => app.popup = Popup::None,
@@ -347,19 +1273,53 @@ fn handle_key(app: &mut App, code: KeyCode, modifiers: KeyModifiers) {
             }
             return;
         }
-        Popup::ExportChoice { selected } => {
+        Popup::ResetInterface { selected, .. } => {
+            match code {
+                KeyCode::Left | KeyCode::Up => *selected = 0,
+                KeyCode::Right | KeyCode::Down => *selected = 1,
+                KeyCode::Enter => {
+                    let reset = *selected == 0;
+                    app.popup = Popup::None;
+                    if reset {
+                        app.reset_interface();
+                    }
+                }
+                KeyCode::Esc => app.popup = Popup::None,
+                _ => {}
+            }
+            return;
+        }
+        Popup::Walk { selected } => {
+            match code {
+                KeyCode::Up => *selected = selected.saturating_sub(1),
+                KeyCode::Down => *selected = (*selected + 1).min(1),
+                // Arriving again while dwelling does nothing
+                KeyCode::Enter if *selected == 0 => app.walk_arrived(),
+                KeyCode::Enter | KeyCode::Esc => app.stop_walk(),
+                _ => {}
+            }
+            return;
+        }
+        Popup::Swap { .. } => {
+            // Waits for the other adapter to be plugged in
+            if matches!(code, KeyCode::Enter | KeyCode::Esc) {
+                app.stop_alternation();
+            }
+            return;
+        }
+        Popup::Snapshot { selected } => {
             match code {
                 KeyCode::Up => *selected = selected.saturating_sub(1),
                 KeyCode::Down => *selected = (*selected + 1).min(1),
                 KeyCode::Enter => {
-                    let csv = *selected == 1;
-                    match app.export_current(csv) {
+                    let svg = *selected == 1;
+                    match app.snapshot(svg) {
                         Ok(path) => {
                             app.popup = Popup::None;
-                            app.show_error(format!("Exported to {}", path.display()));
+                            app.show_error(format!("Saved to {}", path.display()));
                         }
                         Err(e) => {
-                            app.show_error(format!("Export failed: {}", e));
+                            app.show_error(format!("Snapshot failed: {}", e));
                         }
                     }
                 }
@@ -368,10 +1328,18 @@ fn handle_key(app: &mut App, code: KeyCode, modifiers: KeyModifiers) {
             }
             return;
         }
-        Popup::Error { .. } => {
-            match code {
-                KeyCode::Enter | KeyCode::Esc => app.popup = Popup::None,
-                _ => {}
+        Popup::SessionSummary { quit_after, .. } => {
+            if matches!(code, KeyCode::Enter | KeyCode::Esc) {
+                if *quit_after {
+                    app.running = false;
+                }
+                app.popup = Popup::None;
+            }
+            return;
+        }
+        Popup::Error { message, scroll } => {
+            if scroll_message("Error", message, scroll, code, screen) {
+                app.popup = Popup::None;
             }
             return;
         }
@@ -419,9 +1387,126 @@ fn handle_key(app: &mut App, code: KeyCode, modifiers: KeyModifiers) {
             }
             return;
         }
-        Popup::SessionWarning { .. } => {
+        Popup::Exclusions { selected } => {
+            let len = app.config.excluded_aps.len();
             match code {
-                KeyCode::Enter | KeyCode::Esc => app.popup = Popup::None,
+                KeyCode::Up => *selected = selected.saturating_sub(1),
+                KeyCode::Down => *selected = (*selected + 1).min(len.saturating_sub(1)),
+                KeyCode::Char('a') => {
+                    app.popup = Popup::ExclusionPattern {
+                        input: String::new(),
+                        cursor: 0,
+                    }
+                }
+                KeyCode::Char('d') | KeyCode::Delete => {
+                    let index = *selected;
+                    app.remove_exclusion(index);
+                }
+                KeyCode::Char('p') => {
+                    let index = *selected;
+                    app.toggle_exclusion_policy(index);
+                }
+                KeyCode::Char('g') => app.toggle_default_exclusion_policy(),
+                KeyCode::Char('u') => {
+                    app.undo_config();
+                    let len = app.config.excluded_aps.len();
+                    if let Popup::Exclusions { selected } = &mut app.popup {
+                        *selected = (*selected).min(len.saturating_sub(1));
+                    }
+                }
+                KeyCode::Esc => app.popup = Popup::None,
+                _ => {}
+            }
+            return;
+        }
+        Popup::ExclusionPattern { input, cursor } => {
+            match code {
+                KeyCode::Char(c) => {
+                    utils::insert_char(input, *cursor, c);
+                    *cursor += 1;
+                }
+                KeyCode::Backspace if *cursor > 0 => {
+                    *cursor -= 1;
+                    utils::remove_char(input, *cursor);
+                }
+                KeyCode::Left => *cursor = cursor.saturating_sub(1),
+                KeyCode::Right => *cursor = (*cursor + 1).min(input.chars().count()),
+                KeyCode::Enter => {
+                    let pattern = input.clone();
+                    app.add_exclusion_pattern(pattern);
+                }
+                KeyCode::Esc => app.show_exclusions(),
+                _ => {}
+            }
+            return;
+        }
+        Popup::SessionWarning { message, scroll, .. } => {
+            if scroll_message("Warning", message, scroll, code, screen) {
+                app.popup = Popup::None;
+            }
+            return;
+        }
+        Popup::ApDetail { message, scroll } => {
+            if scroll_message("AP Detail", message, scroll, code, screen) {
+                app.popup = Popup::None;
+            }
+            return;
+        }
+        Popup::Columns { selected } => {
+            let choices = app.live.column_choices();
+            let Some(&(column, _)) = choices.get(*selected) else {
+                app.popup = Popup::None;
+                return;
+            };
+            match code {
+                KeyCode::Up => *selected = selected.saturating_sub(1),
+                KeyCode::Down => *selected = (*selected + 1).min(choices.len() - 1),
+                KeyCode::Char(' ') | KeyCode::Enter => app.live.toggle_column(column),
+                KeyCode::Char('[') => app.live.move_column(column, -1),
+                KeyCode::Char(']') => app.live.move_column(column, 1),
+                KeyCode::Left => app.live.resize_column(column, -1),
+                KeyCode::Right => app.live.resize_column(column, 1),
+                KeyCode::Char('0') => app.live.reset_column_width(column),
+                KeyCode::Char('r') => app.live.reset_columns(),
+                KeyCode::Esc => app.popup = Popup::None,
+                _ => {}
+            }
+            // Follow the column as it moves between the shown and hidden ones
+            if !matches!(code, KeyCode::Up | KeyCode::Down) {
+                if let Popup::Columns { selected } = &mut app.popup {
+                    let choices = app.live.column_choices();
+                    *selected = choices.iter().position(|c| c.0 == column).unwrap_or(0);
+                }
+            }
+            return;
+        }
+        Popup::Sort { selected } => {
+            if code == KeyCode::Esc {
+                app.popup = Popup::None;
+                return;
+            }
+            match app.screen {
+                Screen::Compare => {
+                    let before = app.compare.sort.clone();
+                    sort_popup_key(&mut app.compare.sort, selected, code, false);
+                    if app.compare.sort != before {
+                        app.compare.selected_ap_idx = 0;
+                    }
+                }
+                _ => sort_popup_key(&mut app.live.sort, selected, code, true),
+            }
+            return;
+        }
+        Popup::Calibration { selected } => {
+            let done = app.calibration.as_ref().map(|c| c.done).unwrap_or(true);
+            match code {
+                KeyCode::Esc => app.finish_calibration(false),
+                KeyCode::Left | KeyCode::Up if done => *selected = 0,
+                KeyCode::Right | KeyCode::Down if done => *selected = 1,
+                KeyCode::Enter if done => {
+                    let save = *selected == 0;
+                    app.finish_calibration(save);
+                }
                 _ => {}
             }
             return;
@@ -435,71 +1520,149 @@ fn handle_key(app: &mut App, code: KeyCode, modifiers: KeyModifiers) {
         KeyCode::Char('1') => app.switch_screen(Screen::Live),
         KeyCode::Char('2') => app.switch_screen(Screen::History),
         KeyCode::Char('3') => app.switch_screen(Screen::Compare),
+        KeyCode::Char('4') => app.switch_screen(Screen::Channels),
+        KeyCode::Char('y') => app.yank(),
+        KeyCode::Char('u') => app.undo_config(),
+        KeyCode::Char('P') => app.popup = Popup::Snapshot { selected: 0 },
         _ => {
             // Screen-specific keys
             match app.screen {
                 Screen::Live => handle_live_key(app, code),
                 Screen::History => handle_history_key(app, code),
                 Screen::Compare => handle_compare_key(app, code),
+                Screen::Channels => handle_channels_key(app, code),
             }
         }
     }
 }
 
 fn handle_live_key(app: &mut App, code: KeyCode) {
-    match code {
-        KeyCode::Char(' ') => app.perform_scan(),
-        KeyCode::Char('a') => app.live.toggle_auto_scan(),
-        KeyCode::Char('t') => app.show_timer_popup(),
-        KeyCode::Char('r') => app.show_rename_popup(),
-        KeyCode::Char('c') => app.live.toggle_channel(),
-        KeyCode::Char('b') => app.live.toggle_band(),
-        KeyCode::Char('f') => app.live.cycle_filter(),
-        KeyCode::Char('s') => app.live.cycle_sort(),
-        KeyCode::Char('h') => app.live.toggle_highlight(),
-        KeyCode::Char('x') => app.show_exclude_popup(),
-        KeyCode::Char('e') => app.popup = Popup::ExportChoice { selected: 0 },
-        KeyCode::Up => app.live.ap_list_state.select_prev(),
-        KeyCode::Down => {
-            let len = app.live.access_points.len();
+    // g and G are grouping and Geiger mode here
+    let (selected, len) = (app.live.ap_list_state.selected, app.live.row_count());
+    if let Some(to) = list_jump(code, selected, len, app.page_rows(), false) {
+        app.live.ap_list_state.selected = to;
+        return;
+    }
+    let Some(action) = ui::live::live_action(code) else {
+        return;
+    };
+    match action {
+        LiveAction::Scan => app.perform_scan(),
+        LiveAction::AutoScan => app.live.toggle_auto_scan(),
+        LiveAction::Timer => app.show_timer_popup(),
+        LiveAction::TimerMode => app.live.toggle_timer_mode(),
+        LiveAction::Rename => app.show_rename_popup(),
+        LiveAction::Channel => app.live.toggle_channel(),
+        LiveAction::Band => app.live.toggle_band(),
+        LiveAction::Filter => app.live.cycle_filter(),
+        LiveAction::Capability => app.live.cycle_capability(),
+        LiveAction::BssType => app.live.cycle_type(),
+        LiveAction::Sort => app.live.cycle_sort(),
+        LiveAction::Highlight => app.live.toggle_highlight(),
+        LiveAction::Group => app.live.toggle_grouped(),
+        LiveAction::Split => app.live.toggle_split(),
+        LiveAction::Geiger => app.live.toggle_geiger(),
+        LiveAction::ConnectTest => app.start_connect_test(),
+        LiveAction::LoadTest => app.start_load_test(),
+        LiveAction::Expand => app.live.toggle_expand_selected(),
+        LiveAction::Detail => app.show_ap_detail(),
+        LiveAction::Pin => app.live.toggle_pin(),
+        LiveAction::Columns => app.popup = Popup::Columns { selected: 0 },
+        LiveAction::Bssid => app.live.toggle_bssid(),
+        LiveAction::SortOrder => app.popup = Popup::Sort { selected: 0 },
+        LiveAction::ScrollLeft => app.live.scroll_columns(-1, app.terminal_size.0),
+        LiveAction::ScrollRight => app.live.scroll_columns(1, app.terminal_size.0),
+        LiveAction::Exclude => app.show_exclude_popup(),
+        LiveAction::Exclusions => app.show_exclusions(),
+        LiveAction::Calibrate => app.start_calibration(),
+        LiveAction::Interface => app.show_interface_switcher(),
+        LiveAction::Profile => app.show_profile_picker(),
+        LiveAction::Setup => app.edit_setup(),
+        LiveAction::Marker => app.show_marker_popup(),
+        LiveAction::Walk => app.show_walk_setup(),
+        LiveAction::SyncScans => app.toggle_sync_scans(),
+        LiveAction::Alternate => app.show_alternate_setup(),
+        LiveAction::Export => app.show_export_choice(),
+        LiveAction::Up => app.live.ap_list_state.select_prev(),
+        LiveAction::Down => {
+            let len = app.live.row_count();
             app.live.ap_list_state.select_next(len);
         }
-        _ => {}
     }
 }
 
 fn handle_history_key(app: &mut App, code: KeyCode) {
+    let len = app.history.session.as_ref().map_or(0, |s| s.unique_aps().len());
+    if let Some(to) = list_jump(code, app.history.selected_ap_idx, len, app.page_rows(), true) {
+        app.history.selected_ap_idx = to;
+        return;
+    }
     match code {
         KeyCode::Char('l') | KeyCode::Char('+') => app.show_file_picker(),
         KeyCode::Char('w') => app.history.cycle_time_window(),
         KeyCode::Char('d') => app.history.toggle_average(),
-        KeyCode::Char('e') => app.popup = Popup::ExportChoice { selected: 0 },
+        KeyCode::Char('D') => app.history.toggle_outliers(),
+        KeyCode::Char('H') => app.history.cycle_host_series(),
+        KeyCode::Char('a') => app.toggle_across_sessions(),
+        KeyCode::Char('e') => app.show_export_choice(),
         KeyCode::Up => app.history.select_prev_ap(),
         KeyCode::Down => app.history.select_next_ap(),
         _ => {}
     }
 }
 
-fn handle_compare_key(app: &mut App, code: KeyCode) {
-    // Visible height for session list (approximate, actual may vary with terminal size)
-    // The render function uses 4-6 based on terminal height
-    const SESSION_LIST_HEIGHT: usize = 6;
+fn handle_channels_key(app: &mut App, code: KeyCode) {
+    let len = app
+        .current_session
+        .as_ref()
+        .map(|s| app.channels.stats(s).len())
+        .unwrap_or(0);
+    if let Some(to) = list_jump(code, app.channels.selected, len, app.page_rows(), true) {
+        app.channels.selected = to;
+        return;
+    }
+    match code {
+        KeyCode::Up => app.channels.select_prev(),
+        KeyCode::Down => app.channels.select_next(len),
+        _ => {}
+    }
+}
 
+fn handle_compare_key(app: &mut App, code: KeyCode) {
+    let len = app.compare.all_aps().len();
+    if let Some(to) = list_jump(code, app.compare.selected_ap_idx, len, app.page_rows(), true) {
+        app.compare.selected_ap_idx = to;
+        return;
+    }
     match code {
         KeyCode::Char('+') => app.show_file_picker(),
         KeyCode::Char('x') => app.compare.remove_selected_session(),
         KeyCode::Char('m') => app.compare.cycle_match(),
         KeyCode::Char('M') => app.compare.cycle_metric(),
-        KeyCode::Char('e') => app.popup = Popup::ExportChoice { selected: 0 },
+        KeyCode::Char('n') => app.compare.toggle_normalize(),
+        KeyCode::Char('c') => app.compare.toggle_calibrated(),
+        KeyCode::Char('D') => app.compare.toggle_outliers(),
+        KeyCode::Char('w') => app.compare.cycle_window(),
+        KeyCode::Char('t') => app.compare.toggle_aligned(),
+        KeyCode::Char('W') => app.compare.toggle_checkpoints(),
+        KeyCode::Char('f') => app.compare.cycle_band_filter(),
+        KeyCode::Char('s') => app.compare.cycle_min_samples(),
+        KeyCode::Char('o') => app.compare.toggle_sort_by_delta(),
+        KeyCode::Char('O') => app.popup = Popup::Sort { selected: 0 },
+        KeyCode::Char('N') => app.compare.cycle_rows(),
+        KeyCode::Char(']') => app.compare.adjust_buckets(true),
+        KeyCode::Char('[') => app.compare.adjust_buckets(false),
+        KeyCode::Enter => app.compare.toggle_band_breakdown(),
+        KeyCode::Char('e') => app.show_export_choice(),
         KeyCode::Up => app.compare.select_prev_ap(),
         KeyCode::Down => app.compare.select_next_ap(),
         KeyCode::Left => {
             app.compare.select_prev_session();
-            app.compare.ensure_session_visible(SESSION_LIST_HEIGHT);
+            app.compare.ensure_session_visible(app.compare_list_height());
         }
         KeyCode::Right => {
             app.compare.select_next_session();
-            app.compare.ensure_session_visible(SESSION_LIST_HEIGHT);
+            app.compare.ensure_session_visible(app.compare_list_height());
         }
         _ => {}
     }
@@ -1,7 +1,7 @@
 use anyhow::{Context, Result};
 use std::process::Command;
 
-use crate::data::Adapter;
+use crate::data::{Adapter, UsbLink};
 
 /// Detect available wireless adapters
 pub fn detect_adapters() -> Result<Vec<Adapter>> {
@@ -21,9 +21,92 @@ pub fn detect_adapters() -> Result<Vec<Adapter>> {
     parse_iw_dev(&stdout)
 }
 
+/// Find the detected adapter for a named interface.
+///
+/// Errors clearly when the interface doesn't exist or isn't wireless.
+pub fn find_adapter(interface: &str) -> Result<Adapter> {
+    let adapters = detect_adapters()?;
+    if let Some(adapter) = adapters.iter().find(|a| a.interface == interface) {
+        return Ok(adapter.clone());
+    }
+
+    let available = if adapters.is_empty() {
+        "none detected".to_string()
+    } else {
+        adapters
+            .iter()
+            .map(|a| a.interface.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    if interface_exists(interface) {
+        anyhow::bail!(
+            "{} is not a wireless interface (wireless: {})",
+            interface,
+            available
+        );
+    }
+    anyhow::bail!("No such interface: {} (wireless: {})", interface, available)
+}
+
+/// Whether any network interface (wireless or not) has this name
+#[cfg(unix)]
+fn interface_exists(interface: &str) -> bool {
+    std::path::Path::new("/sys/class/net").join(interface).exists()
+}
+
+#[cfg(not(unix))]
+fn interface_exists(_interface: &str) -> bool {
+    false
+}
+
+/// Names of wireless interfaces currently present, from sysfs.
+///
+/// Cheap enough to poll for hotplug detection, unlike `detect_adapters`.
+#[cfg(not(unix))]
+pub fn wireless_interfaces() -> Vec<String> {
+    Vec::new()
+}
+
+#[cfg(unix)]
+pub fn wireless_interfaces() -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir("/sys/class/net") else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().join("phy80211").exists() || e.path().join("wireless").exists())
+        .filter_map(|e| e.file_name().into_string().ok())
+        .collect();
+    names.sort();
+    names
+}
+
 /// Parse output of `iw dev`
 fn parse_iw_dev(output: &str) -> Result<Vec<Adapter>> {
-    let mut adapters = Vec::new();
+    Ok(iw_dev_interfaces(output)
+        .into_iter()
+        .map(|iface| {
+            let (driver, chipset, id) = get_adapter_info(&iface);
+            let versions = driver_versions(&iface, &driver);
+            let usb = usb_link(&iface);
+            Adapter {
+                interface: iface,
+                driver,
+                chipset,
+                label: None,
+                id,
+                driver_version: versions.driver,
+                firmware_version: versions.firmware,
+                usb,
+            }
+        })
+        .collect())
+}
+
+/// Interface names in `iw dev` output
+pub(super) fn iw_dev_interfaces(output: &str) -> Vec<String> {
+    let mut interfaces = Vec::new();
     let mut current_interface: Option<String> = None;
 
     for line in output.lines() {
@@ -31,38 +114,71 @@ fn parse_iw_dev(output: &str) -> Result<Vec<Adapter>> {
         if trimmed.starts_with("Interface ") {
             current_interface = Some(trimmed.trim_start_matches("Interface ").to_string());
         } else if trimmed.starts_with("type ") && current_interface.is_some() {
-            let iface = current_interface.take().unwrap();
-            let (driver, chipset) = get_adapter_info(&iface).unwrap_or_else(|_| {
-                ("unknown".to_string(), "Unknown Adapter".to_string())
-            });
-            adapters.push(Adapter {
-                interface: iface,
-                driver,
-                chipset,
-                label: None,
-            });
+            interfaces.push(current_interface.take().unwrap());
         }
     }
 
-    Ok(adapters)
+    interfaces
+}
+
+/// Driver bound to an interface, from sysfs
+#[cfg(unix)]
+fn read_uevent(interface: &str) -> Option<String> {
+    std::fs::read_to_string(format!("/sys/class/net/{}/device/uevent", interface)).ok()
+}
+
+#[cfg(not(unix))]
+fn read_uevent(_interface: &str) -> Option<String> {
+    None
+}
+
+/// MAC address of an interface, if it is the burned-in one rather than randomized or set
+#[cfg(unix)]
+fn permanent_mac(interface: &str) -> Option<String> {
+    let dir = std::path::Path::new("/sys/class/net").join(interface);
+    let assign_type = std::fs::read_to_string(dir.join("addr_assign_type")).ok()?;
+    // NET_ADDR_PERM
+    if assign_type.trim() != "0" {
+        return None;
+    }
+    let address = std::fs::read_to_string(dir.join("address")).ok()?;
+    Some(address.trim().to_lowercase())
+}
+
+#[cfg(not(unix))]
+fn permanent_mac(_interface: &str) -> Option<String> {
+    None
+}
+
+/// Get driver, chipset and stable id for an interface
+fn get_adapter_info(interface: &str) -> (String, String, Option<String>) {
+    let udevadm = udevadm_info(interface);
+    let (driver, chipset) = adapter_info(read_uevent(interface).as_deref(), udevadm.as_deref());
+    let id = adapter_id(permanent_mac(interface).as_deref(), udevadm.as_deref());
+    (driver, chipset, id)
 }
 
-/// Get driver and chipset info for an interface
-fn get_adapter_info(interface: &str) -> Result<(String, String)> {
-    // Try to get info from /sys
-    let uevent_path = format!("/sys/class/net/{}/device/uevent", interface);
-    let driver = if let Ok(contents) = std::fs::read_to_string(&uevent_path) {
-        contents
+/// Stable id from the permanent MAC, else the USB vendor:product from `udevadm info`
+fn adapter_id(permanent_mac: Option<&str>, udevadm: Option<&str>) -> Option<String> {
+    if let Some(mac) = permanent_mac.filter(|m| !m.is_empty() && *m != "00:00:00:00:00:00") {
+        return Some(format!("mac:{}", mac));
+    }
+    let value = |key: &str| {
+        udevadm?
             .lines()
-            .find(|l| l.starts_with("DRIVER="))
-            .map(|l| l.trim_start_matches("DRIVER=").to_string())
-            .unwrap_or_else(|| "unknown".to_string())
-    } else {
-        "unknown".to_string()
+            .find_map(|line| line.split_once(key).map(|(_, v)| v.trim().to_lowercase()))
     };
+    Some(format!("usb:{}:{}", value("ID_VENDOR_ID=")?, value("ID_MODEL_ID=")?))
+}
 
-    // Try udevadm for more info
-    let chipset = get_chipset_from_udevadm(interface).unwrap_or_else(|| {
+/// Driver and chipset from the interface's sysfs uevent and `udevadm info` output
+pub(super) fn adapter_info(uevent: Option<&str>, udevadm: Option<&str>) -> (String, String) {
+    let driver = uevent
+        .and_then(|contents| contents.lines().find(|l| l.starts_with("DRIVER=")))
+        .map(|l| l.trim_start_matches("DRIVER=").to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+
+    let chipset = udevadm.and_then(chipset_from_udevadm).unwrap_or_else(|| {
         // Fallback to driver name as chipset
         match driver.as_str() {
             "iwlwifi" => "Intel WiFi".to_string(),
@@ -74,11 +190,11 @@ fn get_adapter_info(interface: &str) -> Result<(String, String)> {
         }
     });
 
-    Ok((driver, chipset))
+    (driver, chipset)
 }
 
-/// Get chipset info from udevadm
-fn get_chipset_from_udevadm(interface: &str) -> Option<String> {
+/// Run `udevadm info` for an interface
+fn udevadm_info(interface: &str) -> Option<String> {
     let output = Command::new("udevadm")
         .args(["info", &format!("/sys/class/net/{}", interface)])
         .output()
@@ -88,8 +204,11 @@ fn get_chipset_from_udevadm(interface: &str) -> Option<String> {
         return None;
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
+    Some(String::from_utf8_lossy(&output.stdout).into_owned())
+}
 
+/// Chipset from `udevadm info` output
+fn chipset_from_udevadm(stdout: &str) -> Option<String> {
     // Look for ID_MODEL_FROM_DATABASE or ID_MODEL
     for line in stdout.lines() {
         if line.contains("ID_MODEL_FROM_DATABASE=") {
@@ -105,6 +224,90 @@ fn get_chipset_from_udevadm(interface: &str) -> Option<String> {
     None
 }
 
+/// Driver and firmware versions as reported by `ethtool -i`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DriverVersions {
+    pub driver: Option<String>,
+    pub firmware: Option<String>,
+}
+
+/// Versions from `ethtool -i`, with the driver version from `modinfo` when ethtool is
+/// missing or doesn't report one
+pub fn driver_versions(interface: &str, driver: &str) -> DriverVersions {
+    let mut versions = Command::new("ethtool")
+        .args(["-i", interface])
+        .output()
+        .ok()
+        .filter(|out| out.status.success())
+        .map(|out| parse_ethtool_info(&String::from_utf8_lossy(&out.stdout)))
+        .unwrap_or_default();
+    if versions.driver.is_none() && driver != "unknown" {
+        versions.driver = Command::new("modinfo")
+            .args(["-F", "version", driver])
+            .output()
+            .ok()
+            .filter(|out| out.status.success())
+            .map(|out| String::from_utf8_lossy(&out.stdout).trim().to_string())
+            .filter(|v| !v.is_empty());
+    }
+    versions
+}
+
+/// Parse the `version:` and `firmware-version:` lines of `ethtool -i`
+fn parse_ethtool_info(output: &str) -> DriverVersions {
+    let value = |key: &str| {
+        output
+            .lines()
+            .find_map(|line| line.strip_prefix(key))
+            .map(str::trim)
+            .filter(|v| !v.is_empty() && *v != "N/A")
+            .map(str::to_string)
+    };
+    DriverVersions {
+        driver: value("version:"),
+        firmware: value("firmware-version:"),
+    }
+}
+
+/// USB link of an interface's adapter, from sysfs (None for PCI and SDIO adapters)
+#[cfg(unix)]
+fn usb_link(interface: &str) -> Option<UsbLink> {
+    let device = std::path::Path::new("/sys/class/net").join(interface).join("device");
+    usb_link_from(&std::fs::canonicalize(device).ok()?)
+}
+
+#[cfg(not(unix))]
+fn usb_link(_interface: &str) -> Option<UsbLink> {
+    None
+}
+
+/// The interface's device is a USB interface like `.../usb2/2-1/2-1:1.0`; the USB device
+/// above it, the nearest directory with `speed` and `devpath`, holds the link details
+fn usb_link_from(device: &std::path::Path) -> Option<UsbLink> {
+    let read = |dir: &std::path::Path, name: &str| {
+        std::fs::read_to_string(dir.join(name)).ok().map(|s| s.trim().to_string())
+    };
+    let usb = device
+        .ancestors()
+        .find(|dir| dir.join("speed").is_file() && dir.join("devpath").is_file())?;
+    Some(UsbLink {
+        speed_mbps: read(usb, "speed")?.parse::<f32>().ok()? as u32,
+        // Reported like `500mA`
+        max_power_ma: read(usb, "bMaxPower")
+            .and_then(|p| p.trim_end_matches("mA").trim().parse().ok()),
+        port: usb.file_name()?.to_string_lossy().into_owned(),
+    })
+}
+
+/// Running kernel release, e.g. `6.5.0-14-generic`
+pub fn kernel_version() -> Option<String> {
+    let release = std::fs::read_to_string("/proc/sys/kernel/osrelease").ok().or_else(|| {
+        let out = Command::new("uname").arg("-r").output().ok()?;
+        Some(String::from_utf8_lossy(&out.stdout).into_owned())
+    })?;
+    Some(release.trim().to_string()).filter(|r| !r.is_empty())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -124,4 +327,58 @@ mod tests {
         assert_eq!(adapters.len(), 1);
         assert_eq!(adapters[0].interface, "wlan0");
     }
+
+    #[test]
+    fn test_adapter_id() {
+        let udevadm = "E: ID_VENDOR_ID=0BDA\nE: ID_MODEL_ID=b812\n";
+        assert_eq!(
+            adapter_id(Some("aa:bb:cc:dd:ee:ff"), Some(udevadm)).as_deref(),
+            Some("mac:aa:bb:cc:dd:ee:ff")
+        );
+        // Randomized MAC
+        assert_eq!(adapter_id(None, Some(udevadm)).as_deref(), Some("usb:0bda:b812"));
+        assert_eq!(adapter_id(None, Some("E: ID_VENDOR_ID=0bda\n")), None);
+    }
+
+    #[test]
+    fn test_parse_ethtool_info() {
+        let output = "driver: iwlwifi\n\
+            version: 6.5.0-14-generic\n\
+            firmware-version: 83.e8f84e98.0 ty-a0-gf-a0-83.uc\n\
+            expansion-rom-version: \n\
+            bus-info: 0000:00:14.3\n";
+        assert_eq!(
+            parse_ethtool_info(output),
+            DriverVersions {
+                driver: Some("6.5.0-14-generic".to_string()),
+                firmware: Some("83.e8f84e98.0 ty-a0-gf-a0-83.uc".to_string()),
+            }
+        );
+        let output = "driver: rtw88_8822bu\nversion: \nfirmware-version: N/A\n";
+        assert_eq!(parse_ethtool_info(output), DriverVersions::default());
+    }
+
+    #[test]
+    fn test_usb_link() {
+        let root = std::env::temp_dir().join(format!("wificomp-usb-{}", std::process::id()));
+        let hub = root.join("usb1/1-4");
+        let device = hub.join("1-4.2");
+        let interface = device.join("1-4.2:1.0");
+        std::fs::create_dir_all(&interface).unwrap();
+        for (dir, speed, devpath) in [(&hub, "480", "4"), (&device, "480", "4.2")] {
+            std::fs::write(dir.join("speed"), format!("{}\n", speed)).unwrap();
+            std::fs::write(dir.join("devpath"), format!("{}\n", devpath)).unwrap();
+        }
+        std::fs::write(device.join("bMaxPower"), "500mA\n").unwrap();
+
+        let link = usb_link_from(&interface).unwrap();
+        assert_eq!(
+            link,
+            UsbLink { speed_mbps: 480, max_power_ma: Some(500), port: "1-4.2".to_string() }
+        );
+        assert_eq!(link.label(), "USB 2.0 hub");
+        // Not on USB at all
+        assert_eq!(usb_link_from(&root.join("pci0000:00")), None);
+        let _ = std::fs::remove_dir_all(&root);
+    }
 }
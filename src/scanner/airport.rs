@@ -0,0 +1,302 @@
+use chrono::Utc;
+use std::process::Command;
+
+use super::ScanError;
+use crate::data::{AccessPoint, Adapter, BssType, LinkStats, Roaming, ScanResult, Security};
+
+/// Apple's private airport utility, which wraps CoreWLAN
+const AIRPORT: &str = "/System/Library/PrivateFrameworks/Apple80211.framework/Versions/Current/\
+                       Resources/airport";
+
+/// Driver recorded for adapters found through CoreWLAN, which doesn't name the driver
+const AIRPORT_DRIVER: &str = "corewlan";
+
+/// Run `airport <interface> <args>`, returning stdout
+fn run_airport(interface: &str, args: &[&str]) -> Result<String, ScanError> {
+    log::debug!("running airport {} {}", interface, args.join(" "));
+    let output = Command::new(AIRPORT)
+        .arg(interface)
+        .args(args)
+        .output()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => {
+                ScanError::Other("airport utility not found (removed in macOS 14.4)".to_string())
+            }
+            _ => ScanError::Other(format!("Failed to run airport: {}", e)),
+        })?;
+    let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(ScanError::Other(stderr.trim().to_string()));
+    }
+    Ok(stdout)
+}
+
+/// Classify airport output that isn't a scan or status listing
+fn classify(interface: &str, output: &str) -> Option<ScanError> {
+    let text = output.trim();
+    if text.contains("AirPort: Off") || text.contains("No such interface") {
+        Some(ScanError::InterfaceDown(interface.to_string()))
+    } else {
+        None
+    }
+}
+
+/// Scan on macOS with `airport -s`
+pub fn scan_airport(interface: &str) -> Result<ScanResult, ScanError> {
+    let output = run_airport(interface, &["-s"])?;
+    if let Some(error) = classify(interface, &output) {
+        return Err(error);
+    }
+    if !output.trim().is_empty() && !output.contains("BSSID") {
+        return Err(ScanError::ParseFailure(output.trim().to_string()));
+    }
+
+    let link = run_airport(interface, &["-I"]).ok().and_then(|out| parse_info(&out));
+
+    Ok(ScanResult {
+        timestamp: Utc::now(),
+        access_points: parse_scan(&output),
+        channel_dwell: Vec::new(),
+        link,
+        position: None,
+        trigger: None,
+        host: None,
+    })
+}
+
+/// Wireless hardware ports reported by `networksetup -listallhardwareports`
+pub fn detect_adapters() -> anyhow::Result<Vec<Adapter>> {
+    let output = Command::new("networksetup")
+        .arg("-listallhardwareports")
+        .output()
+        .map_err(|e| anyhow::anyhow!("Failed to run networksetup: {}", e))?;
+    Ok(parse_hardware_ports(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Whether a hardware port name is a wireless one
+fn is_wireless_port(port: &str) -> bool {
+    ["Wi-Fi", "AirPort", "WLAN", "Wireless"].iter().any(|name| port.contains(name))
+}
+
+/// Parse `networksetup -listallhardwareports`, keeping wireless ports
+fn parse_hardware_ports(output: &str) -> Vec<Adapter> {
+    let mut adapters = Vec::new();
+    let mut port: Option<&str> = None;
+
+    for line in output.lines() {
+        if let Some(name) = line.strip_prefix("Hardware Port:") {
+            port = Some(name.trim());
+        } else if let (Some(device), Some(name)) = (line.strip_prefix("Device:"), port.take()) {
+            if is_wireless_port(name) {
+                adapters.push(Adapter {
+                    interface: device.trim().to_string(),
+                    driver: AIRPORT_DRIVER.to_string(),
+                    chipset: name.to_string(),
+                    label: None,
+                    id: None,
+                    driver_version: None,
+                    firmware_version: None,
+                    usb: None,
+                });
+            }
+        }
+    }
+    adapters
+}
+
+/// airport drops leading zeros from BSSID octets (`3c:84:6a:10:0:2`)
+fn normalize_bssid(value: &str) -> Option<String> {
+    let octets: Vec<&str> = value.split(':').collect();
+    let valid = octets.len() == 6
+        && octets
+            .iter()
+            .all(|o| (1..=2).contains(&o.len()) && o.chars().all(|c| c.is_ascii_hexdigit()));
+    if !valid {
+        return None;
+    }
+    Some(octets.iter().map(|o| format!("{:0>2}", o.to_uppercase())).collect::<Vec<_>>().join(":"))
+}
+
+fn parse_security(text: &str) -> Option<Security> {
+    if text.contains("WPA3") {
+        Some(Security::Wpa3)
+    } else if text.contains("WPA2") {
+        Some(Security::Wpa2)
+    } else if text.contains("WPA") {
+        Some(Security::Wpa)
+    } else if text.contains("WEP") {
+        Some(Security::Wep)
+    } else if text.contains("NONE") {
+        Some(Security::Open)
+    } else {
+        None
+    }
+}
+
+/// Channel and width from airport's `36,80` / `1,+1` / `6` notation
+fn parse_channel(value: &str) -> Option<(u32, Option<u32>)> {
+    let (channel, width) = match value.split_once(',') {
+        Some((channel, width)) => (channel, Some(width)),
+        None => (value, None),
+    };
+    let width = match width {
+        Some("+1") | Some("-1") => Some(40),
+        Some(w) => w.parse().ok(),
+        None => None,
+    };
+    Some((channel.parse().ok()?, width))
+}
+
+/// Whether every WPA entry, like `WPA(PSK/TKIP/TKIP)`, has TKIP as its only unicast cipher
+fn tkip_only(security: &[&str]) -> bool {
+    let unicast: Vec<&str> = security
+        .iter()
+        .filter_map(|s| s.split('/').nth(1))
+        .collect();
+    !unicast.is_empty() && unicast.iter().all(|c| *c == "TKIP")
+}
+
+/// Centre frequency for a channel; airport doesn't distinguish 6 GHz channels
+fn channel_to_freq(channel: u32) -> u32 {
+    match channel {
+        14 => 2484,
+        1..=13 => 2407 + channel * 5,
+        _ => 5000 + channel * 5,
+    }
+}
+
+/// Parse `airport -s`; the SSID column is right-aligned and may contain spaces
+fn parse_scan(output: &str) -> Vec<AccessPoint> {
+    let mut aps = Vec::new();
+
+    for line in output.lines().skip(1) {
+        let mut tokens = line.split_whitespace();
+        let Some(raw_bssid) = tokens.find(|t| normalize_bssid(t).is_some()) else {
+            continue;
+        };
+        let ssid = line[..line.find(raw_bssid).unwrap_or(0)].trim().to_string();
+        let (Some(rssi), Some(channel)) = (tokens.next(), tokens.next()) else {
+            continue;
+        };
+        let (Ok(signal_dbm), Some((channel, width))) = (rssi.parse(), parse_channel(channel))
+        else {
+            continue;
+        };
+        // Skip the HT and country code columns
+        let security: Vec<&str> = tokens.skip(2).collect();
+
+        aps.push(AccessPoint {
+            bssid: normalize_bssid(raw_bssid).unwrap_or_default(),
+            ssid,
+            signal_dbm,
+            channel,
+            frequency_mhz: channel_to_freq(channel),
+            security: parse_security(&security.join(" ")),
+            channel_width_mhz: width,
+            mld_address: None,
+            bss_type: BssType::Infrastructure,
+            beacon_interval_tu: None,
+            station_count: None,
+            channel_utilization: None,
+            regulatory: None,
+            wps: false,
+            tkip_only: tkip_only(&security),
+            roaming: Roaming::default(),
+            last_seen_ms: None,
+        });
+    }
+    aps
+}
+
+/// Parse `airport -I` into the current link, if associated
+fn parse_info(output: &str) -> Option<LinkStats> {
+    let mut link = LinkStats {
+        bssid: String::new(),
+        ssid: String::new(),
+        signal_dbm: None,
+        tx_bitrate_mbps: None,
+        rx_bitrate_mbps: None,
+    };
+
+    for line in output.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "state" if value != "running" => return None,
+            "BSSID" => link.bssid = normalize_bssid(value).unwrap_or_default(),
+            "SSID" => link.ssid = value.to_string(),
+            "agrCtlRSSI" => link.signal_dbm = value.parse().ok(),
+            "lastTxRate" => link.tx_bitrate_mbps = value.parse().ok(),
+            _ => {}
+        }
+    }
+    Some(link).filter(|link| !link.bssid.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCAN: &str = include_str!("../../tests/fixtures/airport/scan.txt");
+    const INFO: &str = include_str!("../../tests/fixtures/airport/info.txt");
+    const PORTS: &str = include_str!("../../tests/fixtures/airport/hardware_ports.txt");
+
+    #[test]
+    fn test_parse_scan() {
+        let aps = parse_scan(SCAN);
+        let summary: Vec<String> = aps
+            .iter()
+            .map(|ap| {
+                format!(
+                    "{} {} ch{} {:?} {} {:?} {:?}",
+                    ap.bssid,
+                    ap.frequency_mhz,
+                    ap.channel,
+                    ap.channel_width_mhz,
+                    ap.signal_dbm,
+                    ap.security,
+                    ap.ssid
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                "3C:84:6A:10:00:01 2437 ch6 None -55 Some(Wpa3) \"HomeNet\"",
+                "3C:84:6A:10:00:02 5180 ch36 Some(80) -60 Some(Wpa3) \"HomeNet\"",
+                "F0:9F:C2:20:00:01 2412 ch1 Some(40) -68 Some(Open) \"Office Guest\"",
+                "A0:63:91:50:00:01 5240 ch48 Some(40) -77 Some(Wpa) \"CoffeeShop\"",
+                "A0:63:91:50:00:02 5745 ch149 None -82 Some(Wpa2) \"\"",
+            ]
+        );
+        let tkip: Vec<bool> = aps.iter().map(|ap| ap.tkip_only).collect();
+        assert_eq!(tkip, [false, false, false, true, false]);
+    }
+
+    #[test]
+    fn test_parse_info() {
+        let link = parse_info(INFO).unwrap();
+        assert_eq!(link.bssid, "3C:84:6A:10:00:02");
+        assert_eq!(link.ssid, "HomeNet");
+        assert_eq!(link.signal_dbm, Some(-54));
+        assert_eq!(link.tx_bitrate_mbps, Some(866.0));
+
+        assert!(parse_info("AirPort: Off\n").is_none());
+        assert!(parse_info(&INFO.replace("running", "init")).is_none());
+    }
+
+    #[test]
+    fn test_parse_hardware_ports() {
+        let adapters = parse_hardware_ports(PORTS);
+        let interfaces: Vec<(&str, &str)> =
+            adapters.iter().map(|a| (a.interface.as_str(), a.chipset.as_str())).collect();
+        assert_eq!(interfaces, vec![("en1", "Wi-Fi"), ("en7", "USB WLAN")]);
+        assert_eq!(
+            classify("en1", "AirPort: Off\n"),
+            Some(ScanError::InterfaceDown("en1".to_string()))
+        );
+    }
+}
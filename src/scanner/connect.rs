@@ -0,0 +1,213 @@
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
+use std::io::Read;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::privilege::command_exists;
+use crate::data::{ConnectTiming, Reachability};
+
+/// How often the link and device state are sampled while connecting
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Longest a connect may take before the test counts as failed
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// NetworkManager device state once the handshake is done and IP configuration starts
+const NM_STATE_IP_CONFIG: u32 = 70;
+
+/// NetworkManager device state once the connection is fully up
+const NM_STATE_ACTIVATED: u32 = 100;
+
+/// Answers 204 with no body; anything else means something is intercepting HTTP
+const CHECK_URL: &str = "http://connectivitycheck.gstatic.com/generate_204";
+
+/// Longest the reachability check may take
+const CHECK_TIMEOUT_SECS: &str = "5";
+
+/// When each phase of a connect was first seen, measured from the start
+#[derive(Debug, Default)]
+struct Phases {
+    associated: Option<Duration>,
+    ip_config: Option<Duration>,
+    activated: Option<Duration>,
+}
+
+impl Phases {
+    fn observe(&mut self, linked: bool, state: Option<u32>, at: Duration) {
+        if linked {
+            self.associated.get_or_insert(at);
+        }
+        if let Some(state) = state {
+            if state >= NM_STATE_IP_CONFIG {
+                self.ip_config.get_or_insert(at);
+            }
+            if state >= NM_STATE_ACTIVATED {
+                self.activated.get_or_insert(at);
+            }
+        }
+    }
+
+    /// Phase lengths, or None until the connection is up. A link that came up between
+    /// two samples together with IP configuration counts as associated at that sample.
+    fn timing(&self, network: &str) -> Option<ConnectTiming> {
+        let activated = self.activated?;
+        let ip_config = self.ip_config.unwrap_or(activated);
+        let associated = self.associated.unwrap_or(ip_config).min(ip_config);
+        Some(ConnectTiming {
+            timestamp: Utc::now(),
+            network: network.to_string(),
+            association_ms: associated.as_millis() as u64,
+            handshake_ms: (ip_config - associated).as_millis() as u64,
+            dhcp_ms: (activated - ip_config).as_millis() as u64,
+            reachability: None,
+            portal_url: None,
+        })
+    }
+}
+
+/// Disconnect `interface`, bring NetworkManager profile `network` up on it, and time
+/// association (until `iw` reports a link), the 4-way handshake (until NetworkManager
+/// starts IP configuration) and DHCP (until the connection is activated). Once up,
+/// checks whether the internet is reachable through it.
+pub fn connect_test(interface: &str, network: &str) -> Result<ConnectTiming> {
+    if !command_exists("nmcli") {
+        bail!("Connect tests need nmcli (NetworkManager)");
+    }
+    // Start from a disconnected adapter so every phase is measured
+    let _ = Command::new("nmcli")
+        .args(["device", "disconnect", interface])
+        .output();
+
+    let start = Instant::now();
+    let mut child = Command::new("nmcli")
+        .args(["--wait", "60", "connection", "up", "id", network, "ifname", interface])
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run nmcli")?;
+
+    let mut phases = Phases::default();
+    loop {
+        let at = start.elapsed();
+        phases.observe(link_up(interface), device_state(interface), at);
+        if let Some(mut timing) = phases.timing(network) {
+            let _ = child.wait();
+            let (reachability, portal_url) = check_reachability(interface);
+            timing.reachability = Some(reachability);
+            timing.portal_url = portal_url;
+            return Ok(timing);
+        }
+        if let Some(status) = child.try_wait().context("Failed to run nmcli")? {
+            if !status.success() {
+                let mut stderr = String::new();
+                if let Some(mut pipe) = child.stderr.take() {
+                    let _ = pipe.read_to_string(&mut stderr);
+                }
+                bail!("nmcli couldn't bring up {}: {}", network, stderr.trim());
+            }
+        }
+        if at >= CONNECT_TIMEOUT {
+            let _ = child.kill();
+            bail!("{} didn't connect within {}s", network, CONNECT_TIMEOUT.as_secs());
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Fetch the 204 check URL through `interface` with curl, and the portal it was
+/// redirected to, if any
+fn check_reachability(interface: &str) -> (Reachability, Option<String>) {
+    let output = Command::new("curl")
+        .args(["--silent", "--interface", interface, "--max-time", CHECK_TIMEOUT_SECS])
+        .args(["--output", "/dev/null", "--write-out", "%{http_code} %{redirect_url}"])
+        .arg(CHECK_URL)
+        .output();
+    match output {
+        Ok(out) => parse_check(&String::from_utf8_lossy(&out.stdout)),
+        Err(e) => {
+            log::warn!("couldn't run curl for the reachability check: {}", e);
+            (Reachability::Offline, None)
+        }
+    }
+}
+
+/// Parse curl's `<status> <redirect url>`; status 000 means no response
+fn parse_check(output: &str) -> (Reachability, Option<String>) {
+    let mut parts = output.split_whitespace();
+    match parts.next() {
+        Some("204") => (Reachability::Online, None),
+        None | Some("000") => (Reachability::Offline, None),
+        Some(_) => (Reachability::CaptivePortal, parts.next().map(str::to_string)),
+    }
+}
+
+/// Whether `iw` reports `interface` associated with an AP
+fn link_up(interface: &str) -> bool {
+    Command::new("iw")
+        .args(["dev", interface, "link"])
+        .output()
+        .map(|out| parse_link_connected(&String::from_utf8_lossy(&out.stdout)))
+        .unwrap_or(false)
+}
+
+fn parse_link_connected(output: &str) -> bool {
+    output.trim_start().starts_with("Connected to")
+}
+
+/// NetworkManager's numeric state for `interface`
+fn device_state(interface: &str) -> Option<u32> {
+    let out = Command::new("nmcli")
+        .args(["-t", "-f", "GENERAL.STATE", "device", "show", interface])
+        .output()
+        .ok()?;
+    parse_nm_state(&String::from_utf8_lossy(&out.stdout))
+}
+
+/// Parse `GENERAL.STATE:70 (connecting (getting IP configuration))`
+fn parse_nm_state(output: &str) -> Option<u32> {
+    let value = output.lines().find_map(|l| l.strip_prefix("GENERAL.STATE:"))?;
+    value.split_whitespace().next()?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_connect_phases() {
+        assert_eq!(
+            parse_nm_state("GENERAL.STATE:70 (connecting (getting IP configuration))\n"),
+            Some(70)
+        );
+        assert_eq!(parse_nm_state(""), None);
+        assert!(parse_link_connected("Connected to 3c:84:6a:10:00:01 (on wlan0)\n"));
+        assert!(!parse_link_connected("Not connected.\n"));
+
+        let ms = Duration::from_millis;
+        let mut phases = Phases::default();
+        phases.observe(false, Some(50), ms(50));
+        phases.observe(true, Some(50), ms(100));
+        phases.observe(true, Some(70), ms(150));
+        assert!(phases.timing("Home").is_none());
+        phases.observe(true, Some(100), ms(400));
+        let timing = phases.timing("Home").unwrap();
+        assert_eq!(
+            (timing.association_ms, timing.handshake_ms, timing.dhcp_ms),
+            (100, 50, 250)
+        );
+        assert_eq!(timing.total_ms(), 400);
+    }
+
+    #[test]
+    fn test_parse_check() {
+        assert_eq!(parse_check("204 "), (Reachability::Online, None));
+        assert_eq!(parse_check("000 "), (Reachability::Offline, None));
+        assert_eq!(
+            parse_check("302 http://portal.example/login"),
+            (Reachability::CaptivePortal, Some("http://portal.example/login".to_string()))
+        );
+        assert_eq!(parse_check("200 "), (Reachability::CaptivePortal, None));
+    }
+}
@@ -0,0 +1,278 @@
+use chrono::{DateTime, Utc};
+
+use super::freq_to_channel;
+use crate::data::{
+    AccessPoint, Adapter, ApRegulatory, BssType, ChannelDwell, ConnectTiming, LinkStats, LoadTest,
+    RadioContext, Reachability, Roaming, ScanResult, Security,
+};
+
+/// Synthetic adapters: interface, chipset, and signal offset in dBm
+const DEMO_ADAPTERS: [(&str, &str, i32); 2] =
+    [("demo0", "Demo AX210", 0), ("demo1", "Demo RTL8812AU", -5)];
+
+/// Synthetic AP: BSSID, SSID, frequency, base signal, security, channel width
+type DemoAp = (&'static str, &'static str, u32, i32, Security, Option<u32>);
+
+const DEMO_APS: [DemoAp; 10] = [
+    ("3C:84:6A:10:00:01", "HomeNet", 2437, -48, Security::Wpa3, Some(20)),
+    ("3C:84:6A:10:00:02", "HomeNet", 5180, -55, Security::Wpa3, Some(80)),
+    ("3C:84:6A:10:00:03", "HomeNet", 5955, -66, Security::Wpa3, Some(160)),
+    ("F0:9F:C2:20:00:01", "Office_Guest", 2412, -63, Security::Open, Some(20)),
+    ("F0:9F:C2:20:00:02", "Office", 5745, -70, Security::Wpa2, Some(40)),
+    ("00:14:6C:30:00:01", "NETGEAR42", 2462, -74, Security::Wpa2, Some(20)),
+    ("12:34:56:40:00:01", "カフェ_Free", 2437, -79, Security::Open, None),
+    ("A0:63:91:50:00:01", "", 5240, -77, Security::Wpa2, Some(80)),
+    ("DC:A6:32:60:00:01", "HP-Print-4B-LaserJet", 2412, -84, Security::Wpa, None),
+    ("B8:27:EB:70:00:01", "far-away-mesh", 5500, -88, Security::Wpa3, Some(80)),
+];
+
+/// MLD address of the demo Wi-Fi 7 AP, and the BSSIDs of its links
+const DEMO_MLD: (&str, [&str; 3]) =
+    ("3E:84:6A:10:00:00", ["3C:84:6A:10:00:01", "3C:84:6A:10:00:02", "3C:84:6A:10:00:03"]);
+
+/// BSSID of the demo mesh point
+const DEMO_MESH_POINT: &str = "B8:27:EB:70:00:01";
+
+/// Demo APs that send a BSS Load element: BSSID, station count, base channel utilization %
+const DEMO_LOAD: [(&str, u32, u8); 4] = [
+    ("3C:84:6A:10:00:01", 6, 38),
+    ("3C:84:6A:10:00:02", 3, 9),
+    ("3C:84:6A:10:00:03", 1, 2),
+    ("F0:9F:C2:20:00:02", 23, 71),
+];
+
+/// Demo APs that send Country and Power Constraint elements: BSSID, country, max power in
+/// dBm, constraint in dB. The Office AP was set up for the wrong country.
+const DEMO_REGULATORY: [(&str, &str, i32, i32); 4] = [
+    ("3C:84:6A:10:00:01", "US", 30, 0),
+    ("3C:84:6A:10:00:02", "US", 23, 3),
+    ("3C:84:6A:10:00:03", "US", 24, 0),
+    ("F0:9F:C2:20:00:02", "CA", 30, 0),
+];
+
+/// Demo APs with WPS switched on
+const DEMO_WPS: [&str; 2] = ["00:14:6C:30:00:01", "DC:A6:32:60:00:01"];
+
+/// Demo AP still on WPA with TKIP
+const DEMO_TKIP_ONLY: &str = "DC:A6:32:60:00:01";
+
+/// Demo APs that help clients roam: BSSID, 802.11k, 802.11v, 802.11r
+const DEMO_ROAMING: [(&str, bool, bool, bool); 4] = [
+    ("3C:84:6A:10:00:01", true, true, true),
+    ("3C:84:6A:10:00:02", true, true, true),
+    ("3C:84:6A:10:00:03", true, true, true),
+    ("F0:9F:C2:20:00:02", true, true, false),
+];
+
+/// Regulatory domain of the demo adapters
+const DEMO_COUNTRY: &str = "US";
+
+/// Weakest signal a demo adapter still "hears"
+const DEMO_NOISE_FLOOR: i32 = -92;
+
+/// Adapters offered in `--demo` mode
+pub fn demo_adapters() -> Vec<Adapter> {
+    DEMO_ADAPTERS
+        .iter()
+        .map(|&(interface, chipset, _)| Adapter {
+            interface: interface.to_string(),
+            driver: "demo".to_string(),
+            chipset: chipset.to_string(),
+            label: None,
+            id: Some(format!("demo:{}", interface)),
+            driver_version: None,
+            firmware_version: None,
+            usb: None,
+        })
+        .collect()
+}
+
+/// Tx power and regulatory domain of a demo adapter
+pub fn demo_radio_context() -> RadioContext {
+    RadioContext { tx_power_dbm: Some(20.0), country: Some(DEMO_COUNTRY.to_string()) }
+}
+
+/// Deterministic noise in 0..modulus from a few inputs (splitmix64)
+fn noise(seed: u64, modulus: u64) -> u64 {
+    let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    (z ^ (z >> 31)) % modulus
+}
+
+/// A plausible scan for a demo adapter at time `at`.
+///
+/// Signals drift slowly with per-AP periods plus a little jitter, and weak APs
+/// drop in and out. The same interface and time always give the same scan.
+pub fn demo_scan(interface: &str, at: DateTime<Utc>) -> ScanResult {
+    let (adapter_idx, offset) = DEMO_ADAPTERS
+        .iter()
+        .enumerate()
+        .find(|(_, (name, _, _))| *name == interface)
+        .map(|(i, (_, _, offset))| (i as u64, *offset))
+        .unwrap_or((0, 0));
+    let secs = at.timestamp();
+    let t = at.timestamp_millis() as f64 / 1000.0;
+
+    let access_points: Vec<AccessPoint> = DEMO_APS
+        .iter()
+        .enumerate()
+        .filter_map(|(i, &(bssid, ssid, frequency_mhz, base, security, width))| {
+            let period = 90.0 + 37.0 * i as f64;
+            let drift = 6.0 * (std::f64::consts::TAU * t / period + i as f64).sin();
+            let seed = (secs as u64) ^ ((i as u64) << 40) ^ (adapter_idx << 56);
+            let jitter = noise(seed, 5) as i32 - 2;
+            // The second adapter's antenna is weaker on 5/6 GHz
+            let band_bias = if adapter_idx > 0 && frequency_mhz > 5000 { -3 } else { 0 };
+            let load = DEMO_LOAD.iter().find(|l| l.0 == bssid);
+            let signal_dbm = (base + drift.round() as i32 + jitter + offset + band_bias).min(-25);
+            (signal_dbm >= DEMO_NOISE_FLOOR).then(|| AccessPoint {
+                bssid: bssid.to_string(),
+                ssid: ssid.to_string(),
+                signal_dbm,
+                channel: freq_to_channel(frequency_mhz),
+                frequency_mhz,
+                security: Some(security),
+                channel_width_mhz: width,
+                mld_address: DEMO_MLD.1.contains(&bssid).then(|| DEMO_MLD.0.to_string()),
+                bss_type: if bssid == DEMO_MESH_POINT {
+                    BssType::Mesh
+                } else {
+                    BssType::Infrastructure
+                },
+                beacon_interval_tu: Some(100),
+                station_count: load.map(|l| l.1),
+                channel_utilization: load.map(|l| (l.2 + noise(seed >> 3, 7) as u8).min(100)),
+                regulatory: DEMO_REGULATORY.iter().find(|r| r.0 == bssid).map(|r| ApRegulatory {
+                    country: Some(r.1.to_string()),
+                    max_power_dbm: Some(r.2),
+                    power_constraint_db: Some(r.3),
+                    tx_power_dbm: None,
+                }),
+                wps: DEMO_WPS.contains(&bssid),
+                tkip_only: bssid == DEMO_TKIP_ONLY,
+                roaming: DEMO_ROAMING
+                    .iter()
+                    .find(|r| r.0 == bssid)
+                    .map(|&(_, k, v, r)| Roaming { k, v, r })
+                    .unwrap_or_default(),
+                // Weak APs' beacons get lost more often, so the driver's entry is older
+                last_seen_ms: Some(noise(seed >> 5, 400) + (signal_dbm < -75) as u64 * 2000),
+            })
+        })
+        .collect();
+
+    let mut frequencies: Vec<u32> = DEMO_APS.iter().map(|ap| ap.2).collect();
+    frequencies.sort_unstable();
+    frequencies.dedup();
+    let channel_dwell = frequencies
+        .into_iter()
+        .map(|frequency_mhz| ChannelDwell {
+            frequency_mhz,
+            active_ms: 110,
+            noise_dbm: Some(-95 + noise(secs as u64 ^ frequency_mhz as u64, 4) as i32),
+            busy_ms: Some(noise((secs as u64) << 8 ^ frequency_mhz as u64, 60)),
+        })
+        .collect();
+
+    // Associated to the first AP while it's audible
+    let link = access_points.first().filter(|ap| ap.bssid == DEMO_APS[0].0).map(|ap| {
+        LinkStats {
+            bssid: ap.bssid.clone(),
+            ssid: ap.ssid.clone(),
+            signal_dbm: Some(ap.signal_dbm),
+            tx_bitrate_mbps: Some(144.4),
+            rx_bitrate_mbps: Some(173.3),
+        }
+    });
+
+    ScanResult {
+        timestamp: at,
+        access_points,
+        channel_dwell,
+        link,
+        position: None,
+        trigger: None,
+        host: None,
+    }
+}
+
+/// A plausible connect test for a demo adapter; the weaker second adapter takes longer
+pub fn demo_connect_test(interface: &str, network: &str, at: DateTime<Utc>) -> ConnectTiming {
+    let adapter_idx = DEMO_ADAPTERS
+        .iter()
+        .position(|(name, _, _)| *name == interface)
+        .unwrap_or(0) as u64;
+    let seed = (at.timestamp() as u64) ^ (adapter_idx << 56);
+    let slow = adapter_idx * 20;
+    ConnectTiming {
+        timestamp: at,
+        network: network.to_string(),
+        association_ms: 30 + slow + noise(seed, 40),
+        handshake_ms: 20 + slow + noise(seed ^ 1, 30),
+        dhcp_ms: 300 + 4 * slow + noise(seed ^ 2, 400),
+        reachability: Some(Reachability::Online),
+        portal_url: None,
+    }
+}
+
+/// A plausible load test for a demo adapter; the weaker second adapter drops more
+/// pings and queues longer, the more so the heavier the load
+pub fn demo_load_test(
+    interface: &str,
+    target: &str,
+    secs: u64,
+    mbps: u32,
+    at: DateTime<Utc>,
+) -> LoadTest {
+    let adapter_idx = DEMO_ADAPTERS
+        .iter()
+        .position(|(name, _, _)| *name == interface)
+        .unwrap_or(0) as u64;
+    let seed = (at.timestamp() as u64) ^ (adapter_idx << 56);
+    let strain = (1 + adapter_idx * 3) * mbps as u64 / 10;
+    let pings_sent = (secs * 5) as u32;
+    let lost = noise(seed, strain + 1).min(pings_sent as u64) as u32;
+    let p50 = 3.0 + strain as f32 + noise(seed ^ 1, 20) as f32 / 10.0;
+    let p95 = p50 * 2.0 + noise(seed ^ 2, 10 * (strain + 1)) as f32;
+    let received = pings_sent - lost;
+    LoadTest {
+        timestamp: at,
+        target: target.to_string(),
+        duration_secs: secs,
+        load_mbps: mbps,
+        pings_sent,
+        pings_received: received,
+        rtt_p50_ms: (received > 0).then_some(p50),
+        rtt_p95_ms: (received > 0).then_some(p95),
+        rtt_p99_ms: (received > 0).then_some(p95 * 1.5),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_demo_scan() {
+        let at = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let scan = demo_scan("demo0", at);
+        assert!(scan.access_points.len() >= 8);
+        assert!(scan.link.is_some());
+        assert!(scan
+            .access_points
+            .iter()
+            .all(|ap| (DEMO_NOISE_FLOOR..=-25).contains(&ap.signal_dbm)));
+
+        // Deterministic for a given time and adapter, different across adapters
+        let again = demo_scan("demo0", at);
+        let signals =
+            |s: &ScanResult| s.access_points.iter().map(|a| a.signal_dbm).collect::<Vec<_>>();
+        assert_eq!(signals(&scan), signals(&again));
+        assert_ne!(signals(&scan), signals(&demo_scan("demo1", at)));
+
+        // Signals drift over time
+        let later = demo_scan("demo0", at + chrono::Duration::seconds(40));
+        assert_ne!(signals(&scan), signals(&later));
+    }
+}
@@ -0,0 +1,252 @@
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use super::privilege::{command_exists, privilege, sudo_ready, Privilege};
+use super::ScanBackend;
+use crate::data::DataPaths;
+
+/// Outcome of one environment check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Ok,
+    Warn,
+    Fail,
+}
+
+impl Status {
+    fn tag(&self) -> &'static str {
+        match self {
+            Status::Ok => "[ ok ]",
+            Status::Warn => "[warn]",
+            Status::Fail => "[FAIL]",
+        }
+    }
+}
+
+/// A diagnostic line for `wificomp doctor`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    pub name: String,
+    pub status: Status,
+    pub detail: String,
+    /// What to do about a warning or failure
+    pub remedy: Option<String>,
+}
+
+impl Check {
+    fn new(name: &str, status: Status, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            status,
+            detail: detail.into(),
+            remedy: None,
+        }
+    }
+
+    fn remedy(mut self, remedy: impl Into<String>) -> Self {
+        self.remedy = Some(remedy.into());
+        self
+    }
+}
+
+/// Tools a backend runs: name, whether scanning needs it, and what it's for
+fn tools(backend: ScanBackend) -> &'static [(&'static str, bool, &'static str)] {
+    match backend {
+        ScanBackend::Iw => &[
+            ("iw", true, "scanning"),
+            ("ip", false, "interface resets"),
+            ("nmcli", false, "resets through NetworkManager"),
+            ("wpa_cli", false, "resets through wpa_supplicant"),
+        ],
+        ScanBackend::Netsh => &[("netsh", true, "scanning")],
+        ScanBackend::Airport => &[("networksetup", true, "finding the Wi-Fi port")],
+        ScanBackend::Ifconfig => &[("ifconfig", true, "scanning")],
+        ScanBackend::Remote(_) => &[("ssh", true, "reaching --remote hosts")],
+        ScanBackend::Demo => &[],
+    }
+}
+
+/// Check what scanning with `backend` depends on
+pub fn run_checks(backend: ScanBackend, paths: &DataPaths) -> Vec<Check> {
+    let mut checks = Vec::new();
+
+    for &(tool, required, purpose) in tools(backend) {
+        checks.push(if command_exists(tool) {
+            Check::new(tool, Status::Ok, format!("found (for {})", purpose))
+        } else if required {
+            Check::new(tool, Status::Fail, format!("not found (needed for {})", purpose))
+                .remedy(format!("Install {} with your package manager", tool))
+        } else {
+            Check::new(tool, Status::Warn, format!("not found (optional, for {})", purpose))
+        });
+    }
+
+    if backend.needs_privilege() {
+        checks.push(privilege_check());
+    }
+
+    if backend == ScanBackend::Iw && cfg!(target_os = "linux") {
+        checks.extend(rfkill_checks(Path::new("/sys/class/rfkill")));
+        checks.extend(interface_checks());
+    }
+
+    checks.push(data_dir_check(&paths.sessions_dir));
+    checks
+}
+
+fn privilege_check() -> Check {
+    let name = "permissions";
+    match privilege() {
+        Privilege::Root => Check::new(name, Status::Ok, "running as root"),
+        Privilege::Capability => Check::new(name, Status::Ok, "binary has CAP_NET_ADMIN"),
+        Privilege::Sudo if sudo_ready() => {
+            Check::new(name, Status::Ok, "sudo credentials are cached")
+        }
+        Privilege::Sudo => Check::new(name, Status::Warn, "scans need sudo, which wants a password")
+            .remedy("Run `sudo -v` first, or `sudo setcap cap_net_admin+ep $(which wificomp)`"),
+    }
+}
+
+/// Soft and hard blocks of the wireless radios under `/sys/class/rfkill`
+fn rfkill_checks(dir: &Path) -> Vec<Check> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let read = |path: &Path, file: &str| {
+        fs::read_to_string(path.join(file)).map(|s| s.trim().to_string()).unwrap_or_default()
+    };
+    let mut checks: Vec<Check> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| read(path, "type") == "wlan")
+        .map(|path| {
+            let name = format!("rfkill {}", read(&path, "name"));
+            if read(&path, "hard") == "1" {
+                Check::new(&name, Status::Fail, "hard-blocked")
+                    .remedy("Turn on the wireless switch or function key")
+            } else if read(&path, "soft") == "1" {
+                Check::new(&name, Status::Fail, "soft-blocked").remedy("Run `rfkill unblock wifi`")
+            } else {
+                Check::new(&name, Status::Ok, "not blocked")
+            }
+        })
+        .collect();
+    checks.sort_by(|a, b| a.name.cmp(&b.name));
+    checks
+}
+
+/// Interface names and their `type` from `iw dev`
+fn interface_modes(iw_dev: &str) -> Vec<(String, String)> {
+    let mut modes = Vec::new();
+    let mut interface: Option<String> = None;
+    for line in iw_dev.lines().map(str::trim) {
+        if let Some(name) = line.strip_prefix("Interface ") {
+            interface = Some(name.trim().to_string());
+        } else if let (Some(mode), Some(name)) = (line.strip_prefix("type "), &interface) {
+            modes.push((name.clone(), mode.trim().to_string()));
+            interface = None;
+        }
+    }
+    modes
+}
+
+/// Each wireless interface should be up and in managed mode to scan
+fn interface_checks() -> Vec<Check> {
+    let output = Command::new("iw").arg("dev").output().ok();
+    let Some(output) = output.filter(|o| o.status.success()) else {
+        return Vec::new();
+    };
+    let modes = interface_modes(&String::from_utf8_lossy(&output.stdout));
+    if modes.is_empty() {
+        return vec![Check::new("interfaces", Status::Fail, "no wireless interfaces")
+            .remedy("Plug in an adapter, or check `dmesg` for driver or firmware errors")];
+    }
+    modes
+        .into_iter()
+        .map(|(interface, mode)| {
+            // IFF_UP is bit 0 of the interface flags
+            let flags = fs::read_to_string(format!("/sys/class/net/{}/flags", interface));
+            let up = flags.ok().and_then(|f| {
+                u32::from_str_radix(f.trim().trim_start_matches("0x"), 16).ok()
+            });
+            if mode != "managed" {
+                Check::new(&interface, Status::Warn, format!("in {} mode", mode))
+                    .remedy(format!("Run `sudo iw dev {} set type managed` to scan", interface))
+            } else if up.is_some_and(|flags| flags & 1 == 0) {
+                Check::new(&interface, Status::Warn, "managed, but down")
+                    .remedy(format!("Run `sudo ip link set {} up`", interface))
+            } else {
+                Check::new(&interface, Status::Ok, "managed and up")
+            }
+        })
+        .collect()
+}
+
+/// Sessions are saved under `dir`, so it has to be writable
+fn data_dir_check(dir: &Path) -> Check {
+    let name = "data directory";
+    let probe = dir.join(".wificomp-doctor");
+    let result = fs::create_dir_all(dir)
+        .and_then(|_| fs::write(&probe, b""))
+        .and_then(|_| fs::remove_file(&probe));
+    match result {
+        Ok(()) => Check::new(name, Status::Ok, format!("{} is writable", dir.display())),
+        Err(e) => Check::new(name, Status::Fail, format!("{}: {}", dir.display(), e))
+            .remedy("Fix its permissions, or pass --data-dir"),
+    }
+}
+
+/// One line per check, with remedies indented below problems
+pub fn report(checks: &[Check]) -> String {
+    let mut text = String::new();
+    for check in checks {
+        text.push_str(&format!("{} {}: {}\n", check.status.tag(), check.name, check.detail));
+        if let Some(remedy) = &check.remedy {
+            text.push_str(&format!("       -> {}\n", remedy));
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interface_modes() {
+        let output = "phy#1\n\tInterface wlan1\n\t\tifindex 5\n\t\ttype monitor\n\
+                      phy#0\n\tInterface wlan0\n\t\taddr 00:11:22:33:44:55\n\t\ttype managed\n";
+        assert_eq!(
+            interface_modes(output),
+            vec![
+                ("wlan1".to_string(), "monitor".to_string()),
+                ("wlan0".to_string(), "managed".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rfkill_checks() {
+        let dir = std::env::temp_dir().join(format!("wificomp-rfkill-{}", std::process::id()));
+        for (entry, kind, name, soft, hard) in [
+            ("rfkill0", "wlan", "phy0", "1", "0"),
+            ("rfkill1", "bluetooth", "hci0", "1", "0"),
+            ("rfkill2", "wlan", "phy1", "0", "0"),
+        ] {
+            let path = dir.join(entry);
+            fs::create_dir_all(&path).unwrap();
+            for (file, value) in [("type", kind), ("name", name), ("soft", soft), ("hard", hard)] {
+                fs::write(path.join(file), format!("{}\n", value)).unwrap();
+            }
+        }
+
+        let checks = rfkill_checks(&dir);
+        assert_eq!(
+            report(&checks),
+            "[FAIL] rfkill phy0: soft-blocked\n       -> Run `rfkill unblock wifi`\n\
+             [ ok ] rfkill phy1: not blocked\n"
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
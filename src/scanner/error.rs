@@ -0,0 +1,163 @@
+use thiserror::Error;
+
+/// Seconds before a quick retry after a transient failure
+const QUICK_RETRY_SECS: u64 = 2;
+
+/// Why a scan failed
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ScanError {
+    #[error("Permission denied: {0}")]
+    PermissionDenied(String),
+    #[error("Device busy")]
+    DeviceBusy,
+    #[error("{0} is down")]
+    InterfaceDown(String),
+    #[error("Scan timed out")]
+    Timeout,
+    #[error("Unreadable scan output: {0}")]
+    ParseFailure(String),
+    #[error("Scan failed: {0}")]
+    Other(String),
+}
+
+impl ScanError {
+    /// Classify `iw` stderr from a failed scan
+    pub fn from_stderr(interface: &str, stderr: &str) -> Self {
+        let stderr = stderr.trim();
+        if stderr.contains("a password is required") {
+            ScanError::PermissionDenied("sudo needs a password".to_string())
+        } else if stderr.contains("Operation not permitted") {
+            ScanError::PermissionDenied("iw needs CAP_NET_ADMIN".to_string())
+        } else if stderr.contains("Device or resource busy") {
+            ScanError::DeviceBusy
+        } else if stderr.contains("Network is down") {
+            ScanError::InterfaceDown(interface.to_string())
+        } else if stderr.contains("timed out") || stderr.contains("Timer expired") {
+            ScanError::Timeout
+        } else {
+            ScanError::Other(stderr.to_string())
+        }
+    }
+
+    /// What the user can do about it
+    pub fn remedy(&self) -> String {
+        if cfg!(windows) {
+            return self.windows_remedy();
+        }
+        if cfg!(target_os = "macos") {
+            return self.macos_remedy();
+        }
+        if cfg!(any(target_os = "freebsd", target_os = "openbsd")) {
+            return self.bsd_remedy();
+        }
+        match self {
+            ScanError::PermissionDenied(_) => {
+                "Run `sudo -v` or grant CAP_NET_ADMIN, then press Space".to_string()
+            }
+            ScanError::DeviceBusy => "Another scan is running; retrying".to_string(),
+            ScanError::InterfaceDown(interface) => {
+                format!("Run `sudo ip link set {} up`, then press Space", interface)
+            }
+            ScanError::Timeout => "iw did not answer; retrying".to_string(),
+            ScanError::ParseFailure(_) => "Please report your iw version".to_string(),
+            ScanError::Other(_) => "Check the adapter and iw install".to_string(),
+        }
+    }
+
+    /// What the user can do about it with the netsh backend
+    fn windows_remedy(&self) -> String {
+        match self {
+            ScanError::PermissionDenied(_) => {
+                "Allow location access for desktop apps in Settings, then press Space".to_string()
+            }
+            ScanError::InterfaceDown(_) => {
+                "Start WLAN AutoConfig and enable the adapter, then press Space".to_string()
+            }
+            ScanError::ParseFailure(_) => "Only English netsh output is supported".to_string(),
+            ScanError::Other(_) => "Check the adapter and that netsh runs".to_string(),
+            ScanError::DeviceBusy | ScanError::Timeout => "Retrying".to_string(),
+        }
+    }
+
+    /// What the user can do about it with the airport backend
+    fn macos_remedy(&self) -> String {
+        match self {
+            ScanError::PermissionDenied(_) => {
+                "Check the terminal's Privacy & Security permissions, then press Space".to_string()
+            }
+            ScanError::InterfaceDown(interface) => {
+                format!("Run `networksetup -setairportpower {} on`, then press Space", interface)
+            }
+            ScanError::ParseFailure(_) => "Please report your macOS version".to_string(),
+            ScanError::Other(_) => "Check the adapter and that airport runs".to_string(),
+            ScanError::DeviceBusy | ScanError::Timeout => "Retrying".to_string(),
+        }
+    }
+
+    /// What the user can do about it with the ifconfig backend
+    fn bsd_remedy(&self) -> String {
+        match self {
+            ScanError::PermissionDenied(_) => {
+                "Run `sudo -v` or start wificomp as root, then press Space".to_string()
+            }
+            ScanError::InterfaceDown(interface) => {
+                format!("Run `sudo ifconfig {} up`, then press Space", interface)
+            }
+            ScanError::DeviceBusy => "Another scan is running; retrying".to_string(),
+            ScanError::Timeout => "ifconfig did not answer; retrying".to_string(),
+            ScanError::ParseFailure(_) => "Please report your ifconfig output".to_string(),
+            ScanError::Other(_) => "Check the adapter and that ifconfig scan works".to_string(),
+        }
+    }
+
+    /// Seconds before auto-scan tries again, or None to wait for a manual scan.
+    ///
+    /// Permission and link problems won't fix themselves, and retrying them
+    /// would keep raising password prompts.
+    pub fn retry_after(&self, interval_secs: u64) -> Option<u64> {
+        match self {
+            ScanError::DeviceBusy | ScanError::Timeout => Some(QUICK_RETRY_SECS.min(interval_secs)),
+            ScanError::PermissionDenied(_) | ScanError::InterfaceDown(_) => None,
+            ScanError::ParseFailure(_) | ScanError::Other(_) => Some(interval_secs),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_stderr() {
+        let classify = |stderr| ScanError::from_stderr("wlan0", stderr);
+        assert_eq!(
+            classify("sudo: a password is required\n"),
+            ScanError::PermissionDenied("sudo needs a password".to_string())
+        );
+        assert!(matches!(
+            classify("command failed: Operation not permitted (-1)"),
+            ScanError::PermissionDenied(_)
+        ));
+        assert_eq!(
+            classify("command failed: Device or resource busy (-16)"),
+            ScanError::DeviceBusy
+        );
+        assert_eq!(
+            classify("command failed: Network is down (-100)"),
+            ScanError::InterfaceDown("wlan0".to_string())
+        );
+        assert_eq!(classify("command failed: Connection timed out (-110)"), ScanError::Timeout);
+        assert_eq!(
+            classify("nl80211 not found.\n"),
+            ScanError::Other("nl80211 not found.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_retry_after() {
+        assert_eq!(ScanError::DeviceBusy.retry_after(5), Some(2));
+        assert_eq!(ScanError::Timeout.retry_after(1), Some(1));
+        assert_eq!(ScanError::InterfaceDown("wlan0".to_string()).retry_after(5), None);
+        assert_eq!(ScanError::Other(String::new()).retry_after(5), Some(5));
+    }
+}
@@ -0,0 +1,70 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
+
+use crate::data::GpsFix;
+
+/// Default gpsd address
+pub const GPSD_DEFAULT: &str = "127.0.0.1:2947";
+/// How long to wait for gpsd to report a position
+const GPSD_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Current position from gpsd, or None without a 2D fix
+pub fn gpsd_fix(addr: &str) -> Option<GpsFix> {
+    let addr = addr.to_socket_addrs().ok()?.next()?;
+    let mut stream = TcpStream::connect_timeout(&addr, GPSD_TIMEOUT).ok()?;
+    stream.set_read_timeout(Some(GPSD_TIMEOUT)).ok()?;
+    stream
+        .write_all(b"?WATCH={\"enable\":true,\"json\":true};\n")
+        .ok()?;
+
+    // gpsd sends VERSION/DEVICES/WATCH first, then a TPV report per fix
+    let deadline = Instant::now() + GPSD_TIMEOUT;
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    while Instant::now() < deadline {
+        line.clear();
+        if reader.read_line(&mut line).ok()? == 0 {
+            return None;
+        }
+        if let Some(fix) = parse_tpv(&line) {
+            return Some(fix);
+        }
+    }
+    None
+}
+
+/// Parse a gpsd TPV report with at least a 2D fix
+fn parse_tpv(line: &str) -> Option<GpsFix> {
+    let report: serde_json::Value = serde_json::from_str(line).ok()?;
+    if report["class"] != "TPV" || report["mode"].as_u64()? < 2 {
+        return None;
+    }
+    let accuracy_m = report["eph"].as_f64().or_else(|| {
+        let (x, y) = (report["epx"].as_f64()?, report["epy"].as_f64()?);
+        Some(x.max(y))
+    });
+    Some(GpsFix {
+        lat: report["lat"].as_f64()?,
+        lon: report["lon"].as_f64()?,
+        alt_m: report["altHAE"].as_f64().or_else(|| report["alt"].as_f64()),
+        accuracy_m,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tpv() {
+        let tpv = r#"{"class":"TPV","device":"/dev/ttyACM0","mode":3,"lat":51.5007,"lon":-0.1246,"altHAE":35.2,"epx":4.1,"epy":5.3}"#;
+        let fix = parse_tpv(tpv).unwrap();
+        assert_eq!(fix.lat, 51.5007);
+        assert_eq!(fix.alt_m, Some(35.2));
+        assert_eq!(fix.accuracy_m, Some(5.3));
+
+        assert!(parse_tpv(r#"{"class":"TPV","mode":1}"#).is_none());
+        assert!(parse_tpv(r#"{"class":"SKY","mode":3}"#).is_none());
+    }
+}
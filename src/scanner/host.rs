@@ -0,0 +1,103 @@
+use std::fs;
+use std::path::Path;
+
+use crate::data::HostContext;
+
+const THERMAL_DIR: &str = "/sys/class/thermal";
+const POWER_SUPPLY_DIR: &str = "/sys/class/power_supply";
+
+/// Thermal zone types that measure the CPU, best first: Intel package, AMD, then the
+/// SoC zones of ARM boards like the Raspberry Pi
+const CPU_ZONES: [&str; 5] =
+    ["x86_pkg_temp", "k10temp", "cpu-thermal", "cpu_thermal", "soc_thermal"];
+
+/// CPU temperature and battery state from sysfs (Linux only; None elsewhere or when
+/// the machine reports neither)
+pub fn host_context() -> Option<HostContext> {
+    host_context_in(Path::new(THERMAL_DIR), Path::new(POWER_SUPPLY_DIR))
+}
+
+fn host_context_in(thermal: &Path, power_supply: &Path) -> Option<HostContext> {
+    let (battery_pct, on_battery) = battery(power_supply);
+    let context = HostContext { cpu_temp_c: cpu_temperature(thermal), battery_pct, on_battery };
+    (context != HostContext::default()).then_some(context)
+}
+
+/// Temperature of the zone that looks most like the CPU, else the hottest zone
+fn cpu_temperature(dir: &Path) -> Option<f32> {
+    let zones: Vec<(String, f32)> = fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with("thermal_zone"))
+        .filter_map(|entry| {
+            let kind = fs::read_to_string(entry.path().join("type")).ok()?;
+            let temp = fs::read_to_string(entry.path().join("temp")).ok()?;
+            // Reported in millidegrees Celsius
+            let celsius = temp.trim().parse::<f32>().ok()? / 1000.0;
+            Some((kind.trim().to_string(), celsius))
+        })
+        .collect();
+    CPU_ZONES
+        .iter()
+        .find_map(|cpu| zones.iter().find(|(kind, _)| kind == cpu))
+        .map(|&(_, celsius)| celsius)
+        .or_else(|| zones.iter().map(|&(_, celsius)| celsius).max_by(f32::total_cmp))
+}
+
+/// Charge and whether it's discharging, of the first battery
+fn battery(dir: &Path) -> (Option<u8>, Option<bool>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return (None, None);
+    };
+    let read = |path: &Path, name: &str| {
+        fs::read_to_string(path.join(name)).ok().map(|s| s.trim().to_string())
+    };
+    let mut supplies: Vec<_> = entries.flatten().map(|entry| entry.path()).collect();
+    supplies.sort();
+    let Some(battery) = supplies.iter().find(|p| read(p, "type").as_deref() == Some("Battery"))
+    else {
+        return (None, None);
+    };
+    let capacity = read(battery, "capacity").and_then(|c| c.parse().ok());
+    let discharging = match read(battery, "status").as_deref() {
+        Some("Discharging") => Some(true),
+        Some("Charging" | "Full" | "Not charging") => Some(false),
+        _ => None,
+    };
+    (capacity, discharging)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_host_context() {
+        let root = std::env::temp_dir().join(format!("wificomp-sysfs-{}", std::process::id()));
+        let (thermal, power) = (root.join("thermal"), root.join("power_supply"));
+        let write = |dir: &Path, files: &[(&str, &str)]| {
+            fs::create_dir_all(dir).unwrap();
+            for (name, contents) in files {
+                fs::write(dir.join(name), contents).unwrap();
+            }
+        };
+        assert_eq!(host_context_in(&thermal, &power), None);
+
+        write(&thermal.join("thermal_zone0"), &[("type", "acpitz\n"), ("temp", "71000\n")]);
+        write(&thermal.join("thermal_zone1"), &[("type", "x86_pkg_temp\n"), ("temp", "58500\n")]);
+        write(&thermal.join("cooling_device0"), &[("type", "Processor\n")]);
+        write(&power.join("AC"), &[("type", "Mains\n"), ("online", "0\n")]);
+        write(
+            &power.join("BAT0"),
+            &[("type", "Battery\n"), ("capacity", "64\n"), ("status", "Discharging\n")],
+        );
+        let context = host_context_in(&thermal, &power).unwrap();
+        assert_eq!(context.cpu_temp_c, Some(58.5));
+        assert_eq!((context.battery_pct, context.on_battery), (Some(64), Some(true)));
+
+        // No CPU zone: the hottest one stands in
+        fs::remove_dir_all(thermal.join("thermal_zone1")).unwrap();
+        assert_eq!(cpu_temperature(&thermal), Some(71.0));
+        let _ = fs::remove_dir_all(&root);
+    }
+}
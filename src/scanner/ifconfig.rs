@@ -0,0 +1,366 @@
+use chrono::Utc;
+use std::process::Command;
+
+use super::privilege::privileged_command;
+use super::ScanError;
+use crate::data::{AccessPoint, Adapter, BssType, LinkStats, Roaming, ScanResult, Security};
+
+/// Run `ifconfig <args>` unprivileged, returning stdout
+fn run_ifconfig(args: &[&str]) -> anyhow::Result<String> {
+    let output = Command::new("ifconfig")
+        .args(args)
+        .output()
+        .map_err(|e| anyhow::anyhow!("Failed to run ifconfig: {}", e))?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "ifconfig {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Classify ifconfig stderr from a failed scan
+fn classify(interface: &str, stderr: &str) -> ScanError {
+    if stderr.contains("Operation not permitted") || stderr.contains("Permission denied") {
+        ScanError::PermissionDenied("ifconfig scan needs root".to_string())
+    } else {
+        ScanError::from_stderr(interface, stderr)
+    }
+}
+
+/// Scan on FreeBSD/OpenBSD with `ifconfig <iface> scan`
+pub fn scan_ifconfig(interface: &str) -> Result<ScanResult, ScanError> {
+    let output = privileged_command("ifconfig")
+        .args([interface, "scan"])
+        .output()
+        .map_err(|e| ScanError::Other(format!("Failed to run ifconfig: {}", e)))?;
+    if !output.status.success() {
+        return Err(classify(interface, &String::from_utf8_lossy(&output.stderr)));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    // OpenBSD prints the interface status along with the scan; FreeBSD needs a second call
+    let link = parse_link(&stdout).or_else(|| {
+        run_ifconfig(&[interface]).ok().and_then(|out| parse_link(&out))
+    });
+
+    Ok(ScanResult {
+        timestamp: Utc::now(),
+        access_points: parse_scan(&stdout),
+        channel_dwell: Vec::new(),
+        link,
+        position: None,
+        trigger: None,
+        host: None,
+    })
+}
+
+/// Wireless interfaces in the `wlan` interface group
+pub fn detect_adapters() -> anyhow::Result<Vec<Adapter>> {
+    let interfaces = if cfg!(target_os = "openbsd") {
+        // OpenBSD prints every member of the group in full
+        parse_interfaces(&run_ifconfig(&["wlan"])?)
+    } else {
+        let mut interfaces = Vec::new();
+        for name in run_ifconfig(&["-g", "wlan"])?.split_whitespace() {
+            interfaces.extend(parse_interfaces(&run_ifconfig(&[name])?));
+        }
+        interfaces
+    };
+    Ok(interfaces
+        .into_iter()
+        .map(|i| {
+            // FreeBSD clones wlanN on top of the driver's device, e.g. iwm0
+            let device = i.parent.as_deref().unwrap_or(&i.name);
+            Adapter {
+                driver: driver_name(device).to_string(),
+                chipset: device_description(device).unwrap_or_else(|| "unknown".to_string()),
+                interface: i.name,
+                label: None,
+                id: None,
+                driver_version: None,
+                firmware_version: None,
+                usb: None,
+            }
+        })
+        .collect())
+}
+
+/// Driver name of a device: the name without its unit number (`iwm0` -> `iwm`)
+fn driver_name(device: &str) -> &str {
+    device.trim_end_matches(|c: char| c.is_ascii_digit())
+}
+
+/// FreeBSD's description of a device, e.g. "Intel Dual Band Wireless AC 8265"
+fn device_description(device: &str) -> Option<String> {
+    let unit = &device[driver_name(device).len()..];
+    let output = Command::new("sysctl")
+        .args(["-n", &format!("dev.{}.{}.%desc", driver_name(device), unit)])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())?;
+    let desc = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    Some(desc).filter(|d| !d.is_empty())
+}
+
+/// One interface block from `ifconfig`
+#[derive(Debug, PartialEq)]
+struct IfconfigInterface {
+    name: String,
+    parent: Option<String>,
+}
+
+/// Parse `ifconfig` interface blocks, each headed by an unindented `name: flags=...`
+fn parse_interfaces(output: &str) -> Vec<IfconfigInterface> {
+    let mut interfaces: Vec<IfconfigInterface> = Vec::new();
+    for line in output.lines() {
+        if !line.starts_with(char::is_whitespace) {
+            if let Some((name, _)) = line.split_once(": flags=") {
+                interfaces.push(IfconfigInterface { name: name.to_string(), parent: None });
+            }
+        } else if let Some(parent) = line.trim().strip_prefix("parent interface:") {
+            if let Some(interface) = interfaces.last_mut() {
+                interface.parent = Some(parent.trim().to_string());
+            }
+        }
+    }
+    interfaces
+}
+
+fn is_bssid(token: &str) -> bool {
+    token.len() == 17 && token.split(':').count() == 6
+}
+
+/// Centre frequency for a channel; neither BSD lists 6 GHz channels separately
+fn channel_to_freq(channel: u32) -> u32 {
+    match channel {
+        14 => 2484,
+        1..=13 => 2407 + channel * 5,
+        _ => 5000 + channel * 5,
+    }
+}
+
+/// Signal from OpenBSD's `-55dBm`, or its `64%` when the driver only reports quality
+fn parse_signal(token: &str) -> Option<i32> {
+    if let Some(dbm) = token.strip_suffix("dBm") {
+        dbm.parse().ok()
+    } else {
+        let quality = token.strip_suffix('%')?.parse::<i32>().ok()?;
+        Some(quality.clamp(0, 100) / 2 - 100)
+    }
+}
+
+/// Parse scan output from either FreeBSD's table or OpenBSD's `nwid ...` lines
+fn parse_scan(output: &str) -> Vec<AccessPoint> {
+    output
+        .lines()
+        .filter_map(|line| {
+            if line.trim_start().starts_with("nwid ") {
+                parse_openbsd_node(line.trim())
+            } else {
+                parse_freebsd_row(line)
+            }
+        })
+        .collect()
+}
+
+/// One row of FreeBSD's scan table; the SSID column is padded and may contain spaces
+fn parse_freebsd_row(line: &str) -> Option<AccessPoint> {
+    let mut tokens = line.split_whitespace();
+    let bssid = tokens.find(|t| is_bssid(t))?;
+    let ssid = line[..line.find(bssid)?].trim().to_string();
+    let channel: u32 = tokens.next()?.parse().ok()?;
+    let _rate = tokens.next()?;
+    let (signal, _noise) = tokens.next()?.split_once(':')?;
+    let _interval = tokens.next()?;
+    let caps = tokens.next().unwrap_or("");
+    let ies: Vec<&str> = tokens.collect();
+
+    let security = if ies.contains(&"RSN") {
+        Security::Wpa2
+    } else if ies.contains(&"WPA") {
+        Security::Wpa
+    } else if caps.contains('P') {
+        Security::Wep
+    } else {
+        Security::Open
+    };
+
+    Some(AccessPoint {
+        bssid: bssid.to_uppercase(),
+        ssid,
+        signal_dbm: signal.parse().ok()?,
+        channel,
+        frequency_mhz: channel_to_freq(channel),
+        security: Some(security),
+        channel_width_mhz: None,
+        mld_address: None,
+        bss_type: BssType::Infrastructure,
+        beacon_interval_tu: None,
+        station_count: None,
+        channel_utilization: None,
+        regulatory: None,
+        wps: false,
+        tkip_only: false,
+        roaming: Roaming::default(),
+        last_seen_ms: None,
+    })
+}
+
+/// `nwid`'s value, quoted when it contains spaces, and the rest of the line
+fn split_nwid(rest: &str) -> (String, &str) {
+    match rest.strip_prefix('"').and_then(|r| r.split_once('"')) {
+        Some((ssid, rest)) => (ssid.to_string(), rest),
+        None => {
+            let (ssid, rest) = rest.split_once(' ').unwrap_or((rest, ""));
+            (ssid.to_string(), rest)
+        }
+    }
+}
+
+/// One OpenBSD node: `nwid <ssid> chan <n> bssid <mac> <signal> <rate> <caps>`
+fn parse_openbsd_node(line: &str) -> Option<AccessPoint> {
+    let (ssid, rest) = split_nwid(line.strip_prefix("nwid ")?);
+    let tokens: Vec<&str> = rest.split_whitespace().collect();
+    let value = |key| tokens.iter().position(|t| *t == key).and_then(|i| tokens.get(i + 1));
+    let channel: u32 = value("chan")?.parse().ok()?;
+    let bssid = value("bssid")?;
+    let signal = tokens.iter().find_map(|t| parse_signal(t))?;
+    let caps: Vec<&str> = tokens.last()?.split(',').collect();
+
+    let security = if caps.contains(&"wpa2") {
+        Security::Wpa2
+    } else if caps.contains(&"wpa1") {
+        Security::Wpa
+    } else if caps.contains(&"privacy") {
+        Security::Wep
+    } else {
+        Security::Open
+    };
+
+    Some(AccessPoint {
+        bssid: bssid.to_uppercase(),
+        ssid,
+        signal_dbm: signal,
+        channel,
+        frequency_mhz: channel_to_freq(channel),
+        security: Some(security),
+        channel_width_mhz: None,
+        mld_address: None,
+        bss_type: BssType::Infrastructure,
+        beacon_interval_tu: None,
+        station_count: None,
+        channel_utilization: None,
+        regulatory: None,
+        wps: false,
+        tkip_only: false,
+        roaming: Roaming::default(),
+        last_seen_ms: None,
+    })
+}
+
+/// The associated network from `ifconfig <iface>`, if the link is up
+fn parse_link(output: &str) -> Option<LinkStats> {
+    let associated = output.lines().any(|line| {
+        let line = line.trim();
+        line == "status: associated" || line == "status: active"
+    });
+    if !associated {
+        return None;
+    }
+
+    output.lines().find_map(|line| {
+        let line = line.trim();
+        // FreeBSD: `ssid <ssid> channel ...`, OpenBSD: `ieee80211: join|nwid <ssid> chan ...`
+        let rest = line
+            .strip_prefix("ssid ")
+            .or_else(|| line.strip_prefix("ieee80211: join "))
+            .or_else(|| line.strip_prefix("ieee80211: nwid "))?;
+        let (ssid, rest) = split_nwid(rest);
+        let tokens: Vec<&str> = rest.split_whitespace().collect();
+        let bssid = tokens.iter().position(|t| *t == "bssid").and_then(|i| tokens.get(i + 1))?;
+        Some(LinkStats {
+            bssid: bssid.to_uppercase(),
+            ssid,
+            signal_dbm: tokens.iter().find_map(|t| t.strip_suffix("dBm")?.parse().ok()),
+            tx_bitrate_mbps: None,
+            rx_bitrate_mbps: None,
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FREEBSD_SCAN: &str = include_str!("../../tests/fixtures/ifconfig/freebsd_scan.txt");
+    const FREEBSD_IFCONFIG: &str =
+        include_str!("../../tests/fixtures/ifconfig/freebsd_ifconfig.txt");
+    const OPENBSD_SCAN: &str = include_str!("../../tests/fixtures/ifconfig/openbsd_scan.txt");
+
+    fn summarize(aps: &[AccessPoint]) -> Vec<String> {
+        aps.iter()
+            .map(|ap| {
+                format!(
+                    "{} {} ch{} {} {:?} {:?}",
+                    ap.bssid, ap.frequency_mhz, ap.channel, ap.signal_dbm, ap.security, ap.ssid
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_freebsd_scan() {
+        assert_eq!(
+            summarize(&parse_scan(FREEBSD_SCAN)),
+            vec![
+                "3C:84:6A:10:00:01 2437 ch6 -55 Some(Wpa2) \"HomeNet\"",
+                "3C:84:6A:10:00:02 5180 ch36 -60 Some(Wpa2) \"HomeNet\"",
+                "F0:9F:C2:20:00:01 2412 ch1 -68 Some(Open) \"Office Guest\"",
+                "00:14:BF:30:00:01 2462 ch11 -74 Some(Wpa) \"OldRouter\"",
+                "A0:63:91:50:00:02 5745 ch149 -82 Some(Wpa2) \"\"",
+                "00:0F:66:40:00:01 2422 ch3 -85 Some(Wep) \"Legacy\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_openbsd_scan() {
+        assert_eq!(
+            summarize(&parse_scan(OPENBSD_SCAN)),
+            vec![
+                "3C:84:6A:10:00:01 2437 ch6 -55 Some(Wpa2) \"HomeNet\"",
+                "3C:84:6A:10:00:02 5180 ch36 -60 Some(Wpa2) \"HomeNet\"",
+                "F0:9F:C2:20:00:01 2412 ch1 -68 Some(Open) \"Office Guest\"",
+                "00:14:BF:30:00:01 2462 ch11 -74 Some(Wpa) \"OldRouter\"",
+                "A0:63:91:50:00:02 5745 ch149 -82 Some(Wpa2) \"\"",
+                "00:0F:66:40:00:01 2422 ch3 -85 Some(Wep) \"Legacy\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_link() {
+        let link = parse_link(OPENBSD_SCAN).unwrap();
+        assert_eq!((link.bssid.as_str(), link.ssid.as_str()), ("3C:84:6A:10:00:02", "HomeNet"));
+        assert_eq!(link.signal_dbm, Some(-54));
+
+        let link = parse_link(FREEBSD_IFCONFIG).unwrap();
+        assert_eq!(link.bssid, "3C:84:6A:10:00:02");
+        assert_eq!(link.signal_dbm, None);
+        assert!(parse_link(&FREEBSD_IFCONFIG.replace("associated", "no carrier")).is_none());
+    }
+
+    #[test]
+    fn test_parse_interfaces() {
+        assert_eq!(
+            parse_interfaces(FREEBSD_IFCONFIG),
+            vec![IfconfigInterface { name: "wlan0".to_string(), parent: Some("iwm0".to_string()) }]
+        );
+        assert_eq!(parse_interfaces(OPENBSD_SCAN)[0].name, "iwm0");
+        assert_eq!(driver_name("iwm0"), "iwm");
+        assert_eq!(driver_name("rtwn12"), "rtwn");
+    }
+}
@@ -0,0 +1,207 @@
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
+use std::net::{IpAddr, Ipv4Addr, UdpSocket};
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::privilege::command_exists;
+use crate::data::LoadTest;
+use crate::utils::percentile;
+
+/// Seconds between pings; 0.2 is the shortest interval ping allows without root
+const PING_INTERVAL: &str = "0.2";
+
+/// Size of each datagram in the UDP stream
+const DATAGRAM_BYTES: usize = 1200;
+
+/// Discard service on the target; nothing needs to listen for the stream to load the link
+const DISCARD_PORT: u16 = 9;
+
+/// Send a UDP stream of `mbps` from `interface` to `target` for `secs`, pinging `target`
+/// through the same interface meanwhile, and report ping loss and latency percentiles
+pub fn load_test(interface: &str, target: &str, secs: u64, mbps: u32) -> Result<LoadTest> {
+    if !command_exists("ping") {
+        bail!("Load tests need ping");
+    }
+    // ping -w 0 would never stop on its own
+    if secs == 0 {
+        bail!("Load tests need to run for at least a second");
+    }
+    let address = interface_address(interface)
+        .with_context(|| format!("{} has no IPv4 address; is it connected?", interface))?;
+    let socket = UdpSocket::bind((address, 0)).context("Failed to open the UDP stream")?;
+    socket
+        .connect((target, DISCARD_PORT))
+        .with_context(|| format!("Couldn't resolve {}", target))?;
+    // A source address alone doesn't pick the route, so another interface with a route
+    // to the target would carry the stream instead
+    if let Err(e) = bind_to_device(&socket, interface) {
+        log::debug!("SO_BINDTODEVICE on {} failed ({}), checking the route", interface, e);
+        let peer = socket.peer_addr().context("UDP stream isn't connected")?.ip();
+        match route_device(peer, address) {
+            Some(dev) if dev == interface => {}
+            Some(dev) => bail!("{} is reached through {}, not {}", target, dev, interface),
+            None => bail!("No route to {} from {}", target, interface),
+        }
+    }
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let sender = {
+        let stop = Arc::clone(&stop);
+        thread::spawn(move || send_stream(&socket, mbps, &stop))
+    };
+    let output = Command::new("ping")
+        .args(["-n", "-I", interface, "-i", PING_INTERVAL, "-w", &secs.to_string(), target])
+        .output();
+    stop.store(true, Ordering::Relaxed);
+    let _ = sender.join();
+
+    let output = output.context("Failed to run ping")?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let (sent, received) = parse_ping_counts(&text)
+        .with_context(|| format!("ping {} failed: {}", target, stderr_line(&output.stderr)))?;
+    let mut rtts = parse_ping_rtts(&text);
+    rtts.sort_by(f32::total_cmp);
+    Ok(LoadTest {
+        timestamp: Utc::now(),
+        target: target.to_string(),
+        duration_secs: secs,
+        load_mbps: mbps,
+        pings_sent: sent,
+        pings_received: received,
+        rtt_p50_ms: percentile(&rtts, 50.0).map(|ms| ms as f32),
+        rtt_p95_ms: percentile(&rtts, 95.0).map(|ms| ms as f32),
+        rtt_p99_ms: percentile(&rtts, 99.0).map(|ms| ms as f32),
+    })
+}
+
+/// Send datagrams paced to `mbps` until `stop` is set. Send errors (e.g. a full queue
+/// or an ICMP port unreachable from the target) are the point of a flood, so they're
+/// ignored.
+fn send_stream(socket: &UdpSocket, mbps: u32, stop: &AtomicBool) {
+    let payload = [0u8; DATAGRAM_BYTES];
+    let per_datagram_secs = (DATAGRAM_BYTES * 8) as f64 / (mbps.max(1) as f64 * 1e6);
+    let start = Instant::now();
+    // A fast link sends u32::MAX datagrams in about an hour
+    let mut sent: u64 = 0;
+    while !stop.load(Ordering::Relaxed) {
+        let _ = socket.send(&payload);
+        sent += 1;
+        let due = Duration::from_secs_f64(per_datagram_secs * sent as f64);
+        if let Some(ahead) = due.checked_sub(start.elapsed()) {
+            thread::sleep(ahead);
+        }
+    }
+}
+
+/// Pin the stream's socket to the interface
+#[cfg(target_os = "linux")]
+fn bind_to_device(socket: &UdpSocket, interface: &str) -> std::io::Result<()> {
+    use std::os::fd::AsRawFd;
+    // Needs CAP_NET_RAW before Linux 5.7
+    let result = unsafe {
+        libc::setsockopt(
+            socket.as_raw_fd(),
+            libc::SOL_SOCKET,
+            libc::SO_BINDTODEVICE,
+            interface.as_ptr().cast(),
+            interface.len() as libc::socklen_t,
+        )
+    };
+    if result == 0 {
+        Ok(())
+    } else {
+        Err(std::io::Error::last_os_error())
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn bind_to_device(_socket: &UdpSocket, _interface: &str) -> std::io::Result<()> {
+    Err(std::io::ErrorKind::Unsupported.into())
+}
+
+/// Interface the kernel routes `target` through from `source`, from `ip -4 route get`
+fn route_device(target: IpAddr, source: Ipv4Addr) -> Option<String> {
+    let out = Command::new("ip")
+        .args(["-4", "route", "get", &target.to_string(), "from", &source.to_string()])
+        .output()
+        .ok()?;
+    parse_route_dev(&String::from_utf8_lossy(&out.stdout))
+}
+
+/// Parse `8.8.8.8 from 192.168.1.23 via 192.168.1.1 dev wlan0 uid 1000`
+fn parse_route_dev(output: &str) -> Option<String> {
+    let mut words = output.split_whitespace();
+    words.find(|w| *w == "dev")?;
+    words.next().map(str::to_string)
+}
+
+/// The interface's first IPv4 address, from `ip -4 -o addr show dev <iface>`
+fn interface_address(interface: &str) -> Option<Ipv4Addr> {
+    let out = Command::new("ip")
+        .args(["-4", "-o", "addr", "show", "dev", interface])
+        .output()
+        .ok()?;
+    parse_ip_addr(&String::from_utf8_lossy(&out.stdout))
+}
+
+/// Parse `3: wlan0    inet 192.168.1.23/24 brd ...`
+fn parse_ip_addr(output: &str) -> Option<Ipv4Addr> {
+    let mut words = output.split_whitespace();
+    words.find(|w| *w == "inet")?;
+    words.next()?.split('/').next()?.parse().ok()
+}
+
+/// Parse `10 packets transmitted, 9 received, 10% packet loss, time 9012ms`
+fn parse_ping_counts(output: &str) -> Option<(u32, u32)> {
+    let line = output.lines().find(|l| l.contains("packets transmitted"))?;
+    let mut parts = line.split(',');
+    let sent = parts.next()?.split_whitespace().next()?.parse().ok()?;
+    let received = parts.next()?.split_whitespace().next()?.parse().ok()?;
+    Some((sent, received))
+}
+
+/// Round-trip times of every reply line (`... icmp_seq=1 ttl=64 time=3.41 ms`)
+fn parse_ping_rtts(output: &str) -> Vec<f32> {
+    output
+        .lines()
+        .filter_map(|l| l.split("time=").nth(1)?.split_whitespace().next()?.parse().ok())
+        .collect()
+}
+
+fn stderr_line(stderr: &[u8]) -> String {
+    String::from_utf8_lossy(stderr).lines().next().unwrap_or("no replies").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ping() {
+        let output = "PING 192.168.1.1 (192.168.1.1) from 192.168.1.23 wlan0: 56(84) bytes of data.\n\
+            64 bytes from 192.168.1.1: icmp_seq=1 ttl=64 time=3.41 ms\n\
+            64 bytes from 192.168.1.1: icmp_seq=2 ttl=64 time=12.0 ms\n\
+            64 bytes from 192.168.1.1: icmp_seq=4 ttl=64 time=5.59 ms\n\
+            \n\
+            --- 192.168.1.1 ping statistics ---\n\
+            4 packets transmitted, 3 received, 25% packet loss, time 602ms\n\
+            rtt min/avg/max/mdev = 3.410/7.000/12.000/3.700 ms\n";
+        assert_eq!(parse_ping_counts(output), Some((4, 3)));
+        assert_eq!(parse_ping_rtts(output), vec![3.41, 12.0, 5.59]);
+        assert_eq!(parse_ping_counts("ping: unknown host\n"), None);
+
+        assert_eq!(
+            parse_ip_addr("3: wlan0    inet 192.168.1.23/24 brd 192.168.1.255 scope global wlan0\n"),
+            Some(Ipv4Addr::new(192, 168, 1, 23))
+        );
+        assert_eq!(parse_ip_addr(""), None);
+
+        let route = "8.8.8.8 from 192.168.1.23 via 192.168.1.1 dev wlan0 uid 1000 \n    cache \n";
+        assert_eq!(parse_route_dev(route).as_deref(), Some("wlan0"));
+        assert_eq!(parse_route_dev("RTNETLINK answers: Network is unreachable\n"), None);
+    }
+}
@@ -1,5 +1,20 @@
 pub mod adapter;
+pub mod airport;
+pub mod connect;
+pub mod demo;
+pub mod doctor;
+pub mod error;
+pub mod gps;
+pub mod host;
+pub mod ifconfig;
+pub mod load;
+pub mod netsh;
+pub mod privilege;
+pub mod remote;
+pub mod replay;
+pub mod reset;
 pub mod scan;
 
-pub use adapter::detect_adapters;
-pub use scan::scan_wifi;
+pub use adapter::{detect_adapters, find_adapter, wireless_interfaces};
+pub use error::ScanError;
+pub use scan::{freq_to_channel, ScanBackend};
@@ -0,0 +1,324 @@
+use chrono::Utc;
+use std::process::Command;
+
+use super::ScanError;
+use crate::data::{AccessPoint, Adapter, BssType, LinkStats, Roaming, ScanResult, Security};
+
+/// Driver recorded for adapters found through netsh, which doesn't name the driver
+const NETSH_DRIVER: &str = "windows";
+
+/// Run `netsh wlan <args>`, returning stdout
+fn run_netsh(args: &[&str]) -> Result<String, ScanError> {
+    log::debug!("running netsh wlan {}", args.join(" "));
+    let output = Command::new("netsh")
+        .arg("wlan")
+        .args(args)
+        .output()
+        .map_err(|e| ScanError::Other(format!("Failed to run netsh: {}", e)))?;
+    // netsh reports most failures on stdout with a success status
+    let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !output.status.success() && stdout.trim().is_empty() {
+        return Err(ScanError::Other(stderr.trim().to_string()));
+    }
+    Ok(stdout)
+}
+
+/// Classify a netsh message that isn't scan output
+fn classify(interface: &str, output: &str) -> Option<ScanError> {
+    let text = output.trim();
+    if text.contains("location permission") {
+        Some(ScanError::PermissionDenied("netsh needs location access".to_string()))
+    } else if text.contains("wlansvc") && text.contains("not running") {
+        Some(ScanError::InterfaceDown("WLAN AutoConfig service".to_string()))
+    } else if text.contains("no such wireless interface")
+        || text.contains("no wireless interface")
+        || text.contains("powered down")
+    {
+        Some(ScanError::InterfaceDown(interface.to_string()))
+    } else {
+        None
+    }
+}
+
+/// Scan on Windows: the BSS list Windows keeps from its own periodic scans
+pub fn scan_netsh(interface: &str) -> Result<ScanResult, ScanError> {
+    let interface_arg = format!("interface={}", interface);
+    let output = run_netsh(&["show", "networks", "mode=bssid", &interface_arg])?;
+    if let Some(error) = classify(interface, &output) {
+        return Err(error);
+    }
+    if !output.contains("SSID") && !output.contains("networks currently visible") {
+        return Err(ScanError::ParseFailure(output.trim().to_string()));
+    }
+
+    let link = run_netsh(&["show", "interfaces"])
+        .ok()
+        .and_then(|out| parse_interfaces(&out).into_iter().find(|i| i.name == interface))
+        .and_then(|i| i.link);
+
+    Ok(ScanResult {
+        timestamp: Utc::now(),
+        access_points: parse_networks(&output),
+        channel_dwell: Vec::new(),
+        link,
+        position: None,
+        trigger: None,
+        host: None,
+    })
+}
+
+/// Wireless interfaces reported by `netsh wlan show interfaces`
+pub fn detect_adapters() -> anyhow::Result<Vec<Adapter>> {
+    let output = run_netsh(&["show", "interfaces"]).map_err(|e| anyhow::anyhow!("{}", e))?;
+    if let Some(error) = classify("", &output) {
+        anyhow::bail!("{}", error);
+    }
+    Ok(parse_interfaces(&output)
+        .into_iter()
+        .map(|i| Adapter {
+            interface: i.name,
+            driver: NETSH_DRIVER.to_string(),
+            chipset: i.description,
+            label: None,
+            id: None,
+            driver_version: None,
+            firmware_version: None,
+            usb: None,
+        })
+        .collect())
+}
+
+/// `key : value` from a netsh line; keys never contain a colon, values (BSSIDs) may
+fn field(line: &str) -> Option<(&str, &str)> {
+    let (key, value) = line.split_once(':')?;
+    Some((key.trim(), value.trim()))
+}
+
+/// Signal quality percentage to dBm, the way Windows maps them (0% = -100, 100% = -50)
+fn quality_to_dbm(value: &str) -> Option<i32> {
+    let quality = value.trim_end_matches('%').trim().parse::<i32>().ok()?;
+    Some(quality.clamp(0, 100) / 2 - 100)
+}
+
+fn parse_security(authentication: &str) -> Option<Security> {
+    let auth = authentication.to_uppercase();
+    if auth.contains("WPA3") || auth.contains("SAE") || auth.contains("OWE") {
+        Some(Security::Wpa3)
+    } else if auth.contains("WPA2") {
+        Some(Security::Wpa2)
+    } else if auth.contains("WPA") {
+        Some(Security::Wpa)
+    } else if auth.contains("WEP") || auth.contains("SHARED") {
+        Some(Security::Wep)
+    } else if auth.contains("OPEN") {
+        Some(Security::Open)
+    } else {
+        None
+    }
+}
+
+/// Centre frequency for a channel; netsh only names the band on newer Windows
+fn channel_to_freq(channel: u32, band: Option<&str>) -> u32 {
+    match band {
+        Some(b) if b.starts_with('6') => 5950 + channel * 5,
+        Some(b) if b.starts_with('5') => 5000 + channel * 5,
+        _ if channel == 14 => 2484,
+        _ if channel <= 13 => 2407 + channel * 5,
+        _ => 5000 + channel * 5,
+    }
+}
+
+/// Parse `netsh wlan show networks mode=bssid`
+fn parse_networks(output: &str) -> Vec<AccessPoint> {
+    let mut aps = Vec::new();
+    let mut ssid = String::new();
+    let mut security = None;
+    let mut tkip_only = false;
+    let mut bss_type = BssType::Infrastructure;
+    let mut current: Option<AccessPoint> = None;
+    let mut band: Option<String> = None;
+
+    let mut finish = |ap: Option<AccessPoint>, band: Option<String>| {
+        if let Some(mut ap) = ap.filter(|ap| ap.channel > 0) {
+            ap.frequency_mhz = channel_to_freq(ap.channel, band.as_deref());
+            aps.push(ap);
+        }
+    };
+
+    for line in output.lines() {
+        let Some((key, value)) = field(line) else {
+            continue;
+        };
+        if key.starts_with("SSID") {
+            finish(current.take(), band.take());
+            ssid = value.to_string();
+            security = None;
+            tkip_only = false;
+            bss_type = BssType::Infrastructure;
+        } else if key == "Network type" {
+            // "Infrastructure" or "Adhoc"
+            if value.eq_ignore_ascii_case("adhoc") {
+                bss_type = BssType::Ibss;
+            }
+        } else if key == "Authentication" {
+            security = parse_security(value);
+        } else if key == "Encryption" {
+            tkip_only = value.eq_ignore_ascii_case("TKIP");
+        } else if key.starts_with("BSSID") {
+            finish(current.take(), band.take());
+            current = Some(AccessPoint {
+                bssid: value.to_uppercase(),
+                ssid: ssid.clone(),
+                signal_dbm: -100,
+                channel: 0,
+                frequency_mhz: 0,
+                security,
+                channel_width_mhz: None,
+                mld_address: None,
+                bss_type,
+                beacon_interval_tu: None,
+                station_count: None,
+                channel_utilization: None,
+                regulatory: None,
+                wps: false,
+                tkip_only,
+                roaming: Roaming::default(),
+                last_seen_ms: None,
+            });
+        } else if let Some(ap) = current.as_mut() {
+            match key {
+                "Signal" => ap.signal_dbm = quality_to_dbm(value).unwrap_or(-100),
+                "Channel" => ap.channel = value.parse().unwrap_or(0),
+                "Band" => band = Some(value.to_string()),
+                "Connected Stations" => ap.station_count = value.parse().ok(),
+                // "21 (8 %)": the raw 0-255 value, then the percentage
+                "Channel Utilization" => {
+                    ap.channel_utilization = value
+                        .split_once('(')
+                        .and_then(|(_, pct)| pct.split_whitespace().next()?.parse().ok())
+                }
+                _ => {}
+            }
+        }
+    }
+    finish(current, band);
+    aps
+}
+
+/// One interface from `netsh wlan show interfaces`
+#[derive(Debug)]
+struct NetshInterface {
+    name: String,
+    description: String,
+    link: Option<LinkStats>,
+}
+
+/// Parse `netsh wlan show interfaces`
+fn parse_interfaces(output: &str) -> Vec<NetshInterface> {
+    let mut interfaces: Vec<NetshInterface> = Vec::new();
+
+    for line in output.lines() {
+        let Some((key, value)) = field(line) else {
+            continue;
+        };
+        if key == "Name" {
+            interfaces.push(NetshInterface {
+                name: value.to_string(),
+                description: String::new(),
+                link: None,
+            });
+            continue;
+        }
+        let Some(interface) = interfaces.last_mut() else {
+            continue;
+        };
+        if key == "Description" {
+            interface.description = value.to_string();
+        } else if key == "State" && value == "connected" {
+            interface.link = Some(LinkStats {
+                bssid: String::new(),
+                ssid: String::new(),
+                signal_dbm: None,
+                tx_bitrate_mbps: None,
+                rx_bitrate_mbps: None,
+            });
+        } else if let Some(link) = interface.link.as_mut() {
+            match key {
+                // "AP BSSID" on Windows 11, "BSSID" before
+                "BSSID" | "AP BSSID" => link.bssid = value.to_uppercase(),
+                "SSID" => link.ssid = value.to_string(),
+                "Signal" => link.signal_dbm = quality_to_dbm(value),
+                "Receive rate (Mbps)" => link.rx_bitrate_mbps = value.parse().ok(),
+                "Transmit rate (Mbps)" => link.tx_bitrate_mbps = value.parse().ok(),
+                _ => {}
+            }
+        }
+    }
+
+    for interface in &mut interfaces {
+        interface.link = interface.link.take().filter(|link| !link.bssid.is_empty());
+    }
+    interfaces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NETWORKS: &str = include_str!("../../tests/fixtures/netsh/networks.txt");
+    const INTERFACES: &str = include_str!("../../tests/fixtures/netsh/interfaces.txt");
+
+    #[test]
+    fn test_parse_networks() {
+        let aps = parse_networks(NETWORKS);
+        let summary: Vec<String> = aps
+            .iter()
+            .map(|ap| {
+                format!(
+                    "{} {} ch{} {} {:?} {:?}",
+                    ap.bssid, ap.frequency_mhz, ap.channel, ap.signal_dbm, ap.security, ap.ssid
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                "3C:84:6A:10:00:01 2437 ch6 -55 Some(Wpa3) \"HomeNet\"",
+                "3C:84:6A:10:00:02 5180 ch36 -60 Some(Wpa3) \"HomeNet\"",
+                "3C:84:6A:10:00:03 5955 ch1 -71 Some(Wpa3) \"HomeNet\"",
+                "F0:9F:C2:20:00:01 2412 ch1 -68 Some(Open) \"Office_Guest\"",
+                "A0:63:91:50:00:01 5240 ch48 -77 Some(Wpa2) \"\"",
+            ]
+        );
+        assert_eq!((aps[1].station_count, aps[1].channel_utilization), (Some(5), Some(11)));
+        assert_eq!(aps[0].channel_utilization, None);
+    }
+
+    #[test]
+    fn test_parse_interfaces() {
+        let interfaces = parse_interfaces(INTERFACES);
+        assert_eq!(interfaces.len(), 2);
+        assert_eq!(interfaces[0].name, "Wi-Fi");
+        assert_eq!(interfaces[0].description, "Intel(R) Wi-Fi 6 AX201 160MHz");
+        let link = interfaces[0].link.as_ref().unwrap();
+        assert_eq!(link.bssid, "3C:84:6A:10:00:02");
+        assert_eq!(link.ssid, "HomeNet");
+        assert_eq!(link.signal_dbm, Some(-54));
+        assert_eq!(link.rx_bitrate_mbps, Some(1200.0));
+
+        assert_eq!(interfaces[1].name, "Wi-Fi 2");
+        assert!(interfaces[1].link.is_none());
+    }
+
+    #[test]
+    fn test_classify() {
+        let denied = "Network shell commands need location permission to access WLAN information.";
+        assert!(matches!(classify("Wi-Fi", denied), Some(ScanError::PermissionDenied(_))));
+        assert_eq!(
+            classify("Wi-Fi 3", "There is no such wireless interface on the system."),
+            Some(ScanError::InterfaceDown("Wi-Fi 3".to_string()))
+        );
+        assert_eq!(classify("Wi-Fi", NETWORKS), None);
+    }
+}
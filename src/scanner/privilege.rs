@@ -0,0 +1,184 @@
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+
+/// Linux capability number for CAP_NET_ADMIN
+#[cfg(target_os = "linux")]
+const CAP_NET_ADMIN: u32 = 12;
+#[cfg(target_os = "linux")]
+const LINUX_CAPABILITY_VERSION_3: u32 = 0x2008_0522;
+
+/// How privileged `iw` commands are run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Privilege {
+    /// Running as root
+    Root,
+    /// CAP_NET_ADMIN on the binary, passed to `iw` as an ambient capability
+    Capability,
+    /// `sudo -n` (never prompts; fails if no cached credentials)
+    Sudo,
+}
+
+impl Privilege {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Privilege::Root => "root",
+            Privilege::Capability => "CAP_NET_ADMIN",
+            Privilege::Sudo => "sudo",
+        }
+    }
+}
+
+/// Privilege mode for this process, detected once
+pub fn privilege() -> Privilege {
+    static PRIVILEGE: OnceLock<Privilege> = OnceLock::new();
+    *PRIVILEGE.get_or_init(detect)
+}
+
+/// netsh reads scan results without elevation, so Windows counts as direct
+#[cfg(not(unix))]
+pub fn direct_privilege() -> Option<Privilege> {
+    Some(Privilege::Root)
+}
+
+/// Root or CAP_NET_ADMIN, when the process has either. Unlike `privilege()` this
+/// doesn't settle the mode, so sudo can still be primed before it's detected.
+#[cfg(unix)]
+pub fn direct_privilege() -> Option<Privilege> {
+    if unsafe { libc::geteuid() } == 0 {
+        return Some(Privilege::Root);
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let status = std::fs::read_to_string("/proc/self/status").unwrap_or_default();
+        if has_cap_net_admin(&status) && raise_ambient_net_admin() {
+            return Some(Privilege::Capability);
+        }
+    }
+
+    None
+}
+
+#[cfg(not(unix))]
+fn detect() -> Privilege {
+    Privilege::Root
+}
+
+#[cfg(unix)]
+fn detect() -> Privilege {
+    direct_privilege().unwrap_or(Privilege::Sudo)
+}
+
+/// Build an `iw` command that runs with the detected privilege
+pub fn iw_command() -> Command {
+    privileged_command("iw")
+}
+
+/// Build a command for `program` that runs with the detected privilege
+pub fn privileged_command(program: &str) -> Command {
+    match privilege() {
+        Privilege::Root | Privilege::Capability => Command::new(program),
+        Privilege::Sudo => {
+            let mut cmd = Command::new("sudo");
+            cmd.args(["-n", program]);
+            cmd
+        }
+    }
+}
+
+/// Whether sudo can run without prompting for a password
+pub fn sudo_ready() -> bool {
+    Command::new("sudo")
+        .args(["-n", "true"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map(|s| s.success())
+        .unwrap_or(false)
+}
+
+/// Refresh the sudo timestamp without prompting, so long sessions keep scanning
+pub fn refresh_sudo() {
+    let _ = Command::new("sudo")
+        .args(["-n", "-v"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+}
+
+pub fn command_exists(name: &str) -> bool {
+    std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).any(|dir| dir.join(name).is_file()))
+        .unwrap_or(false)
+}
+
+/// Check the effective capability set in `/proc/self/status` contents
+#[cfg(target_os = "linux")]
+fn has_cap_net_admin(status: &str) -> bool {
+    status
+        .lines()
+        .find_map(|l| l.strip_prefix("CapEff:"))
+        .and_then(|hex| u64::from_str_radix(hex.trim(), 16).ok())
+        .is_some_and(|caps| caps & (1 << CAP_NET_ADMIN) != 0)
+}
+
+#[cfg(target_os = "linux")]
+#[repr(C)]
+struct CapHeader {
+    version: u32,
+    pid: i32,
+}
+
+#[cfg(target_os = "linux")]
+#[repr(C)]
+#[derive(Default, Clone, Copy)]
+struct CapData {
+    effective: u32,
+    permitted: u32,
+    inheritable: u32,
+}
+
+/// Make CAP_NET_ADMIN inheritable and ambient so child `iw` processes get it.
+///
+/// File capabilities only apply to the binary itself; without this the
+/// capability is dropped when `iw` is exec'd.
+#[cfg(target_os = "linux")]
+fn raise_ambient_net_admin() -> bool {
+    let mut header = CapHeader {
+        version: LINUX_CAPABILITY_VERSION_3,
+        pid: 0,
+    };
+    let mut data = [CapData::default(); 2];
+
+    unsafe {
+        if libc::syscall(libc::SYS_capget, &mut header, data.as_mut_ptr()) != 0 {
+            return false;
+        }
+        data[0].inheritable |= 1 << CAP_NET_ADMIN;
+        if libc::syscall(libc::SYS_capset, &mut header, data.as_ptr()) != 0 {
+            return false;
+        }
+        libc::prctl(
+            libc::PR_CAP_AMBIENT,
+            libc::PR_CAP_AMBIENT_RAISE as libc::c_ulong,
+            CAP_NET_ADMIN as libc::c_ulong,
+            0 as libc::c_ulong,
+            0 as libc::c_ulong,
+        ) == 0
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_has_cap_net_admin() {
+        assert!(has_cap_net_admin("Name:\twificomp\nCapEff:\t0000000000001000\n"));
+        assert!(has_cap_net_admin("CapEff:\t000001ffffffffff\n"));
+        assert!(!has_cap_net_admin("CapEff:\t0000000000000000\n"));
+        assert!(!has_cap_net_admin("Name:\twificomp\n"));
+    }
+}
@@ -0,0 +1,241 @@
+use anyhow::{Context, Result};
+use std::process::{Command, Output};
+use std::sync::OnceLock;
+
+use super::adapter::{adapter_info, iw_dev_interfaces};
+use super::reset::{reset_commands, ResetMethod};
+use super::scan::{self, scan_iw};
+use super::ScanError;
+use crate::data::{Adapter, RadioContext, ScanResult};
+
+/// ssh options: never prompt (a prompt would hide behind the TUI), and reuse one
+/// connection for the scans, links and surveys of a session
+const SSH_OPTIONS: [&str; 10] = [
+    "-o",
+    "BatchMode=yes",
+    "-o",
+    "ConnectTimeout=10",
+    "-o",
+    "ControlMaster=auto",
+    "-o",
+    "ControlPath=~/.ssh/wificomp-%C",
+    "-o",
+    "ControlPersist=60",
+];
+
+/// Separates the uevent and udevadm output when reading adapter info in one call
+const INFO_SEPARATOR: &str = "--wificomp--";
+
+/// A machine scanned over SSH, from `--remote [user@]host:iface`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteTarget {
+    /// `[user@]host` as passed to ssh
+    pub destination: String,
+    pub interface: String,
+}
+
+impl std::str::FromStr for RemoteTarget {
+    type Err = String;
+
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let (destination, interface) = spec
+            .rsplit_once(':')
+            .filter(|(destination, interface)| !destination.is_empty() && !interface.is_empty())
+            .ok_or_else(|| format!("expected [user@]host:iface, got '{}'", spec))?;
+        Ok(RemoteTarget {
+            destination: destination.to_string(),
+            interface: interface.to_string(),
+        })
+    }
+}
+
+static TARGETS: OnceLock<Vec<RemoteTarget>> = OnceLock::new();
+
+/// A remote target set at startup, by its position on the command line
+pub fn target(index: usize) -> Option<&'static RemoteTarget> {
+    TARGETS.get()?.get(index)
+}
+
+/// Check each target is reachable and has its interface, then make them the scan targets.
+///
+/// Returns each target's adapter, in order.
+pub fn connect(targets: Vec<RemoteTarget>) -> Result<Vec<Adapter>> {
+    let mut connected = Vec::new();
+    for target in &targets {
+        let adapters = detect_adapters_on(target)?;
+        let adapter = adapters
+            .iter()
+            .find(|a| a.interface == target.interface)
+            .cloned()
+            .with_context(|| {
+                let available: Vec<&str> =
+                    adapters.iter().map(|a| a.interface.as_str()).collect();
+                format!(
+                    "{} has no wireless interface {} (wireless: {})",
+                    target.destination,
+                    target.interface,
+                    available.join(", ")
+                )
+            })?;
+        connected.push(adapter);
+    }
+    let _ = TARGETS.set(targets);
+    Ok(connected)
+}
+
+/// Quote an argument for the remote shell, which ssh hands the joined command to
+fn shell_quote(arg: &str) -> String {
+    let safe = |c: char| c.is_ascii_alphanumeric() || "_-./:=@%+,".contains(c);
+    if !arg.is_empty() && arg.chars().all(safe) {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+impl RemoteTarget {
+    /// Name of the probe location: the host without the user
+    pub fn node_name(&self) -> &str {
+        self.destination.rsplit('@').next().unwrap_or(&self.destination)
+    }
+
+    /// Whether ssh logs in as root, so commands need no sudo
+    fn is_root(&self) -> bool {
+        self.destination.starts_with("root@")
+    }
+
+    /// The remote command line; elevated runs use `sudo -n`, which fails rather than prompting
+    fn remote_command(&self, program: &str, args: &[&str], privileged: bool) -> String {
+        let mut words = Vec::new();
+        if privileged && !self.is_root() {
+            words.extend(["sudo", "-n"]);
+        }
+        words.push(program);
+        words.extend(args);
+        words.iter().map(|w| shell_quote(w)).collect::<Vec<_>>().join(" ")
+    }
+
+    /// Run a command on the remote host
+    fn run(&self, program: &str, args: &[&str], privileged: bool) -> std::io::Result<Output> {
+        let command = self.remote_command(program, args, privileged);
+        log::debug!("running on {}: {}", self.destination, command);
+        Command::new("ssh")
+            .args(SSH_OPTIONS)
+            .arg(&self.destination)
+            .arg(command)
+            .output()
+    }
+
+    /// Run a command remotely, returning stdout or an error with its stderr
+    fn run_checked(&self, program: &str, args: &[&str], privileged: bool) -> Result<String> {
+        let output = self
+            .run(program, args, privileged)
+            .context("Failed to run ssh. Is OpenSSH installed?")?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "{} on {} failed: {}",
+                program,
+                self.destination,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+}
+
+/// Scan an interface on a remote target with its `iw`
+pub fn scan(
+    index: usize,
+    interface: &str,
+    probe_ssids: &[String],
+) -> Result<ScanResult, ScanError> {
+    let target = target(index).ok_or_else(|| ScanError::Other("No remote target".to_string()))?;
+    scan_iw(interface, probe_ssids, &|args, privileged| target.run("iw", args, privileged))
+}
+
+/// Tx power and country code of a remote target's interface
+pub fn radio_context(index: usize, interface: &str) -> Option<RadioContext> {
+    let target = target(index)?;
+    scan::radio_context(interface, &|args, privileged| target.run("iw", args, privileged))
+}
+
+/// Wireless adapters on a remote target
+pub fn detect_adapters(index: usize) -> Result<Vec<Adapter>> {
+    let target = target(index).context("No remote target")?;
+    detect_adapters_on(target)
+}
+
+fn detect_adapters_on(target: &RemoteTarget) -> Result<Vec<Adapter>> {
+    let output = target.run_checked("iw", &["dev"], false)?;
+    iw_dev_interfaces(&output)
+        .into_iter()
+        .map(|interface| {
+            let sys_path = format!("/sys/class/net/{}", interface);
+            let uevent = format!("{}/device/uevent", sys_path);
+            // One round trip for both; either may fail on minimal systems
+            let script = format!(
+                "cat {} 2>/dev/null; echo {}; udevadm info {} 2>/dev/null",
+                shell_quote(&uevent),
+                INFO_SEPARATOR,
+                shell_quote(&sys_path)
+            );
+            let output = target.run_checked("sh", &["-c", &script], false)?;
+            let (uevent, udevadm) = output.split_once(INFO_SEPARATOR).unwrap_or((&output, ""));
+            let (driver, chipset) = adapter_info(Some(uevent), Some(udevadm));
+            Ok(Adapter {
+                interface,
+                driver,
+                chipset,
+                label: None,
+                id: None,
+                driver_version: None,
+                firmware_version: None,
+                usb: None,
+            })
+        })
+        .collect()
+}
+
+/// Bounce an interface on a remote target with `ip link`
+pub fn reset_interface(index: usize, interface: &str) -> Result<ResetMethod> {
+    let target = target(index).context("No remote target")?;
+    let (method, commands) = reset_commands(interface, None);
+    for args in commands {
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        target.run_checked(args[0], &args[1..], true)?;
+    }
+    Ok(method)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_target() {
+        let target: RemoteTarget = "pi@garage.local:wlan1".parse().unwrap();
+        assert_eq!(target.destination, "pi@garage.local");
+        assert_eq!(target.interface, "wlan1");
+        assert_eq!(target.node_name(), "garage.local");
+        let bench: RemoteTarget = "bench:wlan0".parse().unwrap();
+        assert_eq!((bench.destination.as_str(), bench.node_name()), ("bench", "bench"));
+        assert!("garage.local".parse::<RemoteTarget>().is_err());
+        assert!("garage.local:".parse::<RemoteTarget>().is_err());
+    }
+
+    #[test]
+    fn test_remote_command() {
+        let target: RemoteTarget = "pi@garage:wlan1".parse().unwrap();
+        assert_eq!(
+            target.remote_command("iw", &["dev", "wlan1", "scan", "ssid", "Bob's Net", ""], true),
+            "sudo -n iw dev wlan1 scan ssid 'Bob'\\''s Net' ''"
+        );
+        assert_eq!(target.remote_command("iw", &["dev"], false), "iw dev");
+
+        let root: RemoteTarget = "root@garage:wlan1".parse().unwrap();
+        assert_eq!(
+            root.remote_command("ip", &["link", "set", "wlan1", "up"], true),
+            "ip link set wlan1 up"
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use std::time::{Duration, Instant};
+
+use crate::data::{ScanResult, Session};
+
+/// Fastest replay: a day-long session in under 9 seconds
+pub const MAX_SPEED: f64 = 10_000.0;
+
+/// Parse a `--replay-speed` multiplier: a finite number above 0, up to `MAX_SPEED`
+pub fn parse_speed(value: &str) -> Result<f64, String> {
+    let speed: f64 = value.parse().map_err(|_| format!("{} is not a number", value))?;
+    if !speed.is_finite() || speed <= 0.0 {
+        return Err("must be a finite number above 0".to_string());
+    }
+    if speed > MAX_SPEED {
+        return Err(format!("must be at most {}", MAX_SPEED));
+    }
+    Ok(speed)
+}
+
+/// Feeds a recorded session's scans back at real or accelerated speed
+pub struct Replay {
+    scans: Vec<ScanResult>,
+    /// Recorded offset of each scan from the session start
+    offsets: Vec<Duration>,
+    next: usize,
+    pub speed: f64,
+    started: Instant,
+    /// Recorded time jumped over by skipping ahead
+    skipped: Duration,
+}
+
+impl Replay {
+    pub fn new(session: &Session, speed: f64) -> Self {
+        let offsets = session
+            .scans
+            .iter()
+            .map(|s| (s.timestamp - session.started_at).to_std().unwrap_or_default())
+            .collect();
+        Self {
+            scans: session.scans.clone(),
+            offsets,
+            next: 0,
+            speed: if speed.is_finite() && speed > 0.0 { speed.min(MAX_SPEED) } else { 1.0 },
+            started: Instant::now(),
+            skipped: Duration::ZERO,
+        }
+    }
+
+    /// Recorded time reached so far
+    pub fn position(&self) -> Duration {
+        let played = self.started.elapsed().as_secs_f64() * self.speed;
+        Duration::try_from_secs_f64(played).unwrap_or(Duration::MAX).saturating_add(self.skipped)
+    }
+
+    /// Scans whose recorded time has come, in order
+    pub fn due(&mut self) -> Vec<ScanResult> {
+        self.due_at(self.position())
+    }
+
+    fn due_at(&mut self, position: Duration) -> Vec<ScanResult> {
+        let start = self.next;
+        while self.next < self.scans.len() && self.offsets[self.next] <= position {
+            self.next += 1;
+        }
+        self.scans[start..self.next].to_vec()
+    }
+
+    /// Jump straight to the next scan; later scans keep their spacing from it
+    pub fn skip(&mut self) -> Option<ScanResult> {
+        let offset = *self.offsets.get(self.next)?;
+        self.skipped += offset.saturating_sub(self.position());
+        self.due_at(offset).into_iter().last()
+    }
+
+    /// Scans delivered and total
+    pub fn progress(&self) -> (usize, usize) {
+        (self.next, self.scans.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::fixtures::{adapter, scan};
+
+    #[test]
+    fn test_replay_timing() {
+        let mut session = Session::new(adapter("wlan0"), None);
+        for secs in [5, 10, 30] {
+            let mut empty = scan(Vec::new());
+            empty.timestamp = session.started_at + chrono::Duration::seconds(secs);
+            session.add_scan(empty);
+        }
+
+        let mut replay = Replay::new(&session, 10.0);
+        assert!(replay.due_at(Duration::from_secs(4)).is_empty());
+        assert_eq!(replay.due_at(Duration::from_secs(12)).len(), 2);
+        assert_eq!(replay.progress(), (2, 3));
+        assert!(replay.skip().is_some());
+        assert!(replay.position() >= Duration::from_secs(30));
+        assert_eq!(replay.progress(), (3, 3));
+        assert!(replay.skip().is_none());
+    }
+
+    #[test]
+    fn test_parse_speed() {
+        assert_eq!(parse_speed("2.5"), Ok(2.5));
+        for bad in ["0", "-1", "inf", "NaN", "1e300", "fast"] {
+            assert!(parse_speed(bad).is_err(), "{}", bad);
+        }
+        assert_eq!(Replay::new(&Session::new(adapter("wlan0"), None), f64::INFINITY).speed, 1.0);
+        assert_eq!(Replay::new(&Session::new(adapter("wlan0"), None), 1e300).speed, MAX_SPEED);
+    }
+}
@@ -0,0 +1,212 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::process::Command;
+
+use super::privilege::{command_exists, privilege, privileged_command, Privilege};
+
+/// How a stuck interface is brought back
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResetMethod {
+    /// `ip link set <iface> down`, then `up`
+    LinkBounce,
+    /// `usbreset /dev/bus/usb/BBB/DDD`, re-enumerating the whole USB device
+    UsbReset,
+    /// `netsh interface set interface` disabled, then enabled (Windows)
+    NetshToggle,
+    /// `networksetup -setairportpower` off, then on (macOS)
+    AirportPower,
+    /// `ifconfig <iface> down`, then `up` (FreeBSD/OpenBSD)
+    IfconfigBounce,
+}
+
+impl ResetMethod {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ResetMethod::LinkBounce => "ip link down/up",
+            ResetMethod::UsbReset => "usbreset",
+            ResetMethod::NetshToggle => "netsh disable/enable",
+            ResetMethod::AirportPower => "Wi-Fi power off/on",
+            ResetMethod::IfconfigBounce => "ifconfig down/up",
+        }
+    }
+}
+
+/// Commands for a reset; usbreset when the adapter is USB and usbreset can run
+pub(super) fn reset_commands(
+    interface: &str,
+    usb_node: Option<&str>,
+) -> (ResetMethod, Vec<Vec<String>>) {
+    match usb_node {
+        Some(node) => {
+            (ResetMethod::UsbReset, vec![vec!["usbreset".to_string(), node.to_string()]])
+        }
+        None => {
+            let link = |state: &str| {
+                ["ip", "link", "set", interface, state]
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect()
+            };
+            (ResetMethod::LinkBounce, vec![link("down"), link("up")])
+        }
+    }
+}
+
+/// Commands to disable and re-enable an interface on Windows (needs an elevated shell)
+fn netsh_reset_commands(interface: &str) -> (ResetMethod, Vec<Vec<String>>) {
+    let toggle = |state: &str| {
+        vec![
+            "interface".to_string(),
+            "set".to_string(),
+            "interface".to_string(),
+            format!("name={}", interface),
+            format!("admin={}", state),
+        ]
+    };
+    let commands = ["disabled", "enabled"]
+        .iter()
+        .map(|state| [vec!["netsh".to_string()], toggle(state)].concat())
+        .collect();
+    (ResetMethod::NetshToggle, commands)
+}
+
+/// Commands to power an interface off and on again on macOS
+fn airport_power_commands(interface: &str) -> (ResetMethod, Vec<Vec<String>>) {
+    let power = |state: &str| {
+        ["networksetup", "-setairportpower", interface, state]
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    };
+    (ResetMethod::AirportPower, vec![power("off"), power("on")])
+}
+
+/// Commands to bounce an interface on FreeBSD/OpenBSD
+fn ifconfig_reset_commands(interface: &str) -> (ResetMethod, Vec<Vec<String>>) {
+    let link = |state: &str| {
+        ["ifconfig", interface, state]
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    };
+    (ResetMethod::IfconfigBounce, vec![link("down"), link("up")])
+}
+
+/// `/dev/bus/usb/BBB/DDD` node of the USB device behind an interface, if it is USB
+#[cfg(not(unix))]
+fn usb_node(_interface: &str) -> Option<String> {
+    None
+}
+
+#[cfg(unix)]
+fn usb_node(interface: &str) -> Option<String> {
+    let device = Path::new("/sys/class/net")
+        .join(interface)
+        .join("device")
+        .canonicalize()
+        .ok()?;
+    usb_node_from(&device)
+}
+
+/// Bus and device number address this one device; `vendor:product` would pick the first
+/// of two identical adapters
+#[cfg(unix)]
+fn usb_node_from(device: &Path) -> Option<String> {
+    // The interface node (e.g. 1-2:1.0) sits under the device node with the numbers
+    let usb_device = device.ancestors().find(|p| p.join("busnum").exists())?;
+    let read = |name| -> Option<u32> {
+        std::fs::read_to_string(usb_device.join(name)).ok()?.trim().parse().ok()
+    };
+    Some(format!("/dev/bus/usb/{:03}/{:03}", read("busnum")?, read("devnum")?))
+}
+
+/// Bounce the interface so a wedged driver starts answering scans again
+pub fn reset_interface(interface: &str) -> Result<ResetMethod> {
+    let (method, commands) = if cfg!(windows) {
+        netsh_reset_commands(interface)
+    } else if cfg!(target_os = "macos") {
+        airport_power_commands(interface)
+    } else if cfg!(any(target_os = "freebsd", target_os = "openbsd")) {
+        ifconfig_reset_commands(interface)
+    } else {
+        // usbreset opens /dev/bus/usb directly, which CAP_NET_ADMIN doesn't cover
+        let usb = usb_node(interface)
+            .filter(|_| privilege() != Privilege::Capability && command_exists("usbreset"));
+        reset_commands(interface, usb.as_deref())
+    };
+
+    for args in commands {
+        // Admin users can toggle Wi-Fi power without sudo
+        let mut command = match method {
+            ResetMethod::AirportPower => Command::new(&args[0]),
+            _ => privileged_command(&args[0]),
+        };
+        let output = command
+            .args(&args[1..])
+            .output()
+            .with_context(|| format!("Failed to run '{}'", args[0]))?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "{} failed: {}",
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+    }
+    Ok(method)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reset_commands() {
+        let (method, commands) = reset_commands("wlan0", None);
+        assert_eq!(method, ResetMethod::LinkBounce);
+        assert_eq!(
+            commands,
+            vec![
+                vec!["ip", "link", "set", "wlan0", "down"],
+                vec!["ip", "link", "set", "wlan0", "up"],
+            ]
+        );
+
+        let (method, commands) = reset_commands("wlx00c0ca", Some("/dev/bus/usb/001/004"));
+        assert_eq!(method, ResetMethod::UsbReset);
+        assert_eq!(commands, vec![vec!["usbreset", "/dev/bus/usb/001/004"]]);
+
+        let (method, commands) = netsh_reset_commands("Wi-Fi 2");
+        assert_eq!(method, ResetMethod::NetshToggle);
+        assert_eq!(
+            commands[0],
+            vec!["netsh", "interface", "set", "interface", "name=Wi-Fi 2", "admin=disabled"]
+        );
+        assert_eq!(commands[1].last().unwrap(), "admin=enabled");
+
+        let (method, commands) = airport_power_commands("en1");
+        assert_eq!(method, ResetMethod::AirportPower);
+        assert_eq!(commands[0], vec!["networksetup", "-setairportpower", "en1", "off"]);
+        assert_eq!(commands[1], vec!["networksetup", "-setairportpower", "en1", "on"]);
+
+        let (method, commands) = ifconfig_reset_commands("wlan0");
+        assert_eq!(method, ResetMethod::IfconfigBounce);
+        assert_eq!(
+            commands,
+            vec![vec!["ifconfig", "wlan0", "down"], vec!["ifconfig", "wlan0", "up"]]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_usb_node_from() {
+        let dir = std::env::temp_dir().join(format!("wificomp-usbnode-{}", std::process::id()));
+        let interface = dir.join("1-2").join("1-2:1.0");
+        std::fs::create_dir_all(&interface).unwrap();
+        std::fs::write(dir.join("1-2").join("busnum"), "1\n").unwrap();
+        std::fs::write(dir.join("1-2").join("devnum"), "12\n").unwrap();
+
+        assert_eq!(usb_node_from(&interface).as_deref(), Some("/dev/bus/usb/001/012"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,45 +1,408 @@
-use anyhow::{Context, Result};
 use chrono::Utc;
-use std::process::Command;
+use std::process::{Command, Output};
 
-use crate::data::{AccessPoint, ScanResult};
+use super::reset::{self, ResetMethod};
+use super::{
+    airport, connect, demo, detect_adapters, find_adapter, ifconfig, load, netsh, remote,
+    wireless_interfaces, ScanError,
+};
+use crate::data::{
+    AccessPoint, Adapter, ApRegulatory, BssType, ChannelDwell, ConnectTiming, LinkStats, LoadTest,
+    RadioContext, Roaming, ScanResult, Security,
+};
 
-/// Perform a WiFi scan on the given interface
-pub fn scan_wifi(interface: &str) -> Result<ScanResult> {
-    // Check if we're already root
-    let is_root = unsafe { libc::geteuid() } == 0;
+/// Where scans come from: the hardware via `iw` (Linux), `netsh` (Windows), `airport`
+/// (macOS) or `ifconfig` (FreeBSD/OpenBSD); `iw` on another machine over SSH for
+/// `--remote`; or synthetic data for `--demo`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScanBackend {
+    #[cfg_attr(
+        not(any(windows, target_os = "macos", target_os = "freebsd", target_os = "openbsd")),
+        default
+    )]
+    Iw,
+    #[cfg_attr(windows, default)]
+    #[cfg_attr(not(windows), allow(dead_code))]
+    Netsh,
+    #[cfg_attr(target_os = "macos", default)]
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    Airport,
+    #[cfg_attr(any(target_os = "freebsd", target_os = "openbsd"), default)]
+    #[cfg_attr(not(any(target_os = "freebsd", target_os = "openbsd")), allow(dead_code))]
+    Ifconfig,
+    /// The `--remote` target at this position
+    Remote(usize),
+    Demo,
+}
+
+impl ScanBackend {
+    pub fn name(self) -> &'static str {
+        match self {
+            ScanBackend::Iw => "iw",
+            ScanBackend::Netsh => "netsh",
+            ScanBackend::Airport => "airport",
+            ScanBackend::Ifconfig => "ifconfig",
+            ScanBackend::Remote(_) => "remote",
+            ScanBackend::Demo => "demo",
+        }
+    }
+
+    /// Whether scans come from real adapters
+    pub fn is_hardware(self) -> bool {
+        self != ScanBackend::Demo
+    }
+
+    /// Whether scans run through sudo unless the binary has the capability
+    pub fn needs_privilege(self) -> bool {
+        matches!(self, ScanBackend::Iw | ScanBackend::Ifconfig)
+    }
+
+    pub fn scan(self, interface: &str, probe_ssids: &[String]) -> Result<ScanResult, ScanError> {
+        match self {
+            ScanBackend::Iw => scan_wifi(interface, probe_ssids),
+            // Only iw can send directed probes
+            ScanBackend::Netsh => netsh::scan_netsh(interface),
+            ScanBackend::Airport => airport::scan_airport(interface),
+            ScanBackend::Ifconfig => ifconfig::scan_ifconfig(interface),
+            ScanBackend::Remote(index) => remote::scan(index, interface, probe_ssids),
+            ScanBackend::Demo => Ok(demo::demo_scan(interface, Utc::now())),
+        }
+    }
+
+    pub fn detect_adapters(self) -> anyhow::Result<Vec<Adapter>> {
+        match self {
+            ScanBackend::Iw => detect_adapters(),
+            ScanBackend::Netsh => netsh::detect_adapters(),
+            ScanBackend::Airport => airport::detect_adapters(),
+            ScanBackend::Ifconfig => ifconfig::detect_adapters(),
+            ScanBackend::Remote(index) => remote::detect_adapters(index),
+            ScanBackend::Demo => Ok(demo::demo_adapters()),
+        }
+    }
+
+    pub fn find_adapter(self, interface: &str) -> anyhow::Result<Adapter> {
+        let adapters = match self {
+            ScanBackend::Iw => return find_adapter(interface),
+            ScanBackend::Netsh => netsh::detect_adapters()?,
+            ScanBackend::Airport => airport::detect_adapters()?,
+            ScanBackend::Ifconfig => ifconfig::detect_adapters()?,
+            ScanBackend::Remote(index) => remote::detect_adapters(index)?,
+            ScanBackend::Demo => demo::demo_adapters(),
+        };
+        adapters
+            .into_iter()
+            .find(|a| a.interface == interface)
+            .ok_or_else(|| anyhow::anyhow!("No such wireless interface: {}", interface))
+    }
+
+    /// Interface names present now, for hotplug detection. Runs a full adapter listing on
+    /// Windows, macOS and FreeBSD, so it is polled off the UI thread
+    pub fn interfaces(self) -> Vec<String> {
+        match self {
+            ScanBackend::Iw => wireless_interfaces(),
+            ScanBackend::Netsh | ScanBackend::Airport | ScanBackend::Ifconfig => self
+                .detect_adapters()
+                .map(|adapters| adapters.into_iter().map(|a| a.interface).collect())
+                .unwrap_or_default(),
+            // Polling a remote host every few seconds would stall the UI on a slow link
+            ScanBackend::Remote(index) => {
+                remote::target(index).map(|t| t.interface.clone()).into_iter().collect()
+            }
+            ScanBackend::Demo => demo::demo_adapters().into_iter().map(|a| a.interface).collect(),
+        }
+    }
+
+    /// Probe location scans come from, for `--remote`
+    pub fn node(self) -> Option<String> {
+        match self {
+            ScanBackend::Remote(index) => Some(remote::target(index)?.node_name().to_string()),
+            _ => None,
+        }
+    }
 
-    let output = if is_root {
-        Command::new("iw")
-            .args(["dev", interface, "scan"])
-            .output()
+    /// Tx power and country code of the interface, where `iw` reports them
+    pub fn radio_context(self, interface: &str) -> Option<RadioContext> {
+        match self {
+            ScanBackend::Iw => radio_context(interface, &run_iw),
+            ScanBackend::Remote(index) => remote::radio_context(index, interface),
+            ScanBackend::Demo => Some(demo::demo_radio_context()),
+            _ => None,
+        }
+    }
+
+    /// Bounce a stuck interface, on the remote host for `--remote`
+    pub fn reset_interface(self, interface: &str) -> anyhow::Result<ResetMethod> {
+        match self {
+            ScanBackend::Remote(index) => remote::reset_interface(index, interface),
+            _ => reset::reset_interface(interface),
+        }
+    }
+
+    /// Time joining NetworkManager profile `network` on the interface
+    pub fn connect_test(self, interface: &str, network: &str) -> anyhow::Result<ConnectTiming> {
+        match self {
+            ScanBackend::Iw => connect::connect_test(interface, network),
+            ScanBackend::Demo => Ok(demo::demo_connect_test(interface, network, Utc::now())),
+            _ => anyhow::bail!("Connect tests need NetworkManager on this machine"),
+        }
+    }
+
+    /// Ping `target` through the interface while it sends `mbps` of UDP for `secs`
+    pub fn load_test(
+        self,
+        interface: &str,
+        target: &str,
+        secs: u64,
+        mbps: u32,
+    ) -> anyhow::Result<LoadTest> {
+        match self {
+            ScanBackend::Iw => load::load_test(interface, target, secs, mbps),
+            ScanBackend::Demo => {
+                Ok(demo::demo_load_test(interface, target, secs, mbps, Utc::now()))
+            }
+            _ => anyhow::bail!("Load tests need Linux on this machine"),
+        }
+    }
+}
+
+/// Runs `iw` with the given arguments, elevated when the flag is set
+pub(super) type IwRunner<'a> = &'a dyn Fn(&[&str], bool) -> std::io::Result<Output>;
+
+/// Run `iw` locally; elevated runs use the privilege detected at startup
+fn run_iw(args: &[&str], privileged: bool) -> std::io::Result<Output> {
+    if privileged {
+        super::privilege::iw_command().args(args).output()
     } else {
-        Command::new("sudo")
-            .args(["iw", "dev", interface, "scan"])
-            .output()
+        Command::new("iw").args(args).output()
     }
-    .context("Failed to run 'iw scan'. Is iw installed?")?;
+}
+
+/// Perform a WiFi scan on the given interface.
+///
+/// With `probe_ssids`, sends directed probe requests for those SSIDs so hidden
+/// networks answer with their names.
+pub fn scan_wifi(interface: &str, probe_ssids: &[String]) -> Result<ScanResult, ScanError> {
+    scan_iw(interface, probe_ssids, &run_iw)
+}
+
+/// Scan with `iw`, wherever `run_iw` runs it
+pub(super) fn scan_iw(
+    interface: &str,
+    probe_ssids: &[String],
+    run_iw: IwRunner,
+) -> Result<ScanResult, ScanError> {
+    let args = scan_args(interface, probe_ssids);
+    let survey_before = survey_dump(interface, run_iw);
+    log::debug!("running iw {}", args.join(" "));
+    let output = run_iw(&args, true).map_err(|e| {
+        ScanError::Other(format!("Failed to run 'iw scan'. Is iw installed? ({})", e))
+    })?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        if stderr.contains("Operation not permitted") {
-            anyhow::bail!("Permission denied. Run with sudo or set CAP_NET_ADMIN capability.");
-        } else if stderr.contains("Device or resource busy") {
-            anyhow::bail!("Device busy. Another scan may be in progress.");
-        } else {
-            anyhow::bail!("Scan failed: {}", stderr);
-        }
+        log::warn!("iw scan on {} exited with {}: {}", interface, output.status, stderr.trim());
+        return Err(ScanError::from_stderr(interface, &stderr));
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
     let access_points = parse_scan_output(&stdout);
 
+    // BSS entries that all failed to parse point at an unfamiliar iw format
+    let entries = stdout.lines().filter(|l| l.starts_with("BSS ")).count();
+    log::debug!("parsed {} APs from {} BSS entries", access_points.len(), entries);
+    if access_points.is_empty() && entries > 0 {
+        log::trace!("unparsed iw output:\n{}", stdout);
+        return Err(ScanError::ParseFailure(format!(
+            "{} BSS entries, none with signal and frequency",
+            entries
+        )));
+    }
+
     Ok(ScanResult {
         timestamp: Utc::now(),
         access_points,
+        channel_dwell: dwell_since(&survey_before, survey_dump(interface, run_iw)),
+        link: link_stats(interface, run_iw),
+        position: None,
+        trigger: None,
+        host: None,
     })
 }
 
+/// `iw` arguments for a scan, probing the given SSIDs plus the wildcard SSID. `-u` has iw
+/// dump IEs it can't decode, such as the Wi-Fi 7 Multi-Link element.
+fn scan_args<'a>(interface: &'a str, probe_ssids: &'a [String]) -> Vec<&'a str> {
+    let mut args = vec!["dev", interface, "scan", "-u"];
+    if !probe_ssids.is_empty() {
+        args.push("ssid");
+        args.extend(probe_ssids.iter().map(String::as_str));
+        // Empty SSID keeps the broadcast probe so visible APs are still found
+        args.push("");
+    }
+    args
+}
+
+/// Read the adapter's current association (None if not connected or unsupported)
+fn link_stats(interface: &str, run_iw: IwRunner) -> Option<LinkStats> {
+    let output = run_iw(&["dev", interface, "link"], false).ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_link_output(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Parse the output of `iw dev <iface> link`
+fn parse_link_output(output: &str) -> Option<LinkStats> {
+    let mut lines = output.lines().map(str::trim);
+    let bssid = lines
+        .next()?
+        .strip_prefix("Connected to ")?
+        .split_whitespace()
+        .next()?
+        .to_uppercase();
+
+    let mut link = LinkStats {
+        bssid,
+        ssid: String::new(),
+        signal_dbm: None,
+        tx_bitrate_mbps: None,
+        rx_bitrate_mbps: None,
+    };
+    let first_number = |s: &str| s.split_whitespace().next().and_then(|v| v.parse::<f32>().ok());
+
+    for line in lines {
+        if let Some(ssid) = line.strip_prefix("SSID: ") {
+            link.ssid = ssid.to_string();
+        } else if let Some(signal) = line.strip_prefix("signal: ") {
+            link.signal_dbm = first_number(signal).map(|s| s.round() as i32);
+        } else if let Some(rate) = line.strip_prefix("tx bitrate: ") {
+            link.tx_bitrate_mbps = first_number(rate);
+        } else if let Some(rate) = line.strip_prefix("rx bitrate: ") {
+            link.rx_bitrate_mbps = first_number(rate);
+        }
+    }
+
+    Some(link)
+}
+
+/// Read tx power from `iw dev <iface> info` and the country from `iw reg get`
+pub(super) fn radio_context(interface: &str, run_iw: IwRunner) -> Option<RadioContext> {
+    let stdout = |args: &[&str]| {
+        let output = run_iw(args, false).ok().filter(|o| o.status.success())?;
+        Some(String::from_utf8_lossy(&output.stdout).into_owned())
+    };
+    let (tx_power_dbm, wiphy) = stdout(&["dev", interface, "info"])
+        .map(|info| parse_interface_info(&info))
+        .unwrap_or_default();
+    let country = stdout(&["reg", "get"]).and_then(|reg| parse_reg_country(&reg, wiphy));
+    let context = RadioContext { tx_power_dbm, country };
+    (context != RadioContext::default()).then_some(context)
+}
+
+/// Tx power and wiphy index from `iw dev <iface> info`
+fn parse_interface_info(output: &str) -> (Option<f32>, Option<u32>) {
+    let mut tx_power = None;
+    let mut wiphy = None;
+    for line in output.lines().map(str::trim) {
+        let mut words = line.split_whitespace();
+        match (words.next(), words.next()) {
+            (Some("txpower"), Some(value)) => tx_power = value.parse().ok(),
+            (Some("wiphy"), Some(value)) => wiphy = value.parse().ok(),
+            _ => {}
+        }
+    }
+    (tx_power, wiphy)
+}
+
+/// Country code that applies to `wiphy` in `iw reg get` output: the phy's own
+/// (self-managed) domain if it has one, otherwise the global one
+fn parse_reg_country(output: &str, wiphy: Option<u32>) -> Option<String> {
+    let own_section = wiphy.map(|n| format!("phy#{}", n));
+    let mut section = "global".to_string();
+    let mut global = None;
+    for line in output.lines() {
+        if !line.starts_with(char::is_whitespace) && line.starts_with("phy#") {
+            section = line.split_whitespace().next().unwrap_or(line).to_string();
+        } else if let Some(rest) = line.strip_prefix("country ") {
+            let code = rest.split(':').next()?.trim().to_string();
+            if own_section.as_deref() == Some(section.as_str()) {
+                return Some(code);
+            } else if section == "global" {
+                global = Some(code);
+            }
+        }
+    }
+    global
+}
+
+/// Read the per-channel survey counters (best effort; not all drivers support it)
+fn survey_dump(interface: &str, run_iw: IwRunner) -> Vec<ChannelDwell> {
+    match run_iw(&["dev", interface, "survey", "dump"], true) {
+        Ok(output) if output.status.success() => {
+            parse_survey_dump(&String::from_utf8_lossy(&output.stdout))
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Dwell during one scan, from the survey counters before and after it. Most drivers
+/// keep counting from when the interface came up; a counter that went down was reset
+/// by the scan, so its value after the scan is the scan's own.
+fn dwell_since(before: &[ChannelDwell], after: Vec<ChannelDwell>) -> Vec<ChannelDwell> {
+    let since = |now: u64, then: u64| if now >= then { now - then } else { now };
+    after
+        .into_iter()
+        .map(|mut dwell| {
+            if let Some(prev) = before.iter().find(|b| b.frequency_mhz == dwell.frequency_mhz) {
+                dwell.active_ms = since(dwell.active_ms, prev.active_ms);
+                dwell.busy_ms = dwell.busy_ms.map(|busy| since(busy, prev.busy_ms.unwrap_or(0)));
+            }
+            dwell
+        })
+        .filter(|dwell| dwell.active_ms > 0)
+        .collect()
+}
+
+/// Parse the output of `iw dev <iface> survey dump`; its times are counters
+fn parse_survey_dump(output: &str) -> Vec<ChannelDwell> {
+    let mut dwell = Vec::new();
+    let mut current: Option<ChannelDwell> = None;
+    let number = |s: &str| s.split_whitespace().next().map(str::to_string);
+
+    // Entries with no active time are channels the radio never visited
+    let finish = |dwell: &mut Vec<ChannelDwell>, entry: Option<ChannelDwell>| {
+        if let Some(entry) = entry.filter(|e| e.active_ms > 0) {
+            dwell.push(entry);
+        }
+    };
+
+    for line in output.lines() {
+        let trimmed = line.trim();
+        if let Some(freq) = trimmed.strip_prefix("frequency:") {
+            finish(&mut dwell, current.take());
+            current = number(freq)
+                .and_then(|f| f.parse().ok())
+                .map(|frequency_mhz| ChannelDwell {
+                    frequency_mhz,
+                    active_ms: 0,
+                    noise_dbm: None,
+                    busy_ms: None,
+                });
+        } else if let Some(entry) = current.as_mut() {
+            if let Some(noise) = trimmed.strip_prefix("noise:") {
+                entry.noise_dbm = number(noise).and_then(|n| n.parse().ok());
+            } else if let Some(active) = trimmed.strip_prefix("channel active time:") {
+                entry.active_ms = number(active).and_then(|t| t.parse().ok()).unwrap_or(0);
+            } else if let Some(busy) = trimmed.strip_prefix("channel busy time:") {
+                entry.busy_ms = number(busy).and_then(|b| b.parse().ok());
+            }
+        }
+    }
+    finish(&mut dwell, current);
+
+    dwell
+}
+
 /// Parse the output of `iw dev <iface> scan`
 fn parse_scan_output(output: &str) -> Vec<AccessPoint> {
     let mut aps = Vec::new();
@@ -48,18 +411,16 @@ fn parse_scan_output(output: &str) -> Vec<AccessPoint> {
     for line in output.lines() {
         let trimmed = line.trim();
 
-        // New BSS entry
-        if trimmed.starts_with("BSS ") {
+        // New BSS entry; only unindented, so IEs like "BSS Load:" don't match
+        if line.starts_with("BSS ") {
             // Save previous AP if valid
             if let Some(builder) = current_ap.take() {
-                if let Some(ap) = builder.build() {
-                    aps.push(ap);
-                }
+                finish(builder, &mut aps);
             }
-            // Start new AP
+            // Start new AP; "BSS aa:bb:..(on wlan0) -- associated"
             let bssid = trimmed
                 .trim_start_matches("BSS ")
-                .split('(')
+                .split(['(', ' '])
                 .next()
                 .unwrap_or("")
                 .trim()
@@ -67,15 +428,25 @@ fn parse_scan_output(output: &str) -> Vec<AccessPoint> {
             current_ap = Some(AccessPointBuilder::new(bssid));
         } else if let Some(ref mut builder) = current_ap {
             // Parse fields
-            if let Some(signal) = trimmed.strip_prefix("signal: ") {
-                // Format: "-45.00 dBm" or "-45 dBm"
-                if let Some(dbm_str) = signal.split_whitespace().next() {
-                    if let Ok(dbm) = dbm_str.parse::<f32>() {
-                        builder.signal_dbm = Some(dbm.round() as i32);
-                    }
+            if let Some(signal) = trimmed
+                .strip_prefix("signal:")
+                .or_else(|| trimmed.strip_prefix("signal strength:"))
+            {
+                if let Some(dbm) = parse_signal(signal) {
+                    builder.signal_dbm = Some(dbm);
+                }
+            } else if let Some(ms) =
+                trimmed.strip_prefix("last seen: ").and_then(|s| s.strip_suffix(" ms ago"))
+            {
+                builder.last_seen_ms = ms.trim().parse().ok();
+            } else if let Some(ssid) = trimmed.strip_prefix("SSID:") {
+                // Some APs send several SSID IEs; keep the first real name
+                let ssid = unescape_ssid(ssid.strip_prefix(' ').unwrap_or(ssid));
+                if builder.ssid.as_deref().is_none_or(str::is_empty) {
+                    builder.ssid = Some(ssid);
                 }
-            } else if let Some(ssid) = trimmed.strip_prefix("SSID: ") {
-                builder.ssid = Some(ssid.to_string());
+            } else if let Some(mesh_id) = trimmed.strip_prefix("MESH ID:") {
+                builder.mesh_id = Some(unescape_ssid(mesh_id.trim_start()));
             } else if let Some(freq) = trimmed.strip_prefix("freq: ") {
                 // Frequency can be "2437" or "2437.0"
                 if let Ok(f) = freq.parse::<f32>() {
@@ -91,27 +462,186 @@ fn parse_scan_output(output: &str) -> Vec<AccessPoint> {
                         builder.channel = Some(ch);
                     }
                 }
+            } else if let Some(caps) = trimmed.strip_prefix("capability: ") {
+                builder.privacy = caps.split_whitespace().any(|c| c == "Privacy");
+                builder.ibss = caps.split_whitespace().any(|c| c == "IBSS");
+            } else if trimmed.starts_with("RSN:") {
+                builder.rsn = true;
+            } else if trimmed.starts_with("WPA:") {
+                builder.wpa = true;
+            } else if let Some(ciphers) = trimmed.strip_prefix("* Pairwise ciphers: ") {
+                builder.pairwise_ciphers.extend(ciphers.split_whitespace().map(str::to_string));
+            } else if trimmed.starts_with("WPS:") {
+                builder.wps = true;
+            } else if let Some(suites) = trimmed.strip_prefix("* Authentication suites: ") {
+                if suites.split_whitespace().any(|s| s == "SAE") {
+                    builder.sae = true;
+                }
+                // "FT/PSK", "FT/SAE", "FT/IEEE 802.1X"
+                if suites.split_whitespace().any(|s| s.starts_with("FT/")) {
+                    builder.roaming.r = true;
+                }
+            } else if trimmed.starts_with("RM enabled capabilities:") {
+                builder.roaming.k = true;
+            } else if trimmed == "* BSS Transition" {
+                builder.roaming.v = true;
+            } else if trimmed.starts_with("MD:") || trimmed.starts_with("Mobility Domain") {
+                builder.roaming.r = true;
+            } else if let Some(offset) = trimmed.strip_prefix("* secondary channel offset: ") {
+                builder.ht_width = Some(if offset == "no secondary" { 20 } else { 40 });
+            } else if let Some(width) = trimmed.strip_prefix("* channel width: ") {
+                // VHT operation, e.g. "1 (80 MHz)"; "0 (20 or 40 MHz)" defers to HT
+                builder.vht_width = match width.split_whitespace().next() {
+                    Some("1") => Some(80),
+                    Some("2") | Some("3") => Some(160),
+                    _ => None,
+                };
+            } else if let Some(interval) = trimmed.strip_prefix("beacon interval: ") {
+                builder.beacon_interval_tu =
+                    interval.split_whitespace().next().and_then(|i| i.parse().ok());
+            } else if let Some(count) = trimmed.strip_prefix("* station count: ") {
+                builder.station_count = count.parse().ok();
+            } else if let Some(util) = trimmed.strip_prefix("* channel utilisation: ") {
+                builder.channel_utilization = parse_utilization(util);
+            } else if let Some(country) = trimmed.strip_prefix("Country: ") {
+                // "US\tEnvironment: Indoor/Outdoor"
+                builder.country = country.split_whitespace().next().map(str::to_string);
+            } else if let Some(triplet) = trimmed.strip_prefix("Channels [") {
+                builder.country_channels.extend(parse_country_triplet(triplet));
+            } else if let Some(constraint) = trimmed.strip_prefix("Power constraint: ") {
+                builder.power_constraint_db = parse_power(constraint);
+            } else if let Some(power) = trimmed.strip_prefix("TPC report: TX power: ") {
+                builder.tpc_power_dbm = parse_power(power);
+            } else if let Some((_, hex)) = trimmed.split_once(MULTI_LINK_IE) {
+                builder.mld_address = parse_multi_link(hex);
             }
         }
     }
 
     // Don't forget the last AP
     if let Some(builder) = current_ap {
-        if let Some(ap) = builder.build() {
-            aps.push(ap);
-        }
+        finish(builder, &mut aps);
     }
 
     aps
 }
 
+/// How `iw -u` dumps the Multi-Link element (extension ID 107), followed by its bytes
+const MULTI_LINK_IE: &str = "Unknown Extension ID (107):";
+
+/// MLD address from a basic Multi-Link element's hex bytes: 2 bytes of control (type 0
+/// is basic), then the common info's length byte and the MLD MAC address
+fn parse_multi_link(hex: &str) -> Option<String> {
+    let bytes: Vec<u8> = hex
+        .split_whitespace()
+        .map(|b| u8::from_str_radix(b, 16))
+        .collect::<Result<_, _>>()
+        .ok()?;
+    if bytes.len() < 9 || bytes[0] & 0x07 != 0 {
+        return None;
+    }
+    let octets: Vec<String> = bytes[3..9].iter().map(|b| format!("{:02X}", b)).collect();
+    Some(octets.join(":"))
+}
+
+/// A Country element triplet, `36 - 48] @ 23 dBm`: first and last channel, max power
+fn parse_country_triplet(triplet: &str) -> Option<(u32, u32, i32)> {
+    let (channels, power) = triplet.split_once("] @ ")?;
+    let (first, last) = channels.split_once(" - ")?;
+    Some((first.trim().parse().ok()?, last.trim().parse().ok()?, parse_power(power)?))
+}
+
+/// Leading integer of "23 dBm" or "3 dB"
+fn parse_power(value: &str) -> Option<i32> {
+    value.split_whitespace().next()?.parse().ok()
+}
+
+/// BSS Load channel utilization as a percentage, from iw's "34/255"
+fn parse_utilization(value: &str) -> Option<u8> {
+    let (busy, scale) = value.split_once('/')?;
+    let busy = busy.trim().parse::<u32>().ok()?;
+    let scale = scale.trim().parse::<u32>().ok().filter(|s| *s > 0)?;
+    Some(((busy.min(scale) * 100 + scale / 2) / scale) as u8)
+}
+
+/// Keep a parsed BSS entry if it had the required fields
+fn finish(builder: AccessPointBuilder, aps: &mut Vec<AccessPoint>) {
+    let bssid = builder.bssid.clone();
+    match builder.build() {
+        Some(ap) => aps.push(ap),
+        None => log::debug!("skipping BSS {} without signal or frequency", bssid),
+    }
+}
+
+/// Parse a signal value: "-45.00 dBm", or "70/100" from drivers that only report quality
+fn parse_signal(value: &str) -> Option<i32> {
+    let value = value.split_whitespace().next()?;
+    if let Some((quality, scale)) = value.split_once('/') {
+        let quality = quality.parse::<f32>().ok()?;
+        let scale = scale.parse::<f32>().ok().filter(|s| *s > 0.0)?;
+        // Common quality-to-dBm mapping: 0% = -100 dBm, 100% = -50 dBm
+        return Some((quality / scale * 50.0 - 100.0).round() as i32);
+    }
+    value.parse::<f32>().ok().map(|dbm| dbm.round() as i32)
+}
+
+/// Decode iw's SSID escaping (`\xNN` for bytes outside printable ASCII).
+///
+/// All-zero SSIDs, which some hidden networks send, come back empty.
+fn unescape_ssid(escaped: &str) -> String {
+    let mut bytes = Vec::with_capacity(escaped.len());
+    let raw = escaped.as_bytes();
+    let mut i = 0;
+    while i < raw.len() {
+        if raw[i] == b'\\' && raw.get(i + 1) == Some(&b'x') {
+            let byte = raw
+                .get(i + 2..i + 4)
+                .and_then(|h| std::str::from_utf8(h).ok())
+                .and_then(|h| u8::from_str_radix(h, 16).ok());
+            if let Some(byte) = byte {
+                bytes.push(byte);
+                i += 4;
+                continue;
+            }
+        }
+        bytes.push(raw[i]);
+        i += 1;
+    }
+    if bytes.iter().all(|&b| b == 0) {
+        return String::new();
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
 /// Helper to build AccessPoint
 struct AccessPointBuilder {
     bssid: String,
     ssid: Option<String>,
+    mesh_id: Option<String>,
     signal_dbm: Option<i32>,
     channel: Option<u32>,
     frequency_mhz: Option<u32>,
+    privacy: bool,
+    rsn: bool,
+    wpa: bool,
+    sae: bool,
+    ht_width: Option<u32>,
+    vht_width: Option<u32>,
+    mld_address: Option<String>,
+    ibss: bool,
+    beacon_interval_tu: Option<u32>,
+    station_count: Option<u32>,
+    channel_utilization: Option<u8>,
+    country: Option<String>,
+    /// Country element triplets: first channel, last channel, max power in dBm
+    country_channels: Vec<(u32, u32, i32)>,
+    power_constraint_db: Option<i32>,
+    tpc_power_dbm: Option<i32>,
+    wps: bool,
+    /// Pairwise ciphers of the WPA and RSN elements
+    pairwise_ciphers: Vec<String>,
+    roaming: Roaming,
+    last_seen_ms: Option<u64>,
 }
 
 impl AccessPointBuilder {
@@ -119,9 +649,43 @@ impl AccessPointBuilder {
         Self {
             bssid,
             ssid: None,
+            mesh_id: None,
             signal_dbm: None,
             channel: None,
             frequency_mhz: None,
+            privacy: false,
+            rsn: false,
+            wpa: false,
+            sae: false,
+            ht_width: None,
+            vht_width: None,
+            mld_address: None,
+            ibss: false,
+            beacon_interval_tu: None,
+            station_count: None,
+            channel_utilization: None,
+            country: None,
+            country_channels: Vec::new(),
+            power_constraint_db: None,
+            tpc_power_dbm: None,
+            wps: false,
+            pairwise_ciphers: Vec::new(),
+            roaming: Roaming::default(),
+            last_seen_ms: None,
+        }
+    }
+
+    fn security(&self) -> Security {
+        if self.rsn && self.sae {
+            Security::Wpa3
+        } else if self.rsn {
+            Security::Wpa2
+        } else if self.wpa {
+            Security::Wpa
+        } else if self.privacy {
+            Security::Wep
+        } else {
+            Security::Open
         }
     }
 
@@ -129,19 +693,58 @@ impl AccessPointBuilder {
         let signal_dbm = self.signal_dbm?;
         let frequency_mhz = self.frequency_mhz?;
         let channel = self.channel.unwrap_or_else(|| freq_to_channel(frequency_mhz));
+        let security = self.security();
+        let bss_type = if self.mesh_id.is_some() {
+            BssType::Mesh
+        } else if self.ibss {
+            BssType::Ibss
+        } else {
+            BssType::Infrastructure
+        };
+        // Triplet channel numbers repeat across bands, so only trust them for the AP's band
+        let max_power_dbm = self
+            .country_channels
+            .iter()
+            .filter(|&&(first, last, _)| (first..=last).contains(&channel))
+            .filter(|&&(first, _, _)| (first > 14) == (frequency_mhz > 4000))
+            .map(|&(_, _, power)| power)
+            .max();
+        let regulatory = ApRegulatory {
+            country: self.country,
+            max_power_dbm,
+            power_constraint_db: self.power_constraint_db,
+            tx_power_dbm: self.tpc_power_dbm,
+        };
+
+        // Mixed-mode APs that also offer CCMP are fine; only flag TKIP with nothing else
+        let tkip_only =
+            !self.pairwise_ciphers.is_empty() && self.pairwise_ciphers.iter().all(|c| c == "TKIP");
 
         Some(AccessPoint {
             bssid: self.bssid,
-            ssid: self.ssid.unwrap_or_default(),
+            // Mesh points advertise a wildcard SSID and name the mesh separately
+            ssid: self.ssid.filter(|s| !s.is_empty()).or(self.mesh_id).unwrap_or_default(),
             signal_dbm,
             channel,
             frequency_mhz,
+            security: Some(security),
+            channel_width_mhz: self.vht_width.or(self.ht_width),
+            mld_address: self.mld_address,
+            bss_type,
+            beacon_interval_tu: self.beacon_interval_tu,
+            station_count: self.station_count,
+            channel_utilization: self.channel_utilization,
+            regulatory: (regulatory != ApRegulatory::default()).then_some(regulatory),
+            wps: self.wps,
+            tkip_only,
+            roaming: self.roaming,
+            last_seen_ms: self.last_seen_ms,
         })
     }
 }
 
 /// Convert frequency to channel number
-fn freq_to_channel(freq_mhz: u32) -> u32 {
+pub fn freq_to_channel(freq_mhz: u32) -> u32 {
     match freq_mhz {
         // 2.4 GHz
         2412 => 1,
@@ -193,13 +796,13 @@ fn freq_to_channel(freq_mhz: u32) -> u32 {
         _ => {
             if freq_mhz < 3000 {
                 // 2.4 GHz
-                ((freq_mhz - 2407) / 5) as u32
+                (freq_mhz - 2407) / 5
             } else if freq_mhz < 5900 {
                 // 5 GHz
-                ((freq_mhz - 5000) / 5) as u32
+                (freq_mhz - 5000) / 5
             } else {
                 // 6 GHz
-                ((freq_mhz - 5950) / 5) as u32
+                (freq_mhz - 5950) / 5
             }
         }
     }
@@ -243,6 +846,291 @@ BSS 11:22:33:44:55:66(on wlan0)
         assert_eq!(aps[1].channel, 6);
     }
 
+    #[test]
+    fn test_parse_security_and_width() {
+        let output = r#"BSS aa:bb:cc:dd:ee:ff(on wlan0)
+	freq: 5180
+	capability: ESS Privacy ShortSlotTime (0x0411)
+	signal: -45.00 dBm
+	SSID: Secure
+	RSN:	 * Version: 1
+		 * Group cipher: CCMP
+		 * Pairwise ciphers: CCMP
+		 * Authentication suites: PSK SAE
+	HT operation:
+		 * primary channel: 36
+		 * secondary channel offset: above
+	VHT operation:
+		 * channel width: 1 (80 MHz)
+BSS 11:22:33:44:55:66(on wlan0)
+	freq: 2437
+	capability: ESS ShortSlotTime (0x0401)
+	signal: -67.00 dBm
+	SSID: Cafe
+	HT operation:
+		 * primary channel: 6
+		 * secondary channel offset: no secondary
+BSS 22:33:44:55:66:77(on wlan0)
+	freq: 2412
+	capability: ESS Privacy ShortSlotTime (0x0411)
+	signal: -70.00 dBm
+	SSID: Legacy
+	WPA:	 * Version: 1
+		 * Group cipher: TKIP
+		 * Pairwise ciphers: TKIP
+		 * Authentication suites: PSK
+"#;
+        let aps = parse_scan_output(output);
+        assert_eq!(aps[0].security, Some(Security::Wpa3));
+        assert_eq!(aps[0].channel_width_mhz, Some(80));
+        assert!(!aps[0].is_weak());
+        assert_eq!(aps[1].security, Some(Security::Open));
+        assert_eq!(aps[1].channel_width_mhz, Some(20));
+        assert_eq!(aps[1].weaknesses(), ["open"]);
+        assert_eq!(aps[2].security, Some(Security::Wpa));
+        assert!(aps[2].tkip_only);
+    }
+
+    #[test]
+    fn test_parse_multi_link() {
+        let output = r#"BSS aa:bb:cc:dd:ee:01(on wlan0)
+	freq: 5955
+	signal: -52.00 dBm
+	SSID: Wifi7
+	Unknown Extension ID (107): b0 01 0d aa bb cc dd ee 00 01 02 00 00 00 00
+BSS aa:bb:cc:dd:ee:02(on wlan0)
+	freq: 5180
+	signal: -58.00 dBm
+	SSID: Wifi7
+	Unknown Extension ID (107): b0 01 0d aa bb cc dd ee 00 00 02 00 00 00 00
+BSS 11:22:33:44:55:66(on wlan0)
+	freq: 2437
+	signal: -67.00 dBm
+	SSID: Legacy
+	Unknown Extension ID (107): b4 01 0d
+"#;
+        let aps = parse_scan_output(output);
+        assert_eq!(aps[0].mld_address.as_deref(), Some("AA:BB:CC:DD:EE:00"));
+        assert_eq!(aps[1].mld_address, aps[0].mld_address);
+        assert_eq!(aps[2].mld_address, None);
+        assert_eq!(parse_multi_link("b4 00 0d aa bb cc dd ee 00"), None);
+    }
+
+    #[test]
+    fn test_parse_regulatory() {
+        let output = r#"BSS 3c:84:6a:12:34:56(on wlan0)
+	freq: 5180
+	signal: -48.00 dBm
+	Country: DE	Environment: Indoor/Outdoor
+		Channels [1 - 13] @ 20 dBm
+		Channels [36 - 48] @ 23 dBm
+		Channels [52 - 64] @ 20 dBm
+	Power constraint: 3 dB
+	TPC report: TX power: 17 dBm
+BSS 3c:84:6a:12:34:57(on wlan0)
+	freq: 2437
+	signal: -61.00 dBm
+"#;
+        let aps = parse_scan_output(output);
+        let reg = aps[0].regulatory.as_ref().unwrap();
+        assert_eq!(reg.country.as_deref(), Some("DE"));
+        assert_eq!(reg.max_power_dbm, Some(23));
+        assert_eq!(reg.tx_power_dbm, Some(17));
+        assert_eq!(reg.local_max_dbm(), Some(20));
+        assert!(reg.mismatches("US") && !reg.mismatches("de"));
+        assert_eq!(aps[1].regulatory, None);
+    }
+
+    #[test]
+    fn test_parse_roaming() {
+        let output = r#"BSS 3c:84:6a:12:34:56(on wlan0)
+	freq: 5180
+	signal: -48.00 dBm
+	RSN:	 * Version: 1
+		 * Authentication suites: FT/IEEE 802.1X IEEE 802.1X
+	Extended capabilities:
+		 * BSS Transition
+BSS 3c:84:6a:12:34:57(on wlan0)
+	freq: 2437
+	signal: -61.00 dBm
+	RM enabled capabilities:
+		Capabilities: 0x73 0x10 0x91 0x00 0x04
+	Extended capabilities:
+		 * Extended Channel Switching
+"#;
+        let aps = parse_scan_output(output);
+        assert_eq!(aps[0].roaming, Roaming { k: false, v: true, r: true });
+        assert_eq!(aps[1].roaming.label(), "k--");
+    }
+
+    #[test]
+    fn test_parse_bss_load() {
+        assert_eq!(parse_utilization("34/255"), Some(13));
+        assert_eq!(parse_utilization("255/255"), Some(100));
+        assert_eq!(parse_utilization("0/255"), Some(0));
+        assert_eq!(parse_utilization("unknown"), None);
+    }
+
+    /// One line per AP, as stored in the `.expected` golden files
+    fn golden_line(ap: &AccessPoint) -> String {
+        format!(
+            "{} {} ch{} {} {} {} {} {} {} {} {} {:?}",
+            ap.bssid,
+            ap.frequency_mhz,
+            ap.channel,
+            ap.signal_dbm,
+            ap.security.map(|s| s.short_name()).unwrap_or("-"),
+            ap.channel_width_mhz.map(|w| w.to_string()).unwrap_or("-".to_string()),
+            ap.bss_type.short_name(),
+            ap.beacon_interval_tu.map(|b| format!("{}TU", b)).unwrap_or("-".to_string()),
+            ap.channel_utilization
+                .map(|u| format!("{}%/{}sta", u, ap.station_count.unwrap_or(0)))
+                .unwrap_or("-".to_string()),
+            match ap.weaknesses() {
+                weaknesses if weaknesses.is_empty() => "-".to_string(),
+                weaknesses => weaknesses.join(",").replace(' ', "-"),
+            },
+            ap.roaming.label(),
+            ap.ssid
+        )
+    }
+
+    /// Every `tests/fixtures/iw/*.txt` dump must parse to its `.expected` file
+    #[test]
+    fn test_parse_fixtures() {
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/iw");
+        let mut checked = 0;
+        for entry in std::fs::read_dir(&dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().is_none_or(|e| e != "txt") {
+                continue;
+            }
+            let dump = std::fs::read_to_string(&path).unwrap();
+            let expected = std::fs::read_to_string(path.with_extension("expected")).unwrap();
+            let actual: Vec<String> = parse_scan_output(&dump).iter().map(golden_line).collect();
+            assert_eq!(
+                actual,
+                expected.lines().collect::<Vec<_>>(),
+                "fixture {}",
+                path.display()
+            );
+            checked += 1;
+        }
+        assert!(checked > 0, "no fixtures in {}", dir.display());
+    }
+
+    #[test]
+    fn test_parse_signal_and_ssid() {
+        assert_eq!(parse_signal(" -45.00 dBm"), Some(-45));
+        assert_eq!(parse_signal(" 100/100"), Some(-50));
+        assert_eq!(parse_signal(" 0/0"), None);
+        assert_eq!(unescape_ssid("Caf\\xc3\\xa9"), "Café");
+        assert_eq!(unescape_ssid("a\\x5cb"), "a\\b");
+        assert_eq!(unescape_ssid("\\x00\\x00"), "");
+        assert_eq!(unescape_ssid("bad\\xzz"), "bad\\xzz");
+    }
+
+    #[test]
+    fn test_scan_args() {
+        assert_eq!(scan_args("wlan0", &[]), ["dev", "wlan0", "scan", "-u"]);
+        let probes = vec!["Hidden".to_string(), "Lab Net".to_string()];
+        assert_eq!(
+            scan_args("wlan0", &probes),
+            ["dev", "wlan0", "scan", "-u", "ssid", "Hidden", "Lab Net", ""]
+        );
+    }
+
+    #[test]
+    fn test_parse_link_output() {
+        let output = r#"Connected to aa:bb:cc:dd:ee:ff (on wlan0)
+	SSID: MyNetwork
+	freq: 5180
+	signal: -45 dBm
+	rx bitrate: 780.0 MBit/s VHT-MCS 8 80MHz short GI VHT-NSS 2
+	tx bitrate: 866.7 MBit/s VHT-MCS 9 80MHz short GI VHT-NSS 2
+"#;
+        let link = parse_link_output(output).unwrap();
+        assert_eq!(link.bssid, "AA:BB:CC:DD:EE:FF");
+        assert_eq!(link.ssid, "MyNetwork");
+        assert_eq!(link.signal_dbm, Some(-45));
+        assert_eq!(link.tx_bitrate_mbps, Some(866.7));
+        assert!(parse_link_output("Not connected.\n").is_none());
+    }
+
+    #[test]
+    fn test_parse_radio_context() {
+        let info = "Interface wlan0\n\tifindex 3\n\ttype managed\n\twiphy 1\n\
+                    \tchannel 36 (5180 MHz), width: 80 MHz\n\ttxpower 22.00 dBm\n";
+        assert_eq!(parse_interface_info(info), (Some(22.0), Some(1)));
+
+        let reg = "global\ncountry US: DFS-FCC\n\t(2400 - 2472 @ 40), (N/A, 30), (N/A)\n\n\
+                   phy#1 (self-managed)\ncountry DE: DFS-ETSI\n\t(2400 - 2483 @ 40), (N/A, 20)\n";
+        assert_eq!(parse_reg_country(reg, Some(1)).as_deref(), Some("DE"));
+        assert_eq!(parse_reg_country(reg, Some(0)).as_deref(), Some("US"));
+        let unset = parse_reg_country("global\ncountry 00: DFS-UNSET\n", None);
+        assert_eq!(unset.as_deref(), Some("00"));
+    }
+
+    #[test]
+    fn test_parse_survey_dump() {
+        let output = r#"Survey data from wlan0
+	frequency:			2412 MHz
+	noise:				-95 dBm
+	channel active time:		38 ms
+	channel busy time:		5 ms
+Survey data from wlan0
+	frequency:			5180 MHz [in use]
+	noise:				-92 dBm
+	channel active time:		110 ms
+Survey data from wlan0
+	frequency:			5955 MHz
+	channel active time:		0 ms
+"#;
+        let dwell = parse_survey_dump(output);
+        assert_eq!(
+            dwell,
+            vec![
+                ChannelDwell {
+                    frequency_mhz: 2412,
+                    active_ms: 38,
+                    noise_dbm: Some(-95),
+                    busy_ms: Some(5),
+                },
+                ChannelDwell {
+                    frequency_mhz: 5180,
+                    active_ms: 110,
+                    noise_dbm: Some(-92),
+                    busy_ms: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dwell_since() {
+        let dump = |active_2g: u64, busy_2g: u64, active_5g: u64| {
+            parse_survey_dump(&format!(
+                "Survey data from wlan0\n\tfrequency:\t2412 MHz\n\
+                 \tchannel active time:\t{} ms\n\tchannel busy time:\t{} ms\n\
+                 Survey data from wlan0\n\tfrequency:\t5180 MHz\n\
+                 \tchannel active time:\t{} ms\n",
+                active_2g, busy_2g, active_5g
+            ))
+        };
+        // Counters keep growing between scans: each scan gets only its own share
+        let first = dump(1000, 300, 5000);
+        let second = dump(1040, 310, 5000);
+        let dwell = dwell_since(&first, second.clone());
+        assert_eq!(dwell.len(), 1);
+        assert_eq!((dwell[0].frequency_mhz, dwell[0].active_ms), (2412, 40));
+        assert_eq!(dwell[0].busy_ms, Some(10));
+        // A counter the scan reset counts in full
+        let dwell = dwell_since(&second, dump(30, 2, 5020));
+        let active: Vec<_> = dwell.iter().map(|d| (d.frequency_mhz, d.active_ms)).collect();
+        assert_eq!(active, vec![(2412, 30), (5180, 20)]);
+        assert_eq!(dwell_since(&[], first.clone()), first);
+    }
+
     #[test]
     fn test_freq_to_channel() {
         assert_eq!(freq_to_channel(2412), 1);
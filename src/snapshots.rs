@@ -0,0 +1,204 @@
+//! Golden-snapshot tests: screens rendered from fixture sessions into a
+//! `TestBackend`, compared with the text files in `tests/snapshots/`.
+//!
+//! After an intended layout change, regenerate with
+//! `UPDATE_SNAPSHOTS=1 cargo test snapshots` and review the diff.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use chrono::{Local, TimeZone};
+use ratatui::{backend::TestBackend, Terminal};
+
+use crate::app::{App, Popup, Screen};
+use crate::data::{load_session, ApColumn, DataPaths, Session, SortBy};
+use crate::scanner::ScanBackend;
+use crate::ui::buffer_text;
+
+/// Set to rewrite snapshots from the current rendering
+const UPDATE_ENV: &str = "UPDATE_SNAPSHOTS";
+
+fn tests_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests")
+}
+
+fn fixture(name: &str) -> Session {
+    let path = tests_dir().join("fixtures/sessions").join(format!("{}.json", name));
+    load_session(&path).unwrap()
+}
+
+/// App with default settings and no hardware
+fn app() -> App {
+    let dir = std::env::temp_dir().join(format!("wificomp-snapshots-{}", std::process::id()));
+    let mut app = App::new(DataPaths::in_dir(&dir), ScanBackend::Demo).unwrap();
+    app.clock = || Local.with_ymd_and_hms(2026, 3, 14, 9, 26, 0).unwrap();
+    app
+}
+
+/// Live screen after replaying every scan of a fixture
+fn live_app() -> App {
+    let mut app = app();
+    let session = fixture("demo0");
+    let scans = session.scans.len();
+    app.start_replay(session, 1.0);
+    for _ in 0..scans {
+        app.perform_scan();
+    }
+    app
+}
+
+fn history_app() -> App {
+    let mut app = app();
+    app.screen = Screen::History;
+    app.history.session = Some(Arc::new(fixture("demo0")));
+    app
+}
+
+fn compare_app() -> App {
+    let mut app = app();
+    app.screen = Screen::Compare;
+    app.compare.add_session(Arc::new(fixture("demo0")));
+    app.compare.add_session(Arc::new(fixture("demo1")));
+    app
+}
+
+fn render(app: &App, width: u16, height: u16) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+    terminal.draw(|f| super::draw(f, app)).unwrap();
+    buffer_text(terminal.backend().buffer())
+}
+
+fn assert_snapshot(app: &App, name: &str, width: u16, height: u16) {
+    let actual = render(app, width, height);
+    let path = tests_dir()
+        .join("snapshots")
+        .join(format!("{}_{}x{}.txt", name, width, height));
+
+    if std::env::var_os(UPDATE_ENV).is_some() {
+        std::fs::write(&path, &actual).unwrap();
+        return;
+    }
+    let expected = std::fs::read_to_string(&path).unwrap_or_else(|_| {
+        panic!("missing {}; run with {}=1 to create it", path.display(), UPDATE_ENV)
+    });
+    assert_eq!(
+        actual,
+        expected,
+        "{} changed; if intended, rerun with {}=1",
+        path.display(),
+        UPDATE_ENV
+    );
+}
+
+#[test]
+fn test_live_snapshot() {
+    let app = live_app();
+    assert_snapshot(&app, "live", 100, 30);
+    assert_snapshot(&app, "live", 60, 15);
+    assert_snapshot(&app, "live", 40, 12);
+    assert_snapshot(&app, "live", 32, 12);
+}
+
+#[test]
+fn test_live_split_snapshot() {
+    let mut app = live_app();
+    app.live.toggle_split();
+    assert_snapshot(&app, "live_split", 100, 30);
+}
+
+#[test]
+fn test_live_columns_snapshot() {
+    let mut app = live_app();
+    for column in [ApColumn::Type, ApColumn::Band] {
+        app.live.toggle_column(column);
+    }
+    for column in [ApColumn::Bssid, ApColumn::Snr, ApColumn::Security, ApColumn::Vendor] {
+        app.live.toggle_column(column);
+    }
+    app.live.move_column(ApColumn::Bssid, -5);
+    app.live.resize_column(ApColumn::Signal, -6);
+    assert_snapshot(&app, "live_columns", 100, 30);
+    app.popup = Popup::Columns { selected: 1 };
+    assert_snapshot(&app, "live_columns_popup", 100, 30);
+}
+
+/// Band first, strongest last within each band
+#[test]
+fn test_live_sort_snapshot() {
+    let mut app = live_app();
+    app.live.sort.toggle(SortBy::Band);
+    app.live.sort.move_key(SortBy::Band, -1);
+    app.live.sort.toggle_reverse(SortBy::Signal);
+    app.popup = Popup::Sort { selected: 1 };
+    assert_snapshot(&app, "live_sort_popup", 100, 30);
+}
+
+/// Columns that don't fit scroll, keeping the SSID in place
+#[test]
+fn test_live_bssid_snapshot() {
+    let mut app = live_app();
+    app.live.toggle_bssid();
+    app.live.toggle_column(ApColumn::Vendor);
+    assert_snapshot(&app, "live_bssid", 40, 12);
+    app.live.scroll_columns(1, 40);
+    assert_snapshot(&app, "live_bssid_scrolled", 40, 12);
+}
+
+#[test]
+fn test_history_snapshot() {
+    let app = history_app();
+    assert_snapshot(&app, "history", 100, 30);
+    assert_snapshot(&app, "history", 60, 15);
+    assert_snapshot(&app, "history", 40, 12);
+}
+
+#[test]
+fn test_compare_snapshot() {
+    let app = compare_app();
+    assert_snapshot(&app, "compare", 120, 40);
+    assert_snapshot(&app, "compare", 80, 24);
+    assert_snapshot(&app, "compare", 60, 15);
+    assert_snapshot(&app, "compare", 40, 30);
+}
+
+#[test]
+fn test_compare_aligned_snapshot() {
+    let mut app = compare_app();
+    app.compare.toggle_aligned();
+    assert_snapshot(&app, "compare_aligned", 120, 40);
+}
+
+#[test]
+fn test_channels_snapshot() {
+    let mut app = live_app();
+    app.screen = Screen::Channels;
+    assert_snapshot(&app, "channels", 100, 30);
+    assert_snapshot(&app, "channels", 60, 15);
+}
+
+/// A long warning wraps and scrolls instead of being cut off
+#[test]
+fn test_warning_snapshot() {
+    let mut app = history_app();
+    let warnings: Vec<String> = (1..=12)
+        .map(|i| format!("Scan {} has a timestamp earlier than the scan before it", i))
+        .collect();
+    app.popup = Popup::SessionWarning {
+        message: format!("Session loaded with warnings:\n{}", warnings.join("\n")),
+        path: PathBuf::new(),
+        scroll: 3,
+    };
+    assert_snapshot(&app, "warning", 80, 24);
+}
+
+/// Every screen renders at odd and tiny sizes without panicking
+#[test]
+fn test_render_any_size() {
+    for app in [live_app(), history_app(), compare_app()] {
+        for width in (1..=160).step_by(7) {
+            for height in (1..=50).step_by(4) {
+                render(&app, width, height);
+            }
+        }
+    }
+}
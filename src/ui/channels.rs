@@ -0,0 +1,265 @@
+use std::cell::RefCell;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Widget},
+};
+
+use crate::data::{Band, ChannelStats, Session};
+use crate::scanner::freq_to_channel;
+use crate::ui::widgets::{Hint, HintBar, SpectrumPlot};
+use crate::ui::COMPACT_WIDTH;
+use crate::utils::truncate;
+
+/// Screen height from which the spectrum plot is shown above the table
+const SPECTRUM_MIN_HEIGHT: u16 = 20;
+
+/// Channel analyzer screen state
+#[derive(Debug, Default)]
+pub struct ChannelsState {
+    pub selected: usize,
+    /// Channel stats reused across frames and key presses until the next scan
+    stats: StatsCache,
+}
+
+/// Session start, scan count and latest scan time the cached stats were computed for
+type StatsKey = (DateTime<Utc>, usize, Option<DateTime<Utc>>);
+
+/// Last channel stats computed, behind a cell so rendering through `&ChannelsState` can
+/// fill it
+#[derive(Debug, Default)]
+struct StatsCache(RefCell<Option<(StatsKey, Arc<Vec<ChannelStats>>)>>);
+
+impl ChannelsState {
+    /// Per-channel stats of `session`, recomputed only once it has scanned again
+    pub fn stats(&self, session: &Session) -> Arc<Vec<ChannelStats>> {
+        let latest = session.scans.last().map(|scan| scan.timestamp);
+        let key = (session.started_at, session.scans.len(), latest);
+        let mut cache = self.stats.0.borrow_mut();
+        match cache.as_ref().filter(|(k, _)| *k == key) {
+            Some((_, stats)) => Arc::clone(stats),
+            None => Arc::clone(&cache.insert((key, Arc::new(session.channel_stats()))).1),
+        }
+    }
+
+    pub fn select_next(&mut self, len: usize) {
+        if len > 0 {
+            self.selected = (self.selected + 1).min(len - 1);
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+}
+
+/// Channel analyzer screen widget, over the current live session
+pub struct ChannelsScreen<'a> {
+    state: &'a ChannelsState,
+    session: Option<&'a Session>,
+}
+
+impl<'a> ChannelsScreen<'a> {
+    pub fn new(state: &'a ChannelsState, session: Option<&'a Session>) -> Self {
+        Self { state, session }
+    }
+}
+
+impl<'a> Widget for ChannelsScreen<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let spectrum_height = if area.height >= SPECTRUM_MIN_HEIGHT { area.height / 2 } else { 0 };
+        let chunks = Layout::vertical([
+            Constraint::Length(2),               // Header
+            Constraint::Length(spectrum_height), // Spectrum plot
+            Constraint::Min(5),                  // Channel table
+            Constraint::Length(2),               // Footer
+        ])
+        .split(area);
+
+        let stats = self.session.map(|s| self.state.stats(s)).unwrap_or_default();
+
+        self.render_header(chunks[0], buf, &stats);
+        if spectrum_height > 0 {
+            self.render_spectrum(chunks[1], buf, &stats);
+        }
+        self.render_table(chunks[2], buf, &stats);
+        self.render_footer(chunks[3], buf, &stats);
+    }
+}
+
+impl<'a> ChannelsScreen<'a> {
+    fn render_header(&self, area: Rect, buf: &mut Buffer, stats: &[ChannelStats]) {
+        let block = Block::default().borders(Borders::TOP | Borders::LEFT | Borders::RIGHT);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let info = match self.session {
+            Some(session) => {
+                let suspects = stats
+                    .iter()
+                    .filter(|c| !c.interference_hints().is_empty())
+                    .count();
+                format!(
+                    "{} | {} channels | {} suspect",
+                    session.adapter.display_name(),
+                    stats.len(),
+                    suspects
+                )
+            }
+            None => "No live session".to_string(),
+        };
+        buf.set_string(inner.x, inner.y, truncate(&info, inner.width as usize), Style::default());
+    }
+
+    /// APs of the latest scan over the band of the selected channel
+    fn render_spectrum(&self, area: Rect, buf: &mut Buffer, stats: &[ChannelStats]) {
+        let selected = stats.get(self.state.selected.min(stats.len().saturating_sub(1)));
+        let frequency = selected.map(|c| c.frequency_mhz);
+        let band = frequency.map(Band::from_frequency).unwrap_or(Band::TwoPointFourGHz);
+        let block = Block::default()
+            .borders(Borders::LEFT | Borders::RIGHT)
+            .title(format!(" Spectrum {} ", band.short_name()));
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let aps = self
+            .session
+            .and_then(|s| s.scans.last())
+            .map(|scan| &scan.access_points[..])
+            .unwrap_or_default();
+        SpectrumPlot::new(aps, band).marker(frequency).render(inner, buf);
+    }
+
+    fn render_table(&self, area: Rect, buf: &mut Buffer, stats: &[ChannelStats]) {
+        let block = Block::default()
+            .borders(Borders::LEFT | Borders::RIGHT)
+            .title(" Channels ");
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        if inner.height < 2 {
+            return;
+        }
+
+        // Narrow terminals drop the variance and noise columns
+        let compact = area.width < COMPACT_WIDTH;
+        let header = table_row(compact, ["CH", "Band", "APs", "Var", "Noise", "Busy", "Hint"]);
+        buf.set_string(
+            inner.x,
+            inner.y,
+            &header,
+            Style::default().add_modifier(Modifier::BOLD),
+        );
+
+        if stats.is_empty() {
+            buf.set_string(
+                inner.x,
+                inner.y + 1,
+                "No scans yet",
+                Style::default().fg(Color::DarkGray),
+            );
+            return;
+        }
+
+        let visible = (inner.height - 1) as usize;
+        let selected = self.state.selected.min(stats.len() - 1);
+        let offset = (selected + 1).saturating_sub(visible);
+
+        for (row, (i, channel)) in stats
+            .iter()
+            .enumerate()
+            .skip(offset)
+            .take(visible)
+            .enumerate()
+        {
+            let hints = channel.interference_hints();
+            let cells = [
+                freq_to_channel(channel.frequency_mhz).to_string(),
+                Band::from_frequency(channel.frequency_mhz).short_name().to_string(),
+                channel.ap_count.to_string(),
+                channel
+                    .signal_stddev
+                    .map(|s| format!("{:.1}", s))
+                    .unwrap_or_else(|| "-".to_string()),
+                channel
+                    .noise_dbm
+                    .map(|n| n.to_string())
+                    .unwrap_or_else(|| "-".to_string()),
+                channel
+                    .busy_ratio()
+                    .map(|r| format!("{:.0}%", r * 100.0))
+                    .unwrap_or_else(|| "-".to_string()),
+                if hints.is_empty() {
+                    String::new()
+                } else {
+                    format!("⚠ {}", hints.join(","))
+                },
+            ];
+            let line = table_row(compact, cells.each_ref().map(String::as_str));
+
+            let mut style = if hints.is_empty() {
+                Style::default()
+            } else {
+                Style::default().fg(Color::Yellow)
+            };
+            if i == selected {
+                style = style.add_modifier(Modifier::REVERSED);
+            }
+            let width = inner.width as usize;
+            let line: String = format!("{:<width$}", line).chars().take(width).collect();
+            buf.set_string(inner.x, inner.y + 1 + row as u16, &line, style);
+        }
+    }
+
+    fn render_footer(&self, area: Rect, buf: &mut Buffer, stats: &[ChannelStats]) {
+        let block = Block::default().borders(Borders::BOTTOM | Borders::LEFT | Borders::RIGHT);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let legend = if area.width >= COMPACT_WIDTH {
+            "⚠ = possible non-WiFi interference"
+        } else {
+            "⚠ = interference"
+        };
+        let hints = [
+            Hint::new("[↑↓]sel").enabled(stats.len() > 1),
+            Hint::new(legend),
+            Hint::new("[q]uit"),
+        ];
+        HintBar::new(&hints).render(inner, buf);
+    }
+}
+
+/// One table line: CH, Band, APs, Var, Noise, Busy, Hint
+fn table_row(compact: bool, cells: [&str; 7]) -> String {
+    let [ch, band, aps, var, noise, busy, hint] = cells;
+    if compact {
+        format!("{:>4} {:<4} {:>4} {:>5}  {}", ch, band, aps, busy, hint)
+    } else {
+        format!("{:>4} {:<4} {:>4} {:>5} {:>6} {:>5}  {}", ch, band, aps, var, noise, busy, hint)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::demo::demo_scan;
+    use crate::scanner::ScanBackend;
+
+    #[test]
+    fn test_stats_cache() {
+        let state = ChannelsState::default();
+        let mut session = Session::new(ScanBackend::Demo.find_adapter("demo0").unwrap(), None);
+        session.add_scan(demo_scan("demo0", Utc::now()));
+        let stats = state.stats(&session);
+        assert!(Arc::ptr_eq(&stats, &state.stats(&session)));
+
+        session.add_scan(demo_scan("demo0", Utc::now()));
+        assert!(!Arc::ptr_eq(&stats, &state.stats(&session)));
+        assert_eq!(*state.stats(&session), session.channel_stats());
+    }
+}
@@ -1,3 +1,9 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+
 use ratatui::{
     buffer::Buffer,
     layout::{Constraint, Layout, Rect},
@@ -5,23 +11,118 @@ use ratatui::{
     widgets::{Block, Borders, Widget},
 };
 
-use crate::data::{CompareMetric, MatchBy, Session};
-use crate::ui::widgets::ComparisonBar;
+use crate::data::formula::{Formula, SessionVars};
+use crate::data::outlier::outlier_readings;
+use crate::data::{
+    AccessPoint, Adapter, ApStats, Band, CompareMetric, CompareRows, CompareSort, ConnectTiming,
+    FrequencyFilter, LoadTest, MatchBy, Reachability, SampleWindow, ScanResult, RadioContext,
+    Session, SortOrder,
+};
+use crate::ui::widgets::{
+    AlignedChart, AlignedSeries, CheckpointTable, ComparisonBar, Hint, HintBar,
+};
+use crate::ui::{COMPACT_WIDTH, NARROW_WIDTH};
 use crate::utils::truncate;
 
 /// Compare screen state
 #[derive(Debug, Default)]
 pub struct CompareState {
-    pub sessions: Vec<Session>,
+    /// Shared with History when a session is open in both
+    pub sessions: Vec<Arc<Session>>,
     pub selected_session_idx: usize,
     pub session_list_offset: usize,
     pub selected_ap_idx: usize,
     pub match_by: MatchBy,
     pub metric: CompareMetric,
+    /// Formulas from `custom_metrics`, cycled after the built-in metrics
+    pub custom_metrics: Vec<(String, Formula)>,
+    /// Index of the custom metric in use, overriding `metric`
+    pub custom_metric: Option<usize>,
+    /// Show per-band rows when matching co-located radios as a group
+    pub band_breakdown: bool,
+    /// Normalize by channel dwell time (adapters may scan some bands slower)
+    pub normalize: bool,
+    /// Apply per-adapter calibration offsets to displayed values
+    pub calibrated: bool,
+    /// Leave Hampel-filter outliers out of each AP's readings
+    pub drop_outliers: bool,
+    /// Calibration offsets in dB, keyed by adapter display name
+    pub offsets: HashMap<String, i32>,
+    /// Which scans of each session are compared
+    pub window: SampleWindow,
+    /// Scan-count ratio above which a warning is shown
+    pub count_ratio: f64,
+    /// Show signal per elapsed-time bucket instead of one bar per adapter
+    pub aligned: bool,
+    /// Number of elapsed-time buckets in aligned mode
+    pub buckets: usize,
+    /// Show the selected AP at each walk-test checkpoint instead of one bar per adapter
+    pub checkpoints: bool,
+    /// Only list APs seen on this band
+    pub band_filter: FrequencyFilter,
+    /// Only list APs some session saw at least this many times
+    pub min_samples: usize,
+    /// AP list sort keys; none keeps first-seen order
+    pub sort: SortOrder<CompareSort>,
+    /// What each comparison row pools: one session, a node's, or an adapter firmware's
+    pub rows: CompareRows,
+    /// Per-AP aggregates reused across frames
+    pub aggregates: AggregateCache,
+}
+
+/// Last `Aggregates` computed, behind a cell so rendering through `&CompareState` can fill it
+#[derive(Debug, Default)]
+pub struct AggregateCache(RefCell<Option<Arc<Aggregates>>>);
+
+/// Width of the AP list pane beside the comparison bars
+const AP_PANE_WIDTH: u16 = 30;
+/// Sessions shown as presence dots in the AP pane
+const MAX_PRESENCE_DOTS: usize = 8;
+/// Comparison area height at which the AP list stacks above the bars
+const STACKED_MIN_HEIGHT: u16 = 12;
+
+/// Presets cycled by the minimum-sample filter
+const MIN_SAMPLE_STEPS: [usize; 5] = [1, 3, 5, 10, 20];
+
+/// Bucket count limits for aligned mode
+const MIN_BUCKETS: usize = 2;
+const MAX_BUCKETS: usize = 60;
+/// Width of the signal buckets link rates are averaged over
+const EFFICIENCY_BUCKET_DB: i32 = 10;
+
+/// An adapter's connect tests, averaged
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConnectSummary {
+    pub association_ms: u64,
+    pub handshake_ms: u64,
+    pub dhcp_ms: u64,
+    pub tests: usize,
+    /// Tests whose reachability check got through
+    pub online: usize,
+    /// Tests that landed on a captive portal
+    pub portal: usize,
+}
+
+/// An adapter's load tests: overall ping loss and the median of their RTT percentiles
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadSummary {
+    pub loss_pct: f32,
+    pub rtt_p50_ms: Option<f32>,
+    pub rtt_p95_ms: Option<f32>,
+    pub rtt_p99_ms: Option<f32>,
+}
+
+/// Rows for the session list in a Compare screen of the given height
+pub fn session_list_height(area_height: u16) -> u16 {
+    match area_height {
+        h if h > 20 => 6,
+        h if h >= 16 => 4,
+        _ => 2,
+    }
 }
 
 impl CompareState {
-    pub fn add_session(&mut self, session: Session) {
+    pub fn add_session(&mut self, session: Arc<Session>) {
         self.sessions.push(session);
     }
 
@@ -76,27 +177,545 @@ impl CompareState {
     }
 
     pub fn cycle_metric(&mut self) {
-        self.metric = self.metric.next();
+        match self.custom_metric {
+            Some(idx) if idx + 1 < self.custom_metrics.len() => self.custom_metric = Some(idx + 1),
+            Some(_) => {
+                self.custom_metric = None;
+                self.metric = CompareMetric::Avg;
+            }
+            None if self.metric == CompareMetric::Max && !self.custom_metrics.is_empty() => {
+                self.custom_metric = Some(0);
+            }
+            None => self.metric = self.metric.next(),
+        }
+    }
+
+    pub fn metric_name(&self) -> &str {
+        match self.custom_metric.and_then(|idx| self.custom_metrics.get(idx)) {
+            Some((name, _)) => name,
+            None => self.metric.name(),
+        }
+    }
+
+    /// The selected metric for one set of stats from `sessions`, rounded to whole dB
+    /// for custom formulas
+    fn metric_value(&self, stats: &ApStats, sessions: &[usize]) -> Option<i32> {
+        match self.custom_metric.and_then(|idx| self.custom_metrics.get(idx)) {
+            Some((_, formula)) => {
+                formula.eval(stats, &self.session_vars(sessions)).map(|v| v.round() as i32)
+            }
+            None => Some(stats.get(self.metric)),
+        }
+    }
+
+    /// What a custom formula sees of `sessions` as `session`
+    fn session_vars(&self, sessions: &[usize]) -> SessionVars {
+        let mut vars = SessionVars::default();
+        for session in sessions.iter().map(|&idx| &self.sessions[idx]) {
+            if vars.adapter.is_empty() {
+                vars.adapter = session.adapter.display_name();
+                vars.node = session.node.clone().unwrap_or_default();
+            }
+            let scans = self.window_scans(session);
+            vars.scans += scans.len();
+            if let (Some(first), Some(last)) = (scans.first(), scans.last()) {
+                vars.minutes += (last.timestamp - first.timestamp).num_seconds() as f64 / 60.0;
+            }
+        }
+        vars
+    }
+
+    pub fn toggle_calibrated(&mut self) {
+        self.calibrated = !self.calibrated;
+    }
+
+    pub fn toggle_outliers(&mut self) {
+        self.drop_outliers = !self.drop_outliers;
+    }
+
+    /// Calibration offset for a session's adapter (0 when calibration is off)
+    pub fn offset_for(&self, session: &Session) -> i32 {
+        if !self.calibrated {
+            return 0;
+        }
+        self.offsets
+            .get(&session.adapter.display_name())
+            .copied()
+            .unwrap_or(0)
+    }
+
+    pub fn toggle_normalize(&mut self) {
+        self.normalize = !self.normalize;
+    }
+
+    pub fn toggle_band_breakdown(&mut self) {
+        self.band_breakdown = !self.band_breakdown;
+    }
+
+    pub fn cycle_window(&mut self) {
+        self.window = self.window.next();
+    }
+
+    pub fn cycle_band_filter(&mut self) {
+        self.band_filter = self.band_filter.next();
+        self.selected_ap_idx = 0;
+    }
+
+    pub fn cycle_min_samples(&mut self) {
+        self.min_samples = MIN_SAMPLE_STEPS
+            .into_iter()
+            .find(|&n| n > self.min_samples)
+            .unwrap_or(MIN_SAMPLE_STEPS[0]);
+        self.selected_ap_idx = 0;
+    }
+
+    /// Switch between first-seen order and largest difference first
+    pub fn toggle_sort_by_delta(&mut self) {
+        self.sort = if self.sort.primary() == Some(CompareSort::Delta) {
+            SortOrder::default()
+        } else {
+            SortOrder::by_delta()
+        };
+        self.selected_ap_idx = 0;
+    }
+
+    /// Next row grouping, skipping nodes when no session names one
+    pub fn cycle_rows(&mut self) {
+        self.rows = self.rows.next();
+        if self.rows == CompareRows::Node && !self.has_nodes() {
+            self.rows = self.rows.next();
+        }
+    }
+
+    /// Whether any loaded session was scanned at a named node
+    pub fn has_nodes(&self) -> bool {
+        self.sessions.iter().any(|s| s.node.is_some())
+    }
+
+    /// Whether any loaded session recorded its adapter's firmware or driver version
+    pub fn has_versions(&self) -> bool {
+        self.sessions.iter().any(|s| s.adapter.version_label().is_some())
+    }
+
+    /// What the comparison rows are: each session, or each node or adapter firmware with
+    /// its sessions (as indices into `sessions`)
+    fn columns(&self) -> Vec<(String, Vec<usize>)> {
+        let group: fn(&Session) -> (String, String) = match self.rows {
+            CompareRows::Session => {
+                let names = self.sessions.iter().map(|s| session_name(s));
+                return names.enumerate().map(|(idx, name)| (name, vec![idx])).collect();
+            }
+            CompareRows::Node => |s| (node_name(s), node_name(s)),
+            CompareRows::Firmware => |s| {
+                let version = s.adapter.version_label().unwrap_or_else(|| "fw ?".to_string());
+                let key = format!(
+                    "{} {:?} {:?}",
+                    adapter_key(&s.adapter),
+                    s.adapter.firmware_version,
+                    s.adapter.driver_version
+                );
+                (key, format!("{} {}", s.adapter.display_name(), version))
+            },
+        };
+        let mut columns: Vec<(String, String, Vec<usize>)> = Vec::new();
+        for (idx, session) in self.sessions.iter().enumerate() {
+            let (key, name) = group(session);
+            match columns.iter_mut().find(|(k, _, _)| *k == key) {
+                Some((_, _, sessions)) => sessions.push(idx),
+                None => columns.push((key, name, vec![idx])),
+            }
+        }
+        columns.into_iter().map(|(_, name, sessions)| (name, sessions)).collect()
+    }
+
+    /// Aggregates of the current sessions, rebuilt only when the sessions, match mode or
+    /// sample window changed since the last call
+    fn aggregates(&self) -> Arc<Aggregates> {
+        let mut cache = self.aggregates.0.borrow_mut();
+        match cache.as_ref().filter(|agg| agg.is_for(self)) {
+            Some(agg) => Arc::clone(agg),
+            None => Arc::clone(cache.insert(Arc::new(Aggregates::new(self)))),
+        }
+    }
+
+    /// Windowed readings of an AP key across sessions, with each session's offset
+    fn readings(&self, agg: &Aggregates, sessions: &[usize], key: &str) -> Vec<(i32, Band)> {
+        sessions
+            .iter()
+            .flat_map(|&idx| {
+                let offset = self.offset_for(&self.sessions[idx]);
+                let readings = agg.per_session[idx].readings.get(key);
+                readings.into_iter().flatten().map(move |&(signal, band)| (signal + offset, band))
+            })
+            .collect()
+    }
+
+    pub fn toggle_aligned(&mut self) {
+        self.aligned = !self.aligned;
+        self.checkpoints &= !self.aligned;
+    }
+
+    pub fn toggle_checkpoints(&mut self) {
+        self.checkpoints = !self.checkpoints && self.has_checkpoints();
+        self.aligned &= !self.checkpoints;
+    }
+
+    /// Whether any loaded session has walk-test checkpoints
+    pub fn has_checkpoints(&self) -> bool {
+        self.sessions.iter().any(|s| !s.checkpoints.is_empty())
+    }
+
+    /// Selected AP's metric at each walk-test checkpoint, per adapter.
+    ///
+    /// Checkpoints are matched by name, so walks of the same route taken one adapter
+    /// at a time line up. Each checkpoint's dwell scans are used whatever the sample
+    /// window. Returns the checkpoint names in walking order and one series per adapter.
+    pub fn checkpoint_table(&self) -> (Vec<String>, Vec<AlignedSeries>) {
+        let Some((sel_bssid, sel_ssid)) = self.get_selected_ap() else {
+            return (Vec::new(), Vec::new());
+        };
+        let mut names: Vec<String> = Vec::new();
+        for checkpoint in self.sessions.iter().flat_map(|s| &s.checkpoints) {
+            if !names.contains(&checkpoint.name) {
+                names.push(checkpoint.name.clone());
+            }
+        }
+        let selected = |ap: &AccessPoint| self.match_by.matches(ap, &sel_bssid, &sel_ssid);
+
+        let series = self
+            .columns()
+            .into_iter()
+            .map(|(column, sessions)| {
+                let values = names
+                    .iter()
+                    .map(|name| {
+                        let mut signals = Vec::new();
+                        for session in sessions.iter().map(|&idx| &self.sessions[idx]) {
+                            let offset = self.offset_for(session);
+                            let dwells = session.checkpoints.iter().filter(|c| &c.name == name);
+                            for scans in dwells.map(|c| c.scans(&session.scans)) {
+                                let outliers = match self.drop_outliers {
+                                    true => outlier_readings(scans, selected),
+                                    false => HashSet::new(),
+                                };
+                                for (idx, scan) in scans.iter().enumerate() {
+                                    signals.extend(
+                                        scan.access_points
+                                            .iter()
+                                            .filter(|ap| selected(ap))
+                                            .filter(|ap| {
+                                                !outliers.contains(&(idx, ap.bssid.clone()))
+                                            })
+                                            .map(|ap| ap.signal_dbm + offset),
+                                    );
+                                }
+                            }
+                        }
+                        self.metric_of(signals, &sessions)
+                    })
+                    .collect();
+                (column, values)
+            })
+            .collect();
+        (names, series)
+    }
+
+    pub fn adjust_buckets(&mut self, more: bool) {
+        self.buckets = if more {
+            self.buckets + 1
+        } else {
+            self.buckets.saturating_sub(1)
+        }
+        .clamp(MIN_BUCKETS, MAX_BUCKETS);
+    }
+
+    /// Selected AP's metric per elapsed-time bucket for each session.
+    ///
+    /// Sessions are aligned on their first scan, so sequential walk tests line
+    /// up. Sessions of one run (nodes, or the turns of an A/B test) are aligned on the
+    /// run's first scan instead, so turns interleave. Returns the bucket width in
+    /// seconds and one series per session.
+    pub fn aligned_series(&self) -> (u64, Vec<AlignedSeries>) {
+        let Some((sel_bssid, sel_ssid)) = self.get_selected_ap() else {
+            return (0, Vec::new());
+        };
+        let buckets = self.buckets.clamp(MIN_BUCKETS, MAX_BUCKETS);
+        let span_secs = self
+            .sessions
+            .iter()
+            .filter_map(|s| {
+                let last = self.window_scans(s).last()?.timestamp;
+                Some((last - self.aligned_start(s)?).num_seconds())
+            })
+            .max()
+            .unwrap_or(0)
+            .max(1) as u64;
+        let bucket_secs = span_secs.div_ceil(buckets as u64).max(1);
+
+        let series = self
+            .columns()
+            .into_iter()
+            .map(|(name, sessions)| {
+                let mut readings: Vec<Vec<i32>> = vec![Vec::new(); buckets];
+                for session in sessions.iter().map(|&idx| &self.sessions[idx]) {
+                    let scans = self.window_scans(session);
+                    let offset = self.offset_for(session);
+                    let Some(start) = self.aligned_start(session) else {
+                        continue;
+                    };
+                    let selected =
+                        |ap: &AccessPoint| self.match_by.matches(ap, &sel_bssid, &sel_ssid);
+                    let outliers = match self.drop_outliers {
+                        true => outlier_readings(scans, selected),
+                        false => HashSet::new(),
+                    };
+                    for (idx, scan) in scans.iter().enumerate() {
+                        let elapsed = (scan.timestamp - start).num_seconds().max(0);
+                        let bucket = (elapsed as u64 / bucket_secs).min(buckets as u64 - 1);
+                        readings[bucket as usize].extend(
+                            scan.access_points
+                                .iter()
+                                .filter(|ap| selected(ap))
+                                .filter(|ap| !outliers.contains(&(idx, ap.bssid.clone())))
+                                .map(|ap| ap.signal_dbm + offset),
+                        );
+                    }
+                }
+                let values = readings
+                    .into_iter()
+                    .map(|signals| self.metric_of(signals, &sessions))
+                    .collect();
+                (name, values)
+            })
+            .collect();
+        (bucket_secs, series)
+    }
+
+    /// Time a session's elapsed-time buckets count from: its run's first scan
+    fn aligned_start(&self, session: &Session) -> Option<DateTime<Utc>> {
+        let first = |s: &Session| self.window_scans(s).first().map(|scan| scan.timestamp);
+        match &session.run_id {
+            Some(run_id) => {
+                let run = self.sessions.iter().filter(|s| s.run_id.as_ref() == Some(run_id));
+                run.filter_map(|s| first(s)).min()
+            }
+            None => first(session),
+        }
     }
 
-    /// Get all unique APs across all sessions
+    /// Scans of a session inside the current sample window
+    pub fn window_scans<'s>(&self, session: &'s Session) -> &'s [ScanResult] {
+        let scans = &session.scans[..];
+        match self.window {
+            SampleWindow::All => scans,
+            SampleWindow::Equal => {
+                let n = self
+                    .sessions
+                    .iter()
+                    .map(|s| s.scans.len())
+                    .filter(|&n| n > 0)
+                    .min()
+                    .unwrap_or(0);
+                &scans[..n.min(scans.len())]
+            }
+            SampleWindow::Overlap => {
+                let firsts = self.sessions.iter().filter_map(|s| s.scans.first());
+                let lasts = self.sessions.iter().filter_map(|s| s.scans.last());
+                let start = firsts.map(|s| s.timestamp).max();
+                let end = lasts.map(|s| s.timestamp).min();
+                let (Some(start), Some(end)) = (start, end) else {
+                    return scans;
+                };
+                let from = scans.partition_point(|s| s.timestamp < start);
+                let to = scans.partition_point(|s| s.timestamp <= end).max(from);
+                &scans[from..to]
+            }
+        }
+    }
+
+    /// Largest/smallest windowed scan count, when above the warning ratio
+    pub fn scan_count_imbalance(&self) -> Option<f64> {
+        let counts: Vec<usize> = self
+            .sessions
+            .iter()
+            .map(|s| self.window_scans(s).len())
+            .collect();
+        let max = *counts.iter().max()?;
+        let min = *counts.iter().min()?;
+        if min == 0 {
+            return None;
+        }
+        let ratio = max as f64 / min as f64;
+        (ratio > self.count_ratio).then_some(ratio)
+    }
+
+    /// Which radio settings differ between sessions that recorded them
+    pub fn radio_mismatch(&self) -> Option<&'static str> {
+        let radios: Vec<&RadioContext> =
+            self.sessions.iter().filter_map(|s| s.radio.as_ref()).collect();
+        let differs = |values: Vec<String>| values.iter().any(|v| *v != values[0]);
+        let power = differs(
+            radios
+                .iter()
+                .filter_map(|r| r.tx_power_dbm)
+                .map(|dbm| format!("{:.1}", dbm))
+                .collect(),
+        );
+        let country = differs(radios.iter().filter_map(|r| r.country.clone()).collect());
+        match (power, country) {
+            (true, true) => Some("tx power and country differ"),
+            (true, false) => Some("tx power differs"),
+            (false, true) => Some("country differs"),
+            (false, false) => None,
+        }
+    }
+
+    /// Adapters, by display name, whose sessions were recorded on different firmware.
+    /// Sessions share an adapter when their stable ids match, or, lacking ids, their
+    /// driver and chipset do.
+    pub fn firmware_mismatch(&self) -> Vec<String> {
+        let mut seen: Vec<(String, &str)> = Vec::new();
+        let mut names: Vec<String> = Vec::new();
+        for adapter in self.sessions.iter().map(|s| &s.adapter) {
+            let Some(firmware) = adapter.firmware_version.as_deref() else {
+                continue;
+            };
+            let key = adapter_key(adapter);
+            let name = adapter.display_name();
+            if seen.iter().any(|(k, f)| *k == key && *f != firmware) && !names.contains(&name) {
+                names.push(name);
+            }
+            seen.push((key, firmware));
+        }
+        names
+    }
+
+    /// Adapters whose sessions were recorded on different USB links, e.g. `MT7921 on
+    /// USB 2.0 hub vs USB 3.0 root`. A hub or a slower port can cost an adapter
+    /// throughput and power, so its sessions aren't like for like.
+    pub fn usb_mismatch(&self) -> Vec<String> {
+        let mut links: Vec<(String, String, Vec<String>)> = Vec::new();
+        for adapter in self.sessions.iter().map(|s| &s.adapter) {
+            let Some(link) = &adapter.usb else {
+                continue;
+            };
+            let key = adapter_key(adapter);
+            let label = link.label();
+            match links.iter_mut().find(|(k, _, _)| *k == key) {
+                Some((_, _, labels)) if !labels.contains(&label) => labels.push(label),
+                Some(_) => {}
+                None => links.push((key, adapter.display_name(), vec![label])),
+            }
+        }
+        links
+            .into_iter()
+            .filter(|(_, _, labels)| labels.len() > 1)
+            .map(|(_, name, labels)| format!("{} on {}", name, labels.join(" vs ")))
+            .collect()
+    }
+
+    /// Get all unique APs across all sessions that pass the band and sample filters
     pub fn all_aps(&self) -> Vec<(String, String)> {
-        let mut seen = std::collections::HashSet::new();
-        let mut aps = Vec::new();
+        let mut aps = self.aggregates().aps.clone();
+        if self.band_filter != FrequencyFilter::All || self.min_samples > 1 {
+            self.apply_filters(&mut aps);
+        }
+        if !self.sort.is_empty() {
+            aps = self.sorted(aps);
+        }
+        aps
+    }
 
-        for session in &self.sessions {
-            for (bssid, ssid) in session.unique_aps() {
-                let key = match self.match_by {
-                    MatchBy::Bssid => bssid.clone(),
-                    MatchBy::Ssid => ssid.clone(),
-                    MatchBy::Both => format!("{}|{}", bssid, ssid),
+    /// `aps` in `sort` order. APs missing a value (no spread, metric or load) sort
+    /// last whichever way their key goes.
+    fn sorted(&self, aps: Vec<(String, String)>) -> Vec<(String, String)> {
+        let values = self.metric_values();
+        let mut rows: Vec<_> = aps
+            .into_iter()
+            .map(|(bssid, ssid)| {
+                let key = self.match_by.key(&bssid, &ssid);
+                let metric = values.get(key.as_str());
+                let delta = metric
+                    .filter(|values| values.len() >= 2)
+                    .and_then(|values| Some(values.iter().max()? - values.iter().min()?));
+                let mean = metric.map(|values| values.iter().sum::<i32>() / values.len() as i32);
+                let utilization = self.utilization(&key);
+                ((bssid, ssid), [delta, mean, utilization.map(i32::from)])
+            })
+            .collect();
+        rows.sort_by(|a, b| {
+            self.sort.compare(a, b, |key, ((_, a_ssid), a), ((_, b_ssid), b)| {
+                let (a, b) = match key.by {
+                    CompareSort::Ssid => {
+                        return key.apply(a_ssid.to_lowercase().cmp(&b_ssid.to_lowercase()))
+                    }
+                    // Largest first
+                    CompareSort::Delta => (b[0], a[0]),
+                    CompareSort::Metric => (b[1], a[1]),
+                    CompareSort::Utilization => (a[2], b[2]),
                 };
-                if seen.insert(key) {
-                    aps.push((bssid, ssid));
+                a.is_none().cmp(&b.is_none()).then_with(|| key.apply(a.cmp(&b)))
+            })
+        });
+        rows.into_iter().map(|(ap, _)| ap).collect()
+    }
+
+    /// Mean BSS Load channel utilization of an AP key across the sessions that heard it
+    pub fn utilization(&self, key: &str) -> Option<u8> {
+        let agg = self.aggregates();
+        let values: Vec<u32> = agg
+            .per_session
+            .iter()
+            .filter_map(|session| session.utilization.get(key))
+            .map(|&u| u as u32)
+            .collect();
+        (!values.is_empty()).then(|| (values.iter().sum::<u32>() / values.len() as u32) as u8)
+    }
+
+    /// Keep only APs seen on the filtered band and sampled often enough
+    fn apply_filters(&self, aps: &mut Vec<(String, String)>) {
+        let agg = self.aggregates();
+        aps.retain(|(bssid, ssid)| {
+            let key = self.match_by.key(bssid, ssid);
+            // Most samples in any one session, and whether seen on the band
+            let (max, on_band) = agg
+                .per_session
+                .iter()
+                .filter_map(|session| session.readings.get(&key))
+                .fold((0, false), |(max, on_band), readings| {
+                    let band = readings.iter().any(|&(_, b)| self.band_filter.matches(b));
+                    (max.max(readings.len()), on_band || band)
+                });
+            on_band && max >= self.min_samples
+        });
+    }
+
+    /// The current metric of every session that heard it, per AP key
+    fn metric_values(&self) -> HashMap<String, Vec<i32>> {
+        let agg = self.aggregates();
+        let mut values: HashMap<String, Vec<i32>> = HashMap::new();
+        for (idx, (session, aggregates)) in self.sessions.iter().zip(&agg.per_session).enumerate() {
+            let offset = self.offset_for(session);
+            for (key, stats) in &aggregates.stats {
+                if let Some(value) = self.metric_value(&stats.offset(offset), &[idx]) {
+                    values.entry(key.clone()).or_default().push(value);
                 }
             }
         }
-        aps
+        values
+    }
+
+    /// APs in list order with whether each session saw them (in its sample window)
+    pub fn ap_presence(&self) -> Vec<((String, String), Vec<bool>)> {
+        let agg = self.aggregates();
+        self.all_aps()
+            .into_iter()
+            .map(|(bssid, ssid)| {
+                let key = self.match_by.key(&bssid, &ssid);
+                let presence = agg.per_session.iter().map(|s| s.readings.contains_key(&key));
+                ((bssid, ssid), presence.collect())
+            })
+            .collect()
     }
 
     pub fn get_selected_ap(&self) -> Option<(String, String)> {
@@ -105,85 +724,244 @@ impl CompareState {
 
     /// Get comparison data for the selected AP
     pub fn get_comparison_data(&self) -> Vec<(String, Option<i32>)> {
+        self.comparison_rows()
+            .into_iter()
+            .map(|(name, value, _)| (name, value))
+            .collect()
+    }
+
+    /// Comparison rows for the selected AP with each session's windowed scan count
+    pub fn comparison_rows(&self) -> Vec<(String, Option<i32>, usize)> {
         let Some((sel_bssid, sel_ssid)) = self.get_selected_ap() else {
             return Vec::new();
         };
 
-        self.sessions
-            .iter()
-            .map(|session| {
-                let name = session
-                    .adapter
-                    .label
-                    .clone()
-                    .unwrap_or_else(|| session.adapter.interface.clone());
-
-                // Find matching AP in this session
-                let stats = session.scans.iter().flat_map(|s| &s.access_points).find(|ap| {
-                    match self.match_by {
-                        MatchBy::Bssid => ap.bssid == sel_bssid,
-                        MatchBy::Ssid => ap.ssid == sel_ssid,
-                        MatchBy::Both => ap.bssid == sel_bssid && ap.ssid == sel_ssid,
-                    }
-                });
+        let agg = self.aggregates();
+        let key = self.match_by.key(&sel_bssid, &sel_ssid);
+        let breakdown = self.band_breakdown && self.match_by == MatchBy::Group;
+        let mut data = Vec::new();
 
-                if stats.is_some() {
-                    // Calculate metric
-                    let matching_aps: Vec<_> = session
-                        .scans
-                        .iter()
-                        .flat_map(|s| &s.access_points)
-                        .filter(|ap| match self.match_by {
-                            MatchBy::Bssid => ap.bssid == sel_bssid,
-                            MatchBy::Ssid => ap.ssid == sel_ssid,
-                            MatchBy::Both => ap.bssid == sel_bssid && ap.ssid == sel_ssid,
-                        })
-                        .collect();
-
-                    if matching_aps.is_empty() {
-                        return (name, None);
-                    }
+        for (name, sessions) in self.columns() {
+            let readings = self.readings(&agg, &sessions, &key);
+            let count = sessions.iter().map(|&idx| agg.per_session[idx].scan_count).sum();
+            let signals = |band: Option<Band>| {
+                readings
+                    .iter()
+                    .filter(|&&(_, b)| band.is_none_or(|band| b == band))
+                    .map(|&(signal, _)| signal)
+                    .collect::<Vec<i32>>()
+            };
+
+            if !breakdown {
+                data.push((name, self.metric_of(signals(None), &sessions), count));
+                continue;
+            }
+
+            // One row per band the group was seen on
+            for band in [Band::TwoPointFourGHz, Band::FiveGHz, Band::SixGHz] {
+                let band_signals = signals(Some(band));
+                if !band_signals.is_empty() {
+                    data.push((
+                        format!("{} {}", name, band.short_name()),
+                        self.metric_of(band_signals, &sessions),
+                        count,
+                    ));
+                }
+            }
+            if readings.is_empty() {
+                data.push((name, None, count));
+            }
+        }
+        data
+    }
+
+    /// Apply the selected metric to a set of signal readings
+    fn metric_of(&self, signals: Vec<i32>, sessions: &[usize]) -> Option<i32> {
+        ApStats::from_signals(signals).and_then(|stats| self.metric_value(&stats, sessions))
+    }
 
-                    let signal = match self.metric {
-                        CompareMetric::Avg => {
-                            let sum: i32 = matching_aps.iter().map(|ap| ap.signal_dbm).sum();
-                            sum / matching_aps.len() as i32
+    /// Per-session detection rate for the selected AP, normalized by dwell time
+    pub fn get_detection_rates(&self) -> Vec<(String, Option<f32>)> {
+        let Some((sel_bssid, sel_ssid)) = self.get_selected_ap() else {
+            return Vec::new();
+        };
+
+        let agg = self.aggregates();
+        let key = self.match_by.key(&sel_bssid, &sel_ssid);
+        self.columns()
+            .into_iter()
+            .map(|(name, sessions)| {
+                let rates: Vec<f32> = sessions
+                    .iter()
+                    .filter_map(|&idx| agg.per_session[idx].detection_rate(&key))
+                    .collect();
+                let rate =
+                    (!rates.is_empty()).then(|| rates.iter().sum::<f32>() / rates.len() as f32);
+                (name, rate)
+            })
+            .collect()
+    }
+
+    /// Average link bitrate per signal bucket for each adapter, strongest bucket first.
+    ///
+    /// Separates adapters that hear well from ones that also move data well at that
+    /// level. Buckets are named by their weakest signal (-60 covers -60 to -51 dBm).
+    /// Empty when no session was associated while scanning.
+    pub fn link_efficiency(&self) -> Vec<(String, Vec<(i32, f32)>)> {
+        let agg = self.aggregates();
+        if agg.per_session.iter().all(|s| s.link_rates.is_empty()) {
+            return Vec::new();
+        }
+        self.columns()
+            .into_iter()
+            .map(|(name, sessions)| {
+                let mut buckets: Vec<(i32, f32, usize)> = Vec::new();
+                for &idx in &sessions {
+                    let offset = self.offset_for(&self.sessions[idx]);
+                    for &(signal, mbps) in &agg.per_session[idx].link_rates {
+                        let bucket = (signal + offset).div_euclid(EFFICIENCY_BUCKET_DB)
+                            * EFFICIENCY_BUCKET_DB;
+                        match buckets.iter_mut().find(|(b, _, _)| *b == bucket) {
+                            Some((_, sum, count)) => {
+                                *sum += mbps;
+                                *count += 1;
+                            }
+                            None => buckets.push((bucket, mbps, 1)),
                         }
-                        CompareMetric::Min => matching_aps.iter().map(|ap| ap.signal_dbm).min().unwrap(),
-                        CompareMetric::Max => matching_aps.iter().map(|ap| ap.signal_dbm).max().unwrap(),
-                    };
-                    (name, Some(signal))
-                } else {
-                    (name, None)
+                    }
                 }
+                buckets.sort_by_key(|&(bucket, _, _)| std::cmp::Reverse(bucket));
+                let averages =
+                    buckets.into_iter().map(|(b, sum, count)| (b, sum / count as f32)).collect();
+                (name, averages)
+            })
+            .collect()
+    }
+
+    /// Average association, handshake and DHCP time of each adapter's connect tests,
+    /// and how often they got online. Empty when no session has any.
+    pub fn connect_timings(&self) -> Vec<(String, Option<ConnectSummary>)> {
+        if self.sessions.iter().all(|s| s.connect_tests.is_empty()) {
+            return Vec::new();
+        }
+        self.columns()
+            .into_iter()
+            .map(|(name, sessions)| {
+                let tests: Vec<_> =
+                    sessions.iter().flat_map(|&idx| &self.sessions[idx].connect_tests).collect();
+                let n = tests.len() as u64;
+                let avg = |phase: fn(&ConnectTiming) -> u64| {
+                    tests.iter().map(|t| phase(t)).sum::<u64>() / n
+                };
+                let count = |r| tests.iter().filter(|t| t.reachability == Some(r)).count();
+                let summary = (n > 0).then(|| ConnectSummary {
+                    association_ms: avg(|t| t.association_ms),
+                    handshake_ms: avg(|t| t.handshake_ms),
+                    dhcp_ms: avg(|t| t.dhcp_ms),
+                    tests: tests.len(),
+                    online: count(Reachability::Online),
+                    portal: count(Reachability::CaptivePortal),
+                });
+                (name, summary)
+            })
+            .collect()
+    }
+
+    /// Ping loss and latency percentiles of each adapter's load tests. Loss is pooled over
+    /// every ping; each percentile is the median of the tests' values. Empty when no
+    /// session has any.
+    pub fn load_results(&self) -> Vec<(String, Option<LoadSummary>)> {
+        if self.sessions.iter().all(|s| s.load_tests.is_empty()) {
+            return Vec::new();
+        }
+        self.columns()
+            .into_iter()
+            .map(|(name, sessions)| {
+                let tests: Vec<_> =
+                    sessions.iter().flat_map(|&idx| &self.sessions[idx].load_tests).collect();
+                let sent: u32 = tests.iter().map(|t| t.pings_sent).sum();
+                let received: u32 = tests.iter().map(|t| t.pings_received).sum();
+                let median = |rtt: fn(&LoadTest) -> Option<f32>| {
+                    let mut values: Vec<f32> = tests.iter().filter_map(|t| rtt(t)).collect();
+                    values.sort_by(f32::total_cmp);
+                    values.get(values.len() / 2).copied()
+                };
+                let summary = (!tests.is_empty()).then(|| LoadSummary {
+                    loss_pct: if sent == 0 {
+                        0.0
+                    } else {
+                        100.0 * sent.saturating_sub(received) as f32 / sent as f32
+                    },
+                    rtt_p50_ms: median(|t| t.rtt_p50_ms),
+                    rtt_p95_ms: median(|t| t.rtt_p95_ms),
+                    rtt_p99_ms: median(|t| t.rtt_p99_ms),
+                });
+                (name, summary)
             })
             .collect()
     }
 
     /// Calculate which adapter is "best" (most APs with strongest signal)
     pub fn best_adapter(&self) -> Option<String> {
+        self.best_adapter_for(None)
+    }
+
+    /// Winner on each band that has APs, for the summary breakdown
+    pub fn best_adapter_by_band(&self) -> Vec<(Band, String)> {
+        [Band::TwoPointFourGHz, Band::FiveGHz, Band::SixGHz]
+            .into_iter()
+            .filter_map(|band| Some((band, self.best_adapter_for(Some(band))?)))
+            .collect()
+    }
+
+    /// Adapter with the strongest reading on the most APs, optionally only
+    /// counting readings on one band
+    fn best_adapter_for(&self, band: Option<Band>) -> Option<String> {
         if self.sessions.is_empty() {
             return None;
         }
 
-        let aps = self.all_aps();
-        let mut wins: Vec<usize> = vec![0; self.sessions.len()];
+        let agg = self.aggregates();
+        let on_band = |b: Band| band.is_none_or(|band| b == band);
+        let channels = |key: &str| -> Vec<u32> {
+            let seen = agg.per_session.iter().filter_map(|s| s.channels.get(key));
+            seen.flatten().copied().collect()
+        };
+        let mut aps = self.all_aps();
+        let columns = self.columns();
+        let mut wins: Vec<usize> = vec![0; columns.len()];
+
+        if band.is_some() {
+            aps.retain(|(bssid, ssid)| {
+                let key = self.match_by.key(bssid, ssid);
+                channels(&key).into_iter().any(|f| on_band(Band::from_frequency(f)))
+            });
+            if aps.is_empty() {
+                return None;
+            }
+        }
+
+        // When normalizing, only count APs on channels every adapter dwelled on
+        if self.normalize {
+            aps.retain(|(bssid, ssid)| {
+                let key = self.match_by.key(bssid, ssid);
+                channels(&key).into_iter().all(|f| agg.channel_covered(f))
+            });
+        }
 
         for (bssid, ssid) in &aps {
+            let key = self.match_by.key(bssid, ssid);
             let mut best_signal = i32::MIN;
             let mut best_idx = None;
 
-            for (idx, session) in self.sessions.iter().enumerate() {
-                let signal = session
-                    .scans
+            for (idx, (_, sessions)) in columns.iter().enumerate() {
+                let signal = sessions
                     .iter()
-                    .flat_map(|s| &s.access_points)
-                    .filter(|ap| match self.match_by {
-                        MatchBy::Bssid => &ap.bssid == bssid,
-                        MatchBy::Ssid => &ap.ssid == ssid,
-                        MatchBy::Both => &ap.bssid == bssid && &ap.ssid == ssid,
+                    .filter_map(|&session| {
+                        let peaks = agg.per_session[session].peaks.get(&key)?;
+                        let peak = peaks.iter().filter(|&&(b, _)| on_band(b)).map(|&(_, s)| s);
+                        Some(peak.max()? + self.offset_for(&self.sessions[session]))
                     })
-                    .map(|ap| ap.signal_dbm)
                     .max();
 
                 if let Some(s) = signal {
@@ -204,16 +982,221 @@ impl CompareState {
             .enumerate()
             .max_by_key(|(_, w)| *w)?;
 
-        let name = self.sessions[best_idx]
-            .adapter
-            .label
-            .clone()
-            .unwrap_or_else(|| self.sessions[best_idx].adapter.interface.clone());
+        let name = &columns[best_idx].0;
 
         Some(format!("{} ({}/{} APs)", name, best_wins, aps.len()))
     }
 }
 
+/// Per-AP aggregates of the compared sessions, computed once and reused by every frame
+/// until the sessions, match mode or sample window change
+#[derive(Debug)]
+struct Aggregates {
+    sessions: Vec<Arc<Session>>,
+    match_by: MatchBy,
+    window: SampleWindow,
+    drop_outliers: bool,
+    /// APs across all sessions in order of first appearance, one per match key
+    aps: Vec<(String, String)>,
+    /// One per session, in the same order
+    per_session: Vec<SessionAggregates>,
+}
+
+impl Aggregates {
+    fn new(state: &CompareState) -> Self {
+        let mut keys = HashSet::new();
+        let mut aps = Vec::new();
+        for session in &state.sessions {
+            for (bssid, ssid) in session.unique_aps() {
+                // Mesh points and ad-hoc peers aren't APs an adapter would join
+                let peer =
+                    session.totals.get(&bssid).is_some_and(|t| !t.bss_type.is_infrastructure());
+                if !peer && keys.insert(state.match_by.key(&bssid, &ssid)) {
+                    aps.push((bssid, ssid));
+                }
+            }
+        }
+        let per_session = state
+            .sessions
+            .iter()
+            .map(|s| {
+                let window = state.window_scans(s);
+                SessionAggregates::new(s, window, state.match_by, state.drop_outliers)
+            })
+            .collect();
+        Self {
+            sessions: state.sessions.clone(),
+            match_by: state.match_by,
+            window: state.window,
+            drop_outliers: state.drop_outliers,
+            aps,
+            per_session,
+        }
+    }
+
+    /// Whether these were computed for the sessions and modes `state` has now
+    fn is_for(&self, state: &CompareState) -> bool {
+        self.match_by == state.match_by
+            && self.window == state.window
+            && self.drop_outliers == state.drop_outliers
+            && self.sessions.len() == state.sessions.len()
+            && self.sessions.iter().zip(&state.sessions).all(|(a, b)| Arc::ptr_eq(a, b))
+    }
+
+    /// Whether every session that reports dwell data actually visited this channel
+    fn channel_covered(&self, frequency_mhz: u32) -> bool {
+        self.per_session
+            .iter()
+            .filter(|s| s.has_dwell_data)
+            .all(|s| s.dwell_ms.get(&frequency_mhz).is_some_and(|&ms| ms > 0))
+    }
+}
+
+/// One session's readings and stats, keyed by match key
+#[derive(Debug)]
+struct SessionAggregates {
+    /// Scans in the sample window
+    scan_count: usize,
+    /// Scans in the whole session
+    total_scans: usize,
+    /// Signal and band of each windowed reading
+    readings: HashMap<String, Vec<(i32, Band)>>,
+    /// Stats of the windowed readings, before calibration
+    stats: HashMap<String, ApStats>,
+    /// Strongest windowed reading on each band, before calibration
+    peaks: HashMap<String, Vec<(Band, i32)>>,
+    /// Readings in the whole session
+    counts: HashMap<String, usize>,
+    /// Channels seen on anywhere in the session
+    channels: HashMap<String, Vec<u32>>,
+    has_dwell_data: bool,
+    /// Dwell time per channel
+    dwell_ms: HashMap<u32, u64>,
+    /// Signal and bitrate of the adapter's own link in each windowed scan it had one
+    link_rates: Vec<(i32, f32)>,
+    /// Mean windowed channel utilization of APs that sent a BSS Load element
+    utilization: HashMap<String, u8>,
+}
+
+impl SessionAggregates {
+    fn new(session: &Session, window: &[ScanResult], match_by: MatchBy, clean: bool) -> Self {
+        let mut readings: HashMap<String, Vec<(i32, Band)>> = HashMap::new();
+        let mut loads: HashMap<String, Vec<u32>> = HashMap::new();
+        let infrastructure = |ap: &&AccessPoint| ap.bss_type.is_infrastructure();
+        let outliers = match clean {
+            true => outlier_readings(window, |ap| ap.bss_type.is_infrastructure()),
+            false => HashSet::new(),
+        };
+        for (idx, scan) in window.iter().enumerate() {
+            for ap in scan.access_points.iter().filter(infrastructure) {
+                let key = match_by.key(&ap.bssid, &ap.ssid);
+                if let Some(util) = ap.channel_utilization {
+                    loads.entry(key.clone()).or_default().push(util as u32);
+                }
+                if !outliers.contains(&(idx, ap.bssid.clone())) {
+                    readings.entry(key).or_default().push((ap.signal_dbm, ap.band()));
+                }
+            }
+        }
+        let utilization = loads
+            .into_iter()
+            .map(|(key, loads)| (key, (loads.iter().sum::<u32>() / loads.len() as u32) as u8))
+            .collect();
+        let stats = readings
+            .iter()
+            .filter_map(|(key, readings)| {
+                let signals = readings.iter().map(|&(signal, _)| signal).collect();
+                Some((key.clone(), ApStats::from_signals(signals)?))
+            })
+            .collect();
+        let peaks = readings
+            .iter()
+            .map(|(key, readings)| {
+                let mut peaks: Vec<(Band, i32)> = Vec::new();
+                for &(signal, band) in readings {
+                    match peaks.iter_mut().find(|(b, _)| *b == band) {
+                        Some((_, peak)) => *peak = (*peak).max(signal),
+                        None => peaks.push((band, signal)),
+                    }
+                }
+                (key.clone(), peaks)
+            })
+            .collect();
+
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        let mut channels: HashMap<String, Vec<u32>> = HashMap::new();
+        for ap in session.scans.iter().flat_map(|s| &s.access_points).filter(infrastructure) {
+            let key = match_by.key(&ap.bssid, &ap.ssid);
+            *counts.entry(key.clone()).or_default() += 1;
+            let seen = channels.entry(key).or_default();
+            if !seen.contains(&ap.frequency_mhz) {
+                seen.push(ap.frequency_mhz);
+            }
+        }
+        let dwell_ms = session
+            .channel_stats()
+            .iter()
+            .map(|c| (c.frequency_mhz, c.dwell_ms))
+            .collect();
+        let link_rates = window
+            .iter()
+            .filter_map(|scan| {
+                let link = scan.link.as_ref()?;
+                Some((link.signal_dbm?, link.tx_bitrate_mbps.or(link.rx_bitrate_mbps)?))
+            })
+            .collect();
+
+        Self {
+            scan_count: window.len(),
+            total_scans: session.scans.len(),
+            readings,
+            stats,
+            peaks,
+            counts,
+            channels,
+            has_dwell_data: session.has_dwell_data(),
+            dwell_ms,
+            link_rates,
+            utilization,
+        }
+    }
+
+    /// Detection rate of readings under `key`, normalized by channel dwell.
+    ///
+    /// Returns samples per second of dwell on the AP's channel(s) when the driver
+    /// reported dwell time, otherwise samples per scan.
+    fn detection_rate(&self, key: &str) -> Option<f32> {
+        let count = *self.counts.get(key)?;
+        let channels = self.channels.get(key)?;
+        let dwell_ms: u64 = channels.iter().filter_map(|f| self.dwell_ms.get(f)).sum();
+
+        if dwell_ms > 0 {
+            Some(count as f32 / (dwell_ms as f32 / 1000.0))
+        } else {
+            Some(count as f32 / self.total_scans.max(1) as f32)
+        }
+    }
+}
+
+fn session_name(session: &Session) -> String {
+    session
+        .adapter
+        .label
+        .clone()
+        .unwrap_or_else(|| session.adapter.interface.clone())
+}
+
+/// Which physical adapter a session came from: its stable id, or without one its driver
+/// and chipset
+fn adapter_key(adapter: &Adapter) -> String {
+    adapter.id.clone().unwrap_or_else(|| format!("{}/{}", adapter.driver, adapter.chipset))
+}
+
+/// Probe location of a session; sessions scanned on this machine are "local"
+fn node_name(session: &Session) -> String {
+    session.node.clone().unwrap_or_else(|| "local".to_string())
+}
+
 /// Compare screen widget
 pub struct CompareScreen<'a> {
     state: &'a CompareState,
@@ -227,15 +1210,34 @@ impl<'a> CompareScreen<'a> {
 
 impl<'a> Widget for CompareScreen<'a> {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        // Dynamic session list height - at least 4, up to 6 depending on terminal size
-        let session_height = if area.height > 20 { 6 } else { 4 };
+        let session_height = session_list_height(area.height);
+        // Per-band winners get their own lines when there's room; short terminals drop the
+        // summary so the bars keep some height
+        let by_band = self.state.best_adapter_by_band();
+        let efficiency = self.state.link_efficiency();
+        let connect = self.state.connect_timings();
+        let load = self.state.load_results();
+        // The link efficiency, connect and load lines only get their own rows on tall
+        // terminals
+        let extra_lines = if area.height > 30 {
+            u16::from(!efficiency.is_empty())
+                + u16::from(!connect.is_empty())
+                + u16::from(!load.is_empty())
+        } else {
+            0
+        };
+        let summary_height = match area.height {
+            h if h > 26 => 2 + by_band.len() as u16 + extra_lines,
+            h if h >= 16 => 2,
+            _ => 0,
+        };
 
         let chunks = Layout::vertical([
             Constraint::Length(2),              // Header
             Constraint::Length(session_height), // Session list (scrollable)
             Constraint::Length(2),              // AP selector and controls
             Constraint::Min(5),                 // Comparison bars
-            Constraint::Length(2),              // Summary
+            Constraint::Length(summary_height), // Summary
             Constraint::Length(2),              // Footer
         ])
         .split(area);
@@ -244,7 +1246,7 @@ impl<'a> Widget for CompareScreen<'a> {
         self.render_sessions(chunks[1], buf);
         self.render_controls(chunks[2], buf);
         self.render_comparison(chunks[3], buf);
-        self.render_summary(chunks[4], buf);
+        self.render_summary(chunks[4], buf, &by_band, &efficiency, &connect, &load);
         self.render_footer(chunks[5], buf);
     }
 }
@@ -257,12 +1259,32 @@ impl<'a> CompareScreen<'a> {
 
         let info = format!("Sessions: {} loaded", self.state.sessions.len());
         buf.set_string(inner.x, inner.y, &info, Style::default());
-        buf.set_string(
-            inner.x + inner.width - 12,
-            inner.y,
-            "[+]add [x]del",
-            Style::default().fg(Color::DarkGray),
-        );
+        let mut warnings = Vec::new();
+        if let Some(what) = self.state.radio_mismatch() {
+            warnings.push(what.to_string());
+        }
+        let firmware = self.state.firmware_mismatch();
+        if !firmware.is_empty() {
+            warnings.push(format!("{} firmware differs", firmware.join(", ")));
+        }
+        warnings.extend(self.state.usb_mismatch());
+        if let Some(ratio) = self.state.scan_count_imbalance() {
+            warnings.push(format!("scan counts differ {:.0}x, try [w]indow", ratio));
+        }
+        if !warnings.is_empty() {
+            let warning = format!("⚠ {}", warnings.join("; "));
+            let x = inner.x + info.len() as u16 + 2;
+            let room = inner.width.saturating_sub(info.len() as u16 + 16) as usize;
+            buf.set_string(x, inner.y, truncate(&warning, room), Style::default().fg(Color::Yellow));
+        }
+        if area.width >= COMPACT_WIDTH {
+            buf.set_string(
+                inner.x + inner.width - 13,
+                inner.y,
+                "[+]add [x]del",
+                Style::default().fg(Color::DarkGray),
+            );
+        }
     }
 
     fn render_sessions(&self, area: Rect, buf: &mut Buffer) {
@@ -324,30 +1346,42 @@ impl<'a> CompareScreen<'a> {
             }
 
             let prefix = format!("{}. ", actual_idx + 1);
-            let name = session.adapter.display_name();
-            let scan_info = if session.scans.is_empty() {
+            let name = match &session.node {
+                Some(node) => format!("{} @{}", session.adapter.display_name(), node),
+                None => session.adapter.display_name(),
+            };
+            let mut scan_info = if session.scans.is_empty() {
                 "(no data)".to_string()
             } else {
                 format!("{} scans", session.scans.len())
             };
-            let info = format!(
-                "{} - {}",
-                session.started_at.format("%m-%d %H:%M"),
-                scan_info
-            );
+            if let Some(version) = session.adapter.version_label() {
+                scan_info = format!("{} - {}", scan_info, version);
+            }
+            // Narrow terminals drop the start time and shorten the adapter name
+            let (name_width, info_offset, info) = if area.width < NARROW_WIDTH {
+                (12, 16, scan_info)
+            } else {
+                let started = session.started_at.format("%m-%d %H:%M");
+                let mut info = format!("{} - {}", started, scan_info);
+                if let Some(setup) = &session.setup {
+                    info = format!("{} - {}", info, setup.describe());
+                }
+                (18, 22, info)
+            };
 
             buf.set_string(inner.x, y, &prefix, style);
             buf.set_string(
                 inner.x + prefix.len() as u16,
                 y,
-                &truncate(&name, 18),
+                truncate(&name, name_width),
                 style,
             );
 
-            let info_x = inner.x + 22;
+            let info_x = inner.x + info_offset;
             if info_x < inner.x + inner.width {
-                let max_info_len = (inner.width - 22) as usize;
-                buf.set_string(info_x, y, &truncate(&info, max_info_len), style.fg(Color::DarkGray));
+                let max_info_len = (inner.width - info_offset) as usize;
+                buf.set_string(info_x, y, truncate(&info, max_info_len), style.fg(Color::DarkGray));
             }
         }
     }
@@ -358,14 +1392,32 @@ impl<'a> CompareScreen<'a> {
         block.render(area, buf);
 
         // AP selector
+        let ap_count = self.state.all_aps().len();
         let ap_info = if let Some((_, ssid)) = self.state.get_selected_ap() {
             let ssid_display = if ssid.is_empty() { "<hidden>" } else { &ssid };
-            format!("AP: {}", truncate(ssid_display, 30))
+            let position = format!(" ({}/{})", self.state.selected_ap_idx + 1, ap_count);
+            format!("AP: {}{}", truncate(ssid_display, 30 - position.len()), position)
         } else {
             "No APs".to_string()
         };
 
         buf.set_string(inner.x, inner.y, &ap_info, Style::default());
+
+        // AP list filters
+        let filters = format!(
+            "Band:[{}] Min:[{}] Order:[{}]",
+            self.state.band_filter.name(),
+            if self.state.min_samples > 1 {
+                self.state.min_samples.to_string()
+            } else {
+                "off".to_string()
+            },
+            if self.state.sort.is_empty() { "Seen".to_string() } else { self.state.sort.label() }
+        );
+        let filters_x = inner.x + 36;
+        if filters_x + filters.chars().count() as u16 + 7 <= inner.x + inner.width {
+            buf.set_string(filters_x, inner.y, &filters, Style::default().fg(Color::DarkGray));
+        }
         buf.set_string(
             inner.x + inner.width - 6,
             inner.y,
@@ -373,22 +1425,102 @@ impl<'a> CompareScreen<'a> {
             Style::default().fg(Color::DarkGray),
         );
 
-        // Match and metric controls
+        // Match and metric controls, led by the row dimension when nodes are loaded
+        let state = self.state;
+        let grouping = state.has_nodes() || state.has_versions();
+        let dimension = if grouping || state.rows != CompareRows::Session {
+            format!("By: [{}]  ", state.rows.name())
+        } else {
+            String::new()
+        };
         let controls = format!(
-            "Match: [{}]   Metric: [{}]",
+            "{}Match: [{}]  Metric: [{}]  Norm: [{}]  Cal: [{}]  Win: [{}]  Time: [{}]",
+            dimension,
             self.state.match_by.name(),
-            self.state.metric.name()
+            self.state.metric_name(),
+            if self.state.normalize { "Dwell" } else { "Off" },
+            if self.state.calibrated { "On" } else { "Off" },
+            self.state.window.name(),
+            if self.state.aligned {
+                format!("{} buckets", self.state.buckets)
+            } else if self.state.checkpoints {
+                "Walk".to_string()
+            } else {
+                "Off".to_string()
+            }
+        );
+        buf.set_string(
+            inner.x,
+            inner.y + 1,
+            truncate(&controls, inner.width as usize),
+            Style::default(),
         );
-        buf.set_string(inner.x, inner.y + 1, &controls, Style::default());
     }
 
     fn render_comparison(&self, area: Rect, buf: &mut Buffer) {
         let block = Block::default().borders(Borders::LEFT | Borders::RIGHT);
-        let inner = block.inner(area);
+        let mut inner = block.inner(area);
         block.render(area, buf);
 
-        let data = self.state.get_comparison_data();
-        if data.is_empty() {
+        // AP list pane on the left when there's room for bars beside it
+        if inner.width >= AP_PANE_WIDTH + 40 {
+            let chunks = Layout::horizontal([
+                Constraint::Length(AP_PANE_WIDTH),
+                Constraint::Length(1),
+                Constraint::Min(0),
+            ])
+            .split(inner);
+            self.render_ap_pane(chunks[0], buf);
+            for y in chunks[1].y..chunks[1].y + chunks[1].height {
+                buf.set_string(chunks[1].x, y, "│", Style::default().fg(Color::DarkGray));
+            }
+            inner = chunks[2];
+        } else if inner.height >= STACKED_MIN_HEIGHT {
+            // Too narrow to sit beside the bars: stack the AP list above them
+            let chunks = Layout::vertical([
+                Constraint::Percentage(40),
+                Constraint::Length(1),
+                Constraint::Min(0),
+            ])
+            .split(inner);
+            self.render_ap_pane(chunks[0], buf);
+            let rule = "─".repeat(chunks[1].width as usize);
+            buf.set_string(chunks[1].x, chunks[1].y, rule, Style::default().fg(Color::DarkGray));
+            inner = chunks[2];
+        }
+
+        if self.state.checkpoints {
+            let (checkpoints, series) = self.state.checkpoint_table();
+            if series.is_empty() {
+                buf.set_string(
+                    inner.x,
+                    inner.y,
+                    "Select an AP to compare",
+                    Style::default().fg(Color::DarkGray),
+                );
+                return;
+            }
+            CheckpointTable::new(&checkpoints, &series).render(inner, buf);
+            return;
+        }
+
+        if self.state.aligned {
+            let (bucket_secs, series) = self.state.aligned_series();
+            if series.is_empty() {
+                buf.set_string(
+                    inner.x,
+                    inner.y,
+                    "Select an AP to compare",
+                    Style::default().fg(Color::DarkGray),
+                );
+                return;
+            }
+            AlignedChart::new(&series).bucket_secs(bucket_secs).render(inner, buf);
+            return;
+        }
+
+        let rows = self.state.comparison_rows();
+        if rows.is_empty() {
             buf.set_string(
                 inner.x,
                 inner.y,
@@ -398,23 +1530,198 @@ impl<'a> CompareScreen<'a> {
             return;
         }
 
-        ComparisonBar::new(data).render(inner, buf);
+        let counts = rows.iter().map(|(_, _, count)| *count).collect();
+        let data = rows.into_iter().map(|(name, value, _)| (name, value)).collect();
+        ComparisonBar::new(data).sample_counts(counts).render(inner, buf);
     }
 
-    fn render_summary(&self, area: Rect, buf: &mut Buffer) {
+    /// Scrollable AP list with one presence dot per session
+    fn render_ap_pane(&self, area: Rect, buf: &mut Buffer) {
+        let rows = self.state.ap_presence();
+        if area.height == 0 || rows.is_empty() {
+            return;
+        }
+
+        let height = area.height as usize;
+        let selected = self.state.selected_ap_idx.min(rows.len() - 1);
+        let offset = (selected + 1).saturating_sub(height);
+        let dots_width = self.state.sessions.len().min(MAX_PRESENCE_DOTS) as u16;
+        let name_width = area.width.saturating_sub(dots_width + 3) as usize;
+
+        for (i, ((bssid, ssid), presence)) in rows.iter().enumerate().skip(offset).take(height) {
+            let y = area.y + (i - offset) as u16;
+            let style = if i == selected {
+                Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            buf.set_string(area.x, y, " ".repeat(area.width as usize), style);
+
+            let name = if ssid.is_empty() { bssid.as_str() } else { ssid.as_str() };
+            let prefix = if i == selected { "▶" } else { " " };
+            buf.set_string(area.x, y, format!("{}{}", prefix, truncate(name, name_width)), style);
+
+            let dots_x = area.x + area.width - dots_width;
+            for (s, present) in presence.iter().take(MAX_PRESENCE_DOTS).enumerate() {
+                let (dot, color) = if *present {
+                    ("●", Color::Green)
+                } else {
+                    ("○", Color::DarkGray)
+                };
+                buf.set_string(dots_x + s as u16, y, dot, style.fg(color));
+            }
+        }
+    }
+
+    fn render_summary(
+        &self,
+        area: Rect,
+        buf: &mut Buffer,
+        by_band: &[(Band, String)],
+        efficiency: &[(String, Vec<(i32, f32)>)],
+        connect: &[(String, Option<ConnectSummary>)],
+        load: &[(String, Option<LoadSummary>)],
+    ) {
         let block = Block::default().borders(Borders::LEFT | Borders::RIGHT);
         let inner = block.inner(area);
         block.render(area, buf);
+        if inner.height == 0 {
+            return;
+        }
 
+        let band_lines = inner.height as usize >= 2 + by_band.len();
         if let Some(best) = self.state.best_adapter() {
             let summary = format!("Best: {}", best);
+            let style = Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD);
+            buf.set_string(inner.x, inner.y, &summary, style);
+
+            // Not enough height: squeeze the band winners onto the same line
+            if !band_lines && !by_band.is_empty() {
+                let bands: Vec<String> = by_band
+                    .iter()
+                    .map(|(band, best)| format!("{} {}", band.short_name(), best))
+                    .collect();
+                let x = inner.x + summary.len() as u16 + 2;
+                let room = inner.width.saturating_sub(summary.len() as u16 + 2) as usize;
+                buf.set_string(x, inner.y, truncate(&bands.join("  "), room), Style::default());
+            }
+        }
+
+        let mut y = inner.y + 1;
+        if band_lines {
+            for (band, best) in by_band {
+                let line = format!("  {}: {}", band.short_name(), best);
+                buf.set_string(inner.x, y, truncate(&line, inner.width as usize), Style::default());
+                y += 1;
+            }
+        }
+
+        // Detection rate per adapter for the selected AP
+        if self.state.normalize && y < inner.y + inner.height {
+            let unit = if self.state.sessions.iter().all(|s| s.has_dwell_data()) {
+                "/s"
+            } else {
+                "/scan"
+            };
+            let rates: Vec<String> = self
+                .state
+                .get_detection_rates()
+                .iter()
+                .map(|(name, rate)| match rate {
+                    Some(r) => format!("{} {:.1}", truncate(name, 10), r),
+                    None => format!("{} -", truncate(name, 10)),
+                })
+                .collect();
+            let line = format!("Rate{}: {}", unit, rates.join("  "));
             buf.set_string(
                 inner.x,
-                inner.y,
-                &summary,
-                Style::default()
-                    .fg(Color::Yellow)
-                    .add_modifier(Modifier::BOLD),
+                y,
+                truncate(&line, inner.width as usize),
+                Style::default().fg(Color::DarkGray),
+            );
+            y += 1;
+        }
+
+        // Link bitrate per signal bucket, when there's a line left
+        if !efficiency.is_empty() && y < inner.y + inner.height {
+            let adapters: Vec<String> = efficiency
+                .iter()
+                .map(|(name, buckets)| {
+                    let rates: Vec<String> =
+                        buckets.iter().map(|(b, mbps)| format!("{}:{:.0}", b, mbps)).collect();
+                    let rates = if rates.is_empty() { "-".to_string() } else { rates.join(" ") };
+                    format!("{} {}", truncate(name, 10), rates)
+                })
+                .collect();
+            let line = format!("Link Mbps by dBm: {}", adapters.join("  "));
+            buf.set_string(
+                inner.x,
+                y,
+                truncate(&line, inner.width as usize),
+                Style::default().fg(Color::DarkGray),
+            );
+            y += 1;
+        }
+
+        // Connect test phases, when there's a line left
+        if !connect.is_empty() && y < inner.y + inner.height {
+            let adapters: Vec<String> = connect
+                .iter()
+                .map(|(name, summary)| match summary {
+                    Some(c) => {
+                        let mut text = format!(
+                            "{} {}+{}+{}={} online {}/{}",
+                            truncate(name, 10),
+                            c.association_ms,
+                            c.handshake_ms,
+                            c.dhcp_ms,
+                            c.association_ms + c.handshake_ms + c.dhcp_ms,
+                            c.online,
+                            c.tests
+                        );
+                        if c.portal > 0 {
+                            text.push_str(&format!(" portal {}/{}", c.portal, c.tests));
+                        }
+                        text
+                    }
+                    None => format!("{} -", truncate(name, 10)),
+                })
+                .collect();
+            let line = format!("Connect ms (assoc+4way+DHCP): {}", adapters.join("  "));
+            buf.set_string(
+                inner.x,
+                y,
+                truncate(&line, inner.width as usize),
+                Style::default().fg(Color::DarkGray),
+            );
+            y += 1;
+        }
+
+        // Ping loss and latency under load, when there's a line left
+        if !load.is_empty() && y < inner.y + inner.height {
+            let ms = |rtt: Option<f32>| rtt.map_or("-".to_string(), |v| format!("{:.0}", v));
+            let adapters: Vec<String> = load
+                .iter()
+                .map(|(name, summary)| match summary {
+                    Some(l) => format!(
+                        "{} {:.1}% {}/{}/{}",
+                        truncate(name, 10),
+                        l.loss_pct,
+                        ms(l.rtt_p50_ms),
+                        ms(l.rtt_p95_ms),
+                        ms(l.rtt_p99_ms)
+                    ),
+                    None => format!("{} -", truncate(name, 10)),
+                })
+                .collect();
+            let line = format!("Under load (loss, p50/p95/p99 ms): {}", adapters.join("  "));
+            buf.set_string(
+                inner.x,
+                y,
+                truncate(&line, inner.width as usize),
+                Style::default().fg(Color::DarkGray),
             );
         }
     }
@@ -424,8 +1731,344 @@ impl<'a> CompareScreen<'a> {
         let inner = block.inner(area);
         block.render(area, buf);
 
-        let help = "[+]add [x]del [←→]sess [↑↓]AP [m]atch [M]etric [e]xp [q]uit";
-        let help_display = truncate(help, inner.width as usize);
-        buf.set_string(inner.x, inner.y, &help_display, Style::default().fg(Color::DarkGray));
+        let state = self.state;
+        let loaded = !state.sessions.is_empty();
+        // Bucket keys are what aligned mode is adjusted with, so they stay on a narrow bar
+        let buckets = Hint::new("[[/]]buckets").enabled(state.aligned);
+        let buckets = if state.aligned { buckets } else { buckets.wide() };
+        let hints = [
+            Hint::new("[+]add"),
+            Hint::new("[x]del").enabled(loaded),
+            Hint::new("[←→]sess").enabled(state.sessions.len() > 1).wide(),
+            Hint::new("[↑↓]AP").enabled(loaded).wide(),
+            Hint::new("[m]atch").value(state.match_by.name()),
+            Hint::new("[M]etric").value(state.metric_name()),
+            Hint::new("[n]orm").enabled(!state.aligned).wide(),
+            Hint::new("[w]in").value(state.window.name()).wide(),
+            Hint::new("[D]espike").value(if state.drop_outliers { "on" } else { "off" }).wide(),
+            Hint::new("[t]ime").wide(),
+            Hint::new("[W]alk").enabled(state.has_checkpoints()).wide(),
+            Hint::new("[⏎]bands").enabled(state.match_by == MatchBy::Group).wide(),
+            buckets,
+            Hint::new("[e]xp").enabled(loaded),
+            Hint::new("[q]uit"),
+        ];
+        HintBar::new(&hints).render(inner, buf);
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::fixtures::{adapter, ap, scan};
+    use crate::data::{LinkStats, UsbLink};
+    use chrono::Utc;
+
+    fn session(interface: &str, readings: &[(&str, i32)]) -> Arc<Session> {
+        let mut session = Session::new(adapter(interface), None);
+        let access_points = readings
+            .iter()
+            .map(|&(bssid, signal_dbm)| ap(bssid, "Home", signal_dbm))
+            .collect();
+        session.scans.push(scan(access_points));
+        Arc::new(session)
+    }
+
+    #[test]
+    fn test_link_efficiency() {
+        let mut state = CompareState::default();
+        let mut linked = session("wlan0", &[("AA:AA:AA:AA:AA:01", -60)]);
+        let scan = Arc::make_mut(&mut linked).scans[0].clone();
+        let scans = [(-55, 300.0), (-58, 200.0), (-64, 100.0)].map(|(signal, mbps)| {
+            let mut scan = scan.clone();
+            scan.link = Some(LinkStats {
+                bssid: "AA:AA:AA:AA:AA:01".to_string(),
+                ssid: "Home".to_string(),
+                signal_dbm: Some(signal),
+                tx_bitrate_mbps: Some(mbps),
+                rx_bitrate_mbps: None,
+            });
+            scan
+        });
+        Arc::make_mut(&mut linked).scans = scans.to_vec();
+        state.add_session(session("wlan1", &[("AA:AA:AA:AA:AA:01", -50)]));
+        assert!(state.link_efficiency().is_empty());
+
+        state.add_session(linked);
+        let efficiency = state.link_efficiency();
+        assert_eq!(efficiency[0].1, vec![]);
+        assert_eq!(efficiency[1].1, vec![(-60, 250.0), (-70, 100.0)]);
+    }
+
+    #[test]
+    fn test_connect_timings() {
+        let mut state = CompareState::default();
+        state.add_session(session("wlan0", &[("AA:AA:AA:AA:AA:01", -60)]));
+        assert!(state.connect_timings().is_empty());
+
+        let mut tested = session("wlan1", &[("AA:AA:AA:AA:AA:01", -50)]);
+        let test = |association_ms, dhcp_ms, reachability| ConnectTiming {
+            timestamp: Utc::now(),
+            network: "Home".to_string(),
+            association_ms,
+            handshake_ms: 40,
+            dhcp_ms,
+            reachability: Some(reachability),
+            portal_url: None,
+        };
+        Arc::make_mut(&mut tested).connect_tests = vec![
+            test(50, 300, Reachability::Online),
+            test(70, 500, Reachability::CaptivePortal),
+        ];
+        state.add_session(tested);
+        let timings = state.connect_timings();
+        assert_eq!(timings[0].1, None);
+        let expected = ConnectSummary {
+            association_ms: 60,
+            handshake_ms: 40,
+            dhcp_ms: 400,
+            tests: 2,
+            online: 1,
+            portal: 1,
+        };
+        assert_eq!(timings[1].1, Some(expected));
+    }
+
+    #[test]
+    fn test_load_results() {
+        let mut state = CompareState::default();
+        state.add_session(session("wlan0", &[("AA:AA:AA:AA:AA:01", -60)]));
+        assert!(state.load_results().is_empty());
+
+        let mut tested = session("wlan1", &[("AA:AA:AA:AA:AA:01", -50)]);
+        let test = |pings_received, p95| LoadTest {
+            timestamp: Utc::now(),
+            target: "192.168.1.1".to_string(),
+            duration_secs: 10,
+            load_mbps: 20,
+            pings_sent: 50,
+            pings_received,
+            rtt_p50_ms: Some(4.0),
+            rtt_p95_ms: p95,
+            rtt_p99_ms: None,
+        };
+        Arc::make_mut(&mut tested).load_tests = vec![test(50, Some(20.0)), test(40, Some(60.0))];
+        state.add_session(tested);
+        let results = state.load_results();
+        assert_eq!(results[0].1, None);
+        let expected = LoadSummary {
+            loss_pct: 10.0,
+            rtt_p50_ms: Some(4.0),
+            rtt_p95_ms: Some(60.0),
+            rtt_p99_ms: None,
+        };
+        assert_eq!(results[1].1, Some(expected));
+    }
+
+    #[test]
+    fn test_firmware_mismatch() {
+        let mut state = CompareState::default();
+        let mut older = session("wlan0", &[("AA:AA:AA:AA:AA:01", -60)]);
+        Arc::make_mut(&mut older).adapter.firmware_version = Some("77.2df8986f.0".to_string());
+        let mut newer = session("wlan0", &[("AA:AA:AA:AA:AA:01", -58)]);
+        Arc::make_mut(&mut newer).adapter.firmware_version = Some("83.e8f84e98.0".to_string());
+        // Unknown firmware can't disagree
+        state.add_session(session("wlan0", &[]));
+        state.add_session(older.clone());
+        assert!(state.firmware_mismatch().is_empty());
+        state.add_session(newer.clone());
+        assert_eq!(state.firmware_mismatch(), vec!["Intel".to_string()]);
+
+        // A different adapter of the same model
+        let mut state = CompareState::default();
+        Arc::make_mut(&mut newer).adapter.id = Some("mac:aa:bb:cc:dd:ee:ff".to_string());
+        state.add_session(older);
+        state.add_session(newer);
+        assert!(state.firmware_mismatch().is_empty());
+    }
+
+    #[test]
+    fn test_usb_mismatch() {
+        let mut state = CompareState::default();
+        let on = |speed_mbps: u32, port: &str| {
+            let mut session = session("wlan0", &[("AA:AA:AA:AA:AA:01", -60)]);
+            Arc::make_mut(&mut session).adapter.usb =
+                Some(UsbLink { speed_mbps, max_power_ma: Some(500), port: port.to_string() });
+            session
+        };
+        state.add_session(on(5000, "2-1"));
+        // Another root port of the same speed is like for like
+        state.add_session(on(5000, "2-2"));
+        state.add_session(session("wlan0", &[]));
+        assert!(state.usb_mismatch().is_empty());
+        state.add_session(on(480, "1-4.2"));
+        assert_eq!(state.usb_mismatch(), vec!["Intel on USB 3.0 root vs USB 2.0 hub".to_string()]);
+    }
+
+    #[test]
+    fn test_rows_by_firmware() {
+        let mut state = CompareState::default();
+        let with_firmware = |readings: &[(&str, i32)], firmware: &str| {
+            let mut session = session("wlan0", readings);
+            Arc::make_mut(&mut session).adapter.firmware_version =
+                Some(format!("{} ty-a0-gf-a0.uc", firmware));
+            session
+        };
+        state.add_session(with_firmware(&[("AA:AA:AA:AA:AA:01", -60)], "77.2df8986f.0"));
+        state.add_session(with_firmware(&[("AA:AA:AA:AA:AA:01", -50)], "83.e8f84e98.0"));
+        state.add_session(with_firmware(&[("AA:AA:AA:AA:AA:01", -62)], "77.2df8986f.0"));
+
+        // No nodes loaded, so Session goes straight to Firmware
+        state.cycle_rows();
+        assert_eq!(state.rows, CompareRows::Firmware);
+        assert_eq!(
+            state.columns(),
+            vec![
+                ("Intel fw 77.2df8986f.0".to_string(), vec![0, 2]),
+                ("Intel fw 83.e8f84e98.0".to_string(), vec![1]),
+            ]
+        );
+        state.cycle_rows();
+        assert_eq!(state.rows, CompareRows::Session);
+        assert_eq!(state.columns().len(), 3);
+    }
+
+    #[test]
+    fn test_aligned_series() {
+        let walk = |interface: &str, readings: &[(i64, i32)]| {
+            let mut session = session(interface, &[("AA:AA:AA:AA:AA:01", 0)]);
+            let loaded = Arc::make_mut(&mut session);
+            let scan = loaded.scans.remove(0);
+            for &(secs, signal) in readings {
+                let mut scan = scan.clone();
+                scan.timestamp += chrono::Duration::seconds(secs);
+                scan.access_points[0].signal_dbm = signal;
+                loaded.scans.push(scan);
+            }
+            session
+        };
+        let mut state = CompareState {
+            buckets: 2,
+            ..Default::default()
+        };
+        state.add_session(walk("wlan0", &[(0, -60), (10, -70), (100, -50)]));
+        state.add_session(walk("wlan1", &[(0, -80), (50, -40)]));
+
+        // The longest session spans 100s, split in two; its last scan lands in the last bucket
+        let (bucket_secs, series) = state.aligned_series();
+        assert_eq!(bucket_secs, 50);
+        assert_eq!(series[0].1, vec![Some(-65), Some(-50)]);
+        assert_eq!(series[1].1, vec![Some(-80), Some(-40)]);
+
+        state.adjust_buckets(true);
+        let (bucket_secs, series) = state.aligned_series();
+        assert_eq!(bucket_secs, 34);
+        assert_eq!(series[0].1, vec![Some(-65), None, Some(-50)]);
+        assert_eq!(series[1].1, vec![Some(-80), Some(-40), None]);
+    }
+
+    #[test]
+    fn test_aligned_runs() {
+        let turn = |interface: &str, signal: i32, later_secs: i64| {
+            let mut session = session(interface, &[("AA:AA:AA:AA:AA:01", signal)]);
+            let turn = Arc::make_mut(&mut session);
+            turn.scans[0].timestamp += chrono::Duration::seconds(later_secs);
+            turn.run_id = Some("20240115_143000".to_string());
+            session
+        };
+        let mut state = CompareState {
+            buckets: 2,
+            ..Default::default()
+        };
+        state.add_session(turn("wlan0", -60, 0));
+        state.add_session(turn("wlan0", -50, 60));
+
+        // Turns of one run count from the run's first scan, so they interleave
+        let (bucket_secs, series) = state.aligned_series();
+        assert_eq!(bucket_secs, 30);
+        assert_eq!(series[0].1, vec![Some(-60), None]);
+        assert_eq!(series[1].1, vec![None, Some(-50)]);
+    }
+
+    #[test]
+    fn test_checkpoint_table() {
+        let walked = |interface: &str, stops: &[(&str, i32)]| {
+            let mut session = session(interface, &[("AA:AA:AA:AA:AA:01", -60)]);
+            let loaded = Arc::make_mut(&mut session);
+            let scan = loaded.scans.remove(0);
+            for (i, &(name, signal)) in stops.iter().enumerate() {
+                let mut scan = scan.clone();
+                scan.timestamp += chrono::Duration::seconds(10 * i as i64);
+                scan.access_points[0].signal_dbm = signal;
+                loaded.checkpoints.push(crate::data::Checkpoint {
+                    name: name.to_string(),
+                    arrived_at: scan.timestamp,
+                    left_at: scan.timestamp,
+                });
+                loaded.scans.push(scan);
+            }
+            session
+        };
+        let mut state = CompareState::default();
+        state.add_session(walked("wlan0", &[("Desk", -60), ("Hallway", -70)]));
+        state.add_session(walked("wlan1", &[("Garage", -80), ("Desk", -50)]));
+        assert!(state.has_checkpoints());
+
+        let (names, series) = state.checkpoint_table();
+        assert_eq!(names, vec!["Desk", "Hallway", "Garage"]);
+        assert_eq!(series[0].1, vec![Some(-60), Some(-70), None]);
+        assert_eq!(series[1].1, vec![Some(-50), None, Some(-80)]);
+
+        state.toggle_aligned();
+        state.toggle_checkpoints();
+        assert!(state.checkpoints && !state.aligned);
+    }
+
+    #[test]
+    fn test_delta_sort_prefers_idle_aps() {
+        let mut state = CompareState::default();
+        let readings = [
+            ("AA:AA:AA:AA:AA:01", -55),
+            ("AA:AA:AA:AA:AA:02", -65),
+            ("AA:AA:AA:AA:AA:03", -70),
+        ];
+        let mut loaded = session("wlan0", &readings);
+        for (ap, util) in Arc::make_mut(&mut loaded).scans[0].access_points.iter_mut().zip([80, 10])
+        {
+            ap.channel_utilization = Some(util);
+        }
+        state.add_session(loaded);
+        state.add_session(session("wlan1", &readings));
+        assert_eq!(state.utilization("AA:AA:AA:AA:AA:01"), Some(80));
+
+        // Every AP has the same spread, so load decides
+        state.toggle_sort_by_delta();
+        let order: Vec<String> = state.all_aps().into_iter().map(|(bssid, _)| bssid).collect();
+        assert_eq!(order, ["AA:AA:AA:AA:AA:02", "AA:AA:AA:AA:AA:01", "AA:AA:AA:AA:AA:03"]);
+
+        // Most loaded first; unknown load still goes last
+        state.sort.toggle_reverse(CompareSort::Utilization);
+        let order: Vec<String> = state.all_aps().into_iter().map(|(bssid, _)| bssid).collect();
+        assert_eq!(order, ["AA:AA:AA:AA:AA:01", "AA:AA:AA:AA:AA:02", "AA:AA:AA:AA:AA:03"]);
+    }
+
+    #[test]
+    fn test_aggregates_cached_until_inputs_change() {
+        let mut state = CompareState::default();
+        state.add_session(session("wlan0", &[("AA:AA:AA:AA:AA:01", -60)]));
+        state.add_session(session("wlan1", &[("AA:AA:AA:AA:AA:01", -50)]));
+
+        let first = state.aggregates();
+        assert!(Arc::ptr_eq(&first, &state.aggregates()));
+        assert_eq!(state.all_aps().len(), 1);
+        assert!(state.best_adapter().unwrap().starts_with("wlan1"));
+
+        state.add_session(session("wlan2", &[("AA:AA:AA:AA:AA:02", -40)]));
+        assert!(!Arc::ptr_eq(&first, &state.aggregates()));
+        assert_eq!(state.all_aps().len(), 2);
+
+        state.cycle_match();
+        assert_eq!(state.all_aps(), vec![("AA:AA:AA:AA:AA:01".to_string(), "Home".to_string())]);
     }
 }
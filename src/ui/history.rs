@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use chrono::{DateTime, Utc};
 use ratatui::{
     buffer::Buffer,
@@ -6,17 +8,35 @@ use ratatui::{
     widgets::{Block, Borders, Widget},
 };
 
-use crate::data::Session;
-use crate::ui::widgets::SignalGraph;
+use crate::data::outlier::hampel;
+use crate::data::trend::{ap_trend, weekly_slope, SessionTrend};
+use crate::data::{std_dev, ApStats, HostSeries, Session};
+use crate::ui::widgets::{Hint, HintBar, SignalGraph};
+use crate::ui::COMPACT_WIDTH;
 use crate::utils::truncate;
 
+/// Timestamped dBm readings of one AP
+type Readings = Vec<(DateTime<Utc>, i32)>;
+
 /// History screen state
 #[derive(Debug)]
 pub struct HistoryState {
-    pub session: Option<Session>,
+    /// Shared with the live session or Compare rather than copied
+    pub session: Option<Arc<Session>>,
     pub selected_ap_idx: usize,
     pub time_window_mins: u64,
     pub show_average: bool,
+    /// Leave Hampel-filter outliers out of the graph and stats
+    pub drop_outliers: bool,
+    /// Host reading drawn along with the signal
+    pub host_series: HostSeries,
+    /// Saved sessions of the adapter, once loaded for the across-sessions mode
+    pub trends: Option<Vec<SessionTrend>>,
+    /// Adapter whose saved sessions `trends` holds
+    pub trend_adapter: Option<String>,
+    /// Across-sessions mode is on, but the sessions are still loading
+    pub trends_loading: bool,
+    #[allow(dead_code)]
     pub scroll_offset: usize,
 }
 
@@ -27,6 +47,11 @@ impl Default for HistoryState {
             selected_ap_idx: 0,
             time_window_mins: 5,
             show_average: false,
+            drop_outliers: false,
+            host_series: HostSeries::Off,
+            trends: None,
+            trend_adapter: None,
+            trends_loading: false,
             scroll_offset: 0,
         }
     }
@@ -59,6 +84,35 @@ impl HistoryState {
         self.show_average = !self.show_average;
     }
 
+    pub fn toggle_outliers(&mut self) {
+        self.drop_outliers = !self.drop_outliers;
+    }
+
+    /// Next host reading the session has, then none
+    pub fn cycle_host_series(&mut self) {
+        let mut series = self.host_series.next();
+        while series != HostSeries::Off && self.get_host_data_for(series).is_empty() {
+            series = series.next();
+        }
+        self.host_series = series;
+    }
+
+    /// Readings of the host series on show
+    pub fn get_host_data(&self) -> Vec<(DateTime<Utc>, i32)> {
+        self.get_host_data_for(self.host_series)
+    }
+
+    fn get_host_data_for(&self, series: HostSeries) -> Vec<(DateTime<Utc>, i32)> {
+        let Some(session) = &self.session else {
+            return Vec::new();
+        };
+        session
+            .scans
+            .iter()
+            .filter_map(|scan| Some((scan.timestamp, series.value(scan.host.as_ref()?)?)))
+            .collect()
+    }
+
     pub fn get_selected_ap(&self) -> Option<(String, String)> {
         self.session.as_ref().and_then(|s| {
             let aps = s.unique_aps();
@@ -66,6 +120,27 @@ impl HistoryState {
         })
     }
 
+    /// Whether the graph shows the selected AP across saved sessions
+    pub fn across_sessions(&self) -> bool {
+        self.trends.is_some() || self.trends_loading
+    }
+
+    /// Selected AP's stats per saved session, plus the session on screen if it
+    /// isn't saved yet, oldest first
+    pub fn get_trend_data(&self) -> Vec<(DateTime<Utc>, ApStats)> {
+        let (Some(trends), Some((bssid, _))) = (&self.trends, self.get_selected_ap()) else {
+            return Vec::new();
+        };
+        let mut data = ap_trend(trends, &bssid);
+        if let Some(session) = &self.session {
+            if !trends.iter().any(|t| t.started_at == session.started_at) {
+                let current = SessionTrend::from_session(session);
+                data.extend(ap_trend(&[current], &bssid));
+            }
+        }
+        data
+    }
+
     pub fn get_ap_data(&self) -> Vec<(DateTime<Utc>, i32)> {
         let Some(session) = &self.session else {
             return Vec::new();
@@ -85,6 +160,22 @@ impl HistoryState {
             })
             .collect()
     }
+
+    /// Selected AP's readings split into those kept and the outliers left out, which is
+    /// none unless `drop_outliers` is on
+    pub fn get_ap_series(&self) -> (Readings, Readings) {
+        let data = self.get_ap_data();
+        if !self.drop_outliers {
+            return (data, Vec::new());
+        }
+        let signals: Vec<i32> = data.iter().map(|&(_, signal)| signal).collect();
+        let (outliers, kept): (Vec<_>, Vec<_>) =
+            data.into_iter().zip(hampel(&signals)).partition(|&(_, outlier)| outlier);
+        let readings = |flagged: Vec<((DateTime<Utc>, i32), bool)>| -> Readings {
+            flagged.into_iter().map(|(reading, _)| reading).collect()
+        };
+        (readings(kept), readings(outliers))
+    }
 }
 
 /// History screen widget
@@ -100,12 +191,14 @@ impl<'a> HistoryScreen<'a> {
 
 impl<'a> Widget for HistoryScreen<'a> {
     fn render(self, area: Rect, buf: &mut Buffer) {
+        // Short terminals give the stats row to the graph
+        let stats_height = if area.height >= 14 { 2 } else { 0 };
         let chunks = Layout::vertical([
-            Constraint::Length(2), // Header
-            Constraint::Length(2), // AP selector and controls
-            Constraint::Min(8),    // Graph
-            Constraint::Length(2), // Stats
-            Constraint::Length(2), // Footer
+            Constraint::Length(2),            // Header
+            Constraint::Length(2),            // AP selector and controls
+            Constraint::Min(0),               // Graph
+            Constraint::Length(stats_height), // Stats
+            Constraint::Length(2),            // Footer
         ])
         .split(area);
 
@@ -123,7 +216,14 @@ impl<'a> HistoryScreen<'a> {
         let inner = block.inner(area);
         block.render(area, buf);
 
-        let info = if let Some(session) = &self.state.session {
+        let info = if let (Some(session), Some(trends)) = (&self.state.session, &self.state.trends)
+        {
+            format!(
+                "{} | {} saved sessions | across sessions",
+                session.adapter.display_name(),
+                trends.len()
+            )
+        } else if let Some(session) = &self.state.session {
             format!(
                 "{} | {} | {} scans",
                 session.adapter.display_name(),
@@ -152,7 +252,11 @@ impl<'a> HistoryScreen<'a> {
         // AP selector
         let ap_info = if let Some((bssid, ssid)) = self.state.get_selected_ap() {
             let ssid_display = if ssid.is_empty() { "<hidden>" } else { &ssid };
-            format!("AP: {} ({})", truncate(ssid_display, 20), bssid)
+            if area.width >= COMPACT_WIDTH {
+                format!("AP: {} ({})", truncate(ssid_display, 20), bssid)
+            } else {
+                format!("AP: {}", truncate(ssid_display, inner.width as usize / 2))
+            }
         } else {
             "No APs".to_string()
         };
@@ -173,45 +277,146 @@ impl<'a> HistoryScreen<'a> {
         };
         let data_str = if self.state.show_average { "Avg" } else { "Raw" };
 
-        let controls = format!("Time: [{}]   Data: [{}]", time_str, data_str);
+        let mut controls = if self.state.across_sessions() {
+            "Time: [All sessions]   Data: [Session avg]".to_string()
+        } else if self.state.drop_outliers {
+            let dropped = self.state.get_ap_series().1.len();
+            format!("Time: [{}]   Data: [{}]   Outliers: [{} dropped]", time_str, data_str, dropped)
+        } else {
+            format!("Time: [{}]   Data: [{}]", time_str, data_str)
+        };
+        let host = match self.state.host_series {
+            HostSeries::Off => None,
+            HostSeries::CpuTemp => Some("CPU °C"),
+            HostSeries::Battery => Some("Battery %"),
+        };
+        if let (Some(host), false) = (host, self.state.across_sessions()) {
+            controls.push_str(&format!("   Host: [{}]", host));
+        }
         buf.set_string(inner.x, inner.y + 1, &controls, Style::default());
     }
 
     fn render_graph(&self, area: Rect, buf: &mut Buffer) {
-        let block = Block::default()
-            .borders(Borders::LEFT | Borders::RIGHT)
-            .title(" Signal Strength ");
+        let title = match self.state.across_sessions() {
+            true => " Average Signal per Session ",
+            false => " Signal Strength ",
+        };
+        let block = Block::default().borders(Borders::LEFT | Borders::RIGHT).title(title);
         let inner = block.inner(area);
         block.render(area, buf);
 
-        let data = self.state.get_ap_data();
+        if self.state.trends_loading {
+            let message = "Loading saved sessions...";
+            buf.set_string(inner.x, inner.y + inner.height / 2, message, Style::default());
+            return;
+        }
+        if self.state.trends.is_some() {
+            let trend = self.state.get_trend_data();
+            let data: Vec<(DateTime<Utc>, i32)> =
+                trend.iter().map(|(started, stats)| (*started, stats.avg)).collect();
+            let mut graph = SignalGraph::new(&data).time_window(u64::MAX).time_format("%m-%d");
+            if let Some((last, _)) = data.last() {
+                graph = graph.end(*last);
+            }
+            graph.render(inner, buf);
+            return;
+        }
+
+        let (data, outliers) = self.state.get_ap_series();
         let time_window = if self.state.time_window_mins == 0 {
             u64::MAX
         } else {
             self.state.time_window_mins
         };
 
-        SignalGraph::new(&data)
+        // Anchor the window at the last scan so saved sessions still show data
+        let mut graph = SignalGraph::new(&data)
             .time_window(time_window)
             .show_average(self.state.show_average)
-            .render(inner, buf);
+            .excluded(&outliers);
+        if let Some(session) = &self.state.session {
+            graph = graph.markers(&session.markers);
+        }
+        let host = self.state.get_host_data();
+        if !host.is_empty() {
+            graph = graph.secondary(&host, self.state.host_series.unit());
+        }
+        if let Some(last) = self.state.session.as_ref().and_then(|s| s.scans.last()) {
+            graph = graph.end(last.timestamp);
+        }
+        graph.render(inner, buf);
     }
 
     fn render_stats(&self, area: Rect, buf: &mut Buffer) {
         let block = Block::default().borders(Borders::LEFT | Borders::RIGHT);
         let inner = block.inner(area);
         block.render(area, buf);
+        if inner.height == 0 {
+            return;
+        }
 
-        if let Some((bssid, _)) = self.state.get_selected_ap() {
-            if let Some(session) = &self.state.session {
-                if let Some(stats) = session.ap_stats(&bssid) {
-                    let stats_str = format!(
-                        "Avg: {}  Min: {}  Max: {}  Readings: {}",
-                        stats.avg, stats.min, stats.max, stats.count
-                    );
-                    buf.set_string(inner.x, inner.y, &stats_str, Style::default());
-                }
+        if self.state.trends.is_some() {
+            let trend = self.state.get_trend_data();
+            if let (Some((_, first)), Some((_, last))) = (trend.first(), trend.last()) {
+                let best = trend.iter().map(|(_, s)| s.avg).max().unwrap_or(last.avg);
+                let worst = trend.iter().map(|(_, s)| s.avg).min().unwrap_or(last.avg);
+                // The fitted slope shows slow decay that session-to-session noise hides
+                let slope = weekly_slope(&trend)
+                    .map(|slope| format!("  Trend: {:+.1} dB/wk", slope))
+                    .unwrap_or_default();
+                let text = format!(
+                    "Sessions: {}  First: {}  Last: {}  Change: {:+}{}  Best: {}  Worst: {}",
+                    trend.len(),
+                    first.avg,
+                    last.avg,
+                    last.avg - first.avg,
+                    slope,
+                    best,
+                    worst
+                );
+                let text = truncate(&text, inner.width as usize);
+                buf.set_string(inner.x, inner.y, &text, Style::default());
             }
+            return;
+        }
+
+        let (Some((bssid, _)), Some(session)) =
+            (self.state.get_selected_ap(), &self.state.session)
+        else {
+            return;
+        };
+        // Totals cover every reading, so without outliers the stats are recomputed
+        let stats = if self.state.drop_outliers {
+            let signals: Vec<i32> =
+                self.state.get_ap_series().0.iter().map(|&(_, signal)| signal).collect();
+            let sd = std_dev(&signals) as f64;
+            ApStats::from_signals(signals).map(|stats| (stats, sd))
+        } else {
+            let totals = session.totals.get(&bssid);
+            totals.and_then(|t| Some((t.stats()?, t.std_dev())))
+        };
+        if let Some((stats, sd)) = stats {
+            let stats_str = if area.width >= COMPACT_WIDTH {
+                format!(
+                    "Avg: {}  Med: {}  P10: {}  P90: {}  Min: {}  Max: {}  SD: {:.1}  \
+                     Readings: {}",
+                    stats.avg,
+                    stats.median,
+                    stats.p10,
+                    stats.p90,
+                    stats.min,
+                    stats.max,
+                    sd,
+                    stats.count
+                )
+            } else {
+                format!(
+                    "Avg: {}  Min: {}  Max: {}  n={}",
+                    stats.avg, stats.min, stats.max, stats.count
+                )
+            };
+            let stats_display = truncate(&stats_str, inner.width as usize);
+            buf.set_string(inner.x, inner.y, &stats_display, Style::default());
         }
     }
 
@@ -220,7 +425,23 @@ impl<'a> HistoryScreen<'a> {
         let inner = block.inner(area);
         block.render(area, buf);
 
-        let help = "[↑↓]AP [w]indow [d]ata [e]xport [q]uit";
-        buf.set_string(inner.x, inner.y, help, Style::default().fg(Color::DarkGray));
+        let window = match self.state.time_window_mins {
+            0 => "all".to_string(),
+            mins => format!("{}m", mins),
+        };
+        let data = if self.state.show_average { "avg" } else { "raw" };
+        let loaded = self.state.session.is_some();
+        let despike = self.state.drop_outliers;
+        let hints = [
+            Hint::new("[↑↓]AP").enabled(loaded),
+            Hint::new("[w]indow").value(window),
+            Hint::new("[d]ata").value(data),
+            Hint::new("[D]espike").value(if despike { "on" } else { "off" }).wide(),
+            Hint::new("[H]ost").value(self.state.host_series.name()).wide(),
+            Hint::new("[a]cross sessions").enabled(loaded).wide(),
+            Hint::new("[e]xport").enabled(loaded),
+            Hint::new("[q]uit"),
+        ];
+        HintBar::new(&hints).render(inner, buf);
     }
 }
@@ -1,13 +1,22 @@
 use ratatui::{
     buffer::Buffer,
     layout::{Constraint, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Color, Style},
     widgets::{Block, Borders, StatefulWidget, Widget},
 };
 
-use crate::data::{AccessPoint, Adapter, FrequencyFilter, SortBy};
-use std::collections::HashSet;
-use crate::ui::widgets::{ApList, ApListState};
+use chrono::{DateTime, Utc};
+use crossterm::event::KeyCode;
+
+use crate::data::{
+    default_columns, twin_suspects, AccessPoint, Adapter, ApColumn, CapabilityFilter,
+    ColumnConfig, FrequencyFilter, ScanTrigger, Session, SortBy, SortOrder, TimerMode, TwinSuspect,
+    TypeFilter,
+};
+use crate::scanner::ScanError;
+use std::collections::{HashMap, HashSet};
+use crate::ui::widgets::{ApList, ApListState, Hint, HintBar, SignalGraph};
+use crate::ui::{COMPACT_WIDTH, NARROW_WIDTH};
 use crate::utils::{format_timer, truncate};
 
 /// Live scan screen state
@@ -20,15 +29,62 @@ pub struct LiveState {
     pub auto_scan_interval: u64,
     pub timer_target_secs: Option<u64>,
     pub elapsed_secs: u64,
+    pub timer_mode: TimerMode,
     pub show_channel: bool,
     pub show_band: bool,
     pub highlight_best: bool,
     pub frequency_filter: FrequencyFilter,
-    pub sort_by: SortBy,
-    pub last_scan_error: Option<String>,
+    pub capability_filter: CapabilityFilter,
+    /// Only list APs, mesh points or ad-hoc networks
+    pub type_filter: TypeFilter,
+    /// Sort keys in priority order, never empty
+    pub sort: SortOrder<SortBy>,
+    pub last_scan_error: Option<ScanError>,
+    /// Consecutive failed scans
+    pub scan_failures: u32,
     pub scanning: bool,
+    /// Auto-scan suspended because the terminal lost focus
+    pub suspended: bool,
+    /// Selected interface has disappeared (adapter unplugged)
+    pub adapter_missing: bool,
     /// Session-level excluded APs (by BSSID)
     pub session_excluded_bssids: HashSet<String>,
+    /// Collapse co-located multi-band radios into one row
+    pub grouped: bool,
+    /// Group keys currently expanded
+    pub expanded_groups: HashSet<String>,
+    /// Graph the selected AP's signal below the list
+    pub split: bool,
+    /// BSSID of the AP whose signal sets the proximity beep rate
+    pub geiger: Option<String>,
+    /// NetworkManager profile connect tests join, if configured
+    pub connect_network: Option<String>,
+    /// A connect test is running
+    pub connect_testing: bool,
+    /// Host load tests ping, if configured
+    pub load_target: Option<String>,
+    /// A load test is running
+    pub load_testing: bool,
+    /// An interface reset is running
+    pub resetting: bool,
+    /// Hidden SSIDs sent as directed probe requests on each scan
+    pub probe_ssids: Vec<String>,
+    /// Replay progress: scans shown, total, speed
+    pub replay: Option<(usize, usize, f64)>,
+    /// Other probe locations scanning alongside: healthy, total
+    pub nodes: Option<(usize, usize)>,
+    /// Latest synchronized trigger, with the slowest adapter's start latency
+    pub sync: Option<ScanTrigger>,
+    /// SSID watched for evil twins
+    pub pinned_ssid: Option<String>,
+    /// BSSIDs of the pinned SSID that look like impostors, as of the last scan
+    pub twin_suspects: Vec<TwinSuspect>,
+    /// AP list columns in display order
+    pub columns: Vec<ColumnConfig>,
+    /// Noise floor per frequency from the last scan's survey data
+    pub noise_floor: HashMap<u32, i32>,
+    /// List columns after the first scrolled out of view on narrow terminals
+    pub column_scroll: usize,
 }
 
 impl Default for LiveState {
@@ -41,14 +97,38 @@ impl Default for LiveState {
             auto_scan_interval: 5,
             timer_target_secs: Some(300),
             elapsed_secs: 0,
+            timer_mode: TimerMode::Countdown,
             show_channel: true,
             show_band: true,
             highlight_best: true,
             frequency_filter: FrequencyFilter::All,
-            sort_by: SortBy::Signal,
+            capability_filter: CapabilityFilter::All,
+            type_filter: TypeFilter::All,
+            sort: SortOrder::new(SortBy::Signal),
             last_scan_error: None,
+            scan_failures: 0,
             scanning: false,
+            suspended: false,
+            adapter_missing: false,
             session_excluded_bssids: HashSet::new(),
+            grouped: false,
+            expanded_groups: HashSet::new(),
+            split: false,
+            geiger: None,
+            connect_network: None,
+            connect_testing: false,
+            load_target: None,
+            load_testing: false,
+            resetting: false,
+            probe_ssids: Vec::new(),
+            replay: None,
+            nodes: None,
+            sync: None,
+            pinned_ssid: None,
+            twin_suspects: Vec::new(),
+            columns: default_columns(),
+            noise_floor: HashMap::new(),
+            column_scroll: 0,
         }
     }
 }
@@ -58,6 +138,10 @@ impl LiveState {
         self.auto_scan = !self.auto_scan;
     }
 
+    pub fn toggle_timer_mode(&mut self) {
+        self.timer_mode = self.timer_mode.next();
+    }
+
     pub fn toggle_channel(&mut self) {
         self.show_channel = !self.show_channel;
     }
@@ -77,6 +161,18 @@ impl LiveState {
         self.ap_list_state.offset = 0;
     }
 
+    pub fn cycle_capability(&mut self) {
+        self.capability_filter = self.capability_filter.next();
+        self.ap_list_state.selected = 0;
+        self.ap_list_state.offset = 0;
+    }
+
+    pub fn cycle_type(&mut self) {
+        self.type_filter = self.type_filter.next();
+        self.ap_list_state.selected = 0;
+        self.ap_list_state.offset = 0;
+    }
+
     /// Exclude AP for this session only
     pub fn exclude_session(&mut self, bssid: &str) {
         self.session_excluded_bssids.insert(bssid.to_string());
@@ -84,19 +180,215 @@ impl LiveState {
         self.ap_list_state.offset = 0;
     }
 
-    /// Get the currently selected AP
+    pub fn toggle_grouped(&mut self) {
+        self.grouped = !self.grouped;
+        self.ap_list_state.selected = 0;
+        self.ap_list_state.offset = 0;
+    }
+
+    pub fn toggle_split(&mut self) {
+        self.split = !self.split;
+    }
+
+    /// Start beeping for the selected AP, or stop
+    pub fn toggle_geiger(&mut self) {
+        self.geiger = match self.geiger {
+            Some(_) => None,
+            None => self.get_selected_ap().map(|ap| ap.bssid.clone()),
+        };
+    }
+
+    /// Latest signal of the AP proximity mode follows, if the last scan saw it
+    pub fn geiger_signal(&self) -> Option<i32> {
+        let bssid = self.geiger.as_ref()?;
+        self.access_points
+            .iter()
+            .find(|ap| &ap.bssid == bssid)
+            .map(|ap| ap.signal_dbm)
+    }
+
+    /// Expand or collapse the selected group row
+    pub fn toggle_expand_selected(&mut self) {
+        let rows = self.ap_list().rows();
+        let Some(key) = rows
+            .get(self.ap_list_state.selected)
+            .and_then(|row| row.group_key())
+            .map(str::to_string)
+        else {
+            return;
+        };
+        if !self.expanded_groups.remove(&key) {
+            self.expanded_groups.insert(key);
+        }
+    }
+
+    /// AP list widget configured from the current view settings
+    pub fn ap_list(&self) -> ApList<'_> {
+        ApList::new(&self.access_points)
+            .show_channel(self.show_channel)
+            .show_band(self.show_band)
+            .highlight_best(self.highlight_best)
+            .filter(self.frequency_filter)
+            .capability(self.capability_filter)
+            .bss_type(self.type_filter)
+            .sort(self.sort.keys())
+            .excluded(&self.session_excluded_bssids)
+            .suspects(&self.twin_suspects)
+            .grouped(self.grouped)
+            .expanded(&self.expanded_groups)
+            .columns(&self.columns)
+            .noise_floor(&self.noise_floor)
+    }
+
+    /// AP list for a screen `width` wide; narrow screens give the channel and band
+    /// columns to the signal bar
+    pub fn ap_list_for(&self, width: u16) -> ApList<'_> {
+        let list = self.ap_list().column_scroll(self.column_scroll);
+        if width < NARROW_WIDTH {
+            list.show_channel(false).show_band(false)
+        } else {
+            list
+        }
+    }
+
+    /// Number of visible rows in the AP list
+    pub fn row_count(&self) -> usize {
+        self.ap_list().rows().len()
+    }
+
+    /// Get the currently selected AP (strongest radio for a group row)
     pub fn get_selected_ap(&self) -> Option<&AccessPoint> {
-        let filtered: Vec<_> = self.access_points.iter()
-            .filter(|ap| !self.session_excluded_bssids.contains(&ap.bssid))
-            .filter(|ap| self.frequency_filter.matches(ap.band()))
-            .collect();
-        filtered.get(self.ap_list_state.selected).copied()
+        self.ap_list()
+            .rows()
+            .get(self.ap_list_state.selected)
+            .map(|row| row.ap())
+    }
+
+    /// Links of `ap`'s multi-link AP heard in the last scan, 0 if it isn't one
+    pub fn mlo_links(&self, ap: &AccessPoint) -> usize {
+        let Some(mld) = &ap.mld_address else {
+            return 0;
+        };
+        self.access_points
+            .iter()
+            .filter(|other| other.mld_address.as_ref() == Some(mld))
+            .count()
+    }
+
+    /// Watch the selected AP's SSID for evil twins, or stop watching it
+    pub fn toggle_pin(&mut self) {
+        let Some(ssid) = self.get_selected_ap().map(|ap| ap.ssid.clone()) else {
+            return;
+        };
+        if ssid.is_empty() || self.pinned_ssid.as_ref() == Some(&ssid) {
+            self.pinned_ssid = None;
+        } else {
+            self.pinned_ssid = Some(ssid);
+        }
+        self.update_twin_suspects();
+    }
+
+    /// Re-check the last scan for impostors of the pinned SSID
+    pub fn update_twin_suspects(&mut self) {
+        self.twin_suspects = match &self.pinned_ssid {
+            Some(ssid) => twin_suspects(&self.access_points, ssid),
+            None => Vec::new(),
+        };
+    }
+
+    /// Every column for the chooser, shown ones first in list order, with their settings
+    /// (`None` for hidden columns)
+    pub fn column_choices(&self) -> Vec<(ApColumn, Option<ColumnConfig>)> {
+        let mut choices: Vec<(ApColumn, Option<ColumnConfig>)> =
+            self.columns.iter().map(|c| (c.column, Some(*c))).collect();
+        for column in ApColumn::ALL {
+            if !self.columns.iter().any(|c| c.column == column) {
+                choices.push((column, None));
+            }
+        }
+        choices
+    }
+
+    /// Show a hidden column at the end of the list, or hide a shown one (but never the last)
+    pub fn toggle_column(&mut self, column: ApColumn) {
+        if let Some(i) = self.columns.iter().position(|c| c.column == column) {
+            if self.columns.len() > 1 {
+                self.columns.remove(i);
+            }
+        } else {
+            self.columns.push(ColumnConfig::new(column));
+        }
+    }
+
+    /// Show the BSSID column right after the SSID, or hide it
+    pub fn toggle_bssid(&mut self) {
+        if let Some(i) = self.columns.iter().position(|c| c.column == ApColumn::Bssid) {
+            self.columns.remove(i);
+        } else {
+            let after_ssid = self
+                .columns
+                .iter()
+                .position(|c| c.column == ApColumn::Ssid)
+                .map_or(0, |i| i + 1);
+            self.columns.insert(after_ssid, ColumnConfig::new(ApColumn::Bssid));
+        }
+    }
+
+    /// Scroll the list's columns `by` columns right (negative: left) on a screen
+    /// `screen_width` wide, as far as there are columns out of view
+    pub fn scroll_columns(&mut self, by: isize, screen_width: u16) {
+        // The list has a border on either side
+        let max = self.ap_list_for(screen_width).max_column_scroll(screen_width.saturating_sub(2));
+        self.column_scroll = self.column_scroll.min(max).saturating_add_signed(by).min(max);
+    }
+
+    /// Move a shown column `by` places left (negative) or right
+    pub fn move_column(&mut self, column: ApColumn, by: isize) {
+        let Some(i) = self.columns.iter().position(|c| c.column == column) else {
+            return;
+        };
+        let config = self.columns.remove(i);
+        let j = i.saturating_add_signed(by).min(self.columns.len());
+        self.columns.insert(j, config);
+    }
+
+    /// Widen or narrow a shown column by `by` cells, fixing its width. The SSID and
+    /// signal columns start from a typical share of the free space.
+    pub fn resize_column(&mut self, column: ApColumn, by: i16) {
+        let Some(config) = self.columns.iter_mut().find(|c| c.column == column) else {
+            return;
+        };
+        let current = config.width().unwrap_or(match column {
+            ApColumn::Ssid => 16,
+            _ => 24,
+        });
+        config.width = Some(current.saturating_add_signed(by).clamp(2, 80));
+    }
+
+    /// Give a column its default width back
+    pub fn reset_column_width(&mut self, column: ApColumn) {
+        if let Some(config) = self.columns.iter_mut().find(|c| c.column == column) {
+            config.width = None;
+        }
+    }
+
+    pub fn reset_columns(&mut self) {
+        self.columns = default_columns();
     }
 
+    /// Step the first sort key to the next field, keeping the others
     pub fn cycle_sort(&mut self) {
-        self.sort_by = self.sort_by.next();
+        self.sort.set_primary(self.sort.primary().unwrap_or_default().next());
     }
 
+    /// Fraction of the timer used, if a timer is set
+    pub fn timer_progress(&self) -> Option<f64> {
+        self.timer_target_secs
+            .filter(|&t| t > 0)
+            .map(|t| (self.elapsed_secs as f64 / t as f64).min(1.0))
+    }
+
+    #[allow(dead_code)]
     pub fn timer_remaining(&self) -> Option<u64> {
         self.timer_target_secs.map(|t| t.saturating_sub(self.elapsed_secs))
     }
@@ -106,14 +398,27 @@ impl LiveState {
     }
 }
 
+/// Screen height from which the split view has room for its graph
+const SPLIT_MIN_HEIGHT: u16 = 16;
+
 /// Live scan screen widget
 pub struct LiveScreen<'a> {
     state: &'a LiveState,
+    /// Session being recorded, for the split view's graph
+    session: Option<&'a Session>,
 }
 
 impl<'a> LiveScreen<'a> {
     pub fn new(state: &'a LiveState) -> Self {
-        Self { state }
+        Self {
+            state,
+            session: None,
+        }
+    }
+
+    pub fn session(mut self, session: Option<&'a Session>) -> Self {
+        self.session = session;
+        self
     }
 }
 
@@ -130,8 +435,16 @@ impl<'a> Widget for LiveScreen<'a> {
         // Render header
         self.render_header(chunks[0], buf);
 
-        // Render AP list
-        self.render_ap_list(chunks[1], buf);
+        // Render AP list, with the selected AP's graph below it when split
+        if self.state.split && area.height >= SPLIT_MIN_HEIGHT {
+            let [list, graph] =
+                Layout::vertical([Constraint::Percentage(50), Constraint::Percentage(50)])
+                    .areas(chunks[1]);
+            self.render_ap_list(list, buf);
+            self.render_graph(graph, buf);
+        } else {
+            self.render_ap_list(chunks[1], buf);
+        }
 
         // Render footer
         self.render_footer(chunks[2], buf);
@@ -149,16 +462,65 @@ impl<'a> LiveScreen<'a> {
             return;
         }
 
+        // Timer progress drawn over the top border as a thin gauge
+        if let Some(progress) = self.state.timer_progress() {
+            let filled = (progress * inner.width as f64).round() as u16;
+            let style = if progress >= 1.0 {
+                Style::default().fg(Color::Green)
+            } else {
+                Style::default().fg(Color::Cyan)
+            };
+            for x in inner.x..inner.x + filled {
+                buf[(x, area.y)].set_symbol("━").set_style(style);
+            }
+        }
+
         // Line 1: Adapter info
         let adapter_info = if let Some(adapter) = &self.state.adapter {
-            adapter.display_name()
+            if self.state.adapter_missing {
+                format!("{} (UNPLUGGED)", adapter.display_name())
+            } else {
+                adapter.display_name()
+            }
         } else {
             "No adapter detected".to_string()
         };
-        let adapter_display = truncate(&adapter_info, inner.width.saturating_sub(10) as usize);
+        // Active probing transmits, so make it obvious; likewise replayed data
+        let mut probe_tag = String::new();
+        if let Some((done, total, speed)) = self.state.replay {
+            probe_tag.push_str(&format!("REPLAY {}/{} ", done, total));
+            if speed != 1.0 {
+                probe_tag.push_str(&format!("x{} ", speed));
+            }
+        }
+        if !self.state.probe_ssids.is_empty() {
+            probe_tag.push_str(&format!("PROBING {} ", self.state.probe_ssids.len()));
+        }
+        if let Some((healthy, total)) = self.state.nodes {
+            probe_tag.push_str(&format!("+{} NODES ", total));
+            if healthy < total {
+                probe_tag.push_str(&format!("({} failing) ", total - healthy));
+            }
+        }
+        if let Some(trigger) = self.state.sync {
+            probe_tag.push_str(&format!("SYNC {:.0}ms ", trigger.latency_ms));
+        }
+        // Key hints on the right only where there's room to spare
+        let hints = area.width >= COMPACT_WIDTH;
+        let hint_width = if hints { 10 } else { 1 };
+        let adapter_width = inner.width.saturating_sub(hint_width + probe_tag.len() as u16);
+        let adapter_display = truncate(&adapter_info, adapter_width as usize);
         buf.set_string(inner.x, inner.y, &adapter_display, Style::default());
+        if !probe_tag.is_empty() && inner.width >= hint_width + probe_tag.len() as u16 {
+            buf.set_string(
+                inner.x + inner.width + 1 - hint_width - probe_tag.len() as u16,
+                inner.y,
+                &probe_tag,
+                Style::default().fg(Color::Black).bg(Color::Yellow),
+            );
+        }
 
-        if inner.width >= 8 {
+        if hints {
             buf.set_string(
                 inner.x + inner.width.saturating_sub(8),
                 inner.y,
@@ -172,8 +534,12 @@ impl<'a> LiveScreen<'a> {
             let timer = format_timer(
                 std::time::Duration::from_secs(self.state.elapsed_secs),
                 self.state.timer_target_secs.map(std::time::Duration::from_secs),
+                self.state.timer_mode,
             );
-            let auto_status = if self.state.auto_scan {
+            let paused = self.state.suspended || self.state.adapter_missing;
+            let auto_status = if self.state.auto_scan && paused {
+                "Auto: PAUSED".to_string()
+            } else if self.state.auto_scan {
                 format!("Auto: ON {}s", self.state.auto_scan_interval)
             } else {
                 "Auto: OFF".to_string()
@@ -181,14 +547,18 @@ impl<'a> LiveScreen<'a> {
             let ap_count = format!("APs: {}", self.state.access_points.len());
             let scanning = if self.state.scanning { " ⟳" } else { "" };
 
-            let line2 = format!(
-                "Timer: {}  {}  {}{}",
-                timer, auto_status, ap_count, scanning
-            );
-            let line2_display = truncate(&line2, inner.width.saturating_sub(8) as usize);
+            let line2 = if area.width < NARROW_WIDTH {
+                let auto_status = auto_status.replace(": ", ":");
+                let ap_count = ap_count.replace(": ", ":");
+                format!("{} {} {}{}", timer, auto_status, ap_count, scanning)
+            } else {
+                format!("Timer: {}  {}  {}{}", timer, auto_status, ap_count, scanning)
+            };
+            let line2_width = inner.width.saturating_sub(if hints { 8 } else { 0 });
+            let line2_display = truncate(&line2, line2_width as usize);
             buf.set_string(inner.x, inner.y + 1, &line2_display, Style::default());
 
-            if inner.width >= 8 {
+            if hints {
                 buf.set_string(
                     inner.x + inner.width.saturating_sub(8),
                     inner.y + 1,
@@ -201,13 +571,22 @@ impl<'a> LiveScreen<'a> {
         // Show error if any (if there's room for line 3)
         if inner.height >= 3 {
             if let Some(err) = &self.state.last_scan_error {
-                let err_display = truncate(err, inner.width as usize);
+                let message = format!("{}. {}", err, err.remedy());
+                let err_display = truncate(&message, inner.width as usize);
                 buf.set_string(
                     inner.x,
                     inner.y + 2,
                     &err_display,
                     Style::default().fg(Color::Red),
                 );
+            } else if let Some(ssid) = &self.state.pinned_ssid {
+                let (message, style) = match self.state.twin_suspects.len() {
+                    0 => (format!("Watching {} for evil twins", ssid), Color::DarkGray),
+                    1 => (format!("⚠ Possible evil twin of {}", ssid), Color::Red),
+                    n => (format!("⚠ {} possible evil twins of {}", n, ssid), Color::Red),
+                };
+                let display = truncate(&message, inner.width as usize);
+                buf.set_string(inner.x, inner.y + 2, &display, Style::default().fg(style));
             }
         }
     }
@@ -218,44 +597,9 @@ impl<'a> LiveScreen<'a> {
             return;
         }
 
-        // Column header
-        let header_area = Rect::new(area.x, area.y, area.width, 1);
-        let list_area = Rect::new(area.x, area.y + 1, area.width, area.height.saturating_sub(1));
-
-        // Draw header with border
         let block = Block::default().borders(Borders::LEFT | Borders::RIGHT);
-        let header_inner = block.inner(header_area);
-        block.render(header_area, buf);
-
-        if header_inner.width > 0 {
-            let ch_col = if self.state.show_channel { "CH " } else { "" };
-            let band_col = if self.state.show_band { "Band" } else { "" };
-            let header = format!(
-                "{:<15} Signal       {}{} Filter:{}",
-                "SSID",
-                ch_col,
-                band_col,
-                self.state.frequency_filter.name()
-            );
-            let header_display = truncate(&header, header_inner.width as usize);
-            buf.set_string(
-                header_inner.x,
-                header_inner.y,
-                &header_display,
-                Style::default()
-                    .fg(Color::Cyan)
-                    .add_modifier(Modifier::BOLD),
-            );
-        }
-
-        // Draw list
-        if list_area.height == 0 {
-            return;
-        }
-
-        let list_block = Block::default().borders(Borders::LEFT | Borders::RIGHT);
-        let list_inner = list_block.inner(list_area);
-        list_block.render(list_area, buf);
+        let inner = block.inner(area);
+        block.render(area, buf);
 
         // Clone state for rendering (we need to render with borrowed state)
         let mut ap_state = ApListState {
@@ -263,14 +607,43 @@ impl<'a> LiveScreen<'a> {
             offset: self.state.ap_list_state.offset,
         };
 
-        ApList::new(&self.state.access_points)
-            .show_channel(self.state.show_channel)
-            .show_band(self.state.show_band)
-            .highlight_best(self.state.highlight_best)
-            .filter(self.state.frequency_filter)
-            .sort_by(self.state.sort_by)
-            .excluded(&self.state.session_excluded_bssids)
-            .render(list_inner, buf, &mut ap_state);
+        self.state.ap_list_for(area.width).render(inner, buf, &mut ap_state);
+    }
+
+    /// Signal of the selected AP over this session
+    fn render_graph(&self, area: Rect, buf: &mut Buffer) {
+        let selected = self.state.get_selected_ap();
+        let mut title = match selected {
+            Some(ap) if !ap.ssid.is_empty() => format!(" {} ", ap.ssid),
+            Some(ap) => format!(" {} ", ap.bssid),
+            None => " No AP selected ".to_string(),
+        };
+        if let Some(mld) = selected.and_then(|ap| ap.mld_address.as_ref()) {
+            let links = selected.map_or(0, |ap| self.state.mlo_links(ap));
+            title.push_str(&format!("· MLO {} link(s), MLD {} ", links, mld));
+        }
+        let block = Block::default()
+            .borders(Borders::TOP | Borders::LEFT | Borders::RIGHT)
+            .title(title);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let (Some(ap), Some(session)) = (selected, self.session) else {
+            return;
+        };
+        let data: Vec<(DateTime<Utc>, i32)> = session
+            .scans
+            .iter()
+            .flat_map(|scan| {
+                let reading = scan.access_points.iter().find(|a| a.bssid == ap.bssid);
+                reading.map(|a| (scan.timestamp, a.signal_dbm))
+            })
+            .collect();
+        let mut graph = SignalGraph::new(&data).time_window(u64::MAX);
+        if let Some(last) = session.scans.last() {
+            graph = graph.end(last.timestamp);
+        }
+        graph.render(inner, buf);
     }
 
     fn render_footer(&self, area: Rect, buf: &mut Buffer) {
@@ -282,12 +655,185 @@ impl<'a> LiveScreen<'a> {
             return;
         }
 
-        let sort_name = self.state.sort_by.name();
-        let help = format!(
-            "[spc]scan [c]h [b]and [f]req [s]ort:{} [x]clude [e]xp [q]uit",
-            sort_name
-        );
-        let help_display = truncate(&help, inner.width as usize);
-        buf.set_string(inner.x, inner.y, &help_display, Style::default().fg(Color::DarkGray));
+        HintBar::new(&self.hints(area.width)).render(inner, buf);
+    }
+
+    fn hints(&self, width: u16) -> Vec<Hint> {
+        let mut hints: Vec<Hint> = LIVE_KEYS
+            .iter()
+            .filter_map(|key| {
+                let hint = self.hint(key.action, Hint::new(key.hint?), width)?;
+                Some(if key.wide { hint.wide() } else { hint })
+            })
+            .collect();
+        hints.push(Hint::new("[q]uit"));
+        hints
+    }
+
+    /// `hint` with the state of what `action` does; None while it shouldn't be shown
+    fn hint(&self, action: LiveAction, hint: Hint, width: u16) -> Option<Hint> {
+        let state = self.state;
+        let selected = state.get_selected_ap().is_some();
+        let on_off = |on: bool| if on { "on" } else { "off" };
+        let hint = match action {
+            // Replaying, space skips to the next recorded scan
+            LiveAction::Scan => hint.enabled(
+                state.replay.is_some() || !(state.scanning || state.adapter_missing),
+            ),
+            // Only while some columns are out of view
+            LiveAction::ScrollLeft => {
+                let scroll = state.ap_list_for(width).max_column_scroll(width.saturating_sub(2));
+                return (scroll > 0).then_some(hint);
+            }
+            LiveAction::AutoScan => hint.value(if !state.auto_scan {
+                "off"
+            } else if state.suspended || state.adapter_missing {
+                "paused"
+            } else {
+                "on"
+            }),
+            LiveAction::Filter => hint.value(state.frequency_filter.name()),
+            LiveAction::Sort => hint.value(state.sort.label()),
+            LiveAction::Exclude | LiveAction::Detail | LiveAction::Pin => hint.enabled(selected),
+            LiveAction::Geiger => hint.value(on_off(state.geiger.is_some())),
+            LiveAction::Highlight => hint.value(on_off(state.highlight_best)),
+            LiveAction::ConnectTest if state.connect_testing => hint.value("running"),
+            LiveAction::ConnectTest => {
+                hint.enabled(state.connect_network.is_some() && state.adapter.is_some())
+            }
+            LiveAction::LoadTest if state.load_testing => hint.value("running"),
+            LiveAction::LoadTest => {
+                hint.enabled(state.load_target.is_some() && state.adapter.is_some())
+            }
+            LiveAction::Capability => hint.value(state.capability_filter.name()),
+            LiveAction::BssType => hint.value(state.type_filter.name()),
+            LiveAction::Export => hint.enabled(self.session.is_some_and(|s| !s.scans.is_empty())),
+            _ => hint,
+        };
+        Some(hint)
+    }
+}
+
+/// Something a key does on the Live screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LiveAction {
+    Scan,
+    ScrollLeft,
+    ScrollRight,
+    AutoScan,
+    Channel,
+    Band,
+    Filter,
+    Sort,
+    Group,
+    Split,
+    Exclude,
+    Geiger,
+    ConnectTest,
+    LoadTest,
+    Capability,
+    BssType,
+    Detail,
+    Pin,
+    Columns,
+    Bssid,
+    SortOrder,
+    Expand,
+    Timer,
+    TimerMode,
+    Rename,
+    Highlight,
+    Exclusions,
+    Calibrate,
+    Interface,
+    Profile,
+    Setup,
+    Marker,
+    Walk,
+    SyncScans,
+    Alternate,
+    Export,
+    Up,
+    Down,
+}
+
+/// A Live key, what it does and how the footer shows it
+pub struct LiveKey {
+    pub code: KeyCode,
+    pub action: LiveAction,
+    /// Footer text, None when another key's hint covers it
+    hint: Option<&'static str>,
+    /// Dropped first when the footer doesn't fit
+    wide: bool,
+}
+
+const fn key(code: KeyCode, action: LiveAction, hint: &'static str, wide: bool) -> LiveKey {
+    LiveKey { code, action, hint: Some(hint), wide }
+}
+
+const fn unhinted(code: KeyCode, action: LiveAction) -> LiveKey {
+    LiveKey { code, action, hint: None, wide: false }
+}
+
+/// Every Live key, in footer order; keys the footer has no room for come last
+pub const LIVE_KEYS: &[LiveKey] = &[
+    key(KeyCode::Char(' '), LiveAction::Scan, "[spc]scan", false),
+    key(KeyCode::Left, LiveAction::ScrollLeft, "[←→]cols", false),
+    unhinted(KeyCode::Right, LiveAction::ScrollRight),
+    key(KeyCode::Char('a'), LiveAction::AutoScan, "[a]uto", true),
+    key(KeyCode::Char('c'), LiveAction::Channel, "[c]h", true),
+    key(KeyCode::Char('b'), LiveAction::Band, "[b]and", true),
+    key(KeyCode::Char('f'), LiveAction::Filter, "[f]req", false),
+    key(KeyCode::Char('s'), LiveAction::Sort, "[s]ort", false),
+    key(KeyCode::Char('g'), LiveAction::Group, "[g]rp", true),
+    key(KeyCode::Char('v'), LiveAction::Split, "[v]split", true),
+    key(KeyCode::Char('x'), LiveAction::Exclude, "[x]clude", true),
+    key(KeyCode::Char('G'), LiveAction::Geiger, "[G]eiger", true),
+    key(KeyCode::Char('K'), LiveAction::ConnectTest, "[K]connect", true),
+    key(KeyCode::Char('L'), LiveAction::LoadTest, "[L]oad", true),
+    key(KeyCode::Char('M'), LiveAction::Capability, "[M]cap", true),
+    key(KeyCode::Char('B'), LiveAction::BssType, "[B]ss", true),
+    key(KeyCode::Char('d'), LiveAction::Detail, "[d]etail", true),
+    key(KeyCode::Char('n'), LiveAction::Pin, "[n]pin", true),
+    key(KeyCode::Char('o'), LiveAction::Columns, "[o]cols", true),
+    key(KeyCode::Char('S'), LiveAction::Bssid, "[S]bssid", true),
+    key(KeyCode::Char('O'), LiveAction::SortOrder, "[O]rder", true),
+    key(KeyCode::Enter, LiveAction::Expand, "[⏎]expand", true),
+    key(KeyCode::Char('t'), LiveAction::Timer, "[t]imer", true),
+    key(KeyCode::Char('T'), LiveAction::TimerMode, "[T]mode", true),
+    key(KeyCode::Char('r'), LiveAction::Rename, "[r]ename", true),
+    key(KeyCode::Char('h'), LiveAction::Highlight, "[h]ilite", true),
+    key(KeyCode::Char('X'), LiveAction::Exclusions, "[X]cluded", true),
+    key(KeyCode::Char('C'), LiveAction::Calibrate, "[C]alib", true),
+    key(KeyCode::Char('i'), LiveAction::Interface, "[i]face", true),
+    key(KeyCode::Char('p'), LiveAction::Profile, "[p]rofile", true),
+    key(KeyCode::Char('m'), LiveAction::Setup, "[m]setup", true),
+    key(KeyCode::Char('N'), LiveAction::Marker, "[N]mark", true),
+    key(KeyCode::Char('W'), LiveAction::Walk, "[W]alk", true),
+    key(KeyCode::Char('Y'), LiveAction::SyncScans, "[Y]sync", true),
+    key(KeyCode::Char('A'), LiveAction::Alternate, "[A]lt", true),
+    key(KeyCode::Char('e'), LiveAction::Export, "[e]xp", false),
+    unhinted(KeyCode::Up, LiveAction::Up),
+    unhinted(KeyCode::Down, LiveAction::Down),
+];
+
+/// What `code` does on the Live screen
+pub fn live_action(code: KeyCode) -> Option<LiveAction> {
+    LIVE_KEYS.iter().find(|key| key.code == code).map(|key| key.action)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_live_keys() {
+        for key in LIVE_KEYS {
+            assert_eq!(live_action(key.code), Some(key.action), "{:?} bound twice", key.code);
+            if let (KeyCode::Char(c), Some(hint)) = (key.code, key.hint) {
+                let shown = if c == ' ' { "[spc]".to_string() } else { format!("[{}]", c) };
+                assert!(hint.starts_with(&shown), "{} hints {:?}", hint, key.code);
+            }
+        }
     }
 }
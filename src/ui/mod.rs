@@ -1,9 +1,44 @@
+pub mod channels;
 pub mod compare;
 pub mod history;
 pub mod live;
 pub mod popups;
 pub mod widgets;
 
+pub use channels::{ChannelsScreen, ChannelsState};
 pub use compare::{CompareScreen, CompareState};
 pub use history::{HistoryScreen, HistoryState};
 pub use live::{LiveScreen, LiveState};
+
+use ratatui::buffer::Buffer;
+
+/// Smallest terminal the screens are laid out for
+pub const MIN_WIDTH: u16 = 32;
+pub const MIN_HEIGHT: u16 = 12;
+/// Below this width screens drop secondary panels and hints
+pub const COMPACT_WIDTH: u16 = 80;
+/// Below this width (e.g. a tmux split) columns are abbreviated and panels stacked
+pub const NARROW_WIDTH: u16 = 70;
+
+/// Plain-text dump of a rendered frame, one line per row
+pub fn buffer_text(buf: &Buffer) -> String {
+    let area = buf.area;
+    let mut text = String::new();
+    for y in area.top()..area.bottom() {
+        let mut line = String::new();
+        let mut covered = 0;
+        for x in area.left()..area.right() {
+            // Skip the blank cells behind a wide (CJK/emoji) character
+            if covered > 0 {
+                covered -= 1;
+                continue;
+            }
+            let symbol = buf[(x, y)].symbol();
+            covered = crate::utils::display_width(symbol).saturating_sub(1);
+            line.push_str(symbol);
+        }
+        text.push_str(line.trim_end());
+        text.push('\n');
+    }
+    text
+}
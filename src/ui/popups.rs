@@ -1,13 +1,17 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use ratatui::{
     buffer::Buffer,
     layout::{Alignment, Rect},
     style::{Color, Modifier, Style},
+    text::Line,
     widgets::{Block, Borders, Clear, Paragraph, Widget},
 };
 
-use crate::data::{AdapterDirInfo, SessionInfo};
+use crate::data::calibration::Calibration;
+use crate::data::{AdapterDirInfo, SessionInfo, SessionSetup, SessionSummary};
+use crate::utils::{display_width, format_duration, pad, tail_width, truncate, wrap};
 
 /// Centered popup helper
 pub fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
@@ -86,6 +90,98 @@ impl<'a> Widget for Dialog<'a> {
     }
 }
 
+/// Message popup with word wrap that scrolls when the text doesn't fit
+pub struct MessageDialog<'a> {
+    title: &'a str,
+    message: &'a str,
+    scroll: u16,
+}
+
+impl<'a> MessageDialog<'a> {
+    pub fn new(title: &'a str, message: &'a str) -> Self {
+        Self {
+            title,
+            message,
+            scroll: 0,
+        }
+    }
+
+    pub fn scroll(mut self, scroll: u16) -> Self {
+        self.scroll = scroll;
+        self
+    }
+
+    /// Popup area and the wrapped message for a screen of `area`
+    fn layout(&self, area: Rect) -> (Rect, Vec<String>) {
+        let width = (area.width * 7 / 10).max(50).min(area.width);
+        let lines = wrap(self.message, width.saturating_sub(4) as usize);
+        // Borders, a blank line and the OK button around the text, leaving the screen's
+        // top and bottom rows visible
+        let height = (lines.len() as u16 + 4).min(area.height.saturating_sub(2));
+        (centered_rect(width, height, area), lines)
+    }
+
+    /// Message lines that fit in the popup
+    fn visible_lines(popup: Rect) -> u16 {
+        popup.height.saturating_sub(4).max(1)
+    }
+
+    /// Largest useful scroll offset and the page size for `area`
+    pub fn scroll_range(&self, area: Rect) -> (u16, u16) {
+        let (popup, lines) = self.layout(area);
+        let visible = Self::visible_lines(popup);
+        ((lines.len() as u16).saturating_sub(visible), visible)
+    }
+}
+
+impl<'a> Widget for MessageDialog<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let (popup_area, lines) = self.layout(area);
+        let visible = Self::visible_lines(popup_area);
+        let max_scroll = (lines.len() as u16).saturating_sub(visible);
+        let scroll = self.scroll.min(max_scroll);
+
+        Clear.render(popup_area, buf);
+        let mut block = Block::default()
+            .title(format!(" {} ", self.title))
+            .borders(Borders::ALL)
+            .style(Style::default().bg(Color::Black));
+        if max_scroll > 0 {
+            block = block.title_bottom(
+                Line::from(format!(
+                    " [↑↓] {}-{}/{} ",
+                    scroll + 1,
+                    (scroll + visible).min(lines.len() as u16),
+                    lines.len()
+                ))
+                .right_aligned(),
+            );
+        }
+        let inner = block.inner(popup_area);
+        block.render(popup_area, buf);
+        if inner.width < 2 || inner.height == 0 {
+            return;
+        }
+
+        // A one-liner reads best centered, like the plain dialogs
+        let centered = lines.len() == 1;
+        let text_width = inner.width - 2;
+        for (i, line) in lines.iter().skip(scroll as usize).take(visible as usize).enumerate() {
+            let x = if centered {
+                inner.x + 1 + text_width.saturating_sub(display_width(line) as u16) / 2
+            } else {
+                inner.x + 1
+            };
+            buf.set_string(x, inner.y + i as u16, line, Style::default());
+        }
+
+        let ok = "▶ OK";
+        let x = inner.x + inner.width.saturating_sub(display_width(ok) as u16) / 2;
+        let style = Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD);
+        buf.set_string(x, inner.y + inner.height - 1, ok, style);
+    }
+}
+
 /// Input popup for text entry
 pub struct InputPopup<'a> {
     title: &'a str,
@@ -141,11 +237,8 @@ impl<'a> Widget for InputPopup<'a> {
         );
 
         // Draw value
-        let display_value = if self.value.len() > input_width as usize - 2 {
-            &self.value[self.value.len() - input_width as usize + 2..]
-        } else {
-            self.value
-        };
+        let display_value = tail_width(self.value, (input_width as usize).saturating_sub(2));
+        let hidden = display_width(self.value) - display_width(display_value);
         buf.set_string(
             inner.x + 1,
             input_y,
@@ -154,7 +247,9 @@ impl<'a> Widget for InputPopup<'a> {
         );
 
         // Cursor
-        let cursor_x = inner.x + 1 + self.cursor_pos.min(input_width as usize - 1) as u16;
+        let before: String = self.value.chars().take(self.cursor_pos).collect();
+        let column = display_width(&before).saturating_sub(hidden);
+        let cursor_x = inner.x + 1 + column.min((input_width as usize).saturating_sub(1)) as u16;
         buf.set_string(cursor_x, input_y, "▌", Style::default().fg(Color::Yellow));
 
         // Help
@@ -167,21 +262,302 @@ impl<'a> Widget for InputPopup<'a> {
     }
 }
 
+/// Form for the session's antenna and placement notes
+pub struct SetupPopup<'a> {
+    values: &'a [String; 5],
+    field: usize,
+    cursor: usize,
+}
+
+impl<'a> SetupPopup<'a> {
+    pub fn new(values: &'a [String; 5], field: usize, cursor: usize) -> Self {
+        Self { values, field, cursor }
+    }
+}
+
+impl<'a> Widget for SetupPopup<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let width = 60.min(area.width);
+        let height = (SessionSetup::FIELDS.len() as u16 + 4).min(area.height);
+        let popup_area = centered_rect(width, height, area);
+
+        Clear.render(popup_area, buf);
+
+        let block = Block::default()
+            .title(" Session Setup ")
+            .borders(Borders::ALL)
+            .style(Style::default().bg(Color::Black));
+        let inner = block.inner(popup_area);
+        block.render(popup_area, buf);
+
+        let label_width = 17;
+        let value_width = (inner.width as usize).saturating_sub(label_width + 2);
+        for (i, (label, value)) in SessionSetup::FIELDS.iter().zip(self.values).enumerate() {
+            let y = inner.y + i as u16;
+            if y >= inner.y + inner.height.saturating_sub(1) {
+                break;
+            }
+            let selected = i == self.field;
+            let label_style = if selected {
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::Gray)
+            };
+            buf.set_string(inner.x + 1, y, pad(label, label_width), label_style);
+
+            let x = inner.x + 1 + label_width as u16;
+            let shown = tail_width(value, value_width.saturating_sub(1));
+            buf.set_string(x, y, shown, Style::default().fg(Color::White));
+            if selected {
+                let hidden = display_width(value) - display_width(shown);
+                let before: String = value.chars().take(self.cursor).collect();
+                let column = display_width(&before).saturating_sub(hidden);
+                let cursor_x = x + column.min(value_width.saturating_sub(1)) as u16;
+                buf.set_string(cursor_x, y, "▌", Style::default().fg(Color::Yellow));
+            }
+        }
+
+        buf.set_string(
+            inner.x + 1,
+            inner.y + inner.height - 1,
+            "[↑/↓] Field  [Enter] Save  [Esc] Skip",
+            Style::default().fg(Color::DarkGray),
+        );
+    }
+}
+
+/// End-of-session summary popup
+pub struct SummaryPopup<'a> {
+    summary: &'a SessionSummary,
+    saved: Option<&'a Path>,
+}
+
+impl<'a> SummaryPopup<'a> {
+    pub fn new(summary: &'a SessionSummary) -> Self {
+        Self {
+            summary,
+            saved: None,
+        }
+    }
+
+    pub fn saved(mut self, saved: Option<&'a Path>) -> Self {
+        self.saved = saved;
+        self
+    }
+}
+
+impl<'a> Widget for SummaryPopup<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let s = self.summary;
+        let alert_rows = s.alerts.len().min(3) as u16;
+        let height = (9 + s.top_aps.len() as u16 + alert_rows).min(area.height);
+        let width = 60.min(area.width);
+        let popup_area = centered_rect(width, height, area);
+
+        Clear.render(popup_area, buf);
+
+        let block = Block::default()
+            .title(" Session Summary ")
+            .borders(Borders::ALL)
+            .style(Style::default().bg(Color::Black));
+        let inner = block.inner(popup_area);
+        block.render(popup_area, buf);
+
+        if inner.height < 4 || inner.width < 20 {
+            return;
+        }
+        let w = inner.width as usize - 2;
+        let x = inner.x + 1;
+        let help_y = inner.y + inner.height - 1;
+        let mut lines: Vec<(String, Style)> = Vec::new();
+
+        lines.push((
+            format!(
+                "{}  {}  {} scans  {} APs",
+                s.adapter,
+                format_duration(Duration::from_secs(s.duration_secs)),
+                s.scan_count,
+                s.ap_count
+            ),
+            Style::default(),
+        ));
+        let saved = match self.saved {
+            Some(path) => format!("Saved: {}", path.display()),
+            None => "Not saved".to_string(),
+        };
+        lines.push((saved, Style::default().fg(Color::DarkGray)));
+
+        let bands: Vec<String> = s
+            .bands
+            .iter()
+            .map(|b| format!("{}: {} APs best {}", b.band.short_name(), b.ap_count, b.best_avg))
+            .collect();
+        lines.push((bands.join("   "), Style::default()));
+        lines.push((String::new(), Style::default()));
+
+        lines.push((
+            format!("{:<24} {:>4} {:>4} {:>4} {:>5}", "Top SSID", "Avg", "Min", "Max", "Seen"),
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        for ap in &s.top_aps {
+            let ssid = if ap.ssid.is_empty() { "<hidden>" } else { &ap.ssid };
+            lines.push((
+                format!(
+                    "{} {:>4} {:>4} {:>4} {:>5}",
+                    pad(ssid, 24),
+                    ap.avg,
+                    ap.min,
+                    ap.max,
+                    ap.count
+                ),
+                Style::default(),
+            ));
+        }
+
+        if !s.alerts.is_empty() {
+            lines.push((String::new(), Style::default()));
+            for alert in s.alerts.iter().take(alert_rows as usize) {
+                lines.push((format!("⚠ {}", alert), Style::default().fg(Color::Yellow)));
+            }
+        }
+
+        for (i, (line, style)) in lines.iter().enumerate() {
+            let y = inner.y + i as u16;
+            if y >= help_y {
+                break;
+            }
+            buf.set_string(x, y, truncate(line, w), *style);
+        }
+
+        buf.set_string(x, help_y, "[Enter] close", Style::default().fg(Color::DarkGray));
+    }
+}
+
+/// Calibration wizard popup
+pub struct CalibrationPopup<'a> {
+    state: &'a Calibration,
+    selected: usize,
+}
+
+impl<'a> CalibrationPopup<'a> {
+    pub fn new(state: &'a Calibration) -> Self {
+        Self { state, selected: 0 }
+    }
+
+    pub fn selected(mut self, selected: usize) -> Self {
+        self.selected = selected;
+        self
+    }
+}
+
+impl<'a> Widget for CalibrationPopup<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let width = 56.min(area.width);
+        let height = 11.min(area.height);
+        let popup_area = centered_rect(width, height, area);
+
+        Clear.render(popup_area, buf);
+
+        let block = Block::default()
+            .title(" Calibrate ")
+            .borders(Borders::ALL)
+            .style(Style::default().bg(Color::Black));
+        let inner = block.inner(popup_area);
+        block.render(popup_area, buf);
+
+        if inner.height < 7 || inner.width < 20 {
+            return;
+        }
+        let w = inner.width as usize - 2;
+        let cal = self.state;
+
+        let ssid = if cal.ssid.is_empty() { "<hidden>" } else { &cal.ssid };
+        let ap_line = format!("Ref AP: {} ({})", ssid, cal.bssid);
+        buf.set_string(inner.x + 1, inner.y, truncate(&ap_line, w), Style::default());
+
+        let avg = |v: &[i32]| {
+            if v.is_empty() {
+                "--".to_string()
+            } else {
+                format!("{}", v.iter().sum::<i32>() / v.len() as i32)
+            }
+        };
+        let rows = [
+            ("Ref", &cal.reference, cal.reference_scans, &cal.reference_readings),
+            ("Cal", &cal.target, cal.target_scans, &cal.target_readings),
+        ];
+        for (i, (tag, adapter, scans, readings)) in rows.iter().enumerate() {
+            let line = format!(
+                "{} {} {}/{}  avg {}",
+                tag,
+                pad(&adapter.display_name(), 20),
+                scans,
+                cal.rounds,
+                avg(readings)
+            );
+            buf.set_string(
+                inner.x + 1,
+                inner.y + 2 + i as u16,
+                truncate(&line, w),
+                Style::default(),
+            );
+        }
+
+        let help_y = inner.y + inner.height - 1;
+        if !cal.done {
+            buf.set_string(
+                inner.x + 1,
+                inner.y + 5,
+                "Scanning... keep both adapters in place",
+                Style::default().fg(Color::Yellow),
+            );
+            buf.set_string(inner.x + 1, help_y, "[Esc] Cancel", Style::default().fg(Color::DarkGray));
+            return;
+        }
+
+        let result = match cal.offset() {
+            Some(offset) => format!("Offset for {}: {:+} dB", cal.target.display_name(), offset),
+            None => "Reference AP not seen by both adapters".to_string(),
+        };
+        buf.set_string(
+            inner.x + 1,
+            inner.y + 5,
+            truncate(&result, w),
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+        );
+
+        let options = ["Save", "Discard"];
+        let mut x = inner.x + 1;
+        for (i, option) in options.iter().enumerate() {
+            let style = if i == self.selected {
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            let prefix = if i == self.selected { "▶ " } else { "  " };
+            let text = format!("{}{}", prefix, option);
+            buf.set_string(x, inner.y + 7, &text, style);
+            x += text.chars().count() as u16 + 2;
+        }
+        buf.set_string(
+            inner.x + 1,
+            help_y,
+            "[Enter] Select  [Esc] Discard",
+            Style::default().fg(Color::DarkGray),
+        );
+    }
+}
+
 /// File picker browsing level
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Default)]
 pub enum BrowseLevel {
     /// Showing adapter directories
+    #[default]
     Adapters,
     /// Showing sessions in a specific adapter directory
     Sessions { adapter_path: PathBuf, adapter_name: String },
 }
 
-impl Default for BrowseLevel {
-    fn default() -> Self {
-        BrowseLevel::Adapters
-    }
-}
-
 /// File picker state with two-level navigation
 #[derive(Debug, Default)]
 pub struct FilePickerState {
@@ -264,6 +640,7 @@ impl FilePickerState {
     }
 
     /// Get current directory name for display
+    #[allow(dead_code)]
     pub fn current_dir_name(&self) -> Option<&str> {
         match &self.level {
             BrowseLevel::Adapters => None,
@@ -360,12 +737,8 @@ impl<'a> Widget for FilePicker<'a> {
             };
 
             let prefix = if is_selected { "▶ " } else { "  " };
-            let max_len = inner.width as usize - 4;
-            let display = if item.len() > max_len {
-                format!("{}{}...", prefix, &item[..max_len - 3])
-            } else {
-                format!("{}{}", prefix, item)
-            };
+            let max_len = (inner.width as usize).saturating_sub(4);
+            let display = format!("{}{}", prefix, truncate(item, max_len));
             buf.set_string(inner.x + 1, y, &display, style);
         }
 
@@ -0,0 +1,89 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    widgets::Widget,
+};
+
+use crate::utils::{format_duration, signal_color};
+
+/// Width of one bucket column ("-99 ")
+const CELL_WIDTH: u16 = 4;
+
+/// Adapter name and its value per bucket (None: AP not seen)
+pub type AlignedSeries = (String, Vec<Option<i32>>);
+
+/// Per-adapter signal in elapsed-time buckets, one row per adapter
+pub struct AlignedChart<'a> {
+    series: &'a [AlignedSeries],
+    bucket_secs: u64,
+    max_name_width: u16,
+}
+
+impl<'a> AlignedChart<'a> {
+    pub fn new(series: &'a [AlignedSeries]) -> Self {
+        let max_name_width = series.iter().map(|(n, _)| n.len()).max().unwrap_or(10) as u16;
+        Self {
+            series,
+            bucket_secs: 0,
+            // Room for the "elapsed" header
+            max_name_width: max_name_width.clamp(7, 14),
+        }
+    }
+
+    pub fn bucket_secs(mut self, secs: u64) -> Self {
+        self.bucket_secs = secs;
+        self
+    }
+}
+
+impl<'a> Widget for AlignedChart<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.height < 2 || self.series.is_empty() {
+            return;
+        }
+
+        let cells_x = area.x + self.max_name_width + 1;
+        let buckets = self.series.iter().map(|(_, v)| v.len()).max().unwrap_or(0);
+        let visible = (area.width.saturating_sub(self.max_name_width + 1) / CELL_WIDTH) as usize;
+        let dim = Style::default().fg(Color::DarkGray);
+
+        // Header: elapsed time at the start of every other bucket
+        buf.set_string(area.x, area.y, "elapsed", dim);
+        for b in (0..buckets.min(visible)).step_by(2) {
+            let start = std::time::Duration::from_secs(b as u64 * self.bucket_secs);
+            let x = cells_x + b as u16 * CELL_WIDTH;
+            buf.set_string(x, area.y, format_duration(start), dim);
+        }
+
+        for (row, (name, values)) in self.series.iter().enumerate() {
+            let y = area.y + 1 + row as u16;
+            if y >= area.y + area.height {
+                break;
+            }
+
+            let name_display = crate::utils::truncate(name, self.max_name_width as usize);
+            buf.set_string(area.x, y, &name_display, Style::default());
+
+            for (b, value) in values.iter().take(visible).enumerate() {
+                let x = cells_x + b as u16 * CELL_WIDTH;
+                // Highlight the strongest adapter in each bucket
+                let best = self
+                    .series
+                    .iter()
+                    .filter_map(|(_, v)| v.get(b).copied().flatten())
+                    .max();
+                match value {
+                    Some(s) => {
+                        let mut style = Style::default().fg(signal_color(*s));
+                        if self.series.len() > 1 && best == Some(*s) {
+                            style = style.add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+                        }
+                        buf.set_string(x, y, format!("{:>3}", s), style);
+                    }
+                    None => buf.set_string(x, y, "  ·", dim),
+                }
+            }
+        }
+    }
+}
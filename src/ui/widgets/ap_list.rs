@@ -1,14 +1,36 @@
-use std::collections::HashSet;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
 
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
     style::{Color, Modifier, Style},
-    widgets::{Block, StatefulWidget, Widget},
+    widgets::{Block, Scrollbar, ScrollbarOrientation, ScrollbarState, StatefulWidget, Widget},
 };
 
-use crate::data::{AccessPoint, FrequencyFilter, SortBy};
-use crate::utils::{signal_bar_width, signal_color, truncate};
+use crate::data::vendor::vendor_label;
+use crate::data::{
+    default_columns, group_aps, AccessPoint, ApColumn, ApGroup, CapabilityFilter, ColumnConfig,
+    FrequencyFilter, SortBy, SortKey, TwinSuspect, TypeFilter,
+};
+use crate::utils::{
+    display_width, pad, signal_bar_width, signal_color, take_width, truncate,
+};
+
+/// SSID columns kept on narrow terminals before the signal bar is squeezed
+const NARROW_SSID_WIDTH: u16 = 16;
+
+/// Narrowest SSID column
+const MIN_SSID_WIDTH: u16 = 8;
+
+/// Signal value ahead of the bar: "-45 "
+const SIGNAL_VALUE_WIDTH: u16 = 4;
+
+/// Narrowest signal bar while there's room for a readable SSID
+const MIN_BAR_WIDTH: u16 = 10;
+
+/// Age from which an AP's entry is shown dimmed as likely cached
+const STALE_AGE_MS: u64 = 5000;
 
 /// State for the AP list
 #[derive(Debug, Default)]
@@ -37,6 +59,33 @@ impl ApListState {
     }
 }
 
+/// A single row in the AP list
+#[derive(Debug, Clone)]
+pub enum ApRow<'a> {
+    /// A standalone AP, or a radio listed under an expanded group
+    Ap { ap: &'a AccessPoint, member: bool },
+    /// Co-located radios collapsed into one row
+    Group { group: ApGroup<'a>, expanded: bool },
+}
+
+impl<'a> ApRow<'a> {
+    /// The AP this row stands for (strongest radio for groups)
+    pub fn ap(&self) -> &'a AccessPoint {
+        match self {
+            ApRow::Ap { ap, .. } => ap,
+            ApRow::Group { group, .. } => group.best(),
+        }
+    }
+
+    /// Group key if this row is a multi-band group
+    pub fn group_key(&self) -> Option<&str> {
+        match self {
+            ApRow::Group { group, .. } => Some(&group.key),
+            ApRow::Ap { .. } => None,
+        }
+    }
+}
+
 /// Access point list widget
 pub struct ApList<'a> {
     items: &'a [AccessPoint],
@@ -44,9 +93,17 @@ pub struct ApList<'a> {
     show_band: bool,
     highlight_best: bool,
     filter: FrequencyFilter,
-    sort_by: SortBy,
+    capability: CapabilityFilter,
+    bss_type: TypeFilter,
+    sort: &'a [SortKey<SortBy>],
     block: Option<Block<'a>>,
     excluded_bssids: Option<&'a HashSet<String>>,
+    suspects: &'a [TwinSuspect],
+    grouped: bool,
+    expanded: Option<&'a HashSet<String>>,
+    columns: &'a [ColumnConfig],
+    noise_floor: Option<&'a HashMap<u32, i32>>,
+    column_scroll: usize,
 }
 
 impl<'a> ApList<'a> {
@@ -57,12 +114,50 @@ impl<'a> ApList<'a> {
             show_band: true,
             highlight_best: true,
             filter: FrequencyFilter::All,
-            sort_by: SortBy::Signal,
+            capability: CapabilityFilter::All,
+            bss_type: TypeFilter::All,
+            sort: &[],
             block: None,
             excluded_bssids: None,
+            suspects: &[],
+            grouped: false,
+            expanded: None,
+            columns: &[],
+            noise_floor: None,
+            column_scroll: 0,
         }
     }
 
+    /// Columns in display order (the default set when empty)
+    pub fn columns(mut self, columns: &'a [ColumnConfig]) -> Self {
+        self.columns = columns;
+        self
+    }
+
+    /// Columns after the first scrolled out of view while they don't all fit
+    pub fn column_scroll(mut self, columns: usize) -> Self {
+        self.column_scroll = columns;
+        self
+    }
+
+    /// Noise floor per frequency from the last scan, for the SNR column
+    pub fn noise_floor(mut self, noise_floor: &'a HashMap<u32, i32>) -> Self {
+        self.noise_floor = Some(noise_floor);
+        self
+    }
+
+    /// Collapse co-located multi-band radios into one row
+    pub fn grouped(mut self, grouped: bool) -> Self {
+        self.grouped = grouped;
+        self
+    }
+
+    /// Group keys whose member radios are listed below the group row
+    pub fn expanded(mut self, expanded: &'a HashSet<String>) -> Self {
+        self.expanded = Some(expanded);
+        self
+    }
+
     pub fn show_channel(mut self, show: bool) -> Self {
         self.show_channel = show;
         self
@@ -83,26 +178,46 @@ impl<'a> ApList<'a> {
         self
     }
 
+    /// Possible evil twins, whose SSIDs are shown in red
+    pub fn suspects(mut self, suspects: &'a [TwinSuspect]) -> Self {
+        self.suspects = suspects;
+        self
+    }
+
     pub fn filter(mut self, filter: FrequencyFilter) -> Self {
         self.filter = filter;
         self
     }
 
-    pub fn sort_by(mut self, sort_by: SortBy) -> Self {
-        self.sort_by = sort_by;
+    pub fn capability(mut self, capability: CapabilityFilter) -> Self {
+        self.capability = capability;
+        self
+    }
+
+    pub fn bss_type(mut self, bss_type: TypeFilter) -> Self {
+        self.bss_type = bss_type;
+        self
+    }
+
+    /// Sort keys in priority order; strongest signal first without any
+    pub fn sort(mut self, sort: &'a [SortKey<SortBy>]) -> Self {
+        self.sort = sort;
         self
     }
 
+    #[allow(dead_code)]
     pub fn block(mut self, block: Block<'a>) -> Self {
         self.block = Some(block);
         self
     }
 
-    fn filtered_sorted(&self) -> Vec<&AccessPoint> {
+    fn filtered_sorted(&self) -> Vec<&'a AccessPoint> {
         let mut items: Vec<_> = self
             .items
             .iter()
             .filter(|ap| self.filter.matches(ap.band()))
+            .filter(|ap| self.capability.matches(ap))
+            .filter(|ap| self.bss_type.matches(ap.bss_type))
             .filter(|ap| {
                 // Filter out excluded BSSIDs
                 if let Some(excluded) = &self.excluded_bssids {
@@ -113,14 +228,224 @@ impl<'a> ApList<'a> {
             })
             .collect();
 
-        match self.sort_by {
-            SortBy::Signal => items.sort_by(|a, b| b.signal_dbm.cmp(&a.signal_dbm)),
-            SortBy::Ssid => items.sort_by(|a, b| a.ssid.to_lowercase().cmp(&b.ssid.to_lowercase())),
-            SortBy::Channel => items.sort_by(|a, b| a.channel.cmp(&b.channel)),
-        }
+        let signal = [SortKey { by: SortBy::Signal, reverse: false }];
+        let sort = if self.sort.is_empty() { &signal[..] } else { self.sort };
+        // Stable, so APs equal on every key keep their scan order
+        items.sort_by(|a, b| {
+            sort.iter()
+                .map(|key| compare_aps(key, a, b))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(Ordering::Equal)
+        });
 
         items
     }
+
+    /// Rows in display order, after filtering, sorting and grouping
+    pub fn rows(&self) -> Vec<ApRow<'a>> {
+        let items = self.filtered_sorted();
+        if !self.grouped {
+            return items
+                .into_iter()
+                .map(|ap| ApRow::Ap { ap, member: false })
+                .collect();
+        }
+
+        // Items are already sorted, so groups inherit the order of their best member
+        let mut rows = Vec::new();
+        for group in group_aps(&items) {
+            if !group.is_multi() {
+                rows.push(ApRow::Ap {
+                    ap: group.members[0],
+                    member: false,
+                });
+                continue;
+            }
+
+            let expanded = self
+                .expanded
+                .map(|e| e.contains(&group.key))
+                .unwrap_or(false);
+            let mut members = group.members.clone();
+            members.sort_by_key(|ap| ap.frequency_mhz);
+
+            rows.push(ApRow::Group { group, expanded });
+            if expanded {
+                rows.extend(members.into_iter().map(|ap| ApRow::Ap { ap, member: true }));
+            }
+        }
+        rows
+    }
+
+    /// Columns to draw and their widths for `width` cells. Columns switched off with
+    /// `show_channel`/`show_band` are left out, as are sparse ones no listed AP has
+    /// anything for. The SSID and signal columns share what the others leave; on
+    /// narrow terminals the bar gives up width before the SSID does. When even the
+    /// narrowest SSID and signal value don't fit, they keep readable widths and the
+    /// columns overflow to the right.
+    fn full_layout(&self, rows: &[ApRow], width: u16) -> Vec<(ApColumn, u16)> {
+        let defaults;
+        let columns = if self.columns.is_empty() {
+            defaults = default_columns();
+            &defaults
+        } else {
+            self.columns
+        };
+        let shown: Vec<&ColumnConfig> = columns
+            .iter()
+            .filter(|c| match c.column {
+                ApColumn::Channel => self.show_channel,
+                ApColumn::Band => self.show_band,
+                column if column.is_sparse() => rows.iter().any(|row| has_data(column, row.ap())),
+                _ => true,
+            })
+            .collect();
+
+        let flexible = |column| shown.iter().any(|c| c.column == column && c.width().is_none());
+        let (flex_ssid, flex_signal) = (flexible(ApColumn::Ssid), flexible(ApColumn::Signal));
+        let fixed: u16 = shown.iter().filter_map(|c| c.width()).sum();
+        let narrowest = fixed
+            + if flex_ssid { MIN_SSID_WIDTH } else { 0 }
+            + if flex_signal { SIGNAL_VALUE_WIDTH } else { 0 };
+        let free = width.saturating_sub(fixed);
+        let (ssid_width, signal_width) = match (flex_ssid, flex_signal) {
+            _ if narrowest > width => (NARROW_SSID_WIDTH, SIGNAL_VALUE_WIDTH + MIN_BAR_WIDTH),
+            (true, true) => {
+                let signal_min = SIGNAL_VALUE_WIDTH + MIN_BAR_WIDTH;
+                let ssid = if free > signal_min + MIN_SSID_WIDTH {
+                    let readable = NARROW_SSID_WIDTH.min(free - signal_min);
+                    free.saturating_sub(signal_min + MIN_BAR_WIDTH).max(readable)
+                } else {
+                    MIN_SSID_WIDTH.min(free.saturating_sub(SIGNAL_VALUE_WIDTH))
+                };
+                (ssid, free - ssid)
+            }
+            (true, false) => (free, 0),
+            (false, _) => (0, free),
+        };
+
+        shown
+            .iter()
+            .map(|c| {
+                let width = match c.width() {
+                    Some(width) => width,
+                    None if c.column == ApColumn::Ssid => ssid_width,
+                    None => signal_width,
+                };
+                (c.column, width)
+            })
+            .collect()
+    }
+
+    /// `full_layout` scrolled: the first column stays put and the ones after it are
+    /// skipped by `column_scroll`, up to where the rest fit. Also returns how many
+    /// were skipped.
+    fn layout(&self, rows: &[ApRow], width: u16) -> (Vec<(ApColumn, u16)>, usize) {
+        let mut layout = self.full_layout(rows, width);
+        let skip = self.column_scroll.min(scroll_limit(&layout, width));
+        if skip > 0 {
+            layout.drain(1..=skip);
+        }
+        (layout, skip)
+    }
+
+    /// Columns that can be scrolled past in `width` cells (0 when they all fit)
+    pub fn max_column_scroll(&self, width: u16) -> usize {
+        let rows = self.rows();
+        scroll_limit(&self.full_layout(&rows, width), width)
+    }
+
+    /// Column titles lined up with the rows for `width` cells; `filter` is shown beside
+    /// the signal title. Arrows mark columns scrolled out of view on either side.
+    /// Column titles for `width` cells. `position` (selected row, row count) goes in
+    /// the first title, which stays in view while the others scroll.
+    fn header(&self, rows: &[ApRow], width: u16, position: Option<(usize, usize)>) -> String {
+        let mut filter = self.filter.name().to_string();
+        if self.capability != CapabilityFilter::All {
+            filter.push(' ');
+            filter.push_str(self.capability.name());
+        }
+        if self.bss_type != TypeFilter::All {
+            filter.push(' ');
+            filter.push_str(self.bss_type.name());
+        }
+        let (layout, skipped) = self.layout(rows, width);
+        let mut header = String::new();
+        let mut cells = Vec::new();
+        for &(column, column_width) in &layout {
+            let cell = column_width as usize;
+            let mut title = match column {
+                ApColumn::Signal => format!("{} [{}]", column.title(), filter),
+                column => column.title().to_string(),
+            };
+            if let (Some((selected, count)), true) = (position, cells.is_empty()) {
+                title = format!("{} {}/{}", title, selected + 1, count);
+            }
+            // Numbers are right-aligned, so their titles are too
+            let right = matches!(
+                column,
+                ApColumn::Percent | ApColumn::Snr | ApColumn::Channel | ApColumn::Width
+            );
+            if right && cell > title.len() {
+                cells.push(format!("{:>w$} ", title, w = cell - 1));
+            } else {
+                cells.push(pad(&title, cell));
+            }
+        }
+        if skipped > 0 {
+            if let Some(first) = cells.first_mut() {
+                let cell = display_width(first);
+                *first = format!("{}◀", pad(first.trim_end(), cell.saturating_sub(1)));
+            }
+        }
+        for cell in &cells {
+            header.push_str(cell);
+        }
+        let more = layout.iter().map(|&(_, w)| w as u32).sum::<u32>() > width as u32;
+        if more {
+            let shown = take_width(&header, (width as usize).saturating_sub(1));
+            return format!("{}▶", shown);
+        }
+        truncate(header.trim_end(), width as usize)
+    }
+}
+
+/// Columns after the first to skip before the rest of `layout` fits in `width` cells;
+/// the last column is never skipped
+fn scroll_limit(layout: &[(ApColumn, u16)], width: u16) -> usize {
+    let mut total: u32 = layout.iter().map(|&(_, w)| w as u32).sum();
+    let mut skip = 0;
+    while total > width as u32 && skip + 2 < layout.len() {
+        total -= layout[skip + 1].1 as u32;
+        skip += 1;
+    }
+    skip
+}
+
+/// Order of two APs by one sort key. APs without a BSS Load element sort last
+/// either way.
+fn compare_aps(key: &SortKey<SortBy>, a: &AccessPoint, b: &AccessPoint) -> Ordering {
+    match key.by {
+        SortBy::Signal => key.apply(b.signal_dbm.cmp(&a.signal_dbm)),
+        SortBy::Ssid => key.apply(a.ssid.to_lowercase().cmp(&b.ssid.to_lowercase())),
+        SortBy::Channel => key.apply(a.channel.cmp(&b.channel)),
+        SortBy::Band => key.apply(a.band().cmp(&b.band())),
+        SortBy::Utilization => {
+            let (a, b) = (a.channel_utilization, b.channel_utilization);
+            a.is_none().cmp(&b.is_none()).then_with(|| key.apply(a.cmp(&b)))
+        }
+    }
+}
+
+/// Whether a sparse column has anything to show for `ap`
+fn has_data(column: ApColumn, ap: &AccessPoint) -> bool {
+    match column {
+        ApColumn::Util => ap.channel_utilization.is_some(),
+        ApColumn::Roaming => !ap.roaming.is_none(),
+        ApColumn::Type => !ap.bss_type.is_infrastructure(),
+        ApColumn::Warning => ap.is_weak(),
+        _ => true,
+    }
 }
 
 impl<'a> StatefulWidget for ApList<'a> {
@@ -140,65 +465,58 @@ impl<'a> StatefulWidget for ApList<'a> {
             return;
         }
 
-        let items = self.filtered_sorted();
-        if items.is_empty() {
-            if inner.width > 0 {
-                buf.set_string(
-                    inner.x,
-                    inner.y,
-                    "No access points found",
-                    Style::default().fg(Color::DarkGray),
-                );
-            }
-            return;
-        }
-
-        // Find best signal for highlighting
-        let best_signal = items.iter().map(|ap| ap.signal_dbm).max();
+        let rows = self.rows();
 
         // Ensure selection is in bounds
-        if state.selected >= items.len() {
-            state.selected = items.len().saturating_sub(1);
+        if state.selected >= rows.len() {
+            state.selected = rows.len().saturating_sub(1);
         }
 
-        // Calculate visible range
-        let visible_height = inner.height as usize;
+        // The header stays on the first line while the rows under it scroll
+        let visible_height = inner.height.saturating_sub(1) as usize;
+        let overflow = rows.len() > visible_height;
+        let header = self.header(&rows, inner.width, overflow.then_some((state.selected, rows.len())));
+        buf.set_string(
+            inner.x,
+            inner.y,
+            &header,
+            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+        );
+        let list = Rect::new(inner.x, inner.y + 1, inner.width, visible_height as u16);
         if visible_height == 0 {
             return;
         }
+
+        if rows.is_empty() {
+            buf.set_string(
+                list.x,
+                list.y,
+                "No access points found",
+                Style::default().fg(Color::DarkGray),
+            );
+            return;
+        }
+
+        // Find best signal for highlighting
+        let best_signal = rows.iter().map(|row| row.ap().signal_dbm).max();
         state.ensure_visible(visible_height);
 
-        // Layout: SSID (variable) | Signal + Bar | CH | Band
-        // Example: "MyNetwork       -45 ████████████████████████████ 36 5G"
-        let ch_width: u16 = if self.show_channel { 4 } else { 0 }; // " 36 "
-        let band_width: u16 = if self.show_band { 3 } else { 0 }; // "5G "
-        let signal_width: u16 = 4; // "-45 "
-        let min_bar_width: u16 = 10;
-        let min_ssid_width: u16 = 8;
-
-        // Calculate widths safely
-        let suffix_width = ch_width + band_width;
-        let fixed_width = signal_width + suffix_width + min_bar_width;
-        let ssid_width = if inner.width > fixed_width + min_ssid_width {
-            inner.width.saturating_sub(fixed_width + min_bar_width)
-        } else {
-            min_ssid_width.min(inner.width.saturating_sub(signal_width))
-        };
-        let bar_width = inner.width.saturating_sub(ssid_width + signal_width + suffix_width);
+        let (layout, _) = self.layout(&rows, inner.width);
 
-        for (i, ap) in items
+        for (i, row) in rows
             .iter()
             .skip(state.offset)
             .take(visible_height)
             .enumerate()
         {
-            let y = inner.y + i as u16;
+            let y = list.y + i as u16;
 
             // Bounds check for y coordinate
-            if y >= inner.y + inner.height {
+            if y >= list.y + list.height {
                 break;
             }
 
+            let ap = row.ap();
             let is_selected = state.offset + i == state.selected;
             let is_best = self.highlight_best && Some(ap.signal_dbm) == best_signal;
 
@@ -216,53 +534,170 @@ impl<'a> StatefulWidget for ApList<'a> {
                 buf.set_string(x, y, " ", base_style);
             }
 
-            // SSID
-            let ssid_display = if ap.ssid.is_empty() {
-                "<hidden>".to_string()
-            } else {
-                truncate(&ap.ssid, ssid_width as usize)
-            };
-            buf.set_string(inner.x, y, &ssid_display, base_style);
-
-            // Signal value
-            let signal_x = inner.x.saturating_add(ssid_width);
-            if signal_x < line_end {
-                let signal_str = format!("{:>3} ", ap.signal_dbm);
-                let signal_style = if is_best {
-                    base_style.fg(Color::Yellow).add_modifier(Modifier::BOLD)
-                } else {
-                    base_style
+            let mut x = inner.x;
+            for &(column, width) in &layout {
+                if x >= line_end {
+                    break;
+                }
+                let cell_width = width.min(line_end - x);
+                // Text cells keep a blank cell before the next column
+                let mut text = |text: &str, style: Style| {
+                    let text = truncate(text, cell_width.saturating_sub(1) as usize);
+                    buf.set_string(x, y, text, style);
                 };
-                buf.set_string(signal_x, y, &signal_str, signal_style);
-            }
-
-            // Signal bar
-            let bar_x = signal_x.saturating_add(signal_width);
-            if bar_x < line_end && bar_width > 0 {
-                let filled = signal_bar_width(ap.signal_dbm, bar_width);
-                let bar_color = signal_color(ap.signal_dbm);
-                let bar_end = bar_x.saturating_add(bar_width).min(line_end);
-                for x in bar_x..bar_end {
-                    let j = x - bar_x;
-                    let ch = if j < filled { '█' } else { ' ' };
-                    let style = base_style.fg(bar_color);
-                    buf.set_string(x, y, ch.to_string(), style);
+                match column {
+                    // Groups get an expand marker, members show their BSSID
+                    ApColumn::Ssid => {
+                        let ssid_display = match row {
+                            ApRow::Group { expanded, .. } => {
+                                let marker = if *expanded { "▾ " } else { "▸ " };
+                                format!("{}{}", marker, ap.ssid)
+                            }
+                            ApRow::Ap { member: true, .. } => format!("  └ {}", ap.bssid),
+                            ApRow::Ap { .. } if ap.ssid.is_empty() => "<hidden>".to_string(),
+                            ApRow::Ap { .. } => ap.ssid.clone(),
+                        };
+                        let ssid_style = if self.suspects.iter().any(|s| s.bssid == ap.bssid) {
+                            base_style.fg(Color::Red).add_modifier(Modifier::BOLD)
+                        } else {
+                            base_style
+                        };
+                        let ssid_display =
+                            truncate(&ssid_display, cell_width.saturating_sub(1) as usize);
+                        buf.set_string(x, y, &ssid_display, ssid_style);
+                    }
+                    ApColumn::Bssid => text(&ap.bssid, base_style),
+                    ApColumn::Signal => {
+                        let signal_style = if is_best {
+                            base_style.fg(Color::Yellow).add_modifier(Modifier::BOLD)
+                        } else {
+                            base_style
+                        };
+                        text(&format!("{:>3}", ap.signal_dbm), signal_style);
+                        let bar_x = x.saturating_add(SIGNAL_VALUE_WIDTH);
+                        let bar_width = cell_width.saturating_sub(SIGNAL_VALUE_WIDTH);
+                        self.render_bar(row, bar_x, y, bar_width, base_style, buf);
+                    }
+                    ApColumn::Percent => text(&format!("{:>3}%", ap.signal_percent()), base_style),
+                    ApColumn::Snr => {
+                        if let Some(noise) = self.noise_floor.and_then(|n| n.get(&ap.frequency_mhz))
+                        {
+                            text(&format!("{:>3}", ap.signal_dbm - noise), base_style);
+                        }
+                    }
+                    ApColumn::Channel => text(&format!("{:>3}", ap.channel), base_style),
+                    ApColumn::Width => {
+                        if let Some(mhz) = ap.channel_width_mhz {
+                            text(&format!("{:>3}", mhz), base_style);
+                        }
+                    }
+                    ApColumn::Band => text(ap.band().short_name(), base_style),
+                    ApColumn::Security => {
+                        if let Some(security) = ap.security {
+                            text(security.short_name(), base_style);
+                        }
+                    }
+                    ApColumn::Vendor => text(&vendor_label(&ap.bssid), base_style),
+                    // Old entries come from the driver's cache rather than this scan
+                    ApColumn::Age => {
+                        if let Some(ms) = ap.last_seen_ms {
+                            let style = if ms >= STALE_AGE_MS {
+                                base_style.fg(Color::DarkGray)
+                            } else {
+                                base_style
+                            };
+                            text(&format_age(ms), style);
+                        }
+                    }
+                    // Yellow once the AP's channel is busy half the time
+                    ApColumn::Util => {
+                        if let Some(util) = ap.channel_utilization {
+                            let util_style =
+                                if util >= 50 { base_style.fg(Color::Yellow) } else { base_style };
+                            text(&format!("{:>3}%", util), util_style);
+                        }
+                    }
+                    ApColumn::Roaming => {
+                        if !ap.roaming.is_none() {
+                            text(&ap.roaming.label(), base_style.fg(Color::Cyan));
+                        }
+                    }
+                    // Blank for APs so mesh points and ad-hoc networks stand out
+                    ApColumn::Type => {
+                        if !ap.bss_type.is_infrastructure() {
+                            text(ap.bss_type.short_name(), base_style.fg(Color::Magenta));
+                        }
+                    }
+                    // Open, WEP or TKIP-only security, or WPS; the detail popup says which
+                    ApColumn::Warning => {
+                        if ap.is_weak() {
+                            text("⚠", base_style.fg(Color::Yellow));
+                        }
+                    }
                 }
+                x = x.saturating_add(width);
             }
+        }
 
-            // Channel
-            let mut next_x = bar_x.saturating_add(bar_width);
-            if self.show_channel && next_x < line_end {
-                let ch_str = format!("{:>3} ", ap.channel);
-                buf.set_string(next_x, y, &ch_str, base_style);
-                next_x = next_x.saturating_add(ch_width);
-            }
+        // Over the last cell, which the gap after each column leaves blank
+        if overflow {
+            let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+                .begin_symbol(None)
+                .end_symbol(None)
+                .track_symbol(Some("│"))
+                .track_style(Style::default().fg(Color::DarkGray))
+                .thumb_symbol("█");
+            let mut scroll = ScrollbarState::new(rows.len() - visible_height + 1)
+                .viewport_content_length(visible_height)
+                .position(state.offset);
+            scrollbar.render(list, buf, &mut scroll);
+        }
+    }
+}
 
-            // Band
-            if self.show_band && next_x < line_end {
-                let band_str = format!("{}", ap.band().short_name());
-                buf.set_string(next_x, y, &band_str, base_style);
+impl<'a> ApList<'a> {
+    /// Signal bar, or per-band signals for a group
+    fn render_bar(
+        &self,
+        row: &ApRow,
+        bar_x: u16,
+        y: u16,
+        bar_width: u16,
+        base_style: Style,
+        buf: &mut Buffer,
+    ) {
+        if bar_width == 0 {
+            return;
+        }
+        if let ApRow::Group { group, .. } = row {
+            let mut x = bar_x;
+            for (band, signal) in group.band_signals() {
+                let text = format!("{}:{} ", band.short_name(), signal);
+                if x + text.len() as u16 > bar_x + bar_width {
+                    break;
+                }
+                buf.set_string(x, y, &text, base_style.fg(signal_color(signal)));
+                x += text.len() as u16;
+            }
+        } else {
+            let ap = row.ap();
+            let filled = signal_bar_width(ap.signal_dbm, bar_width);
+            let bar_color = signal_color(ap.signal_dbm);
+            for j in 0..bar_width {
+                let ch = if j < filled { '█' } else { ' ' };
+                buf.set_string(bar_x + j, y, ch.to_string(), base_style.fg(bar_color));
             }
         }
     }
 }
+
+/// "12ms", "3.4s", "27s"
+fn format_age(ms: u64) -> String {
+    if ms < 1000 {
+        format!("{}ms", ms)
+    } else if ms < 10_000 {
+        format!("{:.1}s", ms as f64 / 1000.0)
+    } else {
+        format!("{}s", ms / 1000)
+    }
+}
@@ -5,7 +5,7 @@ use ratatui::{
     widgets::Widget,
 };
 
-use crate::utils::{signal_bar_width, signal_color};
+use crate::utils::{pad, signal_bar_width, signal_color};
 
 /// A horizontal bar for signal strength
 pub struct SignalBar {
@@ -23,6 +23,7 @@ impl SignalBar {
         }
     }
 
+    #[allow(dead_code)]
     pub fn show_value(mut self, show: bool) -> Self {
         self.show_value = show;
         self
@@ -74,6 +75,8 @@ impl Widget for SignalBar {
 pub struct ComparisonBar {
     values: Vec<(String, Option<i32>)>, // (name, signal)
     max_name_width: u16,
+    /// Scans behind each value, shown as `n=` after the bar
+    sample_counts: Option<Vec<usize>>,
 }
 
 impl ComparisonBar {
@@ -82,8 +85,14 @@ impl ComparisonBar {
         Self {
             values,
             max_name_width: max_name_width.min(20),
+            sample_counts: None,
         }
     }
+
+    pub fn sample_counts(mut self, counts: Vec<usize>) -> Self {
+        self.sample_counts = Some(counts);
+        self
+    }
 }
 
 impl Widget for ComparisonBar {
@@ -107,16 +116,25 @@ impl Widget for ComparisonBar {
             let y = area.y + i as u16;
 
             // Draw name
-            let name_display = if name.len() > self.max_name_width as usize {
-                format!("{}...", &name[..self.max_name_width as usize - 3])
-            } else {
-                format!("{:width$}", name, width = self.max_name_width as usize)
-            };
+            let name_display = pad(name, self.max_name_width as usize);
             buf.set_string(area.x, y, &name_display, Style::default());
 
+            // Sample count at the right, before the best-star column
+            let count_width = match self.sample_counts.as_ref().and_then(|c| c.get(i)) {
+                Some(count) if area.width > self.max_name_width + 20 => {
+                    let label = format!("n={}", count);
+                    let x = area.x + area.width - 3 - label.len() as u16;
+                    buf.set_string(x, y, &label, Style::default().fg(Color::DarkGray));
+                    label.len() as u16 + 4
+                }
+                _ => 0,
+            };
+
             // Draw signal bar or "N/A"
             let bar_x = area.x + self.max_name_width + 1;
-            let bar_width = area.width.saturating_sub(self.max_name_width + 1);
+            let bar_width = area
+                .width
+                .saturating_sub(self.max_name_width + 1 + count_width);
 
             match signal {
                 Some(s) => {
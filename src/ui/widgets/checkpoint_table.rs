@@ -0,0 +1,86 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    widgets::Widget,
+};
+
+use super::AlignedSeries;
+use crate::utils::{signal_color, truncate};
+
+/// Width of one adapter column
+const CELL_WIDTH: u16 = 10;
+
+/// Width of the checkpoint name column
+const MAX_NAME_WIDTH: usize = 16;
+
+/// Per-adapter signal at each walk-test checkpoint, one row per checkpoint, with the
+/// spread between the strongest and weakest adapter
+pub struct CheckpointTable<'a> {
+    checkpoints: &'a [String],
+    series: &'a [AlignedSeries],
+}
+
+impl<'a> CheckpointTable<'a> {
+    pub fn new(checkpoints: &'a [String], series: &'a [AlignedSeries]) -> Self {
+        Self { checkpoints, series }
+    }
+}
+
+impl<'a> Widget for CheckpointTable<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.height < 2 || self.series.is_empty() {
+            return;
+        }
+
+        let longest = self.checkpoints.iter().map(|n| n.chars().count()).max().unwrap_or(0);
+        let name_width = longest.clamp(10, MAX_NAME_WIDTH);
+        let cells_x = area.x + name_width as u16 + 1;
+        let visible = (area.width.saturating_sub(name_width as u16 + 1) / CELL_WIDTH) as usize;
+        let columns = self.series.len().min(visible);
+        let spread_x = cells_x + columns as u16 * CELL_WIDTH;
+        let dim = Style::default().fg(Color::DarkGray);
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+
+        buf.set_string(area.x, area.y, "checkpoint", dim);
+        for (col, (name, _)) in self.series.iter().take(columns).enumerate() {
+            let x = cells_x + col as u16 * CELL_WIDTH;
+            let header = truncate(name, CELL_WIDTH as usize - 1);
+            buf.set_string(x, area.y, format!("{:>9}", header), bold);
+        }
+        if self.series.len() > 1 && columns < visible {
+            buf.set_string(spread_x, area.y, format!("{:>9}", "spread"), dim);
+        }
+
+        for (row, checkpoint) in self.checkpoints.iter().enumerate() {
+            let y = area.y + 1 + row as u16;
+            if y >= area.y + area.height {
+                break;
+            }
+            buf.set_string(area.x, y, truncate(checkpoint, name_width), Style::default());
+
+            let values: Vec<Option<i32>> =
+                self.series.iter().map(|(_, v)| v.get(row).copied().flatten()).collect();
+            let best = values.iter().flatten().max().copied();
+            let worst = values.iter().flatten().min().copied();
+            for (col, value) in values.iter().take(columns).enumerate() {
+                let x = cells_x + col as u16 * CELL_WIDTH;
+                match value {
+                    Some(s) => {
+                        // Highlight the strongest adapter at each checkpoint
+                        let mut style = Style::default().fg(signal_color(*s));
+                        if self.series.len() > 1 && best == Some(*s) {
+                            style = style.add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+                        }
+                        buf.set_string(x, y, format!("{:>9}", s), style);
+                    }
+                    None => buf.set_string(x, y, format!("{:>9}", "·"), dim),
+                }
+            }
+            let shown = self.series.len() > 1 && columns < visible;
+            if let (true, Some(best), Some(worst)) = (shown, best, worst) {
+                buf.set_string(spread_x, y, format!("{:>9}", best - worst), dim);
+            }
+        }
+    }
+}
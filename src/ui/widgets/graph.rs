@@ -6,19 +6,104 @@ use ratatui::{
     widgets::Widget,
 };
 
-/// A time-series graph for signal strength
+use crate::data::SessionMarker;
+use crate::utils::truncate;
+
+/// Readings that fall in one column of the graph
+#[derive(Debug, Clone, Copy)]
+struct Column {
+    min: i32,
+    max: i32,
+    sum: i64,
+    count: i64,
+    last: i32,
+}
+
+impl Column {
+    fn new(signal: i32) -> Self {
+        Self {
+            min: signal,
+            max: signal,
+            sum: signal as i64,
+            count: 1,
+            last: signal,
+        }
+    }
+
+    fn add(&mut self, signal: i32) {
+        self.min = self.min.min(signal);
+        self.max = self.max.max(signal);
+        self.sum += signal as i64;
+        self.count += 1;
+        self.last = signal;
+    }
+
+    fn avg(&self) -> i32 {
+        (self.sum / self.count) as i32
+    }
+}
+
+/// Bucket time-ordered readings into `width` columns spanning `start..=end`.
+///
+/// Each column keeps a min/max envelope instead of its points, so an 8-hour soak
+/// session costs one pass and no per-point allocation.
+fn columns(
+    data: &[(DateTime<Utc>, i32)],
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    width: usize,
+) -> Vec<Option<Column>> {
+    let mut columns: Vec<Option<Column>> = vec![None; width];
+    for &(timestamp, signal) in data {
+        match columns.get_mut(column_of(timestamp, start, end, width)) {
+            Some(Some(column)) => column.add(signal),
+            Some(slot) => *slot = Some(Column::new(signal)),
+            None => {}
+        }
+    }
+    columns
+}
+
+/// Column of `timestamp` in a graph `width` columns wide spanning `start..=end`
+fn column_of(
+    timestamp: DateTime<Utc>,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    width: usize,
+) -> usize {
+    let range = (end - start).num_seconds() as f32;
+    let last = width.saturating_sub(1);
+    let elapsed = (timestamp - start).num_seconds() as f32;
+    let x = if range > 0.0 { (elapsed / range * last as f32) as usize } else { 0 };
+    x.min(last)
+}
+
+/// A time-series graph for signal strength. Columns holding several readings
+/// show their min-max range as a shaded band behind the plotted value.
 pub struct SignalGraph<'a> {
     data: &'a [(DateTime<Utc>, i32)],
+    excluded: &'a [(DateTime<Utc>, i32)],
+    markers: &'a [SessionMarker],
+    secondary: &'a [(DateTime<Utc>, i32)],
+    secondary_unit: &'a str,
     time_window_mins: u64,
     show_average: bool,
+    end: Option<DateTime<Utc>>,
+    time_format: &'a str,
 }
 
 impl<'a> SignalGraph<'a> {
     pub fn new(data: &'a [(DateTime<Utc>, i32)]) -> Self {
         Self {
             data,
+            excluded: &[],
+            markers: &[],
+            secondary: &[],
+            secondary_unit: "",
             time_window_mins: 5,
             show_average: false,
+            end: None,
+            time_format: "%H:%M",
         }
     }
 
@@ -31,11 +116,43 @@ impl<'a> SignalGraph<'a> {
         self.show_average = show;
         self
     }
+
+    /// Readings left out of `data`, such as outliers, drawn dimmed behind it
+    pub fn excluded(mut self, excluded: &'a [(DateTime<Utc>, i32)]) -> Self {
+        self.excluded = excluded;
+        self
+    }
+
+    /// Timeline notes, drawn as labeled vertical lines
+    pub fn markers(mut self, markers: &'a [SessionMarker]) -> Self {
+        self.markers = markers;
+        self
+    }
+
+    /// A second series on a scale of its own, such as CPU temperature, drawn as dots
+    /// with its range labeled on the right
+    pub fn secondary(mut self, data: &'a [(DateTime<Utc>, i32)], unit: &'a str) -> Self {
+        self.secondary = data;
+        self.secondary_unit = unit;
+        self
+    }
+
+    /// End of the time window (default: now)
+    pub fn end(mut self, end: DateTime<Utc>) -> Self {
+        self.end = Some(end);
+        self
+    }
+
+    /// strftime format of the X-axis labels, five columns wide (default `%H:%M`)
+    pub fn time_format(mut self, format: &'a str) -> Self {
+        self.time_format = format;
+        self
+    }
 }
 
 impl<'a> Widget for SignalGraph<'a> {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        if area.width < 10 || area.height < 5 || self.data.is_empty() {
+        if area.width < 10 || area.height < 4 || self.data.is_empty() {
             if self.data.is_empty() {
                 buf.set_string(
                     area.x,
@@ -58,13 +175,15 @@ impl<'a> Widget for SignalGraph<'a> {
         }
 
         // Filter data by time window
-        let now = Utc::now();
-        let window_start = now - chrono::Duration::minutes(self.time_window_mins as i64);
-        let filtered: Vec<_> = self
-            .data
-            .iter()
-            .filter(|(t, _)| *t >= window_start)
-            .collect();
+        let now = self.end.unwrap_or_else(Utc::now);
+        // Windows too long to represent (u64::MAX for "All") keep everything
+        let window_start = i64::try_from(self.time_window_mins)
+            .ok()
+            .and_then(chrono::Duration::try_minutes)
+            .and_then(|window| now.checked_sub_signed(window));
+        // Readings are in time order, so the window is a suffix
+        let from = window_start.map_or(0, |start| self.data.partition_point(|(t, _)| *t < start));
+        let filtered = &self.data[from..];
 
         if filtered.is_empty() {
             buf.set_string(
@@ -76,9 +195,20 @@ impl<'a> Widget for SignalGraph<'a> {
             return;
         }
 
-        // Calculate Y-axis range (-40 to -90 is typical)
-        let min_signal = filtered.iter().map(|(_, s)| *s).min().unwrap_or(-90);
-        let max_signal = filtered.iter().map(|(_, s)| *s).max().unwrap_or(-40);
+        let time_start = filtered[0].0;
+        let time_end = now;
+        let excluded_from = self.excluded.partition_point(|(t, _)| *t < time_start);
+        let excluded_in_window = &self.excluded[excluded_from..];
+        let excluded = columns(excluded_in_window, time_start, time_end, graph_width as usize);
+        let secondary_from = self.secondary.partition_point(|(t, _)| *t < time_start);
+        let secondary_in_window = &self.secondary[secondary_from..];
+        let secondary = columns(secondary_in_window, time_start, time_end, graph_width as usize);
+        let columns = columns(filtered, time_start, time_end, graph_width as usize);
+
+        // Calculate Y-axis range (-40 to -90 is typical), keeping excluded readings in view
+        let all = || columns.iter().chain(&excluded).flatten();
+        let min_signal = all().map(|c| c.min).min().unwrap_or(-90);
+        let max_signal = all().map(|c| c.max).max().unwrap_or(-40);
         let y_min = (min_signal - 5).max(-100);
         let y_max = (max_signal + 5).min(-20);
         // Ensure y_range is never zero to avoid division by zero
@@ -88,6 +218,10 @@ impl<'a> Widget for SignalGraph<'a> {
         let labels = [y_max, (y_max + y_min) / 2, y_min];
         let gh_safe = graph_height.saturating_sub(1).max(1);
         for (i, &label) in labels.iter().enumerate() {
+            // Two rows only fit the top and bottom labels
+            if i == 1 && graph_height < 3 {
+                continue;
+            }
             let y = area.y + (i as u16 * gh_safe / 2);
             if y < area.y + area.height {
                 buf.set_string(
@@ -128,59 +262,100 @@ impl<'a> Widget for SignalGraph<'a> {
         }
 
         // Draw data points
-        let time_start = filtered.first().unwrap().0;
-        let time_end = now;
-        let time_range = (time_end - time_start).num_seconds() as f32;
-
-        if time_range > 0.0 && graph_width > 0 {
-            // Group points by X position and average if needed
-            let mut columns: Vec<Vec<i32>> = vec![Vec::new(); graph_width as usize];
-
-            let gw_safe = (graph_width as usize).saturating_sub(1).max(1);
-            for (timestamp, signal) in &filtered {
-                let elapsed = (*timestamp - time_start).num_seconds() as f32;
-                let x_pos = ((elapsed / time_range) * gw_safe as f32) as usize;
-                let x_pos = x_pos.min(gw_safe);
-                if x_pos < columns.len() {
-                    columns[x_pos].push(*signal);
-                }
+        let row = |signal: i32| {
+            let y_frac = ((signal - y_min) as f32 / y_range).clamp(0.0, 1.0);
+            let y_pos = gh_safe as f32 * (1.0 - y_frac);
+            let y = area.y + (y_pos.round() as u16).min(gh_safe);
+            y.min(area.y + graph_height.saturating_sub(1))
+        };
+        // Markers, labeled along the top until the next one
+        let marker_style = Style::default().fg(Color::Yellow);
+        let mut shown: Vec<(u16, &str)> = self
+            .markers
+            .iter()
+            .filter(|m| m.timestamp >= time_start && m.timestamp <= time_end)
+            .map(|m| {
+                let x_idx = column_of(m.timestamp, time_start, time_end, graph_width as usize);
+                (graph_x + x_idx as u16, m.note.as_str())
+            })
+            .collect();
+        shown.sort_by_key(|&(x, _)| x);
+        let graph_end = (graph_x + graph_width).min(area.x + area.width);
+        for (idx, &(x, note)) in shown.iter().enumerate() {
+            for y in area.y..area.y + graph_height {
+                buf.set_string(x, y, "┊", marker_style);
             }
+            let next = shown.get(idx + 1).map_or(graph_end, |&(next, _)| next);
+            let room = next.saturating_sub(x + 1) as usize;
+            buf.set_string(x + 1, area.y, truncate(note, room), marker_style);
+        }
 
-            for (x_idx, signals) in columns.iter().enumerate() {
-                if signals.is_empty() {
-                    continue;
-                }
+        for (x_idx, column) in excluded.iter().enumerate() {
+            let Some(column) = column else {
+                continue;
+            };
+            let render_x = graph_x + x_idx as u16;
+            if render_x >= area.x + area.width {
+                break;
+            }
+            for signal in [column.min, column.max] {
+                buf.set_string(render_x, row(signal), "·", Style::default().fg(Color::DarkGray));
+            }
+        }
+        for (x_idx, column) in columns.iter().enumerate() {
+            let Some(column) = column else {
+                continue;
+            };
+            let render_x = graph_x + x_idx as u16;
+            if render_x >= area.x + area.width {
+                break;
+            }
 
-                let signal = if self.show_average {
-                    signals.iter().sum::<i32>() / signals.len().max(1) as i32
-                } else {
-                    *signals.last().unwrap()
-                };
+            let signal = if self.show_average { column.avg() } else { column.last };
+            let color = crate::utils::signal_color(signal);
 
-                let y_frac = ((signal - y_min) as f32 / y_range).clamp(0.0, 1.0);
-                let y_pos = gh_safe as f32 * (1.0 - y_frac);
-                let y = area.y + (y_pos.round() as u16).min(gh_safe);
+            // Min-max envelope of the readings merged into this column
+            for y in row(column.max)..=row(column.min) {
+                buf.set_string(render_x, y, "░", Style::default().fg(color));
+            }
+            buf.set_string(render_x, row(signal), "█", Style::default().fg(color));
+        }
 
-                // Bounds check before rendering
+        // Secondary series over the signal's envelope, but not over its plotted value
+        let lowest = secondary.iter().flatten().map(|c| c.min).min();
+        let highest = secondary.iter().flatten().map(|c| c.max).max();
+        if let (Some(lowest), Some(highest)) = (lowest, highest) {
+            let style = Style::default().fg(Color::Magenta);
+            let range = ((highest - lowest) as f32).max(1.0);
+            for (x_idx, column) in secondary.iter().enumerate() {
+                let Some(column) = column else {
+                    continue;
+                };
                 let render_x = graph_x + x_idx as u16;
-                let render_y = y.min(area.y + graph_height.saturating_sub(1));
-                if render_x < area.x + area.width && render_y < area.y + area.height {
-                    let color = crate::utils::signal_color(signal);
-                    buf.set_string(
-                        render_x,
-                        render_y,
-                        "█",
-                        Style::default().fg(color),
-                    );
+                if render_x >= area.x + area.width {
+                    break;
+                }
+                let y_frac = (column.avg() - lowest) as f32 / range;
+                let y = area.y + (gh_safe as f32 * (1.0 - y_frac)).round() as u16;
+                let plotted = columns[x_idx].map(|c| {
+                    row(if self.show_average { c.avg() } else { c.last })
+                });
+                if plotted != Some(y) {
+                    buf.set_string(render_x, y, "•", style);
                 }
             }
+            for (value, y) in [(highest, area.y), (lowest, area.y + gh_safe)] {
+                let label = format!("{}{}", value, self.secondary_unit);
+                let x = graph_end.saturating_sub(label.chars().count() as u16);
+                buf.set_string(x, y, label, style);
+            }
         }
 
         // Draw time labels on X-axis
         let label_y = axis_y.saturating_add(1);
         if label_y < area.y + area.height && graph_x < area.x + area.width {
-            let start_label = time_start.format("%H:%M").to_string();
-            let end_label = time_end.format("%H:%M").to_string();
+            let start_label = time_start.format(self.time_format).to_string();
+            let end_label = time_end.format(self.time_format).to_string();
             buf.set_string(
                 graph_x,
                 label_y,
@@ -201,3 +376,42 @@ impl<'a> Widget for SignalGraph<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000 + secs, 0).unwrap()
+    }
+
+    #[test]
+    fn test_column_of() {
+        // Five columns over 100 s: each boundary is 25 s along
+        let column = |secs| column_of(at(secs), at(0), at(100), 5);
+        assert_eq!([column(0), column(24), column(25), column(99), column(100)], [0, 0, 1, 3, 4]);
+        assert_eq!(column(150), 4);
+        // A one-column graph keeps every reading
+        assert_eq!(column_of(at(100), at(0), at(100), 1), 0);
+        // So does a graph whose readings all share a timestamp
+        assert_eq!(column_of(at(0), at(0), at(0), 5), 0);
+    }
+
+    #[test]
+    fn test_columns() {
+        let data = [(at(0), -60), (at(10), -70), (at(20), -50), (at(100), -80)];
+        let five = columns(&data, at(0), at(100), 5);
+        assert_eq!(five.len(), 5);
+        // The first three readings share the first column's envelope
+        let first = five[0].unwrap();
+        assert_eq!((first.min, first.max, first.avg(), first.last), (-70, -50, -60, -50));
+        assert_eq!(first.count, 3);
+        assert!(five[1..4].iter().all(Option::is_none));
+        assert_eq!(five[4].unwrap().count, 1);
+
+        let single = columns(&data, at(0), at(100), 1);
+        let column = single[0].unwrap();
+        assert_eq!((column.min, column.max, column.count), (-80, -50, 4));
+        assert!(columns(&data, at(0), at(100), 0).is_empty());
+    }
+}
@@ -0,0 +1,137 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    widgets::Widget,
+};
+
+use crate::utils::display_width;
+
+/// One key of a footer hint bar, such as `[s]ort:signal`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hint {
+    /// Key and label as shown, with the key in brackets: `"[e]xp"`
+    text: &'static str,
+    /// Current mode the key cycles or toggles, shown after the label
+    value: Option<String>,
+    /// Greyed out when the key does nothing right now
+    enabled: bool,
+    /// Dropped first when the bar doesn't fit
+    wide: bool,
+}
+
+impl Hint {
+    pub fn new(text: &'static str) -> Self {
+        Self {
+            text,
+            value: None,
+            enabled: true,
+            wide: false,
+        }
+    }
+
+    pub fn value(mut self, value: impl Into<String>) -> Self {
+        self.value = Some(value.into());
+        self
+    }
+
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// Only shown when the whole bar fits
+    pub fn wide(mut self) -> Self {
+        self.wide = true;
+        self
+    }
+
+    fn width(&self) -> usize {
+        display_width(self.text) + self.value.as_ref().map_or(0, |v| 1 + display_width(v))
+    }
+}
+
+/// Footer line built from a screen's hints: keys that apply are highlighted, the rest
+/// greyed out, and modes shown next to the key that changes them
+pub struct HintBar<'a> {
+    hints: &'a [Hint],
+}
+
+impl<'a> HintBar<'a> {
+    pub fn new(hints: &'a [Hint]) -> Self {
+        Self { hints }
+    }
+
+    /// Hints shown in `width` columns: wide hints are dropped from the right until the
+    /// rest fit, then the bar is cut off at the last whole hint
+    fn visible(&self, width: usize) -> Vec<&'a Hint> {
+        let total = |hints: &[&Hint]| {
+            hints.iter().map(|h| h.width()).sum::<usize>() + hints.len().saturating_sub(1)
+        };
+        let mut hints: Vec<&Hint> = self.hints.iter().collect();
+        while total(&hints) > width {
+            match hints.iter().rposition(|h| h.wide) {
+                Some(idx) => hints.remove(idx),
+                None => break,
+            };
+        }
+        while total(&hints) > width && hints.pop().is_some() {}
+        hints
+    }
+}
+
+impl Widget for HintBar<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.width == 0 || area.height == 0 {
+            return;
+        }
+        let disabled = Style::default().fg(Color::DarkGray);
+        let key_style = Style::default().fg(Color::Cyan);
+        let label_style = Style::default().fg(Color::Gray);
+        let value_style = Style::default().fg(Color::Yellow);
+
+        let mut x = area.x;
+        let mut put = |x: u16, text: &str, style: Style| {
+            buf.set_stringn(x, area.y, text, area.right().saturating_sub(x) as usize, style).0
+        };
+        for hint in self.visible(area.width as usize) {
+            let (key, label) = match hint.text.rfind(']') {
+                Some(end) => hint.text.split_at(end + 1),
+                None => ("", hint.text),
+            };
+            let parts = [
+                (key, if hint.enabled { key_style } else { disabled }),
+                (label, if hint.enabled { label_style } else { disabled }),
+            ];
+            for (text, style) in parts {
+                x = put(x, text, style);
+            }
+            if let Some(value) = &hint.value {
+                let style = if hint.enabled { value_style } else { disabled };
+                x = put(x, &format!(":{}", value), style);
+            }
+            x += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_visible_drops_wide_hints_first() {
+        let hints = [
+            Hint::new("[spc]scan"),
+            Hint::new("[g]rp").wide(),
+            Hint::new("[s]ort").value("signal"),
+            Hint::new("[q]uit"),
+        ];
+        let bar = HintBar::new(&hints);
+        let texts = |width| -> Vec<&str> { bar.visible(width).iter().map(|h| h.text).collect() };
+
+        assert_eq!(texts(40), ["[spc]scan", "[g]rp", "[s]ort", "[q]uit"]);
+        assert_eq!(texts(30), ["[spc]scan", "[s]ort", "[q]uit"]);
+        assert_eq!(texts(20), ["[spc]scan"]);
+    }
+}
@@ -1,7 +1,15 @@
+pub mod aligned_chart;
 pub mod ap_list;
 pub mod bar_chart;
+pub mod checkpoint_table;
 pub mod graph;
+pub mod hint_bar;
+pub mod spectrum;
 
+pub use aligned_chart::{AlignedChart, AlignedSeries};
 pub use ap_list::{ApList, ApListState};
 pub use bar_chart::ComparisonBar;
+pub use checkpoint_table::CheckpointTable;
 pub use graph::SignalGraph;
+pub use hint_bar::{Hint, HintBar};
+pub use spectrum::SpectrumPlot;
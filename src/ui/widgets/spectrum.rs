@@ -0,0 +1,172 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    widgets::Widget,
+};
+
+use crate::data::{AccessPoint, Band};
+use crate::utils::truncate;
+
+/// Weakest and strongest signal on the vertical axis
+const FLOOR_DBM: i32 = -100;
+const CEILING_DBM: i32 = -20;
+/// Columns for the dBm labels left of the plot
+const LABEL_WIDTH: u16 = 4;
+
+const COLORS: [Color; 8] = [
+    Color::Cyan,
+    Color::Green,
+    Color::Yellow,
+    Color::Magenta,
+    Color::LightBlue,
+    Color::LightRed,
+    Color::LightGreen,
+    Color::LightMagenta,
+];
+
+/// Frequency span plotted for a band, in MHz
+fn band_range(band: Band) -> (f32, f32) {
+    match band {
+        Band::TwoPointFourGHz => (2401.0, 2495.0),
+        Band::FiveGHz => (5160.0, 5900.0),
+        Band::SixGHz => (5935.0, 7135.0),
+    }
+}
+
+/// Channels labelled on the axis, with their center frequency
+fn axis_channels(band: Band) -> Vec<(u32, u32)> {
+    match band {
+        Band::TwoPointFourGHz => (1..=13).map(|ch| (ch, 2407 + ch * 5)).collect(),
+        Band::FiveGHz => (36..=64)
+            .chain(100..=144)
+            .chain(149..=177)
+            .step_by(4)
+            .map(|ch| (ch, 5000 + ch * 5))
+            .collect(),
+        Band::SixGHz => (1..=233).step_by(4).map(|ch| (ch, 5950 + ch * 5)).collect(),
+    }
+}
+
+/// Signal vs channel plot in the style of WiFi analyzer apps: each AP is a triangle over
+/// the spectrum its channel width covers, so overlapping networks are easy to spot
+pub struct SpectrumPlot<'a> {
+    aps: &'a [AccessPoint],
+    band: Band,
+    /// Frequency marked with a vertical line, such as the selected channel
+    marker: Option<u32>,
+}
+
+impl<'a> SpectrumPlot<'a> {
+    pub fn new(aps: &'a [AccessPoint], band: Band) -> Self {
+        Self {
+            aps,
+            band,
+            marker: None,
+        }
+    }
+
+    pub fn marker(mut self, frequency_mhz: Option<u32>) -> Self {
+        self.marker = frequency_mhz;
+        self
+    }
+}
+
+impl Widget for SpectrumPlot<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.width <= LABEL_WIDTH + 4 || area.height < 4 {
+            return;
+        }
+        // The bottom row holds channel numbers
+        let plot = Rect {
+            x: area.x + LABEL_WIDTH,
+            y: area.y,
+            width: area.width - LABEL_WIDTH,
+            height: area.height - 1,
+        };
+        let (low, high) = band_range(self.band);
+        let last_col = (plot.width - 1) as f32;
+        let col_of = |freq: f32| ((freq - low) / (high - low) * last_col).round() as i32;
+        let freq_of = |col: u16| low + col as f32 / last_col * (high - low);
+        let last_row = (plot.height - 1) as f32;
+        let row_of = |dbm: f32| {
+            let level = (dbm - FLOOR_DBM as f32) / (CEILING_DBM - FLOOR_DBM) as f32;
+            plot.y + (last_row - level.clamp(0.0, 1.0) * last_row).round() as u16
+        };
+
+        let dim = Style::default().fg(Color::DarkGray);
+        for dbm in [-30, -60, -90] {
+            buf.set_string(area.x, row_of(dbm as f32), format!("{:>3}", dbm), dim);
+        }
+        if let Some(freq) = self.marker {
+            let col = col_of(freq as f32);
+            if (0..plot.width as i32).contains(&col) {
+                for y in plot.top()..plot.bottom() {
+                    buf.set_string(plot.x + col as u16, y, "┊", dim);
+                }
+            }
+        }
+
+        // Weakest first, so strong APs are drawn on top
+        let mut aps: Vec<&AccessPoint> =
+            self.aps.iter().filter(|ap| ap.band() == self.band).collect();
+        aps.sort_by_key(|ap| ap.signal_dbm);
+        for (i, ap) in aps.iter().enumerate() {
+            let style = Style::default().fg(COLORS[i % COLORS.len()]);
+            let center = ap.frequency_mhz as f32;
+            let half = ap.channel_width_mhz.unwrap_or(20) as f32 / 2.0;
+            let peak = ap.signal_dbm as f32;
+
+            let mut previous: Option<u16> = None;
+            for col in 0..plot.width {
+                let offset = (freq_of(col) - center).abs();
+                if offset > half {
+                    previous = None;
+                    continue;
+                }
+                let dbm = FLOOR_DBM as f32 + (peak - FLOOR_DBM as f32) * (1.0 - offset / half);
+                let row = row_of(dbm);
+                // Join steep edges to the previous column so the outline stays unbroken
+                let (top, bottom) = match previous {
+                    Some(prev) => (row.min(prev), row.max(prev)),
+                    None => (row, row),
+                };
+                for y in top..=bottom {
+                    buf.set_string(plot.x + col, y, "•", style);
+                }
+                previous = Some(row);
+            }
+        }
+
+        // Labels go over every outline, so none is hidden by a neighbour's triangle
+        for (i, ap) in aps.iter().enumerate() {
+            let style = Style::default().fg(COLORS[i % COLORS.len()]);
+            let (center, peak) = (ap.frequency_mhz as f32, ap.signal_dbm as f32);
+            let name = if ap.ssid.is_empty() { "<hidden>" } else { &ap.ssid };
+            let label = truncate(name, 12);
+            let label_row = row_of(peak).saturating_sub(1).max(plot.y);
+            let width = label.chars().count() as i32;
+            let start = (col_of(center) - width / 2).clamp(0, (plot.width as i32 - width).max(0));
+            buf.set_stringn(
+                plot.x + start as u16,
+                label_row,
+                &label,
+                (plot.width as i32 - start) as usize,
+                style,
+            );
+        }
+
+        // Channel numbers, skipping any that would touch the previous one
+        let axis_y = plot.bottom();
+        let mut next_free = 0;
+        for (channel, freq) in axis_channels(self.band) {
+            let text = channel.to_string();
+            let col = col_of(freq as f32) - text.len() as i32 / 2;
+            if col < next_free || col + text.len() as i32 > plot.width as i32 {
+                continue;
+            }
+            buf.set_string(plot.x + col as u16, axis_y, &text, dim);
+            next_free = col + text.len() as i32 + 1;
+        }
+    }
+}
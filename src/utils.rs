@@ -1,5 +1,10 @@
 use std::time::Duration;
 
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use crate::data::TimerMode;
+
 /// Format duration as MM:SS
 pub fn format_duration(duration: Duration) -> String {
     let total_secs = duration.as_secs();
@@ -8,14 +13,17 @@ pub fn format_duration(duration: Duration) -> String {
     format!("{:02}:{:02}", mins, secs)
 }
 
-/// Format duration as MM:SS/MM:SS for countdown display
-pub fn format_timer(elapsed: Duration, target: Option<Duration>) -> String {
-    match target {
-        Some(t) => {
+/// Format timer as MM:SS/MM:SS, counting down (remaining) or up (elapsed)
+pub fn format_timer(elapsed: Duration, target: Option<Duration>, mode: TimerMode) -> String {
+    match (target, mode) {
+        (Some(t), TimerMode::Countdown) => {
             let remaining = t.saturating_sub(elapsed);
             format!("{}/{}", format_duration(remaining), format_duration(t))
         }
-        None => format_duration(elapsed),
+        (Some(t), TimerMode::Elapsed) => {
+            format!("{}/{}", format_duration(elapsed.min(t)), format_duration(t))
+        }
+        (None, _) => format_duration(elapsed),
     }
 }
 
@@ -43,21 +51,190 @@ pub fn signal_color(signal_dbm: i32) -> ratatui::style::Color {
     }
 }
 
-/// Truncate string with ellipsis if too long
+/// Terminal columns taken by a string (CJK and emoji count as two)
+pub fn display_width(s: &str) -> usize {
+    UnicodeWidthStr::width(s)
+}
+
+/// Leading graphemes of `s` that fit in `max_width` columns
+pub fn take_width(s: &str, max_width: usize) -> &str {
+    let mut width = 0;
+    for (i, g) in s.grapheme_indices(true) {
+        width += display_width(g);
+        if width > max_width {
+            return &s[..i];
+        }
+    }
+    s
+}
+
+/// Truncate string to `max_len` columns, with ellipsis if too long
 pub fn truncate(s: &str, max_len: usize) -> String {
-    if s.len() <= max_len {
+    if display_width(s) <= max_len {
         s.to_string()
     } else if max_len <= 3 {
-        s.chars().take(max_len).collect()
+        take_width(s, max_len).to_string()
     } else {
-        format!("{}...", &s[..max_len - 3])
+        format!("{}...", take_width(s, max_len - 3))
+    }
+}
+
+/// Trailing graphemes of `s` that fit in `max_width` columns
+pub fn tail_width(s: &str, max_width: usize) -> &str {
+    let mut width = 0;
+    for (i, g) in s.grapheme_indices(true).rev() {
+        width += display_width(g);
+        if width > max_width {
+            return &s[i + g.len()..];
+        }
+    }
+    s
+}
+
+/// Truncate and pad with spaces to exactly `width` columns
+pub fn pad(s: &str, width: usize) -> String {
+    let s = truncate(s, width);
+    let fill = width.saturating_sub(display_width(&s));
+    format!("{}{}", s, " ".repeat(fill))
+}
+
+/// Word-wrap `text` to `width` columns, keeping its line breaks and hard-breaking
+/// words too long for a line
+pub fn wrap(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for (i, word) in paragraph.split(' ').enumerate() {
+            let sep = if i == 0 { "" } else { " " };
+            if display_width(&line) + sep.len() + display_width(word) <= width {
+                line.push_str(sep);
+                line.push_str(word);
+                continue;
+            }
+            if !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+            }
+            let mut rest = word;
+            // A lone grapheme wider than the line still gets a line to itself
+            while display_width(rest) > width && rest.graphemes(true).nth(1).is_some() {
+                let head = match take_width(rest, width) {
+                    "" => rest.graphemes(true).next().unwrap_or(rest),
+                    head => head,
+                };
+                lines.push(head.to_string());
+                rest = &rest[head.len()..];
+            }
+            line.push_str(rest);
+        }
+        lines.push(line);
     }
+    lines
+}
+
+/// Byte offset of the `cursor`-th character, clamped to the end
+pub fn char_offset(s: &str, cursor: usize) -> usize {
+    s.char_indices().nth(cursor).map(|(i, _)| i).unwrap_or(s.len())
+}
+
+/// Insert `c` at character position `cursor`
+pub fn insert_char(s: &mut String, cursor: usize, c: char) {
+    let at = char_offset(s, cursor);
+    s.insert(at, c);
+}
+
+/// Remove the character at position `cursor`, if any
+pub fn remove_char(s: &mut String, cursor: usize) {
+    let at = char_offset(s, cursor);
+    if at < s.len() {
+        s.remove(at);
+    }
+}
+
+/// Case-insensitive glob match supporting `*` (any run) and `?` (one character)
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` and the text index it currently covers up to
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((sp, st)) => {
+                    p = sp + 1;
+                    t = st + 1;
+                    star = Some((sp, st + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Percentile of sorted values, interpolating linearly between ranks (None if empty)
+pub fn percentile<T: Copy + Into<f64>>(sorted: &[T], p: f64) -> Option<f64> {
+    let last = sorted.len().checked_sub(1)?;
+    let rank = p / 100.0 * last as f64;
+    let lo: f64 = sorted[rank.floor() as usize].into();
+    let hi: f64 = sorted[rank.ceil() as usize].into();
+    Some(lo + (hi - lo) * (rank - rank.floor()))
+}
+
+/// Standard base64 encoding (used for OSC 52 clipboard sequences)
+pub fn base64_encode(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        out.push(ALPHABET[(n >> 18) as usize & 63] as char);
+        out.push(ALPHABET[(n >> 12) as usize & 63] as char);
+        out.push(if chunk.len() > 1 { ALPHABET[(n >> 6) as usize & 63] as char } else { '=' });
+        out.push(if chunk.len() > 2 { ALPHABET[n as usize & 63] as char } else { '=' });
+    }
+    out
+}
+
+/// Copy text to the system clipboard via the OSC 52 terminal escape sequence
+pub fn copy_to_clipboard(text: &str) -> std::io::Result<()> {
+    use std::io::Write;
+    let mut stdout = std::io::stdout();
+    write!(stdout, "\x1b]52;c;{}\x07", base64_encode(text.as_bytes()))?;
+    stdout.flush()
+}
+
+/// Ring the terminal bell
+pub fn beep() -> std::io::Result<()> {
+    use std::io::Write;
+    let mut stdout = std::io::stdout();
+    write!(stdout, "\x07")?;
+    stdout.flush()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_format_timer() {
+        let elapsed = Duration::from_secs(94);
+        let target = Some(Duration::from_secs(300));
+        assert_eq!(format_timer(elapsed, target, TimerMode::Countdown), "03:26/05:00");
+        assert_eq!(format_timer(elapsed, target, TimerMode::Elapsed), "01:34/05:00");
+        assert_eq!(format_timer(elapsed, None, TimerMode::Countdown), "01:34");
+    }
+
     #[test]
     fn test_format_duration() {
         assert_eq!(format_duration(Duration::from_secs(0)), "00:00");
@@ -76,5 +253,64 @@ mod tests {
     fn test_truncate() {
         assert_eq!(truncate("hello", 10), "hello");
         assert_eq!(truncate("hello world", 8), "hello...");
+        assert_eq!(truncate("カフェのWiFi", 12), "カフェのWiFi");
+        assert_eq!(truncate("カフェのWiFi", 8), "カフ...");
+        assert_eq!(truncate("📶 Home", 5), "📶...");
+        assert_eq!(truncate("Café-Ñet", 6), "Caf...");
+        assert_eq!(truncate("e\u{301}e\u{301}e\u{301}e\u{301}e\u{301}", 4), "e\u{301}...");
+    }
+
+    #[test]
+    fn test_width_helpers() {
+        assert_eq!(display_width("カフェ"), 6);
+        assert_eq!(tail_width("カフェ_5G", 5), "ェ_5G");
+        assert_eq!(pad("日本", 6), "日本  ");
+        assert_eq!(pad("日本語", 5), "日...");
+
+        let mut input = "Café".to_string();
+        insert_char(&mut input, 4, '✓');
+        insert_char(&mut input, 0, '☕');
+        assert_eq!(input, "☕Café✓");
+        remove_char(&mut input, 4);
+        assert_eq!(input, "☕Caf✓");
+        remove_char(&mut input, 9);
+        assert_eq!(input, "☕Caf✓");
+    }
+
+    #[test]
+    fn test_wrap() {
+        assert_eq!(wrap("one two three", 7), vec!["one two", "three"]);
+        assert_eq!(wrap("a\n\nb", 10), vec!["a", "", "b"]);
+        assert_eq!(wrap("  indented line", 20), vec!["  indented line"]);
+        assert_eq!(wrap("abcdefghij k", 4), vec!["abcd", "efgh", "ij k"]);
+        assert_eq!(wrap("日本語テキスト", 6), vec!["日本語", "テキス", "ト"]);
+        assert_eq!(wrap("日本", 1), vec!["日", "本"]);
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("*_Guest", "Office_guest"));
+        assert!(glob_match("HP-Print-??-*", "HP-Print-4B-LaserJet"));
+        assert!(glob_match("*", ""));
+        assert!(!glob_match("*_Guest", "Guest_Office"));
+        assert!(!glob_match("HP-?", "HP-12"));
+    }
+
+    #[test]
+    fn test_percentile() {
+        let sorted = [1.0f32, 2.0, 3.0, 4.0, 5.0];
+        assert_eq!(percentile(&sorted, 50.0), Some(3.0));
+        assert!((percentile(&sorted, 95.0).unwrap() - 4.8).abs() < 0.01);
+        assert_eq!(percentile(&[-90, -60, -50], 25.0), Some(-75.0));
+        assert_eq!(percentile::<i32>(&[], 50.0), None);
+    }
+
+    #[test]
+    fn test_base64_encode() {
+        assert_eq!(base64_encode(b""), "");
+        assert_eq!(base64_encode(b"f"), "Zg==");
+        assert_eq!(base64_encode(b"fo"), "Zm8=");
+        assert_eq!(base64_encode(b"foo"), "Zm9v");
+        assert_eq!(base64_encode(b"wificomp"), "d2lmaWNvbXA=");
     }
 }
@@ -0,0 +1,150 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use super::{not_found, Response, Shared, WebCommand};
+use crate::data::{list_sessions, load_session};
+
+/// A saved session in `GET /api/sessions`
+#[derive(Debug, Serialize)]
+struct SessionEntry {
+    /// Path under the sessions directory, for `GET /api/sessions/<id>`
+    id: String,
+    adapter: String,
+    started_at: String,
+    scan_count: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    node: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    run_id: Option<String>,
+}
+
+fn json(body: String) -> Response {
+    ("200 OK", "application/json", body)
+}
+
+fn error(status: &'static str, message: &str) -> Response {
+    let body = serde_json::json!({ "error": message }).to_string();
+    (status, "application/json", body)
+}
+
+/// Reply to a request under `/api/`. The token also keeps other sites' pages out: a
+/// browser won't add an Authorization header to a cross-site POST.
+pub(super) fn route(
+    method: &str,
+    path: &str,
+    authorization: Option<&str>,
+    shared: &Shared,
+) -> Response {
+    let token = authorization.and_then(|a| a.strip_prefix("Bearer "));
+    if token != Some(shared.token.as_str()) {
+        return error("401 Unauthorized", "missing or wrong API token");
+    }
+    match (method, path) {
+        ("GET", "/api/sessions") => sessions(shared),
+        ("GET", "/api/stats") => json(shared.state()),
+        ("POST", "/api/scan") => match shared.commands.send(WebCommand::Scan) {
+            Ok(()) => ("202 Accepted", "application/json", "{\"queued\":true}".to_string()),
+            Err(_) => error("503 Service Unavailable", "wificomp is shutting down"),
+        },
+        ("GET", path) => match path.strip_prefix("/api/sessions/") {
+            Some(id) => session(shared, id),
+            None => not_found(),
+        },
+        _ => error("405 Method Not Allowed", "unsupported method"),
+    }
+}
+
+/// Saved sessions, newest first
+fn sessions(shared: &Shared) -> Response {
+    let dir = &shared.paths.sessions_dir;
+    let paths = match list_sessions(&shared.paths) {
+        Ok(paths) => paths,
+        Err(e) => return error("500 Internal Server Error", &format!("{:#}", e)),
+    };
+    let entries: Vec<SessionEntry> = paths
+        .iter()
+        .filter_map(|path| {
+            let session = load_session(path).ok()?;
+            Some(SessionEntry {
+                id: session_id(dir, path)?,
+                adapter: session.adapter.display_name(),
+                started_at: session.started_at.to_rfc3339(),
+                scan_count: session.scans.len(),
+                node: session.node,
+                run_id: session.run_id,
+            })
+        })
+        .collect();
+    json(serde_json::to_string(&entries).unwrap_or_else(|_| "[]".to_string()))
+}
+
+/// A session file as saved
+fn session(shared: &Shared, id: &str) -> Response {
+    let Some(path) = session_path(&shared.paths.sessions_dir, id) else {
+        return error("404 Not Found", "no such session");
+    };
+    match std::fs::read_to_string(&path) {
+        Ok(body) => json(body),
+        Err(_) => error("404 Not Found", "no such session"),
+    }
+}
+
+/// `/`-separated path of a session file relative to the sessions directory
+fn session_id(dir: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(dir).ok()?;
+    let parts: Vec<&str> = relative.iter().map(|p| p.to_str()).collect::<Option<_>>()?;
+    Some(parts.join("/"))
+}
+
+/// Resolve a session id, refusing anything outside the sessions directory
+fn session_path(dir: &Path, id: &str) -> Option<PathBuf> {
+    let parts: Vec<&str> = id.split('/').collect();
+    let plain = |part: &&str| !part.is_empty() && *part != "." && *part != "..";
+    if parts.len() > 2 || !parts.iter().all(plain) || !id.ends_with(".json") {
+        return None;
+    }
+    Some(parts.iter().fold(dir.to_path_buf(), |path, part| path.join(part)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::shared;
+    use super::*;
+    use crate::data::fixtures::adapter;
+    use crate::data::{save_session_to, Session};
+
+    #[test]
+    fn test_api_routes() {
+        let dir = std::env::temp_dir().join(format!("wificomp-api-{}", std::process::id()));
+        let (shared, commands) = shared(&dir);
+        let adapter_dir = shared.paths.sessions_dir.join("wlan0_Intel");
+        std::fs::create_dir_all(&adapter_dir).unwrap();
+        let session = Session::new(adapter("wlan0"), None);
+        save_session_to(&adapter_dir.join("s1.json"), &session).unwrap();
+
+        assert_eq!(route("GET", "/api/stats", None, &shared).0, "401 Unauthorized");
+        assert_eq!(route("POST", "/api/scan", Some("Bearer wrong"), &shared).0, "401 Unauthorized");
+        assert!(commands.try_recv().is_err());
+        let auth = Some("Bearer secret");
+        let get = |path| route("GET", path, auth, &shared);
+
+        let (status, _, body) = get("/api/sessions");
+        assert_eq!(status, "200 OK");
+        let list: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(list[0]["id"], "wlan0_Intel/s1.json");
+        assert_eq!(list[0]["scan_count"], 0);
+
+        let (status, _, body) = get("/api/sessions/wlan0_Intel/s1.json");
+        assert_eq!(status, "200 OK");
+        assert!(body.contains("\"iwlwifi\""));
+        assert_eq!(get("/api/sessions/../config.json").0, "404 Not Found");
+        assert_eq!(get("/api/sessions/wlan0_Intel/s2.json").0, "404 Not Found");
+
+        assert_eq!(get("/api/stats").2, "{\"scan_count\":3}");
+        assert_eq!(route("POST", "/api/scan", auth, &shared).0, "202 Accepted");
+        assert_eq!(commands.try_recv(), Ok(WebCommand::Scan));
+        assert_eq!(route("DELETE", "/api/scan", auth, &shared).0, "405 Method Not Allowed");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,262 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crate::data::DataPaths;
+
+#[cfg(feature = "api")]
+mod api;
+
+/// The dashboard page; it polls `/state` and renders it
+const INDEX: &str = include_str!("index.html");
+
+/// How long a client may take to send its request
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Most a client may send as its request line and headers together
+const MAX_HEAD_BYTES: u64 = 8 * 1024;
+
+/// Connections served at once; more are closed unanswered
+const MAX_CONNECTIONS: usize = 16;
+
+/// What the dashboard shows, rebuilt from the app state every publish
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Snapshot {
+    pub adapter: Option<String>,
+    pub scanning: bool,
+    pub scan_count: usize,
+    pub elapsed_secs: u64,
+    pub error: Option<String>,
+    pub access_points: Vec<ApView>,
+    pub compare: Option<CompareView>,
+}
+
+/// One AP of the latest scan, with its session stats and recent signal
+#[derive(Debug, Clone, Serialize)]
+pub struct ApView {
+    pub bssid: String,
+    pub ssid: String,
+    pub signal_dbm: i32,
+    pub channel: u32,
+    pub band: &'static str,
+    pub avg: Option<i32>,
+    pub min: Option<i32>,
+    pub max: Option<i32>,
+    /// Signal over the last scans, oldest first (null where the AP was missing)
+    pub history: Vec<Option<i32>>,
+}
+
+/// The Compare screen's selected AP and per-session values
+#[derive(Debug, Clone, Serialize)]
+pub struct CompareView {
+    pub ssid: String,
+    pub bssid: String,
+    pub metric: String,
+    pub match_by: &'static str,
+    pub rows: Vec<(String, Option<i32>)>,
+    pub best: Option<String>,
+}
+
+/// Something the HTTP API asks the app to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(not(feature = "api"), allow(dead_code))]
+pub enum WebCommand {
+    Scan,
+}
+
+/// What request handlers share with the app
+struct Shared {
+    /// Latest snapshot as JSON
+    state: Mutex<String>,
+    #[cfg_attr(not(feature = "api"), allow(dead_code))]
+    paths: DataPaths,
+    #[cfg_attr(not(feature = "api"), allow(dead_code))]
+    commands: Sender<WebCommand>,
+    /// What API requests must send as `Authorization: Bearer <token>`
+    #[cfg_attr(not(feature = "api"), allow(dead_code))]
+    token: String,
+    /// Connections being served
+    connections: AtomicUsize,
+}
+
+impl Shared {
+    fn state(&self) -> String {
+        self.state.lock().map(|s| s.clone()).unwrap_or_default()
+    }
+}
+
+/// Web dashboard served on a background thread (`--web PORT`), plus the HTTP API
+/// when built with the `api` feature
+pub struct Dashboard {
+    shared: Arc<Shared>,
+    commands: Receiver<WebCommand>,
+}
+
+impl Dashboard {
+    /// Listen on `address`; the API takes `token`, or a random one when it's None
+    pub fn start(address: SocketAddr, token: Option<String>, paths: DataPaths) -> Result<Self> {
+        let listener = TcpListener::bind(address)
+            .with_context(|| format!("Failed to listen on {}", address))?;
+        let (sender, commands) = mpsc::channel();
+        let shared = Arc::new(Shared {
+            state: Mutex::new(to_json(&Snapshot::default())),
+            paths,
+            commands: sender,
+            token: token.map_or_else(random_token, Ok)?,
+            connections: AtomicUsize::new(0),
+        });
+        let server = Arc::clone(&shared);
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                if server.connections.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+                    server.connections.fetch_sub(1, Ordering::SeqCst);
+                    continue;
+                }
+                let shared = Arc::clone(&server);
+                thread::spawn(move || {
+                    if let Err(e) = handle(stream, &shared) {
+                        log::debug!("web request failed: {}", e);
+                    }
+                    shared.connections.fetch_sub(1, Ordering::SeqCst);
+                });
+            }
+        });
+        log::info!("web dashboard listening on {}", address);
+        Ok(Dashboard { shared, commands })
+    }
+
+    /// Token API requests must send
+    #[cfg(feature = "api")]
+    pub fn token(&self) -> &str {
+        &self.shared.token
+    }
+
+    /// Replace what `/state` returns
+    pub fn publish(&self, snapshot: &Snapshot) {
+        let json = to_json(snapshot);
+        if let Ok(mut state) = self.shared.state.lock() {
+            *state = json;
+        }
+    }
+
+    /// API requests received since the last call
+    pub fn commands(&self) -> Vec<WebCommand> {
+        self.commands.try_iter().collect()
+    }
+}
+
+/// Status line, content type and body of a reply
+type Response = (&'static str, &'static str, String);
+
+fn not_found() -> Response {
+    ("404 Not Found", "text/plain", "Not found\n".to_string())
+}
+
+/// 128 bits from the OS random source, as hex
+fn random_token() -> Result<String> {
+    let mut bytes = [0u8; 16];
+    getrandom::fill(&mut bytes).map_err(|e| anyhow::anyhow!("No random API token: {}", e))?;
+    Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+fn to_json(snapshot: &Snapshot) -> String {
+    serde_json::to_string(snapshot).unwrap_or_else(|_| "{}".to_string())
+}
+
+/// Answer one request and close the connection
+fn handle(stream: TcpStream, shared: &Shared) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut reader = BufReader::new(stream).take(MAX_HEAD_BYTES);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // Only the API token is needed, but every header must be read before replying
+    let mut authorization = None;
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 0 && header.trim() != "" {
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("authorization") {
+                authorization = Some(value.trim().to_string());
+            }
+        }
+        header.clear();
+    }
+
+    let (status, content_type, body) = if reader.limit() == 0 {
+        ("431 Request Header Fields Too Large", "text/plain", "Request too large\n".to_string())
+    } else {
+        let mut words = request_line.split_whitespace();
+        let (method, path) = (words.next().unwrap_or(""), words.next().unwrap_or(""));
+        route(method, path, authorization.as_deref(), shared)
+    };
+
+    let mut stream = reader.into_inner().into_inner();
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\n\
+         Connection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+/// Reply to a request; `authorization` is its Authorization header
+#[cfg_attr(not(feature = "api"), allow(unused_variables))]
+fn route(method: &str, path: &str, authorization: Option<&str>, shared: &Shared) -> Response {
+    let path = path.split('?').next().unwrap_or(path);
+    match (method, path) {
+        ("GET", "/") => ("200 OK", "text/html; charset=utf-8", INDEX.to_string()),
+        ("GET", "/state") => ("200 OK", "application/json", shared.state()),
+        #[cfg(feature = "api")]
+        (_, path) if path.starts_with("/api/") => {
+            api::route(method, path, authorization, shared)
+        }
+        ("GET", _) => not_found(),
+        _ => ("405 Method Not Allowed", "text/plain", "Read-only\n".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Handler state over a data directory, and the app's end of the command channel
+    pub(super) fn shared(dir: &std::path::Path) -> (Shared, Receiver<WebCommand>) {
+        let (commands, receiver) = mpsc::channel();
+        let shared = Shared {
+            state: Mutex::new("{\"scan_count\":3}".to_string()),
+            paths: DataPaths::in_dir(dir),
+            commands,
+            token: "secret".to_string(),
+            connections: AtomicUsize::new(0),
+        };
+        (shared, receiver)
+    }
+
+    #[test]
+    fn test_route() {
+        let (shared, _) = shared(std::path::Path::new("/nonexistent"));
+        let (status, content_type, body) = route("GET", "/", None, &shared);
+        assert_eq!((status, content_type), ("200 OK", "text/html; charset=utf-8"));
+        assert!(body.contains("/state"));
+        assert_eq!(route("GET", "/state?t=1", None, &shared).2, "{\"scan_count\":3}");
+        assert_eq!(route("GET", "/missing", None, &shared).0, "404 Not Found");
+        assert_eq!(route("POST", "/state", None, &shared).0, "405 Method Not Allowed");
+    }
+
+    #[test]
+    fn test_random_token() {
+        let token = random_token().unwrap();
+        assert_eq!(token.len(), 32);
+        assert!(token.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(token, random_token().unwrap());
+    }
+}